@@ -70,10 +70,13 @@ impl<'a> AstDumper<'a> {
                 ElemFunction(ref fct) => self.dump_fct(fct),
                 ElemClass(ref cls) => self.dump_class(cls),
                 ElemStruct(ref struc) => self.dump_struct(struc),
+                ElemEnum(ref xenum) => self.dump_enum(xenum),
+                ElemAlias(ref alias) => self.dump_alias(alias),
                 ElemTrait(ref xtrait) => self.dump_trait(xtrait),
                 ElemImpl(ref ximpl) => self.dump_impl(ximpl),
                 ElemGlobal(ref global) => self.dump_global(global),
                 ElemConst(ref xconst) => self.dump_const(xconst),
+                ElemImport(ref import) => self.dump_import(import),
             }
         }
     }
@@ -109,11 +112,30 @@ impl<'a> AstDumper<'a> {
                     });
     }
 
+    fn dump_import(&mut self, import: &Import) {
+        let mut path = import
+            .path
+            .path
+            .iter()
+            .map(|&name| self.str(name).to_string())
+            .collect::<Vec<_>>();
+
+        if import.glob {
+            path.push("*".into());
+        }
+
+        dump!(self,
+              "import {} @ {} {}",
+              path.join("::"),
+              import.pos,
+              import.id);
+    }
+
     fn dump_impl(&mut self, ximpl: &Impl) {
         dump!(self,
               "impl {} for {} @ {} {}",
-              self.str(ximpl.trait_name),
-              self.str(ximpl.class_name),
+              ximpl.trait_type.to_string(self.interner),
+              ximpl.class_type.to_string(self.interner),
               ximpl.pos,
               ximpl.id);
 
@@ -143,11 +165,46 @@ impl<'a> AstDumper<'a> {
         self.indent(|d| d.dump_type(&field.data_type));
     }
 
+    fn dump_enum(&mut self, xenum: &Enum) {
+        dump!(self,
+              "enum {} @ {} {}",
+              self.str(xenum.name),
+              xenum.pos,
+              xenum.id);
+
+        self.indent(|d| for variant in &xenum.variants {
+                        dump!(d,
+                              "variant {} @ {} {}",
+                              d.str(variant.name),
+                              variant.pos,
+                              variant.id);
+                        d.indent(|d| for ty in &variant.types {
+                                     d.dump_type(ty);
+                                 });
+                    });
+    }
+
+    fn dump_alias(&mut self, alias: &Alias) {
+        dump!(self,
+              "type {} @ {} {}",
+              self.str(alias.name),
+              alias.pos,
+              alias.id);
+
+        self.indent(|d| d.dump_type(&alias.ty));
+    }
+
     fn dump_trait(&mut self, t: &Trait) {
         dump!(self, "trait {} @ {} {}", self.str(t.name), t.pos, t.id);
-        self.indent(|d| for m in &t.methods {
-                        d.dump_fct(m);
-                    });
+        self.indent(|d| {
+            for a in &t.aliases {
+                d.dump_alias(a);
+            }
+
+            for m in &t.methods {
+                d.dump_fct(m);
+            }
+        });
     }
 
     fn dump_class(&mut self, cls: &Class) {
@@ -230,8 +287,9 @@ impl<'a> AstDumper<'a> {
 
     fn dump_param(&mut self, param: &Param) {
         dump!(self,
-              "param {} @ {} {}",
+              "param {}{} @ {} {}",
               self.str(param.name),
+              if param.variadic { "..." } else { "" },
               param.pos,
               param.id);
 
@@ -252,15 +310,16 @@ impl<'a> AstDumper<'a> {
             StmtReturn(ref ret) => self.dump_stmt_return(ret),
             StmtBreak(ref stmt) => self.dump_stmt_break(stmt),
             StmtContinue(ref stmt) => self.dump_stmt_continue(stmt),
+            StmtError(ref stmt) => self.dump_stmt_error(stmt),
             StmtExpr(ref expr) => self.dump_stmt_expr(expr),
             StmtIf(ref stmt) => self.dump_stmt_if(stmt),
             StmtVar(ref stmt) => self.dump_stmt_var(stmt),
+            StmtVarTuple(ref stmt) => self.dump_stmt_var_tuple(stmt),
             StmtWhile(ref stmt) => self.dump_stmt_while(stmt),
             StmtLoop(ref stmt) => self.dump_stmt_loop(stmt),
             StmtThrow(ref stmt) => self.dump_stmt_throw(stmt),
             StmtDefer(ref stmt) => self.dump_stmt_defer(stmt),
             StmtDo(ref stmt) => self.dump_stmt_do(stmt),
-            StmtSpawn(ref stmt) => self.dump_stmt_spawn(stmt),
             StmtFor(ref stmt) => self.dump_stmt_for(stmt),
         }
     }
@@ -289,6 +348,34 @@ impl<'a> AstDumper<'a> {
         });
     }
 
+    fn dump_stmt_var_tuple(&mut self, stmt: &StmtVarTupleType) {
+        let names = stmt.vars
+            .iter()
+            .map(|var| match var.name {
+                     Some(name) => self.str(name).to_string(),
+                     None => "_".into(),
+                 })
+            .collect::<Vec<_>>();
+
+        dump!(self,
+              "let ({}) @ {} {}",
+              names.join(", "),
+              stmt.pos,
+              stmt.id);
+
+        self.indent(|d| {
+            dump!(d, "type");
+            d.indent(|d| if let Some(ref ty) = stmt.data_type {
+                         d.dump_type(ty);
+                     } else {
+                         dump!(d, "<no type given>");
+                     });
+
+            dump!(d, "expr");
+            d.indent(|d| d.dump_expr(&stmt.expr));
+        });
+    }
+
     fn dump_stmt_for(&mut self, stmt: &StmtForType) {
         dump!(self, "for @ {} {}", stmt.pos, stmt.id);
 
@@ -371,13 +458,12 @@ impl<'a> AstDumper<'a> {
         dump!(self, "continue @ {} {}", stmt.pos, stmt.id);
     }
 
-    fn dump_stmt_throw(&mut self, stmt: &StmtThrowType) {
-        dump!(self, "throw @ {} {}", stmt.pos, stmt.id);
-        self.indent(|d| d.dump_expr(&stmt.expr));
+    fn dump_stmt_error(&mut self, stmt: &StmtErrorType) {
+        dump!(self, "error @ {} {}", stmt.pos, stmt.id);
     }
 
-    fn dump_stmt_spawn(&mut self, stmt: &StmtSpawnType) {
-        dump!(self, "spawn @ {} {}", stmt.pos, stmt.id);
+    fn dump_stmt_throw(&mut self, stmt: &StmtThrowType) {
+        dump!(self, "throw @ {} {}", stmt.pos, stmt.id);
         self.indent(|d| d.dump_expr(&stmt.expr));
     }
 
@@ -414,6 +500,7 @@ impl<'a> AstDumper<'a> {
             ExprLitInt(ref lit) => self.dump_expr_lit_int(lit),
             ExprLitFloat(ref lit) => self.dump_expr_lit_float(lit),
             ExprLitStr(ref lit) => self.dump_expr_lit_str(lit),
+            ExprTemplate(ref tmpl) => self.dump_expr_template(tmpl),
             ExprLitStruct(ref lit) => self.dump_expr_lit_struct(lit),
             ExprLitBool(ref lit) => self.dump_expr_lit_bool(lit),
             ExprIdent(ref ident) => self.dump_expr_ident(ident),
@@ -426,6 +513,11 @@ impl<'a> AstDumper<'a> {
             ExprConv(ref expr) => self.dump_expr_conv(expr),
             ExprTry(ref expr) => self.dump_expr_try(expr),
             ExprLambda(ref expr) => self.dump_expr_lambda(expr),
+            ExprSpawn(ref expr) => self.dump_expr_spawn(expr),
+            ExprPath(ref expr) => self.dump_expr_path(expr),
+            ExprMatch(ref expr) => self.dump_expr_match(expr),
+            ExprTuple(ref expr) => self.dump_expr_tuple(expr),
+            ExprTupleElement(ref expr) => self.dump_expr_tuple_element(expr),
         }
     }
 
@@ -482,6 +574,13 @@ impl<'a> AstDumper<'a> {
         dump!(self, "lit string {:?} @ {} {}", lit.value, lit.pos, lit.id);
     }
 
+    fn dump_expr_template(&mut self, tmpl: &ExprTemplateType) {
+        dump!(self, "template @ {} {}", tmpl.pos, tmpl.id);
+        self.indent(|d| for part in &tmpl.parts {
+                        d.dump_expr(part);
+                    });
+    }
+
     fn dump_expr_lit_bool(&mut self, lit: &ExprLitBoolType) {
         dump!(self, "lit bool {} @ {} {}", lit.value, lit.pos, lit.id);
     }
@@ -526,7 +625,70 @@ impl<'a> AstDumper<'a> {
 
     fn dump_expr_lambda(&mut self, expr: &ExprLambdaType) {
         dump!(self, "lambda @ {} {}", expr.pos, expr.id);
-        self.indent(|d| d.dump_stmt(&expr.block));
+        self.indent(|d| d.dump_stmt(expr.fct.block()));
+    }
+
+    fn dump_expr_spawn(&mut self, expr: &ExprSpawnType) {
+        dump!(self, "spawn @ {} {}", expr.pos, expr.id);
+        self.indent(|d| d.dump_stmt(expr.fct.block()));
+    }
+
+    fn dump_expr_path(&mut self, expr: &ExprPathType) {
+        let names = expr.path
+            .path
+            .iter()
+            .map(|&name| self.str(name).to_string())
+            .collect::<Vec<_>>();
+
+        dump!(self, "path {} @ {} {}", names.join("::"), expr.pos, expr.id);
+    }
+
+    fn dump_expr_match(&mut self, expr: &ExprMatchType) {
+        dump!(self, "match @ {} {}", expr.pos, expr.id);
+        self.indent(|d| d.dump_expr(&expr.expr));
+
+        for arm in &expr.arms {
+            match arm.pattern {
+                MatchPattern::Underscore => {
+                    dump!(self, "arm _ @ {} {}", arm.pos, arm.id);
+                }
+
+                MatchPattern::Variant(ref path, ref params) => {
+                    let path = path.path
+                        .iter()
+                        .map(|&name| self.str(name).to_string())
+                        .collect::<Vec<_>>();
+                    let params = params
+                        .iter()
+                        .map(|param| match param.name {
+                                 Some(name) => self.str(name).to_string(),
+                                 None => "_".into(),
+                             })
+                        .collect::<Vec<_>>();
+
+                    dump!(self,
+                          "arm {}({}) @ {} {}",
+                          path.join("::"),
+                          params.join(", "),
+                          arm.pos,
+                          arm.id);
+                }
+            }
+
+            self.indent(|d| d.dump_expr(&arm.value));
+        }
+    }
+
+    fn dump_expr_tuple(&mut self, expr: &ExprTupleType) {
+        dump!(self, "tuple @ {} {}", expr.pos, expr.id);
+        self.indent(|d| for value in &expr.values {
+                        d.dump_expr(value);
+                    });
+    }
+
+    fn dump_expr_tuple_element(&mut self, expr: &ExprTupleElementType) {
+        dump!(self, "tuple element {} @ {} {}", expr.idx, expr.pos, expr.id);
+        self.indent(|d| d.dump_expr(&expr.object));
     }
 
     fn dump_expr_field(&mut self, field: &ExprFieldType) {
@@ -545,9 +707,15 @@ impl<'a> AstDumper<'a> {
     }
 
     fn dump_expr_call(&mut self, expr: &ExprCallType) {
+        let names = expr.path
+            .path
+            .iter()
+            .map(|&name| self.str(name).to_string())
+            .collect::<Vec<_>>();
+
         dump!(self,
               "call {} @ {} {}",
-              self.str(expr.path[0]),
+              names.join("::"),
               expr.pos,
               expr.id);
 
@@ -557,6 +725,11 @@ impl<'a> AstDumper<'a> {
                 d.indent(|d| d.dump_expr(object));
             }
 
+            if let Some(ref callee) = expr.callee {
+                dump!(d, "callee");
+                d.indent(|d| d.dump_expr(callee));
+            }
+
             for arg in &expr.args {
                 d.dump_expr(arg);
             }
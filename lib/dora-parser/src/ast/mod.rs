@@ -55,6 +55,20 @@ impl Ast {
             .unwrap()
     }
 
+    #[cfg(test)]
+    pub fn alias0(&self) -> &Alias {
+        self.files.last().unwrap().elements[0]
+            .to_alias()
+            .unwrap()
+    }
+
+    #[cfg(test)]
+    pub fn enum0(&self) -> &Enum {
+        self.files.last().unwrap().elements[0]
+            .to_enum()
+            .unwrap()
+    }
+
     #[cfg(test)]
     pub fn trai(&self, index: usize) -> &Trait {
         self.files.last().unwrap().elements[index]
@@ -87,12 +101,23 @@ impl Ast {
             .to_const()
             .unwrap()
     }
+
+    #[cfg(test)]
+    pub fn import0(&self) -> &Import {
+        self.files.last().unwrap().elements[0]
+            .to_import()
+            .unwrap()
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct File {
     pub path: String,
+    pub src: String,
     pub elements: Vec<Elem>,
+
+    // path of the module the file belongs to, empty for the root module
+    pub module: Vec<Name>,
 }
 
 #[derive(PartialEq, Eq, Copy, Clone, Debug, Hash)]
@@ -109,10 +134,13 @@ pub enum Elem {
     ElemFunction(Function),
     ElemClass(Class),
     ElemStruct(Struct),
+    ElemEnum(Enum),
+    ElemAlias(Alias),
     ElemTrait(Trait),
     ElemImpl(Impl),
     ElemGlobal(Global),
     ElemConst(Const),
+    ElemImport(Import),
 }
 
 impl Elem {
@@ -121,10 +149,13 @@ impl Elem {
             &ElemFunction(ref fct) => fct.id,
             &ElemClass(ref class) => class.id,
             &ElemStruct(ref s) => s.id,
+            &ElemEnum(ref e) => e.id,
+            &ElemAlias(ref a) => a.id,
             &ElemTrait(ref t) => t.id,
             &ElemImpl(ref i) => i.id,
             &ElemGlobal(ref g) => g.id,
             &ElemConst(ref c) => c.id,
+            &ElemImport(ref i) => i.id,
         }
     }
 
@@ -149,6 +180,20 @@ impl Elem {
         }
     }
 
+    pub fn to_enum(&self) -> Option<&Enum> {
+        match self {
+            &ElemEnum(ref xenum) => Some(xenum),
+            _ => None,
+        }
+    }
+
+    pub fn to_alias(&self) -> Option<&Alias> {
+        match self {
+            &ElemAlias(ref alias) => Some(alias),
+            _ => None,
+        }
+    }
+
     pub fn to_trait(&self) -> Option<&Trait> {
         match self {
             &ElemTrait(ref trai) => Some(trai),
@@ -176,6 +221,13 @@ impl Elem {
             _ => None,
         }
     }
+
+    pub fn to_import(&self) -> Option<&Import> {
+        match self {
+            &ElemImport(ref import) => Some(import),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -183,9 +235,13 @@ pub struct Global {
     pub id: NodeId,
     pub pos: Position,
     pub name: Name,
+    pub is_pub: bool,
     pub reassignable: bool,
     pub data_type: Type,
     pub expr: Option<Box<Expr>>,
+
+    // assigns `expr` to the global, run once before the global is first accessed
+    pub initializer: Option<Function>,
 }
 
 #[derive(Clone, Debug)]
@@ -193,15 +249,29 @@ pub struct Const {
     pub id: NodeId,
     pub pos: Position,
     pub name: Name,
+    pub is_pub: bool,
     pub data_type: Type,
     pub expr: Box<Expr>,
 }
 
+#[derive(Clone, Debug)]
+pub struct Import {
+    pub id: NodeId,
+    pub pos: Position,
+
+    // absolute path starting at the root module
+    pub path: Path,
+
+    // `import a::b::*;` imports all public items of module `a::b`
+    pub glob: bool,
+}
+
 #[derive(Clone, Debug)]
 pub struct Struct {
     pub id: NodeId,
     pub pos: Position,
     pub name: Name,
+    pub is_pub: bool,
     pub fields: Vec<StructField>,
 }
 
@@ -213,6 +283,33 @@ pub struct StructField {
     pub data_type: Type,
 }
 
+#[derive(Clone, Debug)]
+pub struct Alias {
+    pub id: NodeId,
+    pub pos: Position,
+    pub name: Name,
+    pub is_pub: bool,
+    pub type_params: Option<Vec<TypeParam>>,
+    pub ty: Type,
+}
+
+#[derive(Clone, Debug)]
+pub struct Enum {
+    pub id: NodeId,
+    pub pos: Position,
+    pub name: Name,
+    pub is_pub: bool,
+    pub variants: Vec<EnumVariant>,
+}
+
+#[derive(Clone, Debug)]
+pub struct EnumVariant {
+    pub id: NodeId,
+    pub pos: Position,
+    pub name: Name,
+    pub types: Vec<Type>,
+}
+
 #[derive(Clone, Debug)]
 pub enum Type {
     TypeSelf(TypeSelfType),
@@ -221,6 +318,7 @@ pub enum Type {
     TypePtr(TypePtrType),
     TypeArray(TypeArrayType),
     TypeLambda(TypeLambdaType),
+    TypeNullable(TypeNullableType),
 }
 
 #[derive(Clone, Debug)]
@@ -244,6 +342,13 @@ pub struct TypeLambdaType {
     pub ret: Box<Type>,
 }
 
+#[derive(Clone, Debug)]
+pub struct TypeNullableType {
+    pub id: NodeId,
+    pub pos: Position,
+    pub subtype: Box<Type>,
+}
+
 #[derive(Clone, Debug)]
 pub struct TypeBasicType {
     pub id: NodeId,
@@ -313,6 +418,14 @@ impl Type {
                         })
     }
 
+    pub fn create_nullable(id: NodeId, pos: Position, subtype: Box<Type>) -> Type {
+        Type::TypeNullable(TypeNullableType {
+                               id: id,
+                               pos: pos,
+                               subtype: subtype,
+                           })
+    }
+
     pub fn to_basic(&self) -> Option<&TypeBasicType> {
         match *self {
             Type::TypeBasic(ref val) => Some(val),
@@ -348,6 +461,13 @@ impl Type {
         }
     }
 
+    pub fn to_nullable(&self) -> Option<&TypeNullableType> {
+        match *self {
+            Type::TypeNullable(ref val) => Some(val),
+            _ => None,
+        }
+    }
+
     #[cfg(test)]
     pub fn is_unit(&self) -> bool {
         match self {
@@ -359,7 +479,20 @@ impl Type {
     pub fn to_string(&self, interner: &Interner) -> String {
         match *self {
             Type::TypeSelf(_) => "Self".into(),
-            Type::TypeBasic(ref val) => format!("{}", *interner.str(val.name)),
+            Type::TypeBasic(ref val) => {
+                let name = interner.str(val.name).to_string();
+
+                if val.params.is_empty() {
+                    return name;
+                }
+
+                let params: Vec<String> = val.params
+                    .iter()
+                    .map(|t| t.to_string(interner))
+                    .collect();
+
+                format!("{}<{}>", name, params.join(", "))
+            }
 
             Type::TypeTuple(ref val) => {
                 let types: Vec<String> = val.subtypes
@@ -380,6 +513,8 @@ impl Type {
                 format!("({}) -> {}", types.join(", "), ret)
             }
 
+            Type::TypeNullable(ref val) => format!("{}?", val.subtype.to_string(interner)),
+
             Type::TypePtr(ref val) => format!("*{}", val.subtype.to_string(interner)),
 
             Type::TypeArray(ref val) => format!("[{}]", val.subtype.to_string(interner)),
@@ -392,6 +527,7 @@ impl Type {
             Type::TypeBasic(ref val) => val.pos,
             Type::TypeTuple(ref val) => val.pos,
             Type::TypeLambda(ref val) => val.pos,
+            Type::TypeNullable(ref val) => val.pos,
             Type::TypePtr(ref val) => val.pos,
             Type::TypeArray(ref val) => val.pos,
         }
@@ -403,6 +539,7 @@ impl Type {
             Type::TypeBasic(ref val) => val.id,
             Type::TypeTuple(ref val) => val.id,
             Type::TypeLambda(ref val) => val.id,
+            Type::TypeNullable(ref val) => val.id,
             Type::TypePtr(ref val) => val.id,
             Type::TypeArray(ref val) => val.id,
         }
@@ -412,8 +549,9 @@ impl Type {
 #[derive(Clone, Debug)]
 pub struct Impl {
     pub id: NodeId,
-    pub trait_name: Name,
-    pub class_name: Name,
+    pub type_params: Option<Vec<TypeParam>>,
+    pub trait_type: Type,
+    pub class_type: Type,
     pub pos: Position,
     pub methods: Vec<Function>,
 }
@@ -423,6 +561,9 @@ pub struct Trait {
     pub id: NodeId,
     pub name: Name,
     pub pos: Position,
+    pub is_pub: bool,
+    pub type_params: Option<Vec<TypeParam>>,
+    pub aliases: Vec<Alias>,
     pub methods: Vec<Function>,
 }
 
@@ -434,6 +575,7 @@ pub struct Class {
     pub parent_class: Option<ParentClass>,
     pub has_open: bool,
     pub is_abstract: bool,
+    pub is_pub: bool,
     pub internal: bool,
     pub primary_ctor: bool,
 
@@ -457,6 +599,7 @@ pub struct PrimaryCtorParam {
     pub data_type: Type,
     pub field: bool,
     pub reassignable: bool,
+    pub variadic: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -487,6 +630,7 @@ pub struct Field {
     pub primary_ctor: bool,
     pub expr: Option<Box<Expr>>,
     pub reassignable: bool,
+    pub is_pub: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -612,11 +756,13 @@ pub struct Param {
     pub name: Name,
     pub pos: Position,
     pub data_type: Type,
+    pub variadic: bool,
 }
 
 #[derive(Clone, Debug)]
 pub enum Stmt {
     StmtVar(StmtVarType),
+    StmtVarTuple(StmtVarTupleType),
     StmtWhile(StmtWhileType),
     StmtLoop(StmtLoopType),
     StmtIf(StmtIfType),
@@ -628,8 +774,8 @@ pub enum Stmt {
     StmtThrow(StmtThrowType),
     StmtDefer(StmtDeferType),
     StmtDo(StmtDoType),
-    StmtSpawn(StmtSpawnType),
     StmtFor(StmtForType),
+    StmtError(StmtErrorType),
 }
 
 impl Stmt {
@@ -650,6 +796,23 @@ impl Stmt {
                       })
     }
 
+    pub fn create_var_tuple(id: NodeId,
+                            pos: Position,
+                            vars: Vec<TupleVar>,
+                            reassignable: bool,
+                            data_type: Option<Type>,
+                            expr: Box<Expr>)
+                            -> Stmt {
+        Stmt::StmtVarTuple(StmtVarTupleType {
+                               id: id,
+                               pos: pos,
+                               vars: vars,
+                               reassignable: reassignable,
+                               data_type: data_type,
+                               expr: expr,
+                           })
+    }
+
     pub fn create_for(id: NodeId, pos: Position, name: Name, expr: Box<Expr>, block: Box<Stmt>) -> Stmt {
         Stmt::StmtFor(StmtForType {
                             id: id,
@@ -716,6 +879,10 @@ impl Stmt {
         Stmt::StmtContinue(StmtContinueType { id: id, pos: pos })
     }
 
+    pub fn create_error(id: NodeId, pos: Position) -> Stmt {
+        Stmt::StmtError(StmtErrorType { id: id, pos: pos })
+    }
+
     pub fn create_return(id: NodeId, pos: Position, expr: Option<Box<Expr>>) -> Stmt {
         Stmt::StmtReturn(StmtReturnType {
                              id: id,
@@ -755,17 +922,10 @@ impl Stmt {
                      })
     }
 
-    pub fn create_spawn(id: NodeId, pos: Position, expr: Box<Expr>) -> Stmt {
-        Stmt::StmtSpawn(StmtSpawnType {
-                            id: id,
-                            pos: pos,
-                            expr: expr,
-                        })
-    }
-
     pub fn id(&self) -> NodeId {
         match *self {
             Stmt::StmtVar(ref stmt) => stmt.id,
+            Stmt::StmtVarTuple(ref stmt) => stmt.id,
             Stmt::StmtWhile(ref stmt) => stmt.id,
             Stmt::StmtFor(ref stmt) => stmt.id,
             Stmt::StmtLoop(ref stmt) => stmt.id,
@@ -778,13 +938,14 @@ impl Stmt {
             Stmt::StmtThrow(ref stmt) => stmt.id,
             Stmt::StmtDefer(ref stmt) => stmt.id,
             Stmt::StmtDo(ref stmt) => stmt.id,
-            Stmt::StmtSpawn(ref stmt) => stmt.id,
+            Stmt::StmtError(ref stmt) => stmt.id,
         }
     }
 
     pub fn pos(&self) -> Position {
         match *self {
             Stmt::StmtVar(ref stmt) => stmt.pos,
+            Stmt::StmtVarTuple(ref stmt) => stmt.pos,
             Stmt::StmtWhile(ref stmt) => stmt.pos,
             Stmt::StmtFor(ref stmt) => stmt.pos,
             Stmt::StmtLoop(ref stmt) => stmt.pos,
@@ -797,7 +958,7 @@ impl Stmt {
             Stmt::StmtThrow(ref stmt) => stmt.pos,
             Stmt::StmtDefer(ref stmt) => stmt.pos,
             Stmt::StmtDo(ref stmt) => stmt.pos,
-            Stmt::StmtSpawn(ref stmt) => stmt.pos,
+            Stmt::StmtError(ref stmt) => stmt.pos,
         }
     }
 
@@ -857,6 +1018,20 @@ impl Stmt {
         }
     }
 
+    pub fn to_var_tuple(&self) -> Option<&StmtVarTupleType> {
+        match *self {
+            Stmt::StmtVarTuple(ref val) => Some(val),
+            _ => None,
+        }
+    }
+
+    pub fn is_var_tuple(&self) -> bool {
+        match *self {
+            Stmt::StmtVarTuple(_) => true,
+            _ => false,
+        }
+    }
+
     pub fn to_while(&self) -> Option<&StmtWhileType> {
         match *self {
             Stmt::StmtWhile(ref val) => Some(val),
@@ -969,30 +1144,23 @@ impl Stmt {
         }
     }
 
-    pub fn to_continue(&self) -> Option<&StmtContinueType> {
-        match *self {
-            Stmt::StmtContinue(ref val) => Some(val),
-            _ => None,
-        }
-    }
-
-    pub fn is_continue(&self) -> bool {
+    pub fn is_error(&self) -> bool {
         match *self {
-            Stmt::StmtContinue(_) => true,
+            Stmt::StmtError(_) => true,
             _ => false,
         }
     }
 
-    pub fn to_spawn(&self) -> Option<&StmtSpawnType> {
+    pub fn to_continue(&self) -> Option<&StmtContinueType> {
         match *self {
-            Stmt::StmtSpawn(ref val) => Some(val),
+            Stmt::StmtContinue(ref val) => Some(val),
             _ => None,
         }
     }
 
-    pub fn is_spawn(&self) -> bool {
+    pub fn is_continue(&self) -> bool {
         match *self {
-            Stmt::StmtSpawn(_) => true,
+            Stmt::StmtContinue(_) => true,
             _ => false,
         }
     }
@@ -1009,6 +1177,25 @@ pub struct StmtVarType {
     pub expr: Option<Box<Expr>>,
 }
 
+#[derive(Clone, Debug)]
+pub struct StmtVarTupleType {
+    pub id: NodeId,
+    pub pos: Position,
+    pub vars: Vec<TupleVar>,
+    pub reassignable: bool,
+
+    pub data_type: Option<Type>,
+    pub expr: Box<Expr>,
+}
+
+#[derive(Clone, Debug)]
+pub struct TupleVar {
+    pub id: NodeId,
+    pub pos: Position,
+
+    // None for `_`
+    pub name: Option<Name>,
+}
 
 #[derive(Clone, Debug)]
 pub struct StmtForType {
@@ -1078,6 +1265,13 @@ pub struct StmtContinueType {
     pub pos: Position,
 }
 
+// placeholder for a statement that could not be parsed
+#[derive(Clone, Debug)]
+pub struct StmtErrorType {
+    pub id: NodeId,
+    pub pos: Position,
+}
+
 #[derive(Clone, Debug)]
 pub struct StmtThrowType {
     pub id: NodeId,
@@ -1101,13 +1295,6 @@ pub struct StmtDoType {
     pub finally_block: Option<FinallyBlock>,
 }
 
-#[derive(Clone, Debug)]
-pub struct StmtSpawnType {
-    pub id: NodeId,
-    pub pos: Position,
-    pub expr: Box<Expr>,
-}
-
 #[derive(Clone, Debug)]
 pub struct CatchBlock {
     pub id: NodeId,
@@ -1243,9 +1430,11 @@ pub enum Expr {
     ExprLitInt(ExprLitIntType),
     ExprLitFloat(ExprLitFloatType),
     ExprLitStr(ExprLitStrType),
+    ExprTemplate(ExprTemplateType),
     ExprLitBool(ExprLitBoolType),
     ExprLitStruct(ExprLitStructType),
     ExprIdent(ExprIdentType),
+    ExprPath(ExprPathType),
     ExprCall(ExprCallType),
     ExprDelegation(ExprDelegationType),
     ExprAssign(ExprAssignType),
@@ -1257,6 +1446,10 @@ pub enum Expr {
     ExprConv(ExprConvType),
     ExprTry(ExprTryType),
     ExprLambda(ExprLambdaType),
+    ExprSpawn(ExprSpawnType),
+    ExprMatch(ExprMatchType),
+    ExprTuple(ExprTupleType),
+    ExprTupleElement(ExprTupleElementType),
 }
 
 impl Expr {
@@ -1357,6 +1550,14 @@ impl Expr {
                          })
     }
 
+    pub fn create_template(id: NodeId, pos: Position, parts: Vec<Box<Expr>>) -> Expr {
+        Expr::ExprTemplate(ExprTemplateType {
+                               id: id,
+                               pos: pos,
+                               parts: parts,
+                           })
+    }
+
     pub fn create_lit_bool(id: NodeId, pos: Position, value: bool) -> Expr {
         Expr::ExprLitBool(ExprLitBoolType {
                               id: id,
@@ -1374,6 +1575,44 @@ impl Expr {
                             })
     }
 
+    pub fn create_path(id: NodeId, pos: Position, path: Path) -> Expr {
+        Expr::ExprPath(ExprPathType {
+                           id: id,
+                           pos: pos,
+                           path: path,
+                       })
+    }
+
+    pub fn create_match(id: NodeId,
+                        pos: Position,
+                        expr: Box<Expr>,
+                        arms: Vec<MatchArm>)
+                        -> Expr {
+        Expr::ExprMatch(ExprMatchType {
+                            id: id,
+                            pos: pos,
+                            expr: expr,
+                            arms: arms,
+                        })
+    }
+
+    pub fn create_tuple(id: NodeId, pos: Position, values: Vec<Box<Expr>>) -> Expr {
+        Expr::ExprTuple(ExprTupleType {
+                            id: id,
+                            pos: pos,
+                            values: values,
+                        })
+    }
+
+    pub fn create_tuple_element(id: NodeId, pos: Position, object: Box<Expr>, idx: u32) -> Expr {
+        Expr::ExprTupleElement(ExprTupleElementType {
+                                   id: id,
+                                   pos: pos,
+                                   object: object,
+                                   idx: idx,
+                               })
+    }
+
     pub fn create_this(id: NodeId, pos: Position) -> Expr {
         Expr::ExprSelf(ExprSelfType { id: id, pos: pos })
     }
@@ -1412,10 +1651,27 @@ impl Expr {
                            path: path,
                            args: args,
                            object: object,
+                           callee: None,
                            type_params: type_params,
                        })
     }
 
+    pub fn create_call_expr(id: NodeId,
+                            pos: Position,
+                            callee: Box<Expr>,
+                            args: Vec<Box<Expr>>)
+                            -> Expr {
+        Expr::ExprCall(ExprCallType {
+                           id: id,
+                           pos: pos,
+                           path: Path { path: Vec::new() },
+                           args: args,
+                           object: None,
+                           callee: Some(callee),
+                           type_params: None,
+                       })
+    }
+
     pub fn create_delegation(id: NodeId,
                              pos: Position,
                              ty: DelegationType,
@@ -1447,21 +1703,22 @@ impl Expr {
                         })
     }
 
-    pub fn create_lambda(id: NodeId,
-                         pos: Position,
-                         params: Vec<Param>,
-                         ret: Option<Box<Type>>,
-                         block: Box<Stmt>)
-                         -> Expr {
+    pub fn create_lambda(id: NodeId, pos: Position, fct: Box<Function>) -> Expr {
         Expr::ExprLambda(ExprLambdaType {
                              id: id,
                              pos: pos,
-                             params: params,
-                             ret: ret,
-                             block: block,
+                             fct: fct,
                          })
     }
 
+    pub fn create_spawn(id: NodeId, pos: Position, fct: Box<Function>) -> Expr {
+        Expr::ExprSpawn(ExprSpawnType {
+                            id: id,
+                            pos: pos,
+                            fct: fct,
+                        })
+    }
+
     pub fn to_un(&self) -> Option<&ExprUnType> {
         match *self {
             Expr::ExprUn(ref val) => Some(val),
@@ -1588,6 +1845,13 @@ impl Expr {
         }
     }
 
+    pub fn is_lit_str(&self) -> bool {
+        match *self {
+            Expr::ExprLitStr(_) => true,
+            _ => false,
+        }
+    }
+
     pub fn to_lit_str(&self) -> Option<&ExprLitStrType> {
         match *self {
             Expr::ExprLitStr(ref val) => Some(val),
@@ -1595,6 +1859,20 @@ impl Expr {
         }
     }
 
+    pub fn to_template(&self) -> Option<&ExprTemplateType> {
+        match *self {
+            Expr::ExprTemplate(ref val) => Some(val),
+            _ => None,
+        }
+    }
+
+    pub fn is_template(&self) -> bool {
+        match *self {
+            Expr::ExprTemplate(_) => true,
+            _ => false,
+        }
+    }
+
     pub fn to_lit_bool(&self) -> Option<&ExprLitBoolType> {
         match *self {
             Expr::ExprLitBool(ref val) => Some(val),
@@ -1728,6 +2006,76 @@ impl Expr {
         }
     }
 
+    pub fn to_spawn(&self) -> Option<&ExprSpawnType> {
+        match *self {
+            Expr::ExprSpawn(ref val) => Some(val),
+            _ => None,
+        }
+    }
+
+    pub fn is_spawn(&self) -> bool {
+        match *self {
+            Expr::ExprSpawn(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn to_path(&self) -> Option<&ExprPathType> {
+        match *self {
+            Expr::ExprPath(ref val) => Some(val),
+            _ => None,
+        }
+    }
+
+    pub fn is_path(&self) -> bool {
+        match *self {
+            Expr::ExprPath(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn to_match(&self) -> Option<&ExprMatchType> {
+        match *self {
+            Expr::ExprMatch(ref val) => Some(val),
+            _ => None,
+        }
+    }
+
+    pub fn is_match(&self) -> bool {
+        match *self {
+            Expr::ExprMatch(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn to_tuple(&self) -> Option<&ExprTupleType> {
+        match *self {
+            Expr::ExprTuple(ref val) => Some(val),
+            _ => None,
+        }
+    }
+
+    pub fn is_tuple(&self) -> bool {
+        match *self {
+            Expr::ExprTuple(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn to_tuple_element(&self) -> Option<&ExprTupleElementType> {
+        match *self {
+            Expr::ExprTupleElement(ref val) => Some(val),
+            _ => None,
+        }
+    }
+
+    pub fn is_tuple_element(&self) -> bool {
+        match *self {
+            Expr::ExprTupleElement(_) => true,
+            _ => false,
+        }
+    }
+
     pub fn pos(&self) -> Position {
         match *self {
             Expr::ExprUn(ref val) => val.pos,
@@ -1736,6 +2084,7 @@ impl Expr {
             Expr::ExprLitInt(ref val) => val.pos,
             Expr::ExprLitFloat(ref val) => val.pos,
             Expr::ExprLitStr(ref val) => val.pos,
+            Expr::ExprTemplate(ref val) => val.pos,
             Expr::ExprLitBool(ref val) => val.pos,
             Expr::ExprLitStruct(ref val) => val.pos,
             Expr::ExprIdent(ref val) => val.pos,
@@ -1750,6 +2099,11 @@ impl Expr {
             Expr::ExprConv(ref val) => val.pos,
             Expr::ExprTry(ref val) => val.pos,
             Expr::ExprLambda(ref val) => val.pos,
+            Expr::ExprSpawn(ref val) => val.pos,
+            Expr::ExprPath(ref val) => val.pos,
+            Expr::ExprMatch(ref val) => val.pos,
+            Expr::ExprTuple(ref val) => val.pos,
+            Expr::ExprTupleElement(ref val) => val.pos,
         }
     }
 
@@ -1761,6 +2115,7 @@ impl Expr {
             Expr::ExprLitInt(ref val) => val.id,
             Expr::ExprLitFloat(ref val) => val.id,
             Expr::ExprLitStr(ref val) => val.id,
+            Expr::ExprTemplate(ref val) => val.id,
             Expr::ExprLitBool(ref val) => val.id,
             Expr::ExprLitStruct(ref val) => val.id,
             Expr::ExprIdent(ref val) => val.id,
@@ -1775,6 +2130,11 @@ impl Expr {
             Expr::ExprConv(ref val) => val.id,
             Expr::ExprTry(ref val) => val.id,
             Expr::ExprLambda(ref val) => val.id,
+            Expr::ExprSpawn(ref val) => val.id,
+            Expr::ExprPath(ref val) => val.id,
+            Expr::ExprMatch(ref val) => val.id,
+            Expr::ExprTuple(ref val) => val.id,
+            Expr::ExprTupleElement(ref val) => val.id,
         }
     }
 }
@@ -1943,6 +2303,14 @@ pub struct ExprLitStrType {
     pub value: String,
 }
 
+#[derive(Clone, Debug)]
+pub struct ExprTemplateType {
+    pub id: NodeId,
+    pub pos: Position,
+
+    pub parts: Vec<Box<Expr>>,
+}
+
 #[derive(Clone, Debug)]
 pub struct ExprLitBoolType {
     pub id: NodeId,
@@ -1983,9 +2351,62 @@ pub struct ExprLambdaType {
     pub id: NodeId,
     pub pos: Position,
 
-    pub params: Vec<Param>,
-    pub ret: Option<Box<Type>>,
-    pub block: Box<Stmt>,
+    pub fct: Box<Function>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ExprSpawnType {
+    pub id: NodeId,
+    pub pos: Position,
+
+    pub fct: Box<Function>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ExprPathType {
+    pub id: NodeId,
+    pub pos: Position,
+    pub path: Path,
+}
+
+#[derive(Clone, Debug)]
+pub struct ExprMatchType {
+    pub id: NodeId,
+    pub pos: Position,
+    pub expr: Box<Expr>,
+    pub arms: Vec<MatchArm>,
+}
+
+#[derive(Clone, Debug)]
+pub struct MatchArm {
+    pub id: NodeId,
+    pub pos: Position,
+    pub pattern: MatchPattern,
+    pub value: Box<Expr>,
+}
+
+#[derive(Clone, Debug)]
+pub enum MatchPattern {
+    Underscore,
+    Variant(Path, Vec<MatchPatternParam>),
+}
+
+impl MatchPattern {
+    pub fn is_underscore(&self) -> bool {
+        match *self {
+            MatchPattern::Underscore => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MatchPatternParam {
+    pub id: NodeId,
+    pub pos: Position,
+
+    // None for `_`
+    pub name: Option<Name>,
 }
 
 #[derive(Clone, Debug)]
@@ -2026,6 +2447,10 @@ pub struct ExprCallType {
     pub object: Option<Box<Expr>>,
     pub args: Vec<Box<Expr>>,
     pub type_params: Option<Vec<Type>>,
+
+    // calls like `a[0](x)` call the closure the expression evaluates to,
+    // the path is empty in this case
+    pub callee: Option<Box<Expr>>,
 }
 
 #[derive(Clone, Debug)]
@@ -2037,6 +2462,23 @@ pub struct ExprAssignType {
     pub rhs: Box<Expr>,
 }
 
+#[derive(Clone, Debug)]
+pub struct ExprTupleType {
+    pub id: NodeId,
+    pub pos: Position,
+
+    pub values: Vec<Box<Expr>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ExprTupleElementType {
+    pub id: NodeId,
+    pub pos: Position,
+
+    pub object: Box<Expr>,
+    pub idx: u32,
+}
+
 #[derive(Clone, Debug)]
 pub struct ExprFieldType {
     pub id: NodeId,
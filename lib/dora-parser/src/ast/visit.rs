@@ -33,10 +33,20 @@ pub trait Visitor<'v>: Sized {
         walk_struct(self, s);
     }
 
+    fn visit_enum(&mut self, e: &'v Enum) {
+        walk_enum(self, e);
+    }
+
+    fn visit_alias(&mut self, a: &'v Alias) {
+        walk_alias(self, a);
+    }
+
     fn visit_const(&mut self, c: &'v Const) {
         walk_const(self, c);
     }
 
+    fn visit_import(&mut self, _: &'v Import) {}
+
     fn visit_struct_field(&mut self, f: &'v StructField) {
         walk_struct_field(self, f);
     }
@@ -90,10 +100,13 @@ pub fn walk_file<'v, V: Visitor<'v>>(v: &mut V, f: &'v File) {
             ElemFunction(ref f) => v.visit_fct(f),
             ElemClass(ref c) => v.visit_class(c),
             ElemStruct(ref s) => v.visit_struct(s),
+            ElemEnum(ref e) => v.visit_enum(e),
+            ElemAlias(ref a) => v.visit_alias(a),
             ElemTrait(ref t) => v.visit_trait(t),
             ElemImpl(ref i) => v.visit_impl(i),
             ElemGlobal(ref g) => v.visit_global(g),
             ElemConst(ref c) => v.visit_const(c),
+            ElemImport(ref i) => v.visit_import(i),
         }
     }
 }
@@ -107,6 +120,10 @@ pub fn walk_global<'v, V: Visitor<'v>>(v: &mut V, g: &'v Global) {
 }
 
 pub fn walk_trait<'v, V: Visitor<'v>>(v: &mut V, t: &'v Trait) {
+    for a in &t.aliases {
+        v.visit_alias(a);
+    }
+
     for m in &t.methods {
         v.visit_method(m);
     }
@@ -143,6 +160,18 @@ pub fn walk_struct<'v, V: Visitor<'v>>(v: &mut V, s: &'v Struct) {
     }
 }
 
+pub fn walk_enum<'v, V: Visitor<'v>>(v: &mut V, e: &'v Enum) {
+    for variant in &e.variants {
+        for ty in &variant.types {
+            v.visit_type(ty);
+        }
+    }
+}
+
+pub fn walk_alias<'v, V: Visitor<'v>>(v: &mut V, a: &'v Alias) {
+    v.visit_type(&a.ty);
+}
+
 pub fn walk_struct_field<'v, V: Visitor<'v>>(v: &mut V, f: &'v StructField) {
     v.visit_type(&f.data_type);
 }
@@ -187,6 +216,10 @@ pub fn walk_type<'v, V: Visitor<'v>>(v: &mut V, t: &'v Type) {
             v.visit_type(&fct.ret);
         }
 
+        TypeNullable(ref nullable) => {
+            v.visit_type(&nullable.subtype);
+        }
+
         TypePtr(ref ptr) => {
             v.visit_type(&ptr.subtype);
         }
@@ -209,6 +242,14 @@ pub fn walk_stmt<'v, V: Visitor<'v>>(v: &mut V, s: &'v Stmt) {
             }
         }
 
+        StmtVarTuple(ref value) => {
+            if let Some(ref ty) = value.data_type {
+                v.visit_type(ty);
+            }
+
+            v.visit_expr_top(&value.expr);
+        }
+
         StmtFor(ref value) => {
             v.visit_expr_top(&value.expr);
             v.visit_stmt(&value.block);   
@@ -269,12 +310,9 @@ pub fn walk_stmt<'v, V: Visitor<'v>>(v: &mut V, s: &'v Stmt) {
             }
         }
 
-        StmtSpawn(ref value) => {
-            v.visit_expr_top(&value.expr);
-        }
-
         StmtBreak(_) => {}
         StmtContinue(_) => {}
+        StmtError(_) => {}
     }
 }
 
@@ -304,6 +342,10 @@ pub fn walk_expr<'v, V: Visitor<'v>>(v: &mut V, e: &'v Expr) {
                 v.visit_expr(object);
             }
 
+            if let Some(ref callee) = call.callee {
+                v.visit_expr(callee);
+            }
+
             if let Some(ref type_params) = call.type_params {
                 for ty in type_params {
                     v.visit_type(ty);
@@ -341,15 +383,43 @@ pub fn walk_expr<'v, V: Visitor<'v>>(v: &mut V, e: &'v Expr) {
         }
 
         ExprLambda(ref value) => {
-            for param in &value.params {
+            for param in &value.fct.params {
                 v.visit_type(&param.data_type);
             }
 
-            if let Some(ref ret) = value.ret {
+            if let Some(ref ret) = value.fct.return_type {
                 v.visit_type(ret);
             }
 
-            v.visit_stmt(&value.block);
+            v.visit_stmt(value.fct.block());
+        }
+
+        ExprSpawn(ref value) => {
+            v.visit_stmt(value.fct.block());
+        }
+
+        ExprMatch(ref value) => {
+            v.visit_expr(&value.expr);
+
+            for arm in &value.arms {
+                v.visit_expr(&arm.value);
+            }
+        }
+
+        ExprTuple(ref value) => {
+            for value in &value.values {
+                v.visit_expr(value);
+            }
+        }
+
+        ExprTupleElement(ref value) => {
+            v.visit_expr(&value.object);
+        }
+
+        ExprTemplate(ref value) => {
+            for part in &value.parts {
+                v.visit_expr(part);
+            }
         }
 
         ExprSuper(_) => {}
@@ -360,6 +430,7 @@ pub fn walk_expr<'v, V: Visitor<'v>>(v: &mut V, e: &'v Expr) {
         ExprLitStr(_) => {}
         ExprLitBool(_) => {}
         ExprIdent(_) => {}
+        ExprPath(_) => {}
         ExprNil(_) => {}
     }
 }
@@ -5,21 +5,26 @@ use lexer::position::Position;
 
 use parser::NodeIdGenerator;
 
+// all nodes created by a builder share the position `pos`
 pub struct Builder<'a> {
     id_generator: &'a NodeIdGenerator,
+    pos: Position,
 }
 
 impl<'a> Builder<'a> {
-    pub fn new(id_generator: &'a NodeIdGenerator) -> Builder<'a> {
-        Builder { id_generator: id_generator }
+    pub fn new(id_generator: &'a NodeIdGenerator, pos: Position) -> Builder<'a> {
+        Builder {
+            id_generator: id_generator,
+            pos: pos,
+        }
     }
 
     pub fn build_block(&self) -> BuilderBlock<'a> {
-        BuilderBlock::new(self.id_generator)
+        BuilderBlock::new(self.id_generator, self.pos)
     }
 
     pub fn build_fct(&self, name: Name) -> BuilderFct<'a> {
-        BuilderFct::new(self.id_generator, name)
+        BuilderFct::new(self.id_generator, self.pos, name)
     }
 
     pub fn build_this(&self) -> Box<Expr> {
@@ -27,7 +32,7 @@ impl<'a> Builder<'a> {
 
         Box::new(Expr::ExprSelf(ExprSelfType {
                                     id: id,
-                                    pos: Position::new(1, 1),
+                                    pos: self.pos,
                                 }))
     }
 
@@ -36,7 +41,7 @@ impl<'a> Builder<'a> {
 
         Box::new(Expr::ExprAssign(ExprAssignType {
                                       id: id,
-                                      pos: Position::new(1, 1),
+                                      pos: self.pos,
                                       lhs: lhs,
                                       rhs: rhs,
                                   }))
@@ -47,7 +52,7 @@ impl<'a> Builder<'a> {
 
         Box::new(Expr::ExprField(ExprFieldType {
                                      id: id,
-                                     pos: Position::new(1, 1),
+                                     pos: self.pos,
                                      object: object,
                                      name: name,
                                  }))
@@ -58,7 +63,7 @@ impl<'a> Builder<'a> {
 
         Box::new(Expr::ExprIdent(ExprIdentType {
                                      id: id,
-                                     pos: Position::new(1, 1),
+                                     pos: self.pos,
                                      name: name,
                                      type_params: None,
                                  }))
@@ -72,14 +77,16 @@ impl<'a> Builder<'a> {
             idx: idx,
             name: name,
             reassignable: false,
-            pos: Position::new(1, 1),
+            pos: self.pos,
             data_type: ty,
+            variadic: false,
         }
     }
 }
 
 pub struct BuilderFct<'a> {
     id_generator: &'a NodeIdGenerator,
+    pos: Position,
     name: Name,
     is_method: bool,
     is_public: bool,
@@ -90,9 +97,10 @@ pub struct BuilderFct<'a> {
 }
 
 impl<'a> BuilderFct<'a> {
-    pub fn new(id_generator: &'a NodeIdGenerator, name: Name) -> BuilderFct<'a> {
+    pub fn new(id_generator: &'a NodeIdGenerator, pos: Position, name: Name) -> BuilderFct<'a> {
         BuilderFct {
             id_generator: id_generator,
+            pos: pos,
             name: name,
             is_method: false,
             is_public: false,
@@ -108,7 +116,7 @@ impl<'a> BuilderFct<'a> {
         self
     }
 
-    pub fn add_param(&mut self, name: Name, ty: Type) -> &mut BuilderFct<'a> {
+    pub fn add_param(&mut self, name: Name, ty: Type, variadic: bool) -> &mut BuilderFct<'a> {
         let id = self.id_generator.next();
 
         let param = Param {
@@ -116,8 +124,9 @@ impl<'a> BuilderFct<'a> {
             idx: self.params.len() as u32,
             name: name,
             reassignable: false,
-            pos: Position::new(1, 1),
+            pos: self.pos,
             data_type: ty,
+            variadic: variadic,
         };
 
         self.params.push(param);
@@ -147,7 +156,7 @@ impl<'a> BuilderFct<'a> {
     pub fn build(self) -> Function {
         Function {
             id: self.id_generator.next(),
-            pos: Position::new(1, 1),
+            pos: self.pos,
             name: self.name,
             method: self.is_method,
             has_open: false,
@@ -169,13 +178,15 @@ impl<'a> BuilderFct<'a> {
 
 pub struct BuilderBlock<'a> {
     id_generator: &'a NodeIdGenerator,
+    pos: Position,
     stmts: Vec<Box<Stmt>>,
 }
 
 impl<'a> BuilderBlock<'a> {
-    pub fn new(id_generator: &'a NodeIdGenerator) -> BuilderBlock<'a> {
+    pub fn new(id_generator: &'a NodeIdGenerator, pos: Position) -> BuilderBlock<'a> {
         BuilderBlock {
             id_generator: id_generator,
+            pos: pos,
             stmts: Vec::new(),
         }
     }
@@ -199,7 +210,7 @@ impl<'a> BuilderBlock<'a> {
 
         let stmt = Box::new(Stmt::StmtExpr(StmtExprType {
                                                id: id,
-                                               pos: Position::new(1, 1),
+                                               pos: self.pos,
                                                expr: expr,
                                            }));
 
@@ -212,7 +223,7 @@ impl<'a> BuilderBlock<'a> {
 
         Box::new(Stmt::StmtBlock(StmtBlockType {
                                      id: id,
-                                     pos: Position::new(1, 1),
+                                     pos: self.pos,
                                      stmts: self.stmts,
                                  }))
     }
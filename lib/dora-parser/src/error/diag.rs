@@ -1,37 +1,206 @@
+use ast::Ast;
 use error::msg::Msg;
 use error::msg::MsgWithPos;
 
 use lexer::position::Position;
 
+const TABWIDTH: usize = 4;
+
+#[derive(Clone)]
 pub struct Diagnostic {
     errors: Vec<MsgWithPos>,
+    warnings: Vec<MsgWithPos>,
 }
 
 impl Diagnostic {
     pub fn new() -> Diagnostic {
-        Diagnostic { errors: Vec::new() }
+        Diagnostic {
+            errors: Vec::new(),
+            warnings: Vec::new(),
+        }
     }
 
     pub fn errors(&self) -> &[MsgWithPos] {
         &self.errors
     }
 
+    pub fn warnings(&self) -> &[MsgWithPos] {
+        &self.warnings
+    }
+
     pub fn report(&mut self, pos: Position, msg: Msg) {
         self.errors.push(MsgWithPos::new(pos, msg));
     }
 
+    pub fn warn(&mut self, pos: Position, msg: Msg) {
+        self.warnings.push(MsgWithPos::new(pos, msg));
+    }
+
     pub fn report_unimplemented(&mut self, pos: Position) {
         self.errors
             .push(MsgWithPos::new(pos, Msg::Unimplemented));
     }
 
+    pub fn append(&mut self, other: Diagnostic) {
+        self.errors.extend(other.errors);
+        self.warnings.extend(other.warnings);
+    }
+
     pub fn has_errors(&self) -> bool {
         !self.errors.is_empty()
     }
 
-    pub fn dump(&self) {
+    pub fn dump(&self, ast: &Ast) {
         for err in &self.errors {
-            println!("{}", &err.message());
+            println!("{}", format_error(ast, err));
+        }
+
+        for warning in &self.warnings {
+            println!("{}", format_warning(ast, warning));
+        }
+    }
+}
+
+pub fn format_error(ast: &Ast, err: &MsgWithPos) -> String {
+    format_msg(ast, "error", err)
+}
+
+pub fn format_warning(ast: &Ast, warning: &MsgWithPos) -> String {
+    format_msg(ast, "warning", warning)
+}
+
+fn format_msg(ast: &Ast, severity: &str, err: &MsgWithPos) -> String {
+    let file = match ast.files.get(err.pos.file.0 as usize) {
+        Some(file) => file,
+        None => return format!("{} at {}: {}", severity, err.pos, err.msg.message()),
+    };
+
+    let mut msg = format!("{} at {}:{}: {}",
+                          severity,
+                          file.path,
+                          err.pos,
+                          err.msg.message());
+
+    if let Some(excerpt) = excerpt(&file.src, err.pos) {
+        msg.push('\n');
+        msg.push_str(&excerpt);
+    }
+
+    msg
+}
+
+// renders the line of `pos` with the span underlined:
+//
+//   |
+// 3 |     let y = x;
+//   |             ^
+fn excerpt(src: &str, pos: Position) -> Option<String> {
+    let text = match src.lines().nth(pos.line as usize - 1) {
+        Some(text) => expand_tabs(text),
+        None => return None,
+    };
+
+    let start = pos.start as usize;
+    let end = pos.end as usize;
+
+    // the span is underlined up to the end of the line at most
+    let width = if start < end && end <= src.len() {
+        src[start..end].lines().next().map(|s| s.chars().count()).unwrap_or(0)
+    } else {
+        0
+    };
+
+    let lineno = pos.line.to_string();
+    let gutter: String = lineno.chars().map(|_| ' ').collect();
+    let indent: String = (1..pos.column).map(|_| ' ').collect();
+    let carets: String = (0..width.max(1)).map(|_| '^').collect();
+
+    Some(format!("{} |\n{} | {}\n{} | {}{}",
+                 gutter,
+                 lineno,
+                 text.trim_end(),
+                 gutter,
+                 indent,
+                 carets))
+}
+
+fn expand_tabs(text: &str) -> String {
+    let mut result = String::new();
+
+    for ch in text.chars() {
+        if ch == '\t' {
+            let fill = TABWIDTH - result.chars().count() % TABWIDTH;
+            result.extend((0..fill).map(|_| ' '));
+        } else {
+            result.push(ch);
         }
     }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ast::File;
+    use lexer::position::FileId;
+
+    fn pos(line: u32, column: u32, start: u32, end: u32) -> Position {
+        Position {
+            file: FileId(0),
+            line: line,
+            column: column,
+            start: start,
+            end: end,
+        }
+    }
+
+    #[test]
+    fn test_excerpt() {
+        let src = "fun main() {\n    let y = foo;\n}\n";
+
+        assert_eq!(Some("  |\n2 |     let y = foo;\n  |             ^^^".to_string()),
+                   excerpt(src, pos(2, 13, 25, 28)));
+        assert_eq!(Some("  |\n1 | fun main() {\n  | ^".to_string()),
+                   excerpt(src, pos(1, 1, 0, 0)));
+        assert_eq!(None, excerpt(src, pos(7, 1, 0, 0)));
+    }
+
+    fn ast(src: &str) -> Ast {
+        let mut ast = Ast::new();
+        ast.files.push(File {
+            path: "foo.dora".into(),
+            src: src.into(),
+            elements: Vec::new(),
+            module: Vec::new(),
+        });
+
+        ast
+    }
+
+    #[test]
+    fn test_format_error() {
+        let ast = ast("fun main() {\n    let y = foo;\n}\n");
+        let err = MsgWithPos::new(pos(2, 13, 25, 28), Msg::UnknownIdentifier("foo".into()));
+
+        assert_eq!("error at foo.dora:2:13: unknown identifier `foo`.\n  |\n\
+                    2 |     let y = foo;\n  |             ^^^",
+                   format_error(&ast, &err));
+    }
+
+    #[test]
+    fn test_format_error_without_file() {
+        let ast = Ast::new();
+        let err = MsgWithPos::new(pos(2, 13, 25, 28), Msg::UnknownIdentifier("foo".into()));
+
+        assert_eq!(err.message(), format_error(&ast, &err));
+    }
+
+    #[test]
+    fn test_excerpt_with_tab() {
+        let src = "\tfoo();";
+
+        assert_eq!(Some("  |\n1 |     foo();\n  |     ^^^".to_string()),
+                   excerpt(src, pos(1, 5, 1, 4)));
+    }
 }
@@ -23,10 +23,16 @@ pub enum Msg {
     ShadowParam(String),
     ShadowClass(String),
     ShadowStruct(String),
+    ShadowEnum(String),
     ShadowTrait(String),
+    ShadowAlias(String),
     ShadowField(String),
     ShadowGlobal(String),
     ShadowConst(String),
+    ShadowImport(String),
+    UnknownModule(String),
+    UnknownImport(String, String),
+    NotPublic(String),
     VarNeedsTypeInfo(String),
     ParamTypesIncompatible(String, Vec<String>, Vec<String>),
     WhileCondType(String),
@@ -47,9 +53,20 @@ pub enum Msg {
     SuperUnavailable,
     SuperNeedsMethodCall,
     ReferenceTypeExpected(String),
+    LambdaExpected(String),
     ThrowNil,
     CatchOrFinallyExpected,
+    UnreachableCatch(String, String),
+    VariadicParameterNeedsToBeLast,
+    UnusedVariable(String),
+    UnusedParameter(String),
+    UnusedFunction(String),
+    UnreachableCode,
+    UnusedResult(String),
+    ShadowedField(String),
     LetMissingInitialization,
+    MissingInitialization(String, String),
+    NoDefaultValue(String),
     LetReassigned,
     UnderivableType(String),
     CycleInHierarchy,
@@ -73,6 +90,7 @@ pub enum Msg {
     ExpectedTrait(String),
     ExpectedType(String),
     ExpectedIdentifier(String),
+    ExpectedTupleIndex(String),
     MisplacedElse,
     IoError,
     ExpectedClassElement(String),
@@ -85,7 +103,6 @@ pub enum Msg {
     NoSuperDelegationWithPrimaryCtor(String),
     NoSuperClass(String),
     RecursiveStructure,
-    TraitMethodWithBody,
     TryNeedsCall,
     TryCallNonThrowing,
     ThrowingCallWithoutTry,
@@ -110,10 +127,31 @@ pub enum Msg {
     NewAbstractClass,
     MissingAbstractOverride(String, String),
     ModifierNotAllowedForStaticMethod(String),
-    GlobalInitializerNotSupported,
     MakeIteratorReturnType(String),
     UnknownStructField(String, String),
     StructFieldNotInitialized(String, String),
+    CapturedVarReassigned(String),
+    StructFieldImmutable(String, String),
+    ShadowEnumVariant(String, String),
+    EnumExpected(String),
+    UnknownEnumVariant(String, String),
+    EnumVariantArgsIncompatible(String, String, Vec<String>, Vec<String>),
+    MatchEnumExpected(String),
+    MatchPatternParams(String, usize, usize),
+    MatchArmTypes(String, String),
+    MatchUnreachableArm,
+    MatchNotExhaustive(Vec<String>),
+    RecursiveAlias(String),
+    TupleExpected(String),
+    TupleIndexOutOfBounds(String, u32),
+    TuplePatternLength(String, usize, usize),
+    TupleNilElement,
+    TypeParamNotInferred(String, String),
+    TypeParamInferredConflict(String, String, String, String),
+    PossibleNilDereference(String),
+    TemplateNotStringable(String),
+    ConstCycle(String),
+    ConstDivisionByZero,
 }
 
 impl Msg {
@@ -156,9 +194,19 @@ impl Msg {
             ShadowClass(ref name) => format!("can not shadow class `{}`.", name),
             ShadowStruct(ref name) => format!("can not shadow struct `{}`.", name),
             ShadowTrait(ref name) => format!("can not shadow trait `{}`.", name),
+            ShadowEnum(ref name) => format!("can not shadow enum `{}`.", name),
+            ShadowAlias(ref name) => format!("can not shadow type alias `{}`.", name),
             ShadowField(ref name) => format!("field with name `{}` already exists.", name),
             ShadowGlobal(ref name) => format!("can not shadow global variable `{}`.", name),
             ShadowConst(ref name) => format!("can not shadow const `{}`", name),
+            ShadowImport(ref name) => {
+                format!("import of `{}` conflicts with existing definition.", name)
+            }
+            UnknownModule(ref name) => format!("module `{}` does not exist.", name),
+            UnknownImport(ref name, ref module) => {
+                format!("`{}` not found in module `{}`.", name, module)
+            }
+            NotPublic(ref name) => format!("`{}` is not public.", name),
             VarNeedsTypeInfo(ref name) => {
                 format!("variable `{}` needs either type declaration or expression.",
                         name)
@@ -223,9 +271,26 @@ impl Msg {
             }
             SuperNeedsMethodCall => "`super` only allowed in method calls".into(),
             ReferenceTypeExpected(ref name) => format!("`{}` is not a reference type.", name),
+            LambdaExpected(ref name) => format!("`{}` is not a lambda.", name),
             ThrowNil => "throwing `nil` is not allowed.".into(),
             CatchOrFinallyExpected => "`try` without `catch` or `finally`.".into(),
+            UnreachableCatch(ref name, ref prev) => format!(
+                "unreachable `catch` for `{}`, already caught by `catch` for `{}`.",
+                name, prev
+            ),
+            VariadicParameterNeedsToBeLast => "variadic parameter needs to be last.".into(),
+            UnusedVariable(ref name) => format!("variable `{}` is never used.", name),
+            UnusedParameter(ref name) => format!("parameter `{}` is never used.", name),
+            UnusedFunction(ref name) => format!("function `{}` is never called.", name),
+            UnreachableCode => "unreachable code.".into(),
+            UnusedResult(ref ty) => format!("unused result of type `{}`.", ty),
+            ShadowedField(ref name) => format!("variable `{}` shadows field `{}`.", name, name),
             LetMissingInitialization => "`let` binding is missing initialization.".into(),
+            MissingInitialization(ref name, ref ty) => format!(
+                "`{}` of type `{}` needs to be initialized, the type has no default value.",
+                name, ty
+            ),
+            NoDefaultValue(ref ty) => format!("type `{}` has no default value.", ty),
             LetReassigned => "`let` binding cannot be reassigned.".into(),
             UnderivableType(ref name) => format!("type `{}` cannot be used as super class.", name),
             CycleInHierarchy => "cycle in type hierarchy detected.".into(),
@@ -257,6 +322,7 @@ impl Msg {
             ExpectedTrait(ref trt) => format!("expected trait name but got {}.", trt),
             ExpectedType(ref got) => format!("type expected but got {}.", got),
             ExpectedIdentifier(ref tok) => format!("identifier expected but got {}.", tok),
+            ExpectedTupleIndex(ref tok) => format!("tuple index expected but got {}.", tok),
             MisplacedModifier(ref modifier) => format!("misplaced modifier `{}`.", modifier),
             ExpectedTopLevelElement(ref token) => {
                 format!("expected function or class but got {}.", token)
@@ -281,7 +347,6 @@ impl Msg {
             }
             NoSuperClass(ref name) => format!("class `{}` does not have super class.", name),
             RecursiveStructure => "recursive structure is not allowed.".into(),
-            TraitMethodWithBody => "trait method is not allowed to have definition".into(),
             TryNeedsCall => "`try` expects function or method call.".into(),
             TryCallNonThrowing => "given function or method call for `try` does not throw.".into(),
             ThrowingCallWithoutTry => {
@@ -350,9 +415,6 @@ impl Msg {
             ModifierNotAllowedForStaticMethod(ref modifier) => {
                 format!("modifier `{}` not allowed for static method.", modifier)
             }
-            GlobalInitializerNotSupported => {
-                "global variables do no support initial assignment for now.".into()
-            }
             MakeIteratorReturnType(ref ty) => {
                 format!("makeIterator() returns `{}` which does not implement Iterator.",
                         ty)
@@ -363,11 +425,97 @@ impl Msg {
             StructFieldNotInitialized(ref struc, ref field) => {
                 format!("field `{}` in struct `{}` not initialized.", field, struc)
             }
+            CapturedVarReassigned(ref name) => {
+                format!("captured variable `{}` cannot be reassigned in lambda.", name)
+            }
+            StructFieldImmutable(ref struc, ref field) => {
+                format!("field `{}` of struct `{}` cannot be assigned.", field, struc)
+            }
+            ShadowEnumVariant(ref xenum, ref name) => {
+                format!("enum `{}` already has variant `{}`.", xenum, name)
+            }
+            EnumExpected(ref name) => format!("`{}` is not an enum.", name),
+            UnknownEnumVariant(ref xenum, ref name) => {
+                format!("enum `{}` does not have variant `{}`.", xenum, name)
+            }
+            EnumVariantArgsIncompatible(ref xenum, ref name, ref def, ref expr) => {
+                let def = def.join(", ");
+                let expr = expr.join(", ");
+
+                format!("variant `{}::{}({})` cannot be created as `{}::{}({})`.",
+                        xenum,
+                        name,
+                        def,
+                        xenum,
+                        name,
+                        expr)
+            }
+            MatchEnumExpected(ref ty) => {
+                format!("match expects value of enum type but got `{}`.", ty)
+            }
+            MatchPatternParams(ref name, expected, given) => {
+                format!("variant `{}` has {} field(s) but pattern binds {}.",
+                        name,
+                        expected,
+                        given)
+            }
+            MatchArmTypes(ref expected, ref found) => {
+                format!("match arm has type `{}` but previous arms have type `{}`.",
+                        found,
+                        expected)
+            }
+            MatchUnreachableArm => "unreachable match arm.".into(),
+            MatchNotExhaustive(ref variants) => {
+                format!("match is not exhaustive, missing variant(s): {}.",
+                        variants.join(", "))
+            }
+            RecursiveAlias(ref name) => {
+                format!("type alias `{}` is defined in terms of itself.", name)
+            }
+            TupleExpected(ref ty) => format!("tuple expected but got `{}`.", ty),
+            TupleIndexOutOfBounds(ref ty, idx) => {
+                format!("tuple `{}` has no element {}.", ty, idx)
+            }
+            TuplePatternLength(ref ty, expected, given) => {
+                format!("tuple `{}` has {} element(s) but pattern binds {}.",
+                        ty,
+                        expected,
+                        given)
+            }
+            TupleNilElement => "`nil` is not allowed as tuple element.".into(),
+            TypeParamNotInferred(ref name, ref tp) => {
+                format!("cannot infer type parameter `{}` of `{}`, specify it explicitly.",
+                        tp,
+                        name)
+            }
+            TypeParamInferredConflict(ref name, ref tp, ref first, ref second) => {
+                format!("type parameter `{}` of `{}` inferred as both `{}` and `{}`.",
+                        tp,
+                        name,
+                        first,
+                        second)
+            }
+            PossibleNilDereference(ref ty) => {
+                format!("value of nullable type `{}` may be nil, check it against `nil` first.",
+                        ty)
+            }
+            TemplateNotStringable(ref ty) => {
+                format!("type `{}` in string template does not implement Stringable.",
+                        ty)
+            }
+            ConstCycle(ref name) => format!("const `{}` depends on itself.", name),
+            ConstDivisionByZero => "division by zero in constant expression.".into(),
         }
     }
+
+    // stable identifier of the message for tools, the name of the variant
+    pub fn code(&self) -> String {
+        let name = format!("{:?}", self);
+        name.chars().take_while(|ch| ch.is_alphanumeric()).collect()
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, PartialEq, Eq, Debug)]
 pub struct MsgWithPos {
     pub msg: Msg,
     pub pos: Position,
@@ -388,3 +536,15 @@ impl fmt::Display for MsgWithPos {
         write!(f, "error at {}: {}", self.pos, self.msg.message())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code() {
+        assert_eq!("Unimplemented", Unimplemented.code());
+        assert_eq!("UnknownClass", UnknownClass("Foo".into()).code());
+        assert_eq!("UnreachableCode", UnreachableCode.code());
+    }
+}
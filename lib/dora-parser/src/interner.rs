@@ -43,6 +43,7 @@ impl Deref for RcStr {
     }
 }
 
+#[derive(Clone)]
 pub struct Interner {
     map: RefCell<HashMap<RcStr, Name>>,
     vec: RefCell<Vec<RcStr>>,
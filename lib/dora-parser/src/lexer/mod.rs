@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use lexer::position::Position;
+use lexer::position::{FileId, Position};
 use lexer::reader::Reader;
 use lexer::token::{FloatSuffix, IntSuffix, IntBase, Token, TokenKind};
 use error::msg::{Msg, MsgWithPos};
@@ -13,6 +13,9 @@ pub mod position;
 pub struct Lexer {
     reader: Reader,
     keywords: HashMap<&'static str, TokenKind>,
+
+    // number of unclosed `{` for every string template expression
+    open_braces: Vec<usize>,
 }
 
 impl Lexer {
@@ -28,6 +31,7 @@ impl Lexer {
         Lexer {
             reader: reader,
             keywords: keywords,
+            open_braces: Vec::new(),
         }
     }
 
@@ -35,7 +39,22 @@ impl Lexer {
         self.reader.filename()
     }
 
+    pub fn src(&self) -> &str {
+        self.reader.src()
+    }
+
+    pub fn set_file(&mut self, file: FileId) {
+        self.reader.set_file(file);
+    }
+
     pub fn read_token(&mut self) -> Result<Token, MsgWithPos> {
+        let mut token = self.read_token_kind()?;
+        token.position.end = self.reader.offset();
+
+        Ok(token)
+    }
+
+    fn read_token_kind(&mut self) -> Result<Token, MsgWithPos> {
         loop {
             self.skip_white();
 
@@ -61,6 +80,12 @@ impl Lexer {
             } else if is_quote(ch) {
                 return self.read_string();
 
+            } else if ch == Some('}') && self.open_braces.last() == Some(&0) {
+                // `}` ends the embedded expression, the string continues
+                self.open_braces.pop();
+                self.read_char();
+                return self.read_string_part(pos, true);
+
             } else if is_char_quote(ch) {
                 return self.read_char_literal();
 
@@ -70,6 +95,9 @@ impl Lexer {
             } else {
                 let ch = ch.unwrap();
 
+                // skip the character, so that lexing can continue after the error
+                self.read_char();
+
                 return Err(MsgWithPos::new(pos, Msg::UnknownChar(ch)));
             }
         }
@@ -183,6 +211,7 @@ impl Lexer {
                     't' => Ok('\t'),
                     'r' => Ok('\r'),
                     '\"' => Ok('\"'),
+                    '$' => Ok('$'),
                     '\'' => Ok('\''),
                     '0' => Ok('\0'),
                     _ => {
@@ -202,11 +231,25 @@ impl Lexer {
 
     fn read_string(&mut self) -> Result<Token, MsgWithPos> {
         let pos = self.reader.pos();
-        let mut value = String::new();
-
         self.read_char();
 
+        self.read_string_part(pos, false)
+    }
+
+    // reads string content up to the closing quote or the next `${`
+    fn read_string_part(&mut self, pos: Position, template: bool) -> Result<Token, MsgWithPos> {
+        let mut value = String::new();
+
         while !self.cur().is_none() && !is_quote(self.cur()) {
+            if self.cur() == Some('$') && self.next() == Some('{') {
+                self.read_char();
+                self.read_char();
+                self.open_braces.push(0);
+
+                let ttype = TokenKind::StringTemplate(value);
+                return Ok(Token::new(ttype, pos));
+            }
+
             let ch = self.read_escaped_char(pos, Msg::UnclosedString)?;
             value.push(ch);
         }
@@ -214,7 +257,12 @@ impl Lexer {
         if is_quote(self.cur()) {
             self.read_char();
 
-            let ttype = TokenKind::String(value);
+            let ttype = if template {
+                TokenKind::StringTemplateEnd(value)
+            } else {
+                TokenKind::String(value)
+            };
+
             Ok(Token::new(ttype, pos))
 
         } else {
@@ -249,8 +297,21 @@ impl Lexer {
             ')' => TokenKind::RParen,
             '[' => TokenKind::LBracket,
             ']' => TokenKind::RBracket,
-            '{' => TokenKind::LBrace,
-            '}' => TokenKind::RBrace,
+            '{' => {
+                if let Some(open) = self.open_braces.last_mut() {
+                    *open += 1;
+                }
+
+                TokenKind::LBrace
+            }
+
+            '}' => {
+                if let Some(open) = self.open_braces.last_mut() {
+                    *open -= 1;
+                }
+
+                TokenKind::RBrace
+            }
 
             '|' => {
                 if nch == '|' {
@@ -271,6 +332,7 @@ impl Lexer {
             }
 
             '^' => TokenKind::Caret,
+            '?' => TokenKind::QuestionMark,
             '~' => TokenKind::Tilde,
             ',' => TokenKind::Comma,
             ';' => TokenKind::Semicolon,
@@ -282,7 +344,15 @@ impl Lexer {
                     TokenKind::Colon
                 }
             }
-            '.' => TokenKind::Dot,
+            '.' => {
+                if nch == '.' && nnch == '.' {
+                    self.read_char();
+                    self.read_char();
+                    TokenKind::DotDotDot
+                } else {
+                    TokenKind::Dot
+                }
+            }
             '=' => {
                 if nch == '=' {
                     self.read_char();
@@ -293,6 +363,9 @@ impl Lexer {
                     } else {
                         TokenKind::EqEq
                     }
+                } else if nch == '>' {
+                    self.read_char();
+                    TokenKind::DoubleArrow
                 } else {
                     TokenKind::Eq
                 }
@@ -436,6 +509,22 @@ impl Lexer {
                 IntSuffix::Byte
             }
 
+            Some('S') => {
+                self.read_char();
+                IntSuffix::Short
+            }
+
+            Some('U') => {
+                self.read_char();
+
+                if self.cur() == Some('L') {
+                    self.read_char();
+                    IntSuffix::ULong
+                } else {
+                    IntSuffix::UInt
+                }
+            }
+
             Some('D') if base == IntBase::Dec => {
                 self.read_char();
 
@@ -520,7 +609,7 @@ fn is_char_quote(ch: Option<char>) -> bool {
 }
 
 fn is_operator(ch: Option<char>) -> bool {
-    ch.map(|ch| "^+-*/%&|,=!~;:.()[]{}<>".contains(ch))
+    ch.map(|ch| "^+-*/%&|,=!~;:.()[]{}<>?".contains(ch))
         .unwrap_or(false)
 }
 
@@ -559,6 +648,7 @@ fn keywords_in_map() -> HashMap<&'static str, TokenKind> {
     keywords.insert("false", TokenKind::False);
     keywords.insert("nil", TokenKind::Nil);
     keywords.insert("enum", TokenKind::Enum);
+    keywords.insert("match", TokenKind::Match);
     keywords.insert("type", TokenKind::Type);
     keywords.insert("alias", TokenKind::Alias);
     keywords.insert("struct", TokenKind::Struct);
@@ -582,6 +672,7 @@ fn keywords_in_map() -> HashMap<&'static str, TokenKind> {
     keywords.insert("static", TokenKind::Static);
     keywords.insert("spawn", TokenKind::Spawn);
     keywords.insert("const", TokenKind::Const);
+    keywords.insert("import", TokenKind::Import);
 
     keywords
 }
@@ -656,6 +747,25 @@ mod tests {
                    TokenKind::LitInt("1__1".into(), IntBase::Dec, IntSuffix::Int),
                    1,
                    16);
+
+        let mut reader = Lexer::from_str("7S 1U 2UL 0xFFU");
+        assert_tok(&mut reader,
+                   TokenKind::LitInt("7".into(), IntBase::Dec, IntSuffix::Short),
+                   1,
+                   1);
+        assert_tok(&mut reader,
+                   TokenKind::LitInt("1".into(), IntBase::Dec, IntSuffix::UInt),
+                   1,
+                   4);
+        assert_tok(&mut reader,
+                   TokenKind::LitInt("2".into(), IntBase::Dec, IntSuffix::ULong),
+                   1,
+                   7);
+        assert_tok(&mut reader,
+                   TokenKind::LitInt("FF".into(), IntBase::Hex, IntSuffix::UInt),
+                   1,
+                   11);
+        assert_end(&mut reader, 1, 16);
     }
 
     #[test]
@@ -868,6 +978,22 @@ mod tests {
         assert_tok(&mut reader, TokenKind::String("abc\ndef".into()), 1, 1);
     }
 
+    #[test]
+    fn test_string_template() {
+        let mut reader = Lexer::from_str("\"a${x}b${ {y} }c\"");
+        assert_tok(&mut reader, TokenKind::StringTemplate("a".into()), 1, 1);
+        assert_tok(&mut reader, TokenKind::Identifier("x".into()), 1, 5);
+        assert_tok(&mut reader, TokenKind::StringTemplate("b".into()), 1, 6);
+        assert_tok(&mut reader, TokenKind::LBrace, 1, 11);
+        assert_tok(&mut reader, TokenKind::Identifier("y".into()), 1, 12);
+        assert_tok(&mut reader, TokenKind::RBrace, 1, 13);
+        assert_tok(&mut reader, TokenKind::StringTemplateEnd("c".into()), 1, 15);
+        assert_end(&mut reader, 1, 18);
+
+        let mut reader = Lexer::from_str("\"\\${x}\"");
+        assert_tok(&mut reader, TokenKind::String("${x}".into()), 1, 1);
+    }
+
     #[test]
     fn test_escape_sequences() {
         let mut reader = Lexer::from_str("\"\\\"\"");
@@ -952,6 +1078,10 @@ mod tests {
         assert_tok(&mut reader, TokenKind::Trait, 1, 24);
         assert_tok(&mut reader, TokenKind::Const, 1, 30);
 
+        let mut reader = Lexer::from_str("match defer");
+        assert_tok(&mut reader, TokenKind::Match, 1, 1);
+        assert_tok(&mut reader, TokenKind::Defer, 1, 7);
+
         let mut reader = Lexer::from_str("pub static for in impl Self spawn");
         assert_tok(&mut reader, TokenKind::Pub, 1, 1);
         assert_tok(&mut reader, TokenKind::Static, 1, 5);
@@ -968,6 +1098,25 @@ mod tests {
         assert_tok(&mut reader, TokenKind::Defer, 1, 24);
     }
 
+    #[test]
+    fn test_token_span() {
+        let mut reader = Lexer::from_str("fun  foo\n\"ab\"");
+
+        let tok = reader.read_token().unwrap();
+        assert_eq!((0, 3), (tok.position.start, tok.position.end));
+
+        let tok = reader.read_token().unwrap();
+        assert_eq!((5, 8), (tok.position.start, tok.position.end));
+
+        let tok = reader.read_token().unwrap();
+        assert_eq!((2, 1), (tok.position.line, tok.position.column));
+        assert_eq!((9, 13), (tok.position.start, tok.position.end));
+
+        let tok = reader.read_token().unwrap();
+        assert_eq!(TokenKind::End, tok.kind);
+        assert_eq!((13, 13), (tok.position.start, tok.position.end));
+    }
+
     #[test]
     fn test_operators() {
         let mut reader = Lexer::from_str("==+=-*/%~.");
@@ -993,6 +1142,10 @@ mod tests {
         assert_tok(&mut reader, TokenKind::EqEqEq, 1, 4);
         assert_tok(&mut reader, TokenKind::Not, 1, 7);
 
+        let mut reader = Lexer::from_str("a?");
+        assert_tok(&mut reader, TokenKind::Identifier("a".into()), 1, 1);
+        assert_tok(&mut reader, TokenKind::QuestionMark, 1, 2);
+
         let mut reader = Lexer::from_str("!=!");
         assert_tok(&mut reader, TokenKind::Ne, 1, 1);
         assert_tok(&mut reader, TokenKind::Not, 1, 3);
@@ -1000,6 +1153,14 @@ mod tests {
         let mut reader = Lexer::from_str("->");
         assert_tok(&mut reader, TokenKind::Arrow, 1, 1);
 
+        let mut reader = Lexer::from_str("....");
+        assert_tok(&mut reader, TokenKind::DotDotDot, 1, 1);
+        assert_tok(&mut reader, TokenKind::Dot, 1, 4);
+
+        let mut reader = Lexer::from_str("=>==");
+        assert_tok(&mut reader, TokenKind::DoubleArrow, 1, 1);
+        assert_tok(&mut reader, TokenKind::EqEq, 1, 3);
+
         let mut reader = Lexer::from_str("try!try?1");
         assert_tok(&mut reader, TokenKind::TryForce, 1, 1);
         assert_tok(&mut reader, TokenKind::TryOpt, 1, 5);
@@ -1,10 +1,19 @@
 use std::fmt::{Formatter, Display, Error};
 use std::result::Result;
 
+// index of the file in `Ast::files`
+#[derive(PartialEq,Eq,Debug,Copy,Clone,Hash)]
+pub struct FileId(pub u32);
+
 #[derive(PartialEq,Eq,Debug,Copy,Clone)]
 pub struct Position {
+    pub file: FileId,
     pub line: u32,
     pub column: u32,
+
+    // byte offsets into the source, `end` is exclusive
+    pub start: u32,
+    pub end: u32,
 }
 
 impl Position {
@@ -12,7 +21,17 @@ impl Position {
         assert!(l >= 1);
         assert!(c >= 1);
 
-        Position { line: l, column: c }
+        Position {
+            file: FileId(0),
+            line: l,
+            column: c,
+            start: 0,
+            end: 0,
+        }
+    }
+
+    pub fn len(&self) -> u32 {
+        self.end - self.start
     }
 }
 
@@ -28,6 +47,7 @@ fn test_new() {
 
     assert_eq!(pos.line, 3);
     assert_eq!(pos.column, 1);
+    assert_eq!(pos.len(), 0);
 
     assert_eq!(&format!("{}", pos)[..], "3:1");
 }
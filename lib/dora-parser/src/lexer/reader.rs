@@ -1,11 +1,12 @@
 use std::fs::File;
 use std::io::{self, Read, Error};
 
-use lexer::position::Position;
+use lexer::position::{FileId, Position};
 
 pub struct Reader {
     filename: String,
     src: String,
+    file: FileId,
 
     pos: usize,
     next_pos: usize,
@@ -37,6 +38,11 @@ impl Reader {
         common_init("<<code>>".into(), src.into())
     }
 
+    // source that is not read from `filename`, e.g. an unsaved buffer of an editor
+    pub fn from_named_string(filename: &str, src: &str) -> Reader {
+        common_init(filename.into(), src.into())
+    }
+
     pub fn set_tabwidth(&mut self, width: usize) {
         self.tabwidth = width;
     }
@@ -45,6 +51,23 @@ impl Reader {
         &self.filename
     }
 
+    pub fn src(&self) -> &str {
+        &self.src
+    }
+
+    pub fn set_file(&mut self, file: FileId) {
+        self.file = file;
+    }
+
+    // byte offset of the current character
+    pub fn offset(&self) -> u32 {
+        if self.cur.is_some() {
+            self.pos as u32
+        } else {
+            self.src.len() as u32
+        }
+    }
+
     pub fn advance(&mut self) -> Option<char> {
         match self.cur {
             Some('\n') => {
@@ -83,9 +106,14 @@ impl Reader {
     }
 
     pub fn pos(&self) -> Position {
+        let offset = self.offset();
+
         Position {
+            file: self.file,
             line: self.line as u32,
             column: self.col as u32,
+            start: offset,
+            end: offset,
         }
     }
 
@@ -104,6 +132,7 @@ fn common_init(name: String, src: String) -> Reader {
     let mut reader = Reader {
         filename: name,
         src: src,
+        file: FileId(0),
         pos: 0,
         next_pos: 0,
 
@@ -6,6 +6,8 @@ use lexer::position::Position;
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub enum TokenKind {
     String(String),
+    StringTemplate(String),
+    StringTemplateEnd(String),
     LitChar(char),
     LitInt(String, IntBase, IntSuffix),
     LitFloat(String, FloatSuffix),
@@ -50,12 +52,14 @@ pub enum TokenKind {
     Spawn,
 
     Enum,
+    Match,
     Type,
     Alias,
     Struct,
     Trait,
     Impl,
     Const,
+    Import,
 
     Underscore,
     Defer,
@@ -81,13 +85,16 @@ pub enum TokenKind {
     Comma,
     Semicolon,
     Dot,
+    DotDotDot,
     Colon,
     Sep, // ::
     Arrow,
+    DoubleArrow,
     Tilde,
     BitOr,
     BitAnd,
     Caret,
+    QuestionMark,
     And,
     Or,
     Internal,
@@ -113,11 +120,15 @@ impl TokenKind {
     pub fn name(&self) -> &str {
         match *self {
             TokenKind::String(_) => "string",
+            TokenKind::StringTemplate(_) | TokenKind::StringTemplateEnd(_) => "string template",
             TokenKind::LitInt(_, _, suffix) => {
                 match suffix {
                     IntSuffix::Byte => "byte number",
                     IntSuffix::Int => "int number",
                     IntSuffix::Long => "long number",
+                    IntSuffix::Short => "short number",
+                    IntSuffix::UInt => "uint number",
+                    IntSuffix::ULong => "ulong number",
                 }
             }
 
@@ -171,12 +182,14 @@ impl TokenKind {
             TokenKind::Spawn => "spawn",
 
             TokenKind::Enum => "enum",
+            TokenKind::Match => "match",
             TokenKind::Type => "type",
             TokenKind::Alias => "alias",
             TokenKind::Struct => "struct",
             TokenKind::Trait => "trait",
             TokenKind::Impl => "impl",
             TokenKind::Const => "const",
+            TokenKind::Import => "import",
 
             TokenKind::Underscore => "_",
             TokenKind::Defer => "defer",
@@ -202,13 +215,16 @@ impl TokenKind {
             TokenKind::Comma => ",",
             TokenKind::Semicolon => ";",
             TokenKind::Dot => ".",
+            TokenKind::DotDotDot => "...",
             TokenKind::Colon => ":",
             TokenKind::Sep => "::",
-            TokenKind::Arrow => "=>",
+            TokenKind::Arrow => "->",
+            TokenKind::DoubleArrow => "=>",
             TokenKind::Tilde => "~",
             TokenKind::BitOr => "|",
             TokenKind::BitAnd => "&",
             TokenKind::Caret => "^",
+            TokenKind::QuestionMark => "?",
             TokenKind::And => "&&",
             TokenKind::Or => "||",
             TokenKind::Internal => "internal",
@@ -238,6 +254,9 @@ pub enum IntSuffix {
     Int,
     Long,
     Byte,
+    Short,
+    UInt,
+    ULong,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
@@ -275,12 +294,17 @@ impl Token {
                     IntSuffix::Byte => "B",
                     IntSuffix::Int => "",
                     IntSuffix::Long => "L",
+                    IntSuffix::Short => "S",
+                    IntSuffix::UInt => "U",
+                    IntSuffix::ULong => "UL",
                 };
 
                 format!("{}{}", val, suffix)
             }
 
             TokenKind::String(ref val) => format!("\"{}\"", &val),
+            TokenKind::StringTemplate(ref val) => format!("\"{}${{", &val),
+            TokenKind::StringTemplateEnd(ref val) => format!("}}{}\"", &val),
             TokenKind::Identifier(ref val) => val.clone(),
 
             _ => self.kind.name().into(),
@@ -4,13 +4,14 @@ use std::mem;
 use ast::*;
 use ast::Elem::*;
 use builder::Builder;
+use error::diag::Diagnostic;
 use error::msg::*;
 
 use interner::*;
 
 use lexer::*;
 use lexer::token::*;
-use lexer::position::Position;
+use lexer::position::{FileId, Position};
 use lexer::reader::Reader;
 
 pub struct Parser<'a> {
@@ -22,6 +23,7 @@ pub struct Parser<'a> {
     param_idx: u32,
     field_idx: u32,
     in_class: bool,
+    errors: Vec<MsgWithPos>,
 
     next_id: NodeId,
 }
@@ -46,6 +48,7 @@ impl<'a> Parser<'a> {
             param_idx: 0,
             field_idx: 0,
             in_class: false,
+            errors: Vec::new(),
             ast: ast,
             next_id: NodeId(1),
         };
@@ -57,22 +60,42 @@ impl<'a> Parser<'a> {
         self.id_generator.next()
     }
 
-    pub fn parse(&mut self) -> Result<(), MsgWithPos> {
-        self.init()?;
-        let mut elements = vec![];
-
-        while !self.token.is_eof() {
-            self.parse_top_level_element(&mut elements)?;
-        }
+    pub fn parse(&mut self, diag: &mut Diagnostic) {
+        // register the file before parsing, so that even parse errors
+        // can refer to its source
+        let file = FileId(self.ast.files.len() as u32);
+        self.lexer.set_file(file);
 
         self.ast
             .files
             .push(File {
                       path: self.lexer.filename().to_string(),
-                      elements: elements,
+                      src: self.lexer.src().to_string(),
+                      elements: Vec::new(),
+                      module: Vec::new(),
                   });
 
-        Ok(())
+        if let Err(err) = self.init() {
+            self.errors.push(err);
+            self.skip_token();
+        }
+
+        let mut elements = vec![];
+
+        while !self.token.is_eof() {
+            let start = self.token.position;
+
+            if let Err(err) = self.parse_top_level_element(&mut elements) {
+                self.errors.push(err);
+                self.sync_top_level(start);
+            }
+        }
+
+        self.ast.files[file.0 as usize].elements = elements;
+
+        for err in self.errors.drain(..) {
+            diag.report(err.pos, err.msg);
+        }
     }
 
     fn init(&mut self) -> Result<(), MsgWithPos> {
@@ -86,27 +109,42 @@ impl<'a> Parser<'a> {
 
         match self.token.kind {
             TokenKind::Fun => {
-                self.restrict_modifiers(&modifiers, &[Modifier::Internal])?;
+                self.restrict_modifiers(&modifiers, &[Modifier::Internal, Modifier::Pub])?;
                 let fct = self.parse_function(&modifiers)?;
                 elements.push(ElemFunction(fct));
             }
 
             TokenKind::Class => {
                 self.restrict_modifiers(&modifiers,
-                                        &[Modifier::Abstract, Modifier::Open, Modifier::Internal])?;
+                                        &[Modifier::Abstract,
+                                          Modifier::Open,
+                                          Modifier::Internal,
+                                          Modifier::Pub])?;
                 let class = self.parse_class(&modifiers)?;
                 elements.push(ElemClass(class));
             }
 
             TokenKind::Struct => {
-                self.ban_modifiers(&modifiers)?;
-                let struc = self.parse_struct()?;
+                self.restrict_modifiers(&modifiers, &[Modifier::Pub])?;
+                let struc = self.parse_struct(&modifiers)?;
                 elements.push(ElemStruct(struc))
             }
 
+            TokenKind::Enum => {
+                self.restrict_modifiers(&modifiers, &[Modifier::Pub])?;
+                let xenum = self.parse_enum(&modifiers)?;
+                elements.push(ElemEnum(xenum))
+            }
+
+            TokenKind::Type => {
+                self.restrict_modifiers(&modifiers, &[Modifier::Pub])?;
+                let alias = self.parse_alias(&modifiers)?;
+                elements.push(ElemAlias(alias));
+            }
+
             TokenKind::Trait => {
-                self.ban_modifiers(&modifiers)?;
-                let xtrait = self.parse_trait()?;
+                self.restrict_modifiers(&modifiers, &[Modifier::Pub])?;
+                let xtrait = self.parse_trait(&modifiers)?;
                 elements.push(ElemTrait(xtrait));
             }
 
@@ -117,16 +155,22 @@ impl<'a> Parser<'a> {
             }
 
             TokenKind::Let | TokenKind::Var => {
-                self.ban_modifiers(&modifiers)?;
-                self.parse_global(elements)?;
+                self.restrict_modifiers(&modifiers, &[Modifier::Pub])?;
+                self.parse_global(&modifiers, elements)?;
             }
 
             TokenKind::Const => {
-                self.ban_modifiers(&modifiers)?;
-                let xconst = self.parse_const()?;
+                self.restrict_modifiers(&modifiers, &[Modifier::Pub])?;
+                let xconst = self.parse_const(&modifiers)?;
                 elements.push(ElemConst(xconst));
             }
 
+            TokenKind::Import => {
+                self.ban_modifiers(&modifiers)?;
+                let import = self.parse_import()?;
+                elements.push(ElemImport(import));
+            }
+
             _ => {
                 let msg = Msg::ExpectedTopLevelElement(self.token.name());
                 return Err(MsgWithPos::new(self.token.position, msg));
@@ -136,7 +180,34 @@ impl<'a> Parser<'a> {
         Ok(())
     }
 
-    fn parse_const(&mut self) -> Result<Const, MsgWithPos> {
+    fn parse_import(&mut self) -> Result<Import, MsgWithPos> {
+        let pos = self.expect_token(TokenKind::Import)?.position;
+        let mut path = vec![self.expect_identifier()?];
+        let mut glob = false;
+
+        while self.token.is(TokenKind::Sep) {
+            self.advance_token()?;
+
+            if self.token.is(TokenKind::Mul) {
+                self.advance_token()?;
+                glob = true;
+                break;
+            }
+
+            path.push(self.expect_identifier()?);
+        }
+
+        self.expect_semicolon()?;
+
+        Ok(Import {
+               id: self.generate_id(),
+               pos: pos,
+               path: Path { path: path },
+               glob: glob,
+           })
+    }
+
+    fn parse_const(&mut self, modifiers: &Modifiers) -> Result<Const, MsgWithPos> {
         let pos = self.expect_token(TokenKind::Const)?.position;
         let name = self.expect_identifier()?;
         self.expect_token(TokenKind::Colon)?;
@@ -149,6 +220,7 @@ impl<'a> Parser<'a> {
                id: self.generate_id(),
                pos: pos,
                name: name,
+               is_pub: modifiers.contains(Modifier::Pub),
                data_type: ty,
                expr: expr,
            })
@@ -156,9 +228,10 @@ impl<'a> Parser<'a> {
 
     fn parse_impl(&mut self) -> Result<Impl, MsgWithPos> {
         let pos = self.expect_token(TokenKind::Impl)?.position;
-        let trait_name = self.expect_identifier()?;
+        let type_params = self.parse_type_params()?;
+        let trait_type = self.parse_type()?;
         self.expect_token(TokenKind::For)?;
-        let class_name = self.expect_identifier()?;
+        let class_type = self.parse_type()?;
 
         self.expect_token(TokenKind::LBrace)?;
 
@@ -176,14 +249,18 @@ impl<'a> Parser<'a> {
 
         Ok(Impl {
                id: self.generate_id(),
-               trait_name: trait_name,
-               class_name: class_name,
+               type_params: type_params,
+               trait_type: trait_type,
+               class_type: class_type,
                pos: pos,
                methods: methods,
            })
     }
 
-    fn parse_global(&mut self, elements: &mut Vec<Elem>) -> Result<(), MsgWithPos> {
+    fn parse_global(&mut self,
+                    modifiers: &Modifiers,
+                    elements: &mut Vec<Elem>)
+                    -> Result<(), MsgWithPos> {
         let pos = self.token.position;
         let reassignable = self.token.is(TokenKind::Var);
 
@@ -203,15 +280,18 @@ impl<'a> Parser<'a> {
 
         self.expect_semicolon()?;
 
-        let builder = Builder::new(self.id_generator);
+        let initializer = expr.as_ref()
+            .map(|expr| self.generate_global_initializer(pos, name, expr.clone()));
 
         let global = Global {
             id: self.generate_id(),
             name: name,
             pos: pos,
+            is_pub: modifiers.contains(Modifier::Pub),
             data_type: data_type,
             reassignable: reassignable,
             expr: expr,
+            initializer: initializer,
         };
 
         elements.push(ElemGlobal(global));
@@ -219,16 +299,25 @@ impl<'a> Parser<'a> {
         Ok(())
     }
 
-    fn parse_trait(&mut self) -> Result<Trait, MsgWithPos> {
+    fn parse_trait(&mut self, modifiers: &Modifiers) -> Result<Trait, MsgWithPos> {
         let pos = self.expect_token(TokenKind::Trait)?.position;
         let ident = self.expect_identifier()?;
+        let type_params = self.parse_type_params()?;
 
         self.expect_token(TokenKind::LBrace)?;
 
+        let mut aliases = Vec::new();
         let mut methods = Vec::new();
 
         while !self.token.is(TokenKind::RBrace) {
             let modifiers = self.parse_modifiers()?;
+
+            if self.token.is(TokenKind::Type) {
+                self.ban_modifiers(&modifiers)?;
+                aliases.push(self.parse_alias(&modifiers)?);
+                continue;
+            }
+
             let mods = &[Modifier::Static];
             self.restrict_modifiers(&modifiers, mods)?;
 
@@ -241,11 +330,33 @@ impl<'a> Parser<'a> {
                id: self.generate_id(),
                name: ident,
                pos: pos,
+               is_pub: modifiers.contains(Modifier::Pub),
+               type_params: type_params,
+               aliases: aliases,
                methods: methods,
            })
     }
 
-    fn parse_struct(&mut self) -> Result<Struct, MsgWithPos> {
+    fn parse_alias(&mut self, modifiers: &Modifiers) -> Result<Alias, MsgWithPos> {
+        let pos = self.expect_token(TokenKind::Type)?.position;
+        let name = self.expect_identifier()?;
+        let type_params = self.parse_type_params()?;
+
+        self.expect_token(TokenKind::Eq)?;
+        let ty = self.parse_type()?;
+        self.expect_semicolon()?;
+
+        Ok(Alias {
+               id: self.generate_id(),
+               pos: pos,
+               name: name,
+               is_pub: modifiers.contains(Modifier::Pub),
+               type_params: type_params,
+               ty: ty,
+           })
+    }
+
+    fn parse_struct(&mut self, modifiers: &Modifiers) -> Result<Struct, MsgWithPos> {
         let pos = self.expect_token(TokenKind::Struct)?.position;
         let ident = self.expect_identifier()?;
 
@@ -256,6 +367,7 @@ impl<'a> Parser<'a> {
                id: self.generate_id(),
                name: ident,
                pos: pos,
+               is_pub: modifiers.contains(Modifier::Pub),
                fields: fields,
            })
     }
@@ -275,10 +387,46 @@ impl<'a> Parser<'a> {
            })
     }
 
+    fn parse_enum(&mut self, modifiers: &Modifiers) -> Result<Enum, MsgWithPos> {
+        let pos = self.expect_token(TokenKind::Enum)?.position;
+        let ident = self.expect_identifier()?;
+
+        self.expect_token(TokenKind::LBrace)?;
+        let variants = self.parse_comma_list(TokenKind::RBrace, |p| p.parse_enum_variant())?;
+
+        Ok(Enum {
+               id: self.generate_id(),
+               name: ident,
+               pos: pos,
+               is_pub: modifiers.contains(Modifier::Pub),
+               variants: variants,
+           })
+    }
+
+    fn parse_enum_variant(&mut self) -> Result<EnumVariant, MsgWithPos> {
+        let pos = self.token.position;
+        let ident = self.expect_identifier()?;
+
+        let types = if self.token.is(TokenKind::LParen) {
+            self.advance_token()?;
+            self.parse_comma_list(TokenKind::RParen, |p| p.parse_type())?
+        } else {
+            Vec::new()
+        };
+
+        Ok(EnumVariant {
+               id: self.generate_id(),
+               name: ident,
+               pos: pos,
+               types: types,
+           })
+    }
+
     fn parse_class(&mut self, modifiers: &Modifiers) -> Result<Class, MsgWithPos> {
         let has_open = modifiers.contains(Modifier::Open);
         let internal = modifiers.contains(Modifier::Internal);
         let is_abstract = modifiers.contains(Modifier::Abstract);
+        let is_pub = modifiers.contains(Modifier::Pub);
 
         let pos = self.expect_token(TokenKind::Class)?.position;
         let ident = self.expect_identifier()?;
@@ -291,6 +439,7 @@ impl<'a> Parser<'a> {
             has_open: has_open,
             internal: internal,
             is_abstract: is_abstract,
+            is_pub: is_pub,
             primary_ctor: false,
             parent_class: None,
             ctors: Vec::new(),
@@ -378,7 +527,7 @@ impl<'a> Parser<'a> {
     }
 
     fn add_field_initializers_to_ctors(&mut self, cls: &mut Class) {
-        let builder = Builder::new(self.id_generator);
+        let builder = Builder::new(self.id_generator, cls.pos);
 
         for ctor in &mut cls.ctors {
             let mut block = builder.build_block();
@@ -428,6 +577,18 @@ impl<'a> Parser<'a> {
     fn parse_primary_ctor_param(&mut self,
                                 cls: &mut Class)
                                 -> Result<PrimaryCtorParam, MsgWithPos> {
+        let is_pub = self.token.is(TokenKind::Pub);
+
+        if is_pub {
+            self.advance_token()?;
+
+            // only fields can be public, plain parameters are not visible at all
+            if !self.token.is(TokenKind::Var) && !self.token.is(TokenKind::Let) {
+                return Err(MsgWithPos::new(self.token.position,
+                                           Msg::MisplacedModifier("pub".into())));
+            }
+        }
+
         let field = self.token.is(TokenKind::Var) || self.token.is(TokenKind::Let);
         let reassignable = self.token.is(TokenKind::Var);
 
@@ -441,17 +602,30 @@ impl<'a> Parser<'a> {
 
         self.expect_token(TokenKind::Colon)?;
         let data_type = self.parse_type()?;
+        let variadic = self.parse_variadic()?;
 
         if field {
+            // the field stores all arguments passed for a variadic parameter
+            let field_type = if variadic {
+                let name = self.interner.intern("Array");
+                Type::create_basic(self.generate_id(),
+                                   data_type.pos(),
+                                   name,
+                                   vec![Box::new(data_type.clone())])
+            } else {
+                data_type.clone()
+            };
+
             cls.fields
                 .push(Field {
                           id: self.generate_id(),
                           name: name,
                           pos: pos,
-                          data_type: data_type.clone(),
+                          data_type: field_type,
                           primary_ctor: true,
                           expr: None,
                           reassignable: reassignable,
+                          is_pub: is_pub,
                       })
         }
 
@@ -461,6 +635,7 @@ impl<'a> Parser<'a> {
                data_type: data_type,
                field: field,
                reassignable: reassignable,
+               variadic: variadic,
            })
     }
 
@@ -472,46 +647,61 @@ impl<'a> Parser<'a> {
         self.advance_token()?;
 
         while !self.token.is(TokenKind::RBrace) {
-            let modifiers = self.parse_modifiers()?;
+            let start = self.token.position;
 
-            match self.token.kind {
-                TokenKind::Fun => {
-                    let mods = &[Modifier::Abstract,
-                                 Modifier::Internal,
-                                 Modifier::Open,
-                                 Modifier::Override,
-                                 Modifier::Final,
-                                 Modifier::Pub,
-                                 Modifier::Static];
-                    self.restrict_modifiers(&modifiers, mods)?;
-
-                    let fct = self.parse_function(&modifiers)?;
-                    cls.methods.push(fct);
+            if let Err(err) = self.parse_class_element(cls) {
+                if self.token.is_eof() {
+                    return Err(err);
                 }
 
-                TokenKind::Init => {
-                    let mods = &[Modifier::Internal];
-                    self.restrict_modifiers(&modifiers, mods)?;
+                self.errors.push(err);
+                self.sync_nested(start, is_class_element_start);
+            }
+        }
+
+        self.advance_token()?;
+        Ok(())
+    }
 
-                    let ctor = self.parse_ctor(cls, &modifiers)?;
-                    cls.ctors.push(ctor);
-                }
+    fn parse_class_element(&mut self, cls: &mut Class) -> Result<(), MsgWithPos> {
+        let modifiers = self.parse_modifiers()?;
+
+        match self.token.kind {
+            TokenKind::Fun => {
+                let mods = &[Modifier::Abstract,
+                             Modifier::Internal,
+                             Modifier::Open,
+                             Modifier::Override,
+                             Modifier::Final,
+                             Modifier::Pub,
+                             Modifier::Static];
+                self.restrict_modifiers(&modifiers, mods)?;
 
-                TokenKind::Var | TokenKind::Let => {
-                    self.ban_modifiers(&modifiers)?;
+                let fct = self.parse_function(&modifiers)?;
+                cls.methods.push(fct);
+            }
 
-                    let field = self.parse_field()?;
-                    cls.fields.push(field);
-                }
+            TokenKind::Init => {
+                let mods = &[Modifier::Internal, Modifier::Pub];
+                self.restrict_modifiers(&modifiers, mods)?;
 
-                _ => {
-                    return Err(MsgWithPos::new(self.token.position,
-                                               Msg::ExpectedClassElement(self.token.name())))
-                }
+                let ctor = self.parse_ctor(cls, &modifiers)?;
+                cls.ctors.push(ctor);
+            }
+
+            TokenKind::Var | TokenKind::Let => {
+                self.restrict_modifiers(&modifiers, &[Modifier::Pub])?;
+
+                let field = self.parse_field(&modifiers)?;
+                cls.fields.push(field);
+            }
+
+            _ => {
+                return Err(MsgWithPos::new(self.token.position,
+                                           Msg::ExpectedClassElement(self.token.name())))
             }
         }
 
-        self.advance_token()?;
         Ok(())
     }
 
@@ -567,7 +757,7 @@ impl<'a> Parser<'a> {
         let params = self.parse_function_params()?;
         let delegation = self.parse_delegation()?;
         let mut block = self.parse_function_block()?;
-        let builder = Builder::new(self.id_generator);
+        let builder = Builder::new(self.id_generator, pos);
 
         if let Some(delegation) = delegation {
             let expr = Expr::create_delegation(self.generate_id(),
@@ -589,7 +779,7 @@ impl<'a> Parser<'a> {
                has_open: false,
                has_override: false,
                has_final: false,
-               is_pub: true,
+               is_pub: modifiers.contains(Modifier::Pub),
                is_static: false,
                is_abstract: false,
                internal: modifiers.contains(Modifier::Internal),
@@ -631,7 +821,7 @@ impl<'a> Parser<'a> {
                 }))
     }
 
-    fn parse_field(&mut self) -> Result<Field, MsgWithPos> {
+    fn parse_field(&mut self, modifiers: &Modifiers) -> Result<Field, MsgWithPos> {
         let pos = self.token.position;
         let reassignable = if self.token.is(TokenKind::Var) {
             self.expect_token(TokenKind::Var)?;
@@ -665,6 +855,7 @@ impl<'a> Parser<'a> {
                primary_ctor: false,
                expr: expr,
                reassignable: reassignable,
+               is_pub: modifiers.contains(Modifier::Pub),
            })
     }
 
@@ -767,6 +958,7 @@ impl<'a> Parser<'a> {
 
         self.expect_token(TokenKind::Colon)?;
         let data_type = self.parse_type()?;
+        let variadic = self.parse_variadic()?;
 
         Ok(Param {
                id: self.generate_id(),
@@ -775,9 +967,19 @@ impl<'a> Parser<'a> {
                name: name,
                pos: pos,
                data_type: data_type,
+               variadic: variadic,
            })
     }
 
+    fn parse_variadic(&mut self) -> Result<bool, MsgWithPos> {
+        if self.token.is(TokenKind::DotDotDot) {
+            self.advance_token()?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
     fn parse_function_type(&mut self) -> Result<Option<Type>, MsgWithPos> {
         if self.token.is(TokenKind::Arrow) {
             self.advance_token()?;
@@ -802,6 +1004,18 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_type(&mut self) -> Result<Type, MsgWithPos> {
+        let mut ty = self.parse_type_without_suffix()?;
+
+        while self.token.is(TokenKind::QuestionMark) {
+            self.advance_token()?;
+            let pos = ty.pos();
+            ty = Type::create_nullable(self.generate_id(), pos, Box::new(ty));
+        }
+
+        Ok(ty)
+    }
+
+    fn parse_type_without_suffix(&mut self) -> Result<Type, MsgWithPos> {
         match self.token.kind {
             TokenKind::CapitalThis => {
                 let pos = self.token.position;
@@ -859,21 +1073,11 @@ impl<'a> Parser<'a> {
             TokenKind::Throw => self.parse_throw(),
             TokenKind::Defer => self.parse_defer(),
             TokenKind::Do => self.parse_do(),
-            TokenKind::Spawn => self.parse_spawn(),
             TokenKind::For => self.parse_for(),
             _ => self.parse_expression_statement(),
         }
     }
 
-    fn parse_spawn(&mut self) -> StmtResult {
-        let pos = self.expect_token(TokenKind::Spawn)?.position;
-        let expr = self.parse_expression()?;
-
-        self.expect_semicolon()?;
-
-        Ok(Box::new(Stmt::create_spawn(self.generate_id(), pos, expr)))
-    }
-
     fn parse_throw(&mut self) -> StmtResult {
         let pos = self.expect_token(TokenKind::Throw)?.position;
         let expr = self.parse_expression()?;
@@ -942,6 +1146,11 @@ impl<'a> Parser<'a> {
         };
 
         let pos = self.advance_token()?.position;
+
+        if self.token.is(TokenKind::LParen) {
+            return self.parse_var_tuple(pos, reassignable);
+        }
+
         let ident = self.expect_identifier()?;
         let data_type = self.parse_var_type()?;
         let expr = self.parse_var_assignment()?;
@@ -956,6 +1165,40 @@ impl<'a> Parser<'a> {
                                      expr)))
     }
 
+    fn parse_var_tuple(&mut self, pos: Position, reassignable: bool) -> StmtResult {
+        self.expect_token(TokenKind::LParen)?;
+        let vars = self.parse_comma_list(TokenKind::RParen, |p| p.parse_tuple_var())?;
+        let data_type = self.parse_var_type()?;
+        self.expect_token(TokenKind::Eq)?;
+        let expr = self.parse_expression()?;
+
+        self.expect_semicolon()?;
+
+        Ok(Box::new(Stmt::create_var_tuple(self.generate_id(),
+                                           pos,
+                                           vars,
+                                           reassignable,
+                                           data_type,
+                                           expr)))
+    }
+
+    fn parse_tuple_var(&mut self) -> Result<TupleVar, MsgWithPos> {
+        let pos = self.token.position;
+
+        let name = if self.token.is(TokenKind::Underscore) {
+            self.advance_token()?;
+            None
+        } else {
+            Some(self.expect_identifier()?)
+        };
+
+        Ok(TupleVar {
+               id: self.generate_id(),
+               pos: pos,
+               name: name,
+           })
+    }
+
     fn parse_var_type(&mut self) -> Result<Option<Type>, MsgWithPos> {
         if self.token.is(TokenKind::Colon) {
             self.advance_token()?;
@@ -981,9 +1224,22 @@ impl<'a> Parser<'a> {
         let pos = self.expect_token(TokenKind::LBrace)?.position;
         let mut stmts = vec![];
 
-        while !self.token.is(TokenKind::RBrace) && !self.token.is_eof() {
-            let stmt = self.parse_statement()?;
-            stmts.push(stmt);
+        // a declaration means the closing brace is missing
+        while !self.token.is(TokenKind::RBrace) && !self.token.is_eof() &&
+              !is_declaration_start(&self.token.kind) {
+            let start = self.token.position;
+
+            match self.parse_statement() {
+                Ok(stmt) => stmts.push(stmt),
+
+                Err(err) => {
+                    self.errors.push(err);
+                    self.sync_nested(start, is_statement_start);
+
+                    let stmt = Stmt::create_error(self.generate_id(), start);
+                    stmts.push(Box::new(stmt));
+                }
+            }
         }
 
         self.expect_token(TokenKind::RBrace)?;
@@ -1168,6 +1424,17 @@ impl<'a> Parser<'a> {
             left = match self.token.kind {
                 TokenKind::Dot => {
                     let tok = self.advance_token()?;
+
+                    match self.token.kind {
+                        TokenKind::LitInt(_, _, _) |
+                        TokenKind::LitFloat(_, _) => {
+                            left = self.parse_tuple_element(tok.position, left)?;
+                            continue;
+                        }
+
+                        _ => {}
+                    }
+
                     let ident = self.expect_identifier()?;
 
                     let type_params = if self.token.is(TokenKind::Sep) {
@@ -1196,6 +1463,13 @@ impl<'a> Parser<'a> {
                     Box::new(Expr::create_array(self.generate_id(), tok.position, left, index))
                 }
 
+                TokenKind::LParen => {
+                    let tok = self.advance_token()?;
+                    let args = self.parse_comma_list(TokenKind::RParen, |p| p.parse_expression())?;
+
+                    Box::new(Expr::create_call_expr(self.generate_id(), tok.position, left, args))
+                }
+
                 _ => {
                     return Ok(left);
                 }
@@ -1243,6 +1517,7 @@ impl<'a> Parser<'a> {
             TokenKind::LitInt(_, _, _) => self.parse_lit_int(),
             TokenKind::LitFloat(_, _) => self.parse_lit_float(),
             TokenKind::String(_) => self.parse_string(),
+            TokenKind::StringTemplate(_) => self.parse_template(),
             TokenKind::Identifier(_) => self.parse_identifier_or_call(opts),
             TokenKind::True => self.parse_bool_literal(),
             TokenKind::False => self.parse_bool_literal(),
@@ -1252,6 +1527,8 @@ impl<'a> Parser<'a> {
             TokenKind::Try => self.parse_try(),
             TokenKind::TryForce | TokenKind::TryOpt => self.parse_try_op(),
             TokenKind::BitOr | TokenKind::Or => self.parse_lambda(),
+            TokenKind::Spawn => self.parse_spawn(),
+            TokenKind::Match => self.parse_match(),
             _ => {
                 Err(MsgWithPos::new(self.token.position,
                                     Msg::ExpectedFactor(self.token.name().clone())))
@@ -1288,9 +1565,13 @@ impl<'a> Parser<'a> {
             assert!(type_params.is_none());
             self.parse_lit_struct(pos, Path { path: path })
 
+            // paths like Foo::Bar are used for enum variants
+        } else if path.len() > 1 {
+            assert!(type_params.is_none());
+            Ok(Box::new(Expr::create_path(self.generate_id(), pos, Path { path: path })))
+
             // if not we have a simple identifier
         } else {
-            assert_eq!(1, path.len());
             let name = path[0];
             Ok(Box::new(Expr::create_ident(self.generate_id(), pos, name, type_params)))
         }
@@ -1333,11 +1614,57 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_parentheses(&mut self) -> ExprResult {
-        self.advance_token()?;
+        let pos = self.advance_token()?.position;
         let exp = self.parse_expression()?;
-        self.expect_token(TokenKind::RParen)?;
 
-        Ok(exp)
+        if !self.token.is(TokenKind::Comma) {
+            self.expect_token(TokenKind::RParen)?;
+            return Ok(exp);
+        }
+
+        self.advance_token()?;
+        let mut values = vec![exp];
+        values.extend(self.parse_comma_list(TokenKind::RParen, |p| p.parse_expression())?);
+
+        Ok(Box::new(Expr::create_tuple(self.generate_id(), pos, values)))
+    }
+
+    // `t.0.1` is lexed as `t` `.` `0.1`, so a float literal after the dot
+    // accesses two nested tuple elements.
+    fn parse_tuple_element(&mut self, pos: Position, object: Box<Expr>) -> ExprResult {
+        let tok = self.advance_token()?;
+
+        let indices = match tok.kind {
+            TokenKind::LitInt(ref value, IntBase::Dec, IntSuffix::Int) => {
+                value.parse::<u32>().ok().map(|idx| vec![idx])
+            }
+
+            TokenKind::LitFloat(ref value, FloatSuffix::Double) => {
+                let parts = value
+                    .split('.')
+                    .map(|part| part.parse::<u32>().ok())
+                    .collect::<Option<Vec<_>>>();
+
+                parts.and_then(|parts| if parts.len() == 2 { Some(parts) } else { None })
+            }
+
+            _ => None,
+        };
+
+        let indices = match indices {
+            Some(indices) => indices,
+            None => {
+                return Err(MsgWithPos::new(tok.position, Msg::ExpectedTupleIndex(tok.name())));
+            }
+        };
+
+        let mut object = object;
+
+        for idx in indices {
+            object = Box::new(Expr::create_tuple_element(self.generate_id(), pos, object, idx));
+        }
+
+        Ok(object)
     }
 
     fn parse_try_op(&mut self) -> ExprResult {
@@ -1403,6 +1730,9 @@ impl<'a> Parser<'a> {
                         IntSuffix::Byte => "byte",
                         IntSuffix::Int => "int",
                         IntSuffix::Long => "long",
+                        IntSuffix::Short => "short",
+                        IntSuffix::UInt => "uint",
+                        IntSuffix::ULong => "ulong",
                     };
 
                     Err(MsgWithPos::new(pos, Msg::NumberOverflow(bits.into())))
@@ -1444,6 +1774,45 @@ impl<'a> Parser<'a> {
         }
     }
 
+    fn parse_template(&mut self) -> ExprResult {
+        let start = self.advance_token()?;
+        let mut parts = Vec::new();
+
+        if let TokenKind::StringTemplate(value) = start.kind {
+            parts.push(Box::new(Expr::create_lit_str(self.generate_id(), start.position, value)));
+        } else {
+            unreachable!();
+        }
+
+        loop {
+            parts.push(self.parse_expression()?);
+
+            let tok = self.advance_token()?;
+
+            match tok.kind {
+                TokenKind::StringTemplate(value) => {
+                    parts.push(Box::new(Expr::create_lit_str(self.generate_id(),
+                                                             tok.position,
+                                                             value)));
+                }
+
+                TokenKind::StringTemplateEnd(value) => {
+                    parts.push(Box::new(Expr::create_lit_str(self.generate_id(),
+                                                             tok.position,
+                                                             value)));
+                    break;
+                }
+
+                _ => {
+                    return Err(MsgWithPos::new(tok.position,
+                                               Msg::ExpectedToken("}".into(), tok.name())));
+                }
+            }
+        }
+
+        Ok(Box::new(Expr::create_template(self.generate_id(), start.position, parts)))
+    }
+
     fn parse_bool_literal(&mut self) -> ExprResult {
         let tok = self.advance_token()?;
         let value = tok.is(TokenKind::True);
@@ -1486,15 +1855,118 @@ impl<'a> Parser<'a> {
 
         let ret = if self.token.is(TokenKind::Arrow) {
             self.advance_token()?;
-            Some(Box::new(self.parse_type()?))
+            Some(self.parse_type()?)
 
         } else {
             None
         };
 
         let block = self.parse_block()?;
+        let fct = self.build_closure_fct("<lambda>", tok.position, params, ret, block);
+
+        Ok(Box::new(Expr::create_lambda(self.generate_id(), tok.position, Box::new(fct))))
+    }
+
+    fn parse_spawn(&mut self) -> ExprResult {
+        let pos = self.expect_token(TokenKind::Spawn)?.position;
+        let block = self.parse_block()?;
+        let fct = self.build_closure_fct("<spawn>", pos, Vec::new(), None, block);
+
+        Ok(Box::new(Expr::create_spawn(self.generate_id(), pos, Box::new(fct))))
+    }
+
+    fn parse_match(&mut self) -> ExprResult {
+        let pos = self.expect_token(TokenKind::Match)?.position;
+
+        let mut opts = ExprParsingOpts::new();
+        opts.parse_struct_lit(false);
+        let expr = self.parse_expression_with_opts(&opts)?;
+
+        self.expect_token(TokenKind::LBrace)?;
+        let arms = self.parse_comma_list(TokenKind::RBrace, |p| p.parse_match_arm())?;
 
-        Ok(Box::new(Expr::create_lambda(self.generate_id(), tok.position, params, ret, block)))
+        Ok(Box::new(Expr::create_match(self.generate_id(), pos, expr, arms)))
+    }
+
+    fn parse_match_arm(&mut self) -> Result<MatchArm, MsgWithPos> {
+        let pos = self.token.position;
+
+        let pattern = if self.token.is(TokenKind::Underscore) {
+            self.advance_token()?;
+            MatchPattern::Underscore
+        } else {
+            let mut path = vec![self.expect_identifier()?];
+
+            while self.token.is(TokenKind::Sep) {
+                self.advance_token()?;
+                path.push(self.expect_identifier()?);
+            }
+
+            let params = if self.token.is(TokenKind::LParen) {
+                self.advance_token()?;
+                self.parse_comma_list(TokenKind::RParen, |p| p.parse_match_pattern_param())?
+            } else {
+                Vec::new()
+            };
+
+            MatchPattern::Variant(Path { path: path }, params)
+        };
+
+        self.expect_token(TokenKind::DoubleArrow)?;
+        let value = self.parse_expression()?;
+
+        Ok(MatchArm {
+               id: self.generate_id(),
+               pos: pos,
+               pattern: pattern,
+               value: value,
+           })
+    }
+
+    fn parse_match_pattern_param(&mut self) -> Result<MatchPatternParam, MsgWithPos> {
+        let pos = self.token.position;
+
+        let name = if self.token.is(TokenKind::Underscore) {
+            self.advance_token()?;
+            None
+        } else {
+            Some(self.expect_identifier()?)
+        };
+
+        Ok(MatchPatternParam {
+               id: self.generate_id(),
+               pos: pos,
+               name: name,
+           })
+    }
+
+    fn build_closure_fct(
+        &mut self,
+        name: &str,
+        pos: Position,
+        params: Vec<Param>,
+        ret: Option<Type>,
+        block: Box<Stmt>,
+    ) -> Function {
+        Function {
+            id: self.generate_id(),
+            name: self.interner.intern(name),
+            pos: pos,
+            method: false,
+            has_open: false,
+            has_override: false,
+            has_final: false,
+            is_pub: false,
+            is_static: false,
+            internal: false,
+            is_abstract: false,
+            ctor: CtorType::None,
+            params: params,
+            throws: false,
+            return_type: ret,
+            block: Some(block),
+            type_params: None,
+        }
     }
 
     fn expect_identifier(&mut self) -> Result<Name, MsgWithPos> {
@@ -1531,11 +2003,104 @@ impl<'a> Parser<'a> {
         Ok(mem::replace(&mut self.token, tok))
     }
 
+    // like `advance_token` but only records errors of the lexer
+    fn skip_token(&mut self) {
+        while let Err(err) = self.advance_token() {
+            self.errors.push(err);
+        }
+    }
+
+    // skips tokens after a syntax error in a top level element until
+    // the next top level element starts
+    fn sync_top_level(&mut self, start: Position) {
+        if self.token.position == start {
+            self.skip_token();
+        }
+
+        let mut depth = 0;
+
+        loop {
+            match self.token.kind {
+                TokenKind::End => return,
+                TokenKind::LBrace => depth += 1,
+                TokenKind::RBrace => depth = if depth > 0 { depth - 1 } else { 0 },
+                ref kind if depth == 0 && is_top_level_start(kind) => return,
+                _ => {}
+            }
+
+            self.skip_token();
+        }
+    }
+
+    // skips tokens after a syntax error in a statement or class element until
+    // behind the next `;` or at the next token for which `at_boundary` holds,
+    // the `}` closing the surrounding block is not consumed
+    fn sync_nested(&mut self, start: Position, at_boundary: fn(&TokenKind) -> bool) {
+        if self.token.position == start && !self.token.is(TokenKind::Semicolon) {
+            self.skip_token();
+        }
+
+        let mut depth = 0;
+
+        loop {
+            match self.token.kind {
+                TokenKind::End => return,
+
+                TokenKind::Semicolon if depth == 0 => {
+                    self.skip_token();
+                    return;
+                }
+
+                TokenKind::LBrace => depth += 1,
+
+                TokenKind::RBrace => {
+                    if depth == 0 {
+                        return;
+                    }
+
+                    depth -= 1;
+
+                    if depth == 0 {
+                        self.skip_token();
+                        return;
+                    }
+                }
+
+                ref kind if depth == 0 && (at_boundary(kind) || is_declaration_start(kind)) => {
+                    return
+                }
+
+                _ => {}
+            }
+
+            self.skip_token();
+        }
+    }
+
+    fn generate_global_initializer(&mut self,
+                                   pos: Position,
+                                   name: Name,
+                                   expr: Box<Expr>)
+                                   -> Function {
+        let builder = Builder::new(self.id_generator, pos);
+        let mut block = builder.build_block();
+
+        // use position of global for errors in the assignment
+        let lhs = Box::new(Expr::create_ident(self.generate_id(), pos, name, None));
+        let ass = Expr::create_assign(self.generate_id(), pos, lhs, expr);
+        block.add_expr(Box::new(ass));
+
+        let mut fct = builder.build_fct(name);
+        fct.block(block.build());
+
+        fct.build()
+    }
+
     fn generate_primary_ctor(&mut self,
                              cls: &mut Class,
                              ctor_params: Vec<PrimaryCtorParam>)
                              -> Function {
-        let builder = Builder::new(self.id_generator);
+        let builder = Builder::new(self.id_generator, cls.pos);
         let mut block = builder.build_block();
 
         if let Some(ref parent_class) = cls.parent_class {
@@ -1567,11 +2132,11 @@ impl<'a> Parser<'a> {
         let mut fct = builder.build_fct(cls.name);
 
         for field in &ctor_params {
-            fct.add_param(field.name, field.data_type.clone());
+            fct.add_param(field.name, field.data_type.clone(), field.variadic);
         }
 
         fct.is_method(true)
-            .is_public(true)
+            .is_public(cls.is_pub)
             .ctor(CtorType::Primary)
             .block(block.build());
 
@@ -1579,6 +2144,46 @@ impl<'a> Parser<'a> {
     }
 }
 
+fn is_declaration_start(kind: &TokenKind) -> bool {
+    match *kind {
+        TokenKind::Fun | TokenKind::Class | TokenKind::Struct | TokenKind::Enum |
+        TokenKind::Trait | TokenKind::Impl => true,
+        _ => false,
+    }
+}
+
+fn is_modifier(kind: &TokenKind) -> bool {
+    match *kind {
+        TokenKind::Abstract | TokenKind::Open | TokenKind::Override | TokenKind::Final |
+        TokenKind::Internal | TokenKind::Pub | TokenKind::Static => true,
+        _ => false,
+    }
+}
+
+fn is_top_level_start(kind: &TokenKind) -> bool {
+    match *kind {
+        TokenKind::Type | TokenKind::Let | TokenKind::Var | TokenKind::Const |
+        TokenKind::Import => true,
+        _ => is_declaration_start(kind) || is_modifier(kind),
+    }
+}
+
+fn is_class_element_start(kind: &TokenKind) -> bool {
+    match *kind {
+        TokenKind::Init | TokenKind::Let | TokenKind::Var => true,
+        _ => is_modifier(kind),
+    }
+}
+
+fn is_statement_start(kind: &TokenKind) -> bool {
+    match *kind {
+        TokenKind::Let | TokenKind::Var | TokenKind::If | TokenKind::While |
+        TokenKind::Loop | TokenKind::For | TokenKind::Break | TokenKind::Continue |
+        TokenKind::Return | TokenKind::Throw | TokenKind::Defer | TokenKind::Do => true,
+        _ => false,
+    }
+}
+
 struct ExprParsingOpts {
     parse_struct_lit: bool,
 }
@@ -1601,7 +2206,7 @@ struct Delegation {
     pub args: Vec<Box<Expr>>,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct NodeIdGenerator {
     value: RefCell<usize>,
 }
@@ -1624,8 +2229,8 @@ mod tests {
     use ast::*;
     use interner::*;
 
+    use error::diag::Diagnostic;
     use error::msg::{Msg, MsgWithPos};
-    use lexer::position::Position;
     use lexer::reader::Reader;
     use parser::{NodeIdGenerator, Parser};
 
@@ -1706,27 +2311,31 @@ mod tests {
     }
 
     fn parse(code: &'static str) -> (Ast, Interner) {
+        let (ast, interner, diag) = parse_with_errors(code);
+        assert!(!diag.has_errors(), "unexpected errors: {:?}", diag.errors());
+
+        (ast, interner)
+    }
+
+    fn parse_with_errors(code: &'static str) -> (Ast, Interner, Diagnostic) {
         let id_generator = NodeIdGenerator::new();
         let mut interner = Interner::new();
         let mut ast = Ast::new();
+        let mut diag = Diagnostic::new();
 
-        let reader = Reader::from_string(code);
-        Parser::new(reader, &id_generator, &mut ast, &mut interner)
-            .parse()
-            .unwrap();
+        {
+            let reader = Reader::from_string(code);
+            Parser::new(reader, &id_generator, &mut ast, &mut interner).parse(&mut diag);
+        }
 
-        (ast, interner)
+        (ast, interner, diag)
     }
 
     fn parse_err(code: &'static str) -> MsgWithPos {
-        let id_generator = NodeIdGenerator::new();
-        let mut interner = Interner::new();
-        let mut ast = Ast::new();
+        let (_, _, diag) = parse_with_errors(code);
+        assert_eq!(1, diag.errors().len(), "errors: {:?}", diag.errors());
 
-        let reader = Reader::from_string(code);
-        Parser::new(reader, &id_generator, &mut ast, &mut interner)
-            .parse()
-            .unwrap_err()
+        diag.errors()[0].clone()
     }
 
     #[test]
@@ -1761,6 +2370,27 @@ mod tests {
         assert_eq!("abc", &lit.value);
     }
 
+    #[test]
+    fn parse_template() {
+        let (expr, _) = parse_expr("\"a${x}b${1+2}\"");
+
+        let tmpl = expr.to_template().unwrap();
+        assert_eq!(5, tmpl.parts.len());
+        assert_eq!("a", &tmpl.parts[0].to_lit_str().unwrap().value);
+        assert!(tmpl.parts[1].is_ident());
+        assert_eq!("b", &tmpl.parts[2].to_lit_str().unwrap().value);
+        assert!(tmpl.parts[3].is_bin());
+        assert_eq!("", &tmpl.parts[4].to_lit_str().unwrap().value);
+    }
+
+    #[test]
+    fn parse_template_missing_brace() {
+        err_expr("\"a${x y}\"",
+                 Msg::ExpectedToken("}".into(), "y".into()),
+                 1,
+                 7);
+    }
+
     #[test]
     fn parse_true() {
         let (expr, _) = parse_expr("true");
@@ -1795,7 +2425,7 @@ mod tests {
 
     #[test]
     fn parse_field_non_ident() {
-        err_expr("obj.12", Msg::ExpectedIdentifier("12".into()), 1, 5);
+        err_expr("obj.true", Msg::ExpectedIdentifier("true".into()), 1, 5);
     }
 
     #[test]
@@ -2128,6 +2758,19 @@ mod tests {
         assert_eq!(0, call.args.len());
     }
 
+    #[test]
+    fn parse_call_with_callee() {
+        let (expr, interner) = parse_expr("a[0](1)");
+
+        let call = expr.to_call().unwrap();
+        assert_eq!(0, call.path.len());
+        assert!(call.object.is_none());
+        assert_eq!(1, call.args.len());
+
+        let array = call.callee.as_ref().unwrap().to_array().unwrap();
+        assert_eq!("a", *interner.str(array.object.to_ident().unwrap().name));
+    }
+
     #[test]
     fn parse_call_with_params() {
         let (expr, interner) = parse_expr("fname2(1,2,3)");
@@ -2150,7 +2793,8 @@ mod tests {
         assert_eq!("b", *interner.str(fct.name));
         assert_eq!(0, fct.params.len());
         assert!(fct.return_type.is_none());
-        assert_eq!(Position::new(1, 1), fct.pos);
+        assert_eq!((1, 1), (fct.pos.line, fct.pos.column));
+        assert_eq!((0, 3), (fct.pos.start, fct.pos.end));
     }
 
     #[test]
@@ -2174,6 +2818,17 @@ mod tests {
         assert_eq!("int", *interner2.str(p2.data_type.to_basic().unwrap().name));
     }
 
+    #[test]
+    fn parse_function_with_variadic_param() {
+        let (prog, interner) = parse("fun f(a: int, b: Str...) { }");
+        let fct = prog.fct0();
+
+        assert_eq!(false, fct.params[0].variadic);
+        assert_eq!(true, fct.params[1].variadic);
+        assert_eq!("Str",
+                   *interner.str(fct.params[1].data_type.to_basic().unwrap().name));
+    }
+
     #[test]
     fn parse_function_with_multiple_params() {
         let (p1, interner1) = parse("fun f(a:int, b:str) { }");
@@ -2291,12 +2946,12 @@ mod tests {
         let f = prog.fct0();
         assert_eq!("f", *interner.str(f.name));
         assert_eq!(false, f.method);
-        assert_eq!(Position::new(1, 1), f.pos);
+        assert_eq!((1, 1), (f.pos.line, f.pos.column));
 
         let g = prog.fct(1);
         assert_eq!("g", *interner.str(g.name));
         assert_eq!(false, g.method);
-        assert_eq!(Position::new(1, 13), g.pos);
+        assert_eq!((1, 13), (g.pos.line, g.pos.column));
     }
 
     #[test]
@@ -2506,6 +3161,28 @@ mod tests {
         assert_eq!("b", *interner.str(ty2.name));
     }
 
+    #[test]
+    fn parse_type_nullable() {
+        let (ty, interner) = parse_type("Foo<A>?");
+        let nullable = ty.to_nullable().unwrap();
+
+        let basic = nullable.subtype.to_basic().unwrap();
+        assert_eq!("Foo", *interner.str(basic.name));
+        assert_eq!("Foo<A>?", ty.to_string(&interner));
+
+        let (ty, interner) = parse_type("(() -> int)?");
+        let nullable = ty.to_nullable().unwrap();
+        let tuple = nullable.subtype.to_tuple().unwrap();
+
+        assert!(tuple.subtypes[0].to_fct().is_some());
+        assert_eq!("(() -> int)?", ty.to_string(&interner));
+
+        let (ty, _) = parse_type("() -> int?");
+        let fct = ty.to_fct().unwrap();
+
+        assert!(fct.ret.to_nullable().is_some());
+    }
+
     #[test]
     fn parse_method() {
         let (prog, interner) = parse("class Foo {
@@ -2568,7 +3245,7 @@ mod tests {
         assert_eq!(0, class.fields.len());
         assert_eq!(false, class.has_open);
         assert_eq!(false, class.is_abstract);
-        assert_eq!(Position::new(1, 1), class.pos);
+        assert_eq!((1, 1), (class.pos.line, class.pos.column));
         assert_eq!("Foo", *interner.str(class.name));
     }
 
@@ -2588,7 +3265,7 @@ mod tests {
 
         assert_eq!(0, class.fields.len());
         assert_eq!(true, class.has_open);
-        assert_eq!(Position::new(1, 6), class.pos);
+        assert_eq!((1, 6), (class.pos.line, class.pos.column));
         assert_eq!("Foo", *interner.str(class.name));
     }
 
@@ -2626,6 +3303,15 @@ mod tests {
         assert_eq!(false, class.ctors[0].params[0].reassignable);
     }
 
+    #[test]
+    fn parse_class_with_variadic_param() {
+        let (prog, interner) = parse("class Foo(let a: int...)");
+        let class = prog.cls0();
+
+        assert_eq!(true, class.ctors[0].params[0].variadic);
+        assert_eq!("Array<int>", class.fields[0].data_type.to_string(&interner));
+    }
+
     #[test]
     fn parse_class_with_params() {
         let (prog, _) = parse("class Foo(a: int, b: int)");
@@ -2996,6 +3682,80 @@ mod tests {
         assert_eq!("fb", *interner.str(f2.name));
     }
 
+    #[test]
+    fn parse_enum() {
+        let (prog, interner) = parse("enum Shape { Circle(double), Rect(double, double), Empty }");
+        let xenum = prog.enum0();
+        assert_eq!("Shape", *interner.str(xenum.name));
+        assert_eq!(3, xenum.variants.len());
+
+        assert_eq!("Circle", *interner.str(xenum.variants[0].name));
+        assert_eq!(1, xenum.variants[0].types.len());
+        assert_eq!(2, xenum.variants[1].types.len());
+        assert_eq!(0, xenum.variants[2].types.len());
+
+        let (prog, _) = parse("enum Foo {}");
+        assert_eq!(0, prog.enum0().variants.len());
+    }
+
+    #[test]
+    fn parse_enum_variant_path() {
+        let (expr, interner) = parse_expr("Shape::Empty");
+        let path = expr.to_path().unwrap();
+        assert_eq!(2, path.path.len());
+        assert_eq!("Empty", *interner.str(path.path.path[1]));
+
+        let (expr, _) = parse_expr("Shape::Circle(1.0)");
+        assert_eq!(2, expr.to_call().unwrap().path.len());
+    }
+
+    #[test]
+    fn parse_match() {
+        let (expr, interner) = parse_expr("match s {
+            Shape::Circle(r) => r,
+            Rect(w, _) => w,
+            Empty => 0.0,
+            _ => 1.0,
+        }");
+        let xmatch = expr.to_match().unwrap();
+        assert!(xmatch.expr.is_ident());
+        assert_eq!(4, xmatch.arms.len());
+
+        match xmatch.arms[0].pattern {
+            MatchPattern::Variant(ref path, ref params) => {
+                assert_eq!(2, path.len());
+                assert_eq!(1, params.len());
+                assert_eq!("r", *interner.str(params[0].name.unwrap()));
+            }
+            _ => unreachable!(),
+        }
+
+        match xmatch.arms[1].pattern {
+            MatchPattern::Variant(ref path, ref params) => {
+                assert_eq!(1, path.len());
+                assert_eq!(2, params.len());
+                assert!(params[1].name.is_none());
+            }
+            _ => unreachable!(),
+        }
+
+        match xmatch.arms[2].pattern {
+            MatchPattern::Variant(_, ref params) => assert!(params.is_empty()),
+            _ => unreachable!(),
+        }
+
+        assert!(xmatch.arms[3].pattern.is_underscore());
+        assert!(xmatch.arms[3].value.is_lit_float());
+    }
+
+    #[test]
+    fn parse_match_missing_arrow() {
+        err_expr("match s { _ 1 }",
+                 Msg::ExpectedToken("=>".into(), "1".into()),
+                 1,
+                 13);
+    }
+
     #[test]
     fn parse_struct_lit() {
         let (expr, _) = parse_expr("Foo { a: 1, b: 2 }");
@@ -3068,6 +3828,19 @@ mod tests {
         assert_eq!(0, xtrait.methods.len());
     }
 
+    #[test]
+    fn parse_generic_trait() {
+        let (prog, interner) = parse("trait Foo<T, U: Bar> { fun get() -> T; }");
+        let xtrait = prog.trait0();
+
+        assert_eq!("Foo", *interner.str(xtrait.name));
+
+        let type_params = xtrait.type_params.as_ref().unwrap();
+        assert_eq!(2, type_params.len());
+        assert_eq!("U", *interner.str(type_params[1].name));
+        assert_eq!(1, type_params[1].bounds.len());
+    }
+
     #[test]
     fn parse_trait_with_function() {
         let (prog, interner) = parse("trait Foo { fun empty(); }");
@@ -3088,13 +3861,116 @@ mod tests {
         assert_eq!(true, xtrait.methods[0].is_static);
     }
 
+    #[test]
+    fn parse_alias() {
+        let (prog, interner) = parse("type Meters = int;");
+        let alias = prog.alias0();
+        assert_eq!("Meters", *interner.str(alias.name));
+        assert!(alias.type_params.is_none());
+        assert_eq!("int", alias.ty.to_string(&interner));
+
+        let (prog, interner) = parse("type Pair<T> = Foo<T, T>;");
+        let alias = prog.alias0();
+        assert_eq!("Pair", *interner.str(alias.name));
+        assert_eq!(1, alias.type_params.as_ref().unwrap().len());
+    }
+
+    #[test]
+    fn parse_alias_in_trait() {
+        let (prog, interner) = parse("trait Foo { type Item = int; fun get() -> Item; }");
+        let xtrait = prog.trait0();
+
+        assert_eq!(1, xtrait.aliases.len());
+        assert_eq!("Item", *interner.str(xtrait.aliases[0].name));
+        assert_eq!(1, xtrait.methods.len());
+    }
+
+    #[test]
+    fn parse_alias_missing_semicolon() {
+        let err = parse_err("type Meters = int");
+        assert_eq!(Msg::ExpectedToken(";".into(), "<<EOF>>".into()), err.msg);
+        assert_eq!(1, err.pos.line);
+        assert_eq!(18, err.pos.column);
+    }
+
+    #[test]
+    fn parse_tuple() {
+        let (expr, _) = parse_expr("(1, a, 2+3)");
+        let tuple = expr.to_tuple().unwrap();
+
+        assert_eq!(3, tuple.values.len());
+        assert!(tuple.values[0].is_lit_int());
+        assert!(tuple.values[1].is_ident());
+        assert!(tuple.values[2].is_bin());
+
+        let (expr, _) = parse_expr("(1)");
+        assert!(expr.is_lit_int());
+    }
+
+    #[test]
+    fn parse_tuple_element() {
+        let (expr, interner) = parse_expr("a.1");
+        let elem = expr.to_tuple_element().unwrap();
+
+        assert_eq!(1, elem.idx);
+        assert_eq!("a", *interner.str(elem.object.to_ident().unwrap().name));
+    }
+
+    #[test]
+    fn parse_nested_tuple_element() {
+        let (expr, _) = parse_expr("a.0.1.f");
+        let field = expr.to_field().unwrap();
+        let outer = field.object.to_tuple_element().unwrap();
+        assert_eq!(1, outer.idx);
+
+        let inner = outer.object.to_tuple_element().unwrap();
+        assert_eq!(0, inner.idx);
+        assert!(inner.object.is_ident());
+    }
+
+    #[test]
+    fn parse_tuple_element_invalid_index() {
+        err_expr("a.1L", Msg::ExpectedTupleIndex("1L".into()), 1, 3);
+    }
+
+    #[test]
+    fn parse_let_tuple() {
+        let stmt = parse_stmt("let (a, _, b) = x;");
+        let var = stmt.to_var_tuple().unwrap();
+
+        assert_eq!(false, var.reassignable);
+        assert_eq!(3, var.vars.len());
+        assert!(var.vars[0].name.is_some());
+        assert!(var.vars[1].name.is_none());
+        assert!(var.vars[2].name.is_some());
+        assert!(var.data_type.is_none());
+        assert!(var.expr.is_ident());
+    }
+
+    #[test]
+    fn parse_var_tuple_with_type() {
+        let stmt = parse_stmt("var (a, b): (int, Str) = x;");
+        let var = stmt.to_var_tuple().unwrap();
+
+        assert_eq!(true, var.reassignable);
+        assert_eq!(2, var.data_type.as_ref().unwrap().to_tuple().unwrap().subtypes.len());
+    }
+
+    #[test]
+    fn parse_let_tuple_without_assignment() {
+        err_stmt("let (a, b);",
+                 Msg::ExpectedToken("=".into(), ";".into()),
+                 1,
+                 11);
+    }
+
     #[test]
     fn parse_empty_impl() {
         let (prog, interner) = parse("impl Foo for A {}");
         let ximpl = prog.impl0();
 
-        assert_eq!("Foo", *interner.str(ximpl.trait_name));
-        assert_eq!("A", *interner.str(ximpl.class_name));
+        assert_eq!("Foo", ximpl.trait_type.to_string(&interner));
+        assert_eq!("A", ximpl.class_type.to_string(&interner));
         assert_eq!(0, ximpl.methods.len());
     }
 
@@ -3103,19 +3979,35 @@ mod tests {
         let (prog, interner) = parse("impl Bar for B { fun foo(); }");
         let ximpl = prog.impl0();
 
-        assert_eq!("Bar", *interner.str(ximpl.trait_name));
-        assert_eq!("B", *interner.str(ximpl.class_name));
+        assert_eq!("Bar", ximpl.trait_type.to_string(&interner));
+        assert_eq!("B", ximpl.class_type.to_string(&interner));
         assert_eq!(1, ximpl.methods.len());
         assert_eq!(false, ximpl.methods[0].is_static);
     }
 
+    #[test]
+    fn parse_generic_impl() {
+        let (prog, interner) = parse("impl<T> Foo<T> for A<T> { }");
+        let ximpl = prog.impl0();
+
+        assert_eq!(1, ximpl.type_params.as_ref().unwrap().len());
+        assert_eq!("Foo<T>", ximpl.trait_type.to_string(&interner));
+        assert_eq!("A<T>", ximpl.class_type.to_string(&interner));
+
+        let (prog, interner) = parse("impl Foo<Str> for A { }");
+        let ximpl = prog.impl0();
+
+        assert!(ximpl.type_params.is_none());
+        assert_eq!("Foo<Str>", ximpl.trait_type.to_string(&interner));
+    }
+
     #[test]
     fn parse_impl_with_static_function() {
         let (prog, interner) = parse("impl Bar for B { static fun foo(); }");
         let ximpl = prog.impl0();
 
-        assert_eq!("Bar", *interner.str(ximpl.trait_name));
-        assert_eq!("B", *interner.str(ximpl.class_name));
+        assert_eq!("Bar", ximpl.trait_type.to_string(&interner));
+        assert_eq!("B", ximpl.class_type.to_string(&interner));
         assert_eq!(1, ximpl.methods.len());
         assert_eq!(true, ximpl.methods[0].is_static);
     }
@@ -3138,6 +4030,20 @@ mod tests {
         assert_eq!(false, global.reassignable);
     }
 
+    #[test]
+    fn parse_global_initializer() {
+        let (prog, interner) = parse("let b: int = 0;");
+        let global = prog.global0();
+        let initializer = global.initializer.as_ref().unwrap();
+
+        assert_eq!("b", *interner.str(initializer.name));
+        assert!(initializer.params.is_empty());
+        assert!(initializer.return_type.is_none());
+
+        let (prog, _) = parse("var b: int;");
+        assert!(prog.global0().initializer.is_none());
+    }
+
     #[test]
     fn parse_lit_char() {
         let (expr, _) = parse_expr("'a'");
@@ -3148,9 +4054,14 @@ mod tests {
 
     #[test]
     fn parse_spawn() {
-        let stmt = parse_stmt("spawn 1;");
-        let spawn = stmt.to_spawn().unwrap();
-        assert!(spawn.expr.is_lit_int());
+        let (expr, _) = parse_expr("spawn { 1; }");
+        let spawn = expr.to_spawn().unwrap();
+        assert!(spawn.fct.params.is_empty());
+        assert!(spawn.fct.return_type.is_none());
+
+        let stmt = parse_stmt("spawn { foo(); };");
+        let expr = &stmt.to_expr().unwrap().expr;
+        assert!(expr.is_spawn());
     }
 
     #[test]
@@ -3214,6 +4125,82 @@ mod tests {
         assert_eq!("x", *interner.str(xconst.name));
     }
 
+    #[test]
+    fn parse_pub_elements() {
+        let (prog, _) = parse("pub const x: int = 0;");
+        assert!(prog.const0().is_pub);
+
+        let (prog, _) = parse("pub var x: int;");
+        assert!(prog.global0().is_pub);
+
+        let (prog, _) = parse("pub internal class Foo");
+        assert!(prog.cls0().is_pub);
+
+        let (prog, _) = parse("pub fun f() {}");
+        assert!(prog.fct0().is_pub);
+
+        let (prog, _) = parse("fun f() {}");
+        assert!(!prog.fct0().is_pub);
+
+        let (prog, _) = parse("pub struct Foo { a: int }");
+        assert!(prog.struct0().is_pub);
+
+        let (prog, _) = parse("pub enum Foo { A }");
+        assert!(prog.enum0().is_pub);
+
+        let (prog, _) = parse("pub type Foo = int;");
+        assert!(prog.alias0().is_pub);
+
+        let (prog, _) = parse("pub trait Foo {}");
+        assert!(prog.trait0().is_pub);
+
+        let err = parse_err("pub impl Foo for Bar {}");
+        assert_eq!(Msg::MisplacedModifier("pub".into()), err.msg);
+    }
+
+    #[test]
+    fn parse_pub_members() {
+        let (prog, _) = parse("class Foo(pub let a: int, let b: int) { pub var c: int; var d: int; }");
+        let cls = prog.cls0();
+        assert!(cls.fields[0].is_pub);
+        assert!(!cls.fields[1].is_pub);
+        assert!(cls.fields[2].is_pub);
+        assert!(!cls.fields[3].is_pub);
+
+        let (prog, _) = parse("class Foo { pub fun f() {} fun g() {} pub init() {} }");
+        let cls = prog.cls0();
+        assert!(cls.methods[0].is_pub);
+        assert!(!cls.methods[1].is_pub);
+        assert!(cls.ctors[0].is_pub);
+
+        let err = parse_err("class Foo(pub a: int)");
+        assert_eq!(Msg::MisplacedModifier("pub".into()), err.msg);
+    }
+
+    #[test]
+    fn parse_import() {
+        let (prog, interner) = parse("import foo::bar::Baz;");
+        let import = prog.import0();
+
+        assert_eq!(3, import.path.len());
+        assert_eq!("foo", *interner.str(import.path[0]));
+        assert_eq!("Baz", *interner.str(import.path[2]));
+        assert!(!import.glob);
+
+        let (prog, interner) = parse("import foo::*;");
+        let import = prog.import0();
+
+        assert_eq!(1, import.path.len());
+        assert_eq!("foo", *interner.str(import.path[0]));
+        assert!(import.glob);
+
+        let err = parse_err("import foo::1;");
+        assert_eq!((1, 13), (err.pos.line, err.pos.column));
+
+        let err = parse_err("pub import foo;");
+        assert_eq!(Msg::MisplacedModifier("pub".into()), err.msg);
+    }
+
     #[test]
     fn parse_generic_with_bound() {
         let (prog, _) = parse("class A<T: Foo>");
@@ -3247,14 +4234,14 @@ mod tests {
         let (expr, _) = parse_expr("|| {}");
         let lambda = expr.to_lambda().unwrap();
 
-        assert!(lambda.ret.is_none());
+        assert!(lambda.fct.return_type.is_none());
     }
 
     #[test]
     fn parse_lambda_no_params_unit_as_return_value() {
         let (expr, _) = parse_expr("|| -> () {}");
         let lambda = expr.to_lambda().unwrap();
-        let ret = lambda.ret.as_ref().unwrap();
+        let ret = lambda.fct.return_type.as_ref().unwrap();
 
         assert!(ret.is_unit());
     }
@@ -3263,7 +4250,7 @@ mod tests {
     fn parse_lambda_no_params_with_return_value() {
         let (expr, interner) = parse_expr("|| -> A {}");
         let lambda = expr.to_lambda().unwrap();
-        let ret = lambda.ret.as_ref().unwrap();
+        let ret = lambda.fct.return_type.as_ref().unwrap();
         let basic = ret.to_basic().unwrap();
 
         assert_eq!("A", *interner.str(basic.name));
@@ -3274,14 +4261,14 @@ mod tests {
         let (expr, interner) = parse_expr("|a: A| -> B {}");
         let lambda = expr.to_lambda().unwrap();
 
-        assert_eq!(1, lambda.params.len());
+        assert_eq!(1, lambda.fct.params.len());
 
-        let param = &lambda.params[0];
+        let param = &lambda.fct.params[0];
         assert_eq!("a", *interner.str(param.name));
         let basic = param.data_type.to_basic().unwrap();
         assert_eq!("A", *interner.str(basic.name));
 
-        let ret = lambda.ret.as_ref().unwrap();
+        let ret = lambda.fct.return_type.as_ref().unwrap();
         let basic = ret.to_basic().unwrap();
 
         assert_eq!("B", *interner.str(basic.name));
@@ -3292,19 +4279,19 @@ mod tests {
         let (expr, interner) = parse_expr("|a: A, b: B| -> C {}");
         let lambda = expr.to_lambda().unwrap();
 
-        assert_eq!(2, lambda.params.len());
+        assert_eq!(2, lambda.fct.params.len());
 
-        let param = &lambda.params[0];
+        let param = &lambda.fct.params[0];
         assert_eq!("a", *interner.str(param.name));
         let basic = param.data_type.to_basic().unwrap();
         assert_eq!("A", *interner.str(basic.name));
 
-        let param = &lambda.params[1];
+        let param = &lambda.fct.params[1];
         assert_eq!("b", *interner.str(param.name));
         let basic = param.data_type.to_basic().unwrap();
         assert_eq!("B", *interner.str(basic.name));
 
-        let ret = lambda.ret.as_ref().unwrap();
+        let ret = lambda.fct.return_type.as_ref().unwrap();
         let basic = ret.to_basic().unwrap();
 
         assert_eq!("C", *interner.str(basic.name));
@@ -3315,4 +4302,73 @@ mod tests {
         let stmt = parse_stmt("for i in a+b {}");
         assert!(stmt.is_for());
     }
+
+    fn error_positions(diag: &Diagnostic) -> Vec<(u32, u32)> {
+        diag.errors()
+            .iter()
+            .map(|err| (err.pos.line, err.pos.column))
+            .collect()
+    }
+
+    #[test]
+    fn recover_from_statement_errors() {
+        let (ast, _, diag) = parse_with_errors("fun f() {\n  let x = ;\n  let y = 1;\n  \
+                                                foo(;\n}\nfun g() {}");
+        assert_eq!(vec![(2, 11), (4, 7)], error_positions(&diag));
+
+        let block = ast.fct(0).block.as_ref().unwrap().to_block().unwrap();
+        assert_eq!(3, block.stmts.len());
+        assert!(block.stmts[0].is_error());
+        assert!(block.stmts[1].to_var().is_some());
+        assert!(block.stmts[2].is_error());
+
+        assert_eq!(0, ast.fct(1).block.as_ref().unwrap().to_block().unwrap().stmts.len());
+    }
+
+    #[test]
+    fn recover_from_nested_block_errors() {
+        let (ast, _, diag) = parse_with_errors("fun f() { if x { 1 + ; } else { } g(); }");
+        assert_eq!(vec![(1, 22)], error_positions(&diag));
+
+        let block = ast.fct0().block.as_ref().unwrap().to_block().unwrap();
+        assert_eq!(2, block.stmts.len());
+        let then_block = block.stmts[0].to_if().unwrap().then_block.to_block().unwrap();
+        assert!(then_block.stmts[0].is_error());
+    }
+
+    #[test]
+    fn recover_from_missing_rbrace() {
+        let (ast, _, diag) = parse_with_errors("fun f() {\n  let x = 1;\nfun g() {}");
+        assert_eq!(vec![(3, 1)], error_positions(&diag));
+        assert_eq!(1, ast.files[0].elements.len());
+        assert!(ast.fct0().block.is_some());
+    }
+
+    #[test]
+    fn recover_from_top_level_errors() {
+        let (ast, interner, diag) = parse_with_errors("fun f( {}\n}\nclass A {}\nfun g() {}");
+        assert_eq!(vec![(1, 8)], error_positions(&diag));
+        assert_eq!(2, ast.files[0].elements.len());
+        assert_eq!("A", *interner.str(ast.cls(0).name));
+        assert_eq!("g", *interner.str(ast.fct(1).name));
+    }
+
+    #[test]
+    fn recover_from_class_element_errors() {
+        let (ast, _, diag) = parse_with_errors("class A {\n  var x: ;\n  42\n  fun f() {}\n}");
+        assert_eq!(vec![(2, 10), (3, 3)], error_positions(&diag));
+
+        let cls = ast.cls0();
+        assert_eq!(0, cls.fields.len());
+        assert_eq!(1, cls.methods.len());
+    }
+
+    #[test]
+    fn recover_from_lexer_errors() {
+        let (ast, _, diag) = parse_with_errors("fun f() { let x = 1 # 2; let y = 3; }");
+        assert_eq!(vec![(1, 21)], error_positions(&diag));
+
+        let block = ast.fct0().block.as_ref().unwrap().to_block().unwrap();
+        assert_eq!(2, block.stmts.len());
+    }
 }
@@ -0,0 +1,102 @@
+#!/usr/bin/ruby
+
+# Minimal JSON-RPC client for `dora-lsp`: opens a file, sends the given
+# requests and prints every message the server sends back.
+#
+#   ./lsp-client.rb [--release] file.dora [definition:LINE:COL] [hover:LINE:COL]
+#
+# LINE and COL are 0-based like in the protocol.
+
+require 'json'
+require 'open3'
+
+$ARGS = ARGV.clone
+$release = $ARGS.delete("--release") != nil
+
+METHODS = {
+  "definition" => "textDocument/definition",
+  "hover" => "textDocument/hover"
+}
+
+def binary
+  "target/#{$release ? "release" : "debug"}/dora-lsp"
+end
+
+def send_message(io, msg)
+  content = JSON.generate(msg)
+  io.write("Content-Length: #{content.bytesize}\r\n\r\n#{content}")
+  io.flush
+end
+
+def read_message(io)
+  len = nil
+
+  while (line = io.gets)
+    line = line.strip
+    break if line.empty?
+
+    name, value = line.split(":", 2)
+    len = value.to_i if name.casecmp("Content-Length") == 0
+  end
+
+  return nil if len.nil?
+  JSON.parse(io.read(len))
+end
+
+def request(stdin, stdout, id, method, params = {})
+  send_message(stdin, { jsonrpc: "2.0", id: id, method: method, params: params })
+
+  # print notifications until the response arrives
+  while (msg = read_message(stdout))
+    puts JSON.generate(msg)
+    return msg if msg["id"] == id
+  end
+end
+
+def notify(stdin, method, params = {})
+  send_message(stdin, { jsonrpc: "2.0", method: method, params: params })
+end
+
+def main
+  file = $ARGS.shift
+
+  if file.nil?
+    puts "usage: lsp-client.rb [--release] <file> [definition:LINE:COL | hover:LINE:COL]..."
+    exit 1
+  end
+
+  path = File.expand_path(file)
+  uri = "file://#{path}"
+  document = { uri: uri }
+
+  Open3.popen2(binary) do |stdin, stdout, thread|
+    request(stdin, stdout, 1, "initialize", { processId: Process.pid, capabilities: {} })
+    notify(stdin, "initialized")
+
+    notify(stdin, "textDocument/didOpen", {
+      textDocument: { uri: uri, languageId: "dora", version: 1, text: File.read(path) }
+    })
+    puts JSON.generate(read_message(stdout))
+
+    id = 2
+
+    $ARGS.each do |arg|
+      kind, line, column = arg.split(":")
+      method = METHODS.fetch(kind) { abort "unknown request `#{kind}`" }
+
+      request(stdin, stdout, id, method, {
+        textDocument: document,
+        position: { line: line.to_i, character: column.to_i }
+      })
+      id += 1
+    end
+
+    request(stdin, stdout, id, "textDocument/documentSymbol", { textDocument: document })
+    request(stdin, stdout, id + 1, "shutdown")
+    notify(stdin, "exit")
+
+    exit thread.value.exitstatus
+  end
+end
+
+main
@@ -18,7 +18,7 @@ use baseline::fct::{CatchType, Comment, CommentFormat, GcPoint, JitBaselineFct,
 use baseline::info::{self, JitInfo};
 use baseline::map::CodeData;
 use class::{ClassDef, TypeParams};
-use cpu::{Mem, FREG_PARAMS, FREG_RESULT, REG_PARAMS, REG_RESULT};
+use cpu::{Mem, FREG_PARAMS, FREG_RESULT, REG_PARAMS, REG_RESULT, REG_TMP1, REG_TMP2};
 use ctxt::{CallSite, Fct, FctId, FctParent, FctSrc, SemContext, VarId};
 use driver::cmd::AsmSyntax;
 use masm::*;
@@ -27,7 +27,8 @@ use opt;
 use os;
 use os::signal::Trap;
 use semck::always_returns;
-use semck::specialize::specialize_class_ty;
+use semck::specialize::{add_ref_fields, lambda_field_offsets, specialize_class_ty,
+                        specialize_struct_ty};
 use ty::{BuiltinType, MachineMode};
 
 pub fn generate<'ast>(
@@ -370,9 +371,42 @@ where
             }
         }
 
+        if self.fct.is_lambda() {
+            let closure = self.src.map_lambdas.get(self.ast.id).unwrap().closure;
+
+            self.masm.emit_comment(Comment::StoreParam(closure));
+            var_store(self.ctxt, &mut self.masm, &self.jit_info, REG_PARAMS[0].into(), closure);
+
+            let offset = self.jit_info.offset(closure);
+            self.scopes.add_var(closure, offset);
+
+            reg_idx += 1;
+        }
+
         for p in &self.ast.params {
             let varid = *self.src.map_vars.get(p.id).unwrap();
             let ty = self.jit_info.ty(varid);
+
+            if ty.is_struct() {
+                // struct arguments are passed by address, copy them into the local slot
+                let src = if reg_idx < REG_PARAMS.len() {
+                    reg_idx += 1;
+                    REG_PARAMS[reg_idx - 1]
+                } else {
+                    let offset = *self.jit_info.map_offsets.get(p.id).unwrap();
+                    self.masm
+                        .load_mem(MachineMode::Ptr, REG_TMP2.into(), Mem::Local(offset));
+                    REG_TMP2
+                };
+
+                self.masm.emit_comment(Comment::StoreParam(varid));
+                var_store(self.ctxt, &mut self.masm, &self.jit_info, src.into(), varid);
+
+                let offset = self.jit_info.offset(varid);
+                add_var_refs(self.ctxt, &mut self.scopes, ty, offset);
+                continue;
+            }
+
             let is_float = ty.mode().is_float();
 
             if ty.reference_type() {
@@ -384,20 +418,69 @@ where
                 let reg = FREG_PARAMS[freg_idx];
 
                 self.masm.emit_comment(Comment::StoreParam(varid));
-                var_store(&mut self.masm, &self.jit_info, reg.into(), varid);
+                var_store(self.ctxt, &mut self.masm, &self.jit_info, reg.into(), varid);
 
                 freg_idx += 1;
             } else if !is_float && reg_idx < REG_PARAMS.len() {
                 let reg = REG_PARAMS[reg_idx];
 
                 self.masm.emit_comment(Comment::StoreParam(varid));
-                var_store(&mut self.masm, &self.jit_info, reg.into(), varid);
+                var_store(self.ctxt, &mut self.masm, &self.jit_info, reg.into(), varid);
 
                 reg_idx += 1;
             } else {
                 // ignore params not stored in register
             }
         }
+
+        if let Some(offset) = self.jit_info.struct_return {
+            // the address for the returned struct is passed as last argument
+            if reg_idx < REG_PARAMS.len() {
+                self.masm
+                    .store_mem(MachineMode::Ptr, Mem::Local(offset), REG_PARAMS[reg_idx].into());
+            }
+        }
+
+        // slots for struct values are part of the gc point during the whole function
+        for &(offset, ty) in &self.jit_info.struct_slots {
+            clear_var_refs(self.ctxt, &mut self.masm, ty, offset);
+            add_var_refs(self.ctxt, &mut self.scopes, ty, offset);
+        }
+
+        if self.fct.is_lambda() {
+            self.load_captured_vars();
+        }
+    }
+
+    // copy captured values from the closure into the lambda's own variables
+    fn load_captured_vars(&mut self) {
+        let lambda = self.src.map_lambdas.get(self.ast.id).unwrap();
+        let captures: Vec<_> = lambda.captures.iter().map(|&var| self.jit_info.ty(var)).collect();
+        let (offsets, _) = lambda_field_offsets(self.ctxt, &captures);
+
+        for ((&var, ty), field_offset) in lambda.captures.iter().zip(captures).zip(offsets) {
+            let dest = register_for_type(ty);
+
+            var_load(&mut self.masm, &self.jit_info, lambda.closure, REG_TMP1.into());
+
+            if ty.is_struct() {
+                self.masm
+                    .load_address(REG_RESULT, Mem::Base(REG_TMP1, field_offset));
+            } else {
+                self.masm
+                    .load_mem(ty.mode(), dest, Mem::Base(REG_TMP1, field_offset));
+            }
+
+            var_store(self.ctxt, &mut self.masm, &self.jit_info, dest, var);
+
+            let offset = self.jit_info.offset(var);
+
+            if ty.is_struct() {
+                add_var_refs(self.ctxt, &mut self.scopes, ty, offset);
+            } else if ty.reference_type() {
+                self.scopes.add_var(var, offset);
+            }
+        }
     }
 
     fn emit_prolog(&mut self) {
@@ -420,9 +503,23 @@ where
         if let Some(ref expr) = s.expr {
             self.emit_expr(expr);
 
+            if return_type.is_struct() {
+                // copy value into the caller provided storage and return its address
+                let offset = self.jit_info.struct_return.unwrap();
+                self.masm
+                    .load_mem(MachineMode::Ptr, REG_TMP1.into(), Mem::Local(offset));
+                self.masm.copy_mem(
+                    Mem::Base(REG_TMP1, 0),
+                    Mem::Base(REG_RESULT, 0),
+                    return_type.size(self.ctxt),
+                    REG_TMP2,
+                );
+                self.masm.copy_reg(MachineMode::Ptr, REG_RESULT, REG_TMP1);
+            }
+
             if len > 0 {
                 let offset = self.jit_info.eh_return_value.unwrap();
-                let rmode = return_type.mode();
+                let rmode = return_mode(return_type);
                 self.masm
                     .store_mem(rmode, Mem::Local(offset), register_for_mode(rmode));
             }
@@ -449,7 +546,7 @@ where
 
             if s.expr.is_some() {
                 let offset = self.jit_info.eh_return_value.unwrap();
-                let rmode = return_type.mode();
+                let rmode = return_mode(return_type);
                 self.masm
                     .load_mem(rmode, register_for_mode(rmode), Mem::Local(offset));
             }
@@ -503,6 +600,15 @@ where
         self.masm
             .store_mem(MachineMode::Ptr, Mem::Local(offset), dest);
 
+        // for-variable is part of the gc point for the whole loop
+        let for_var_id = *self.src.map_vars.get(s.id).unwrap();
+        let for_var_ty = self.jit_info.ty(for_var_id);
+        let for_var_offset = self.jit_info.offset(for_var_id);
+        clear_var_refs(self.ctxt, &mut self.masm, for_var_ty, for_var_offset);
+
+        self.scopes.push_scope();
+        add_var_refs(self.ctxt, &mut self.scopes, for_var_ty, for_var_offset);
+
         let lbl_start = self.masm.create_label();
         let lbl_end = self.masm.create_label();
 
@@ -518,9 +624,7 @@ where
 
         // emit: <for_var> = iterator.next()
         let dest = self.emit_call_site(&for_info.next, s.pos);
-
-        let for_var_id = *self.src.map_vars.get(s.id).unwrap();
-        var_store(&mut self.masm, &self.jit_info, dest, for_var_id);
+        var_store(self.ctxt, &mut self.masm, &self.jit_info, dest, for_var_id);
 
         self.save_label_state(lbl_end, lbl_start, |this| {
             // execute while body, then jump back to condition
@@ -532,6 +636,7 @@ where
 
         self.masm.bind_label(lbl_end);
         self.active_loop = saved_active_loop;
+        self.scopes.pop_scope();
     }
 
     fn emit_stmt_loop(&mut self, s: &'ast StmtLoopType) {
@@ -563,6 +668,14 @@ where
         self.masm.emit_gcpoint(gcpoint);
     }
 
+    // exceptions for traps are allocated right before entering their handler,
+    // at that point the GC needs to know the handler's live references
+    fn emit_handler_gcpoint(&mut self) {
+        let temps = TempOffsets::new();
+        let gcpoint = create_gcpoint(&self.scopes, &temps);
+        self.masm.emit_gcpoint(gcpoint);
+    }
+
     fn save_label_state<F>(&mut self, lbl_break: Label, lbl_continue: Label, f: F)
     where
         F: FnOnce(&mut CodeGen<'a, 'ast>),
@@ -651,12 +764,73 @@ where
 
     fn emit_stmt_block(&mut self, s: &'ast StmtBlockType) {
         self.scopes.push_scope();
+        self.emit_stmts(&s.stmts);
+        self.scopes.pop_scope();
+    }
+
+    fn emit_stmts(&mut self, stmts: &'ast [Box<Stmt>]) {
+        for (idx, stmt) in stmts.iter().enumerate() {
+            // all following statements of the block are guarded by the defer
+            if let StmtDefer(ref defer) = **stmt {
+                self.emit_stmt_defer(stmt, defer, &stmts[idx + 1..]);
+                return;
+            }
 
-        for stmt in &s.stmts {
             self.visit_stmt(stmt);
         }
+    }
+
+    // the deferred expression is handled like a finally block around
+    // the rest of the block: it is executed on fall-through, return,
+    // break/continue and when an exception unwinds through the block
+    fn emit_stmt_defer(
+        &mut self,
+        stmt: &'ast Stmt,
+        defer: &'ast StmtDeferType,
+        rest: &'ast [Box<Stmt>],
+    ) {
+        let lbl_after = self.masm.create_label();
+
+        self.active_finallys.push(stmt);
+        self.scopes.push_scope();
+
+        let start = self.masm.pos();
+        self.emit_stmts(rest);
+        let end = self.masm.pos();
 
         self.scopes.pop_scope();
+        self.active_finallys.pop();
+
+        if !rest.iter().any(|stmt| always_returns(stmt)) {
+            self.emit_expr(&defer.expr);
+            self.masm.jump(lbl_after);
+        }
+
+        let handler_start = self.masm.pos();
+        let offset = *self.jit_info.map_offsets.get(defer.id).unwrap();
+
+        self.scopes.push_scope();
+        self.scopes.add_var_offset(offset);
+        self.emit_handler_gcpoint();
+
+        self.emit_expr(&defer.expr);
+
+        self.masm
+            .load_mem(MachineMode::Ptr, REG_RESULT.into(), Mem::Local(offset));
+        self.masm.trap(Trap::THROW);
+
+        self.scopes.pop_scope();
+
+        self.masm.bind_label(lbl_after);
+
+        if start != end {
+            self.masm.emit_exception_handler(
+                (start, end),
+                handler_start,
+                Some(offset),
+                CatchType::Any,
+            );
+        }
     }
 
     fn emit_stmt_var(&mut self, s: &'ast StmtVarType) {
@@ -667,25 +841,74 @@ where
             let value = self.emit_expr(expr);
             initialized = true;
 
-            var_store(&mut self.masm, &self.jit_info, value, var);
+            var_store(self.ctxt, &mut self.masm, &self.jit_info, value, var);
         }
 
-        let reference_type = {
-            let ty = self.jit_info.ty(var);
+        let ty = self.jit_info.ty(var);
 
-            if ty.reference_type() {
-                let offset = self.jit_info.offset(var);
-                self.scopes.add_var(var, offset);
+        if ty.is_struct() {
+            let offset = self.jit_info.offset(var);
+
+            if !initialized {
+                clear_var_refs(self.ctxt, &mut self.masm, ty, offset);
             }
 
-            ty.reference_type()
-        };
+            add_var_refs(self.ctxt, &mut self.scopes, ty, offset);
+            return;
+        }
+
+        let reference_type = ty.reference_type();
+
+        if reference_type {
+            let offset = self.jit_info.offset(var);
+            self.scopes.add_var(var, offset);
+        }
 
         // uninitialized variables which reference objects need to be initialized to null
         // otherwise the GC  can't know if the stored value is a valid pointer
         if reference_type && !initialized {
             self.masm.load_nil(REG_RESULT);
-            var_store(&mut self.masm, &self.jit_info, REG_RESULT.into(), var);
+            var_store(self.ctxt, &mut self.masm, &self.jit_info, REG_RESULT.into(), var);
+        }
+    }
+
+    fn emit_stmt_var_tuple(&mut self, s: &'ast StmtVarTupleType) {
+        let value = self.emit_expr(&s.expr);
+        let ty = self.specialize_type(self.src.ty(s.expr.id()));
+
+        let struct_id = specialize_struct_ty(self.ctxt, ty);
+        let fields = self.ctxt.struct_defs[struct_id].borrow().fields.clone();
+
+        // copy elements out of the tuple, the tuple's address stays in `value`
+        for (var, field) in s.vars.iter().zip(fields.iter()) {
+            let var = match self.src.map_vars.get(var.id) {
+                Some(&var) => var,
+                None => continue,
+            };
+
+            let offset = self.jit_info.offset(var);
+            let ty = self.jit_info.ty(var);
+            let element = Mem::Base(value.reg(), field.offset);
+
+            if ty.is_struct() {
+                self.masm
+                    .copy_mem(Mem::Local(offset), element, ty.size(self.ctxt), REG_TMP1);
+                add_var_refs(self.ctxt, &mut self.scopes, ty, offset);
+                continue;
+            }
+
+            let tmp = if ty.is_float() {
+                FREG_RESULT.into()
+            } else {
+                REG_TMP1.into()
+            };
+
+            self.masm.load_mem(ty.mode(), tmp, element);
+            self.masm.store_mem(ty.mode(), Mem::Local(offset), tmp);
+
+            if ty.reference_type() {
+                self.scopes.add_var(var, offset);
+            }
         }
     }
 
@@ -735,6 +958,7 @@ where
 
             self.scopes.push_scope();
             self.scopes.add_var(varid, offset);
+            self.emit_handler_gcpoint();
 
             let catch_span = self.stmt_with_finally(s, &catch.block, lbl_after);
 
@@ -797,6 +1021,7 @@ where
 
         let offset = *self.jit_info.map_offsets.get(s.id).unwrap();
         self.scopes.add_var_offset(offset);
+        self.emit_handler_gcpoint();
 
         self.visit_stmt(&finally_block.block);
 
@@ -842,10 +1067,7 @@ where
     }
 
     fn emit_call_site(&mut self, call_site: &CallSite<'ast>, pos: Position) -> ExprStore {
-        let callee = self.ctxt.fcts[call_site.callee].borrow();
-        let return_type = self.specialize_type(callee.return_type);
-
-        let dest = register_for_mode(return_type.mode());
+        let dest = register_for_type(call_site.return_type);
 
         let mut expr_gen = ExprGen::new(
             self.ctxt,
@@ -867,12 +1089,12 @@ where
     fn specialize_type(&self, ty: BuiltinType) -> BuiltinType {
         match ty {
             BuiltinType::ClassTypeParam(cls_id, id) => {
-                debug_assert!(self.fct.parent == FctParent::Class(cls_id));
+                debug_assert!(self.fct.is_lambda() || self.fct.is_method_of(self.ctxt, cls_id));
                 self.cls_type_params[id.idx()]
             }
 
             BuiltinType::FctTypeParam(fct_id, id) => {
-                debug_assert!(self.fct.id == fct_id);
+                debug_assert!(self.fct.id == fct_id || self.fct.parent == FctParent::Fct(fct_id));
                 self.fct_type_params[id.idx()]
             }
 
@@ -886,7 +1108,36 @@ where
                 BuiltinType::Class(cls_id, list_id)
             }
 
-            BuiltinType::Lambda(_) => unimplemented!(),
+            BuiltinType::Lambda(lambda_id) => {
+                let lambda = self.ctxt.lambda_types.borrow().get(lambda_id);
+                let params: Vec<_> = lambda
+                    .params()
+                    .iter()
+                    .map(|&t| self.specialize_type(t))
+                    .collect();
+                let ret = self.specialize_type(lambda.ret());
+
+                let lambda_id = self.ctxt.lambda_types.borrow_mut().insert(params, ret);
+
+                BuiltinType::Lambda(lambda_id)
+            }
+
+            BuiltinType::Tuple(tuple_id) => {
+                let elements = self.ctxt.tuples.borrow().get(tuple_id);
+                let elements: Vec<_> = elements
+                    .iter()
+                    .map(|&t| self.specialize_type(t))
+                    .collect();
+
+                let tuple_id = self.ctxt.tuples.borrow_mut().insert(elements);
+
+                BuiltinType::Tuple(tuple_id)
+            }
+
+            BuiltinType::Nullable(_) => {
+                let ty = self.specialize_type(ty.non_nullable(self.ctxt));
+                BuiltinType::nullable(self.ctxt, ty)
+            }
 
             _ => ty,
         }
@@ -914,10 +1165,14 @@ impl<'a, 'ast> visit::Visitor<'ast> for CodeGen<'a, 'ast> {
             StmtContinue(ref stmt) => self.emit_stmt_continue(stmt),
             StmtBlock(ref stmt) => self.emit_stmt_block(stmt),
             StmtVar(ref stmt) => self.emit_stmt_var(stmt),
+            StmtVarTuple(ref stmt) => self.emit_stmt_var_tuple(stmt),
             StmtThrow(ref stmt) => self.emit_stmt_throw(stmt),
-            StmtDefer(_) => unimplemented!(),
+            // executes deferred expression, defer itself is handled by emit_stmts
+            StmtDefer(ref stmt) => {
+                self.emit_expr(&stmt.expr);
+            }
             StmtDo(ref stmt) => self.emit_stmt_do(stmt),
-            StmtSpawn(_) => unimplemented!(),
+            StmtError(_) => unreachable!("syntax error in code generation"),
         }
     }
 
@@ -940,18 +1195,75 @@ pub enum CondCode {
     UnsignedGreaterEq,
     UnsignedLess,
     UnsignedLessEq,
+    Overflow,
 }
 
-pub fn var_store(masm: &mut MacroAssembler, jit_info: &JitInfo, src: ExprStore, var_id: VarId) {
+pub fn var_store(
+    ctxt: &SemContext,
+    masm: &mut MacroAssembler,
+    jit_info: &JitInfo,
+    src: ExprStore,
+    var_id: VarId,
+) {
     let offset = jit_info.offset(var_id);
     let ty = jit_info.ty(var_id);
-    masm.store_mem(ty.mode(), Mem::Local(offset), src);
+
+    if ty.is_struct() {
+        let tmp = if src.reg() == REG_TMP1 { REG_TMP2 } else { REG_TMP1 };
+        masm.copy_mem(Mem::Local(offset), Mem::Base(src.reg(), 0), ty.size(ctxt), tmp);
+    } else {
+        masm.store_mem(ty.mode(), Mem::Local(offset), src);
+    }
 }
 
 pub fn var_load(masm: &mut MacroAssembler, jit_info: &JitInfo, var_id: VarId, dest: ExprStore) {
     let offset = jit_info.offset(var_id);
     let ty = jit_info.ty(var_id);
-    masm.load_mem(ty.mode(), dest, Mem::Local(offset));
+
+    if ty.is_struct() {
+        masm.load_address(dest.reg(), Mem::Local(offset));
+    } else {
+        masm.load_mem(ty.mode(), dest, Mem::Local(offset));
+    }
+}
+
+// only the address of a returned struct is kept in a register
+fn return_mode(ty: BuiltinType) -> MachineMode {
+    if ty.is_struct() {
+        MachineMode::Ptr
+    } else {
+        ty.mode()
+    }
+}
+
+// struct values are passed around by their address
+pub fn register_for_type(ty: BuiltinType) -> ExprStore {
+    if ty.is_float() {
+        FREG_RESULT.into()
+    } else {
+        REG_RESULT.into()
+    }
+}
+
+// adds all references stored in a value at `offset` to the gc point
+pub fn add_var_refs(ctxt: &SemContext, scopes: &mut Scopes, ty: BuiltinType, offset: i32) {
+    let mut ref_fields = Vec::new();
+    add_ref_fields(ctxt, &mut ref_fields, ty, offset);
+
+    for offset in ref_fields {
+        scopes.add_var_offset(offset);
+    }
+}
+
+// initializes all references in a value to nil
+pub fn clear_var_refs(ctxt: &SemContext, masm: &mut MacroAssembler, ty: BuiltinType, offset: i32) {
+    let mut ref_fields = Vec::new();
+    add_ref_fields(ctxt, &mut ref_fields, ty, offset);
+
+    for offset in ref_fields {
+        masm.load_nil(REG_TMP1);
+        masm.store_mem(MachineMode::Ptr, Mem::Local(offset), REG_TMP1.into());
+    }
 }
 
 pub struct Scopes {
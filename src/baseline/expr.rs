@@ -1,6 +1,7 @@
 use dora_parser::ast::*;
 use dora_parser::ast::Expr::*;
-use baseline::codegen::{self, dump_asm, register_for_mode, should_emit_asm, should_emit_debug,
+use baseline;
+use baseline::codegen::{self, dump_asm, register_for_type, should_emit_asm, should_emit_debug,
                         CondCode, Scopes, TempOffsets};
 use baseline::fct::{CatchType, Comment};
 use baseline::info::JitInfo;
@@ -13,11 +14,15 @@ use ctxt::*;
 use driver::cmd::AsmSyntax;
 use dora_parser::lexer::position::Position;
 use dora_parser::lexer::token::{FloatSuffix, IntSuffix};
+use exception;
+use globals;
 use masm::*;
 use mem;
-use object::{Header, Str};
+use object::{offset_of_array_data, Header, Str};
 use os::signal::Trap;
-use semck::specialize::{specialize_class_id, specialize_class_ty};
+use semck::specialize::{add_ref_fields, lambda_field_offsets, specialize_class_id,
+                        specialize_class_ty, specialize_enum_variant, specialize_lambda,
+                        specialize_struct_ty};
 use stdlib;
 use threads::ThreadLocalData;
 use ty::{BuiltinType, MachineMode};
@@ -130,6 +135,7 @@ where
             ExprLitFloat(ref expr) => self.emit_lit_float(expr, dest.freg()),
             ExprLitBool(ref expr) => self.emit_lit_bool(expr, dest.reg()),
             ExprLitStr(ref expr) => self.emit_lit_str(expr, dest.reg()),
+            ExprTemplate(ref expr) => self.emit_template(expr, dest.reg()),
             ExprLitStruct(ref expr) => self.emit_lit_struct(expr, dest),
             ExprUn(ref expr) => self.emit_un(expr, dest),
             ExprIdent(ref expr) => self.emit_ident(expr, dest),
@@ -144,7 +150,12 @@ where
             ExprArray(ref expr) => self.emit_array(expr, dest),
             ExprConv(ref expr) => self.emit_conv(expr, dest.reg()),
             ExprTry(ref expr) => self.emit_try(expr, dest),
-            ExprLambda(_) => unimplemented!(),
+            ExprLambda(ref expr) => self.emit_closure(&expr.fct, expr.pos, dest.reg()),
+            ExprSpawn(ref expr) => self.emit_spawn(expr, dest.reg()),
+            ExprPath(ref expr) => self.emit_path(expr, dest.reg()),
+            ExprMatch(ref expr) => self.emit_match(expr, dest),
+            ExprTuple(ref expr) => self.emit_tuple(expr, dest),
+            ExprTupleElement(ref expr) => self.emit_tuple_element(expr, dest),
         }
     }
 
@@ -354,7 +365,12 @@ where
             match intrinsic {
                 Intrinsic::GenericArrayGet => {
                     let ty = self.ty(e.id);
-                    self.emit_array_get(e.pos, ty.mode(), &e.object, &e.index, dest);
+
+                    if ty.is_struct() {
+                        self.emit_array_get_struct(e, ty, dest.reg());
+                    } else {
+                        self.emit_array_get(e.pos, ty.mode(), &e.object, &e.index, dest);
+                    }
                 }
 
                 Intrinsic::StrGet => {
@@ -382,13 +398,38 @@ where
 
     fn reserve_temp_for_arg(&mut self, arg: &Arg<'ast>) -> i32 {
         let offset = -(self.jit_info.localsize + arg.offset());
-        let ty = arg.ty();
+        offset
+    }
 
-        if ty.reference_type() {
+    // arguments are only part of the gc point after the value was stored
+    fn add_temp_refs(&mut self, ty: BuiltinType, offset: i32) {
+        let mut ref_fields = Vec::new();
+        add_ref_fields(self.ctxt, &mut ref_fields, ty, offset);
+
+        for offset in ref_fields {
             self.temps.insert(offset);
         }
+    }
 
-        offset
+    fn store_arg(&mut self, ty: BuiltinType, offset: i32, value: ExprStore) {
+        if ty.is_struct() {
+            let size = ty.size(self.ctxt);
+            self.masm
+                .copy_mem(Mem::Local(offset), Mem::Base(value.reg(), 0), size, REG_TMP1);
+        } else {
+            self.masm.store_mem(ty.mode(), Mem::Local(offset), value);
+        }
+
+        self.add_temp_refs(ty, offset);
+    }
+
+    // struct arguments are passed as pointer to the copy in the temporary
+    fn load_arg(&mut self, ty: BuiltinType, offset: i32, dest: ExprStore) {
+        if ty.is_struct() {
+            self.masm.load_address(dest.reg(), Mem::Local(offset));
+        } else {
+            self.masm.load_mem(ty.mode(), dest, Mem::Local(offset));
+        }
     }
 
     fn reserve_temp_for_self(&mut self, arg: &Arg<'ast>) -> i32 {
@@ -406,7 +447,10 @@ where
     }
 
     fn free_temp_with_type(&mut self, ty: BuiltinType, offset: i32) {
-        if ty.reference_type() {
+        let mut ref_fields = Vec::new();
+        add_ref_fields(self.ctxt, &mut ref_fields, ty, offset);
+
+        for offset in ref_fields {
             self.temps.remove(offset);
         }
     }
@@ -430,23 +474,30 @@ where
     }
 
     fn emit_field(&mut self, expr: &'ast ExprFieldType, dest: ExprStore) {
-        let (ty, field) = {
-            let ident_type = self.src.map_idents.get(expr.id).unwrap();
+        let &ident_type = self.src.map_idents.get(expr.id).unwrap();
 
-            match ident_type {
-                &IdentType::Field(ty, field) => (ty, field),
-                _ => unreachable!(),
+        match ident_type {
+            IdentType::Field(ty, field) => {
+                let ty = self.specialize_type(ty);
+
+                self.emit_expr(&expr.object, REG_RESULT.into());
+                self.emit_field_access(expr.id, expr.pos, ty, field, REG_RESULT, dest);
             }
-        };
 
-        let ty = self.specialize_type(ty);
+            IdentType::StructField(ty, field) => {
+                let ty = self.specialize_type(ty);
+
+                self.emit_expr(&expr.object, REG_RESULT.into());
+                self.emit_struct_field_access(ty, field, REG_RESULT, dest);
+            }
 
-        self.emit_expr(&expr.object, REG_RESULT.into());
-        self.emit_field_access(expr.pos, ty, field, REG_RESULT, dest);
+            _ => unreachable!(),
+        }
     }
 
     fn emit_field_access(
         &mut self,
+        id: NodeId,
         pos: Position,
         ty: BuiltinType,
         fieldid: FieldId,
@@ -458,8 +509,84 @@ where
         let field = &cls.fields[fieldid.idx()];
 
         self.masm.emit_comment(Comment::LoadField(cls_id, fieldid));
-        self.masm
-            .load_field(field.ty.mode(), dest, src, field.offset, pos.line as i32);
+
+        if field.ty.is_struct() {
+            // copy struct out of the object, the object could be moved by the GC
+            let offset = *self.jit_info.map_offsets.get(id).unwrap();
+            let size = field.ty.size(self.ctxt);
+            let tmp = if src == REG_TMP1 { REG_TMP2 } else { REG_TMP1 };
+
+            self.masm.test_if_nil_bailout(pos, src, Trap::NIL);
+            self.masm
+                .copy_mem(Mem::Local(offset), Mem::Base(src, field.offset), size, tmp);
+            self.masm.load_address(dest.reg(), Mem::Local(offset));
+        } else {
+            self.masm
+                .load_field(field.ty.mode(), dest, src, field.offset, pos.line as i32);
+        }
+    }
+
+    // `src` contains the address of the struct value
+    fn emit_struct_field_access(
+        &mut self,
+        ty: BuiltinType,
+        fieldid: StructFieldId,
+        src: Reg,
+        dest: ExprStore,
+    ) {
+        let struct_id = specialize_struct_ty(self.ctxt, ty);
+        let struc = self.ctxt.struct_defs[struct_id].borrow();
+        let field = &struc.fields[fieldid.idx()];
+
+        if field.ty.is_struct() {
+            self.masm
+                .load_address(dest.reg(), Mem::Base(src, field.offset));
+        } else {
+            self.masm
+                .load_mem(field.ty.mode(), dest, Mem::Base(src, field.offset));
+        }
+    }
+
+    fn emit_tuple(&mut self, e: &'ast ExprTupleType, dest: ExprStore) {
+        let ty = self.ty(e.id);
+        let offset = *self.jit_info.map_offsets.get(e.id).unwrap();
+
+        let struct_id = specialize_struct_ty(self.ctxt, ty);
+        let fields = self.ctxt.struct_defs[struct_id].borrow().fields.clone();
+
+        for (value, field) in e.values.iter().zip(fields.iter()) {
+            let reg = register_for_type(field.ty);
+            self.emit_expr(value, reg);
+
+            let field_mem = Mem::Local(offset + field.offset);
+
+            if field.ty.is_struct() {
+                let size = field.ty.size(self.ctxt);
+                self.masm
+                    .copy_mem(field_mem, Mem::Base(reg.reg(), 0), size, REG_TMP1);
+            } else {
+                self.masm.store_mem(field.ty.mode(), field_mem, reg);
+            }
+        }
+
+        self.masm.load_address(dest.reg(), Mem::Local(offset));
+    }
+
+    fn emit_tuple_element(&mut self, e: &'ast ExprTupleElementType, dest: ExprStore) {
+        let ty = self.ty(e.object.id());
+        self.emit_expr(&e.object, REG_RESULT.into());
+
+        let struct_id = specialize_struct_ty(self.ctxt, ty);
+        let struc = self.ctxt.struct_defs[struct_id].borrow();
+        let field = &struc.fields[e.idx as usize];
+
+        if field.ty.is_struct() {
+            self.masm
+                .load_address(dest.reg(), Mem::Base(REG_RESULT, field.offset));
+        } else {
+            self.masm
+                .load_mem(field.ty.mode(), dest, Mem::Base(REG_RESULT, field.offset));
+        }
     }
 
     fn emit_lit_char(&mut self, lit: &'ast ExprLitCharType, dest: Reg) {
@@ -470,8 +597,9 @@ where
     fn emit_lit_int(&mut self, lit: &'ast ExprLitIntType, dest: Reg) {
         let ty = match lit.suffix {
             IntSuffix::Byte => MachineMode::Int8,
-            IntSuffix::Int => MachineMode::Int32,
-            IntSuffix::Long => MachineMode::Int64,
+            IntSuffix::Short => MachineMode::Int16,
+            IntSuffix::Int | IntSuffix::UInt => MachineMode::Int32,
+            IntSuffix::Long | IntSuffix::ULong => MachineMode::Int64,
         };
 
         self.masm.load_int_const(ty, dest, lit.value as i64);
@@ -504,8 +632,84 @@ where
         self.masm.load_constpool(dest, disp + pos);
     }
 
-    fn emit_lit_struct(&mut self, _: &'ast ExprLitStructType, _: ExprStore) {
-        unimplemented!();
+    fn emit_template(&mut self, e: &'ast ExprTemplateType, dest: Reg) {
+        let info = self.jit_info.map_templates.get(e.id).unwrap().clone();
+        let mut offsets = Vec::with_capacity(info.parts.len());
+
+        for part in &info.parts {
+            let pos = part.expr.pos();
+
+            if let Some(ref to_string) = part.to_string {
+                self.emit_call_site(to_string, pos, REG_RESULT.into());
+            } else {
+                self.emit_expr(part.expr, REG_RESULT.into());
+            }
+
+            self.masm.test_if_nil_bailout(pos, REG_RESULT, Trap::NIL);
+
+            let offset = -(self.jit_info.localsize + part.offset);
+            self.masm
+                .store_mem(MachineMode::Ptr, Mem::Local(offset), REG_RESULT.into());
+            self.temps.insert(offset);
+            offsets.push(offset);
+        }
+
+        // the first part is stored at the lowest address
+        let start = offsets.first().cloned().unwrap_or(-self.jit_info.localsize);
+
+        self.masm.load_address(REG_PARAMS[0], Mem::Local(start));
+        self.masm
+            .load_int_const(MachineMode::Int32, REG_PARAMS[1], offsets.len() as i64);
+
+        let internal_fct = InternalFct {
+            ptr: stdlib::str_concat_parts as *mut u8,
+            args: &[BuiltinType::Ptr, BuiltinType::Int],
+            return_type: BuiltinType::Ptr,
+            throws: false,
+            id: FctId(0),
+        };
+
+        self.emit_native_call_insn(e.pos, internal_fct, dest.into());
+
+        for offset in offsets {
+            self.temps.remove(offset);
+        }
+    }
+
+    fn emit_lit_struct(&mut self, e: &'ast ExprLitStructType, dest: ExprStore) {
+        let ty = self.ty(e.id);
+        let offset = *self.jit_info.map_offsets.get(e.id).unwrap();
+
+        let struct_id = specialize_struct_ty(self.ctxt, ty);
+        let fields = self.ctxt.struct_defs[struct_id].borrow().fields.clone();
+
+        let sid = match ty {
+            BuiltinType::Struct(sid, _) => sid,
+            _ => unreachable!(),
+        };
+
+        for arg in &e.args {
+            let idx = {
+                let struc = self.ctxt.structs[sid].borrow();
+                struc.fields.iter().position(|f| f.name == arg.name).unwrap()
+            };
+
+            let field = &fields[idx];
+            let value = register_for_type(field.ty);
+            self.emit_expr(&arg.expr, value);
+
+            let field_mem = Mem::Local(offset + field.offset);
+
+            if field.ty.is_struct() {
+                let size = field.ty.size(self.ctxt);
+                self.masm
+                    .copy_mem(field_mem, Mem::Base(value.reg(), 0), size, REG_TMP1);
+            } else {
+                self.masm.store_mem(field.ty.mode(), field_mem, value);
+            }
+        }
+
+        self.masm.load_address(dest.reg(), Mem::Local(offset));
     }
 
     fn emit_ident(&mut self, e: &'ast ExprIdentType, dest: ExprStore) {
@@ -518,23 +722,31 @@ where
             }
 
             IdentType::Global(gid) => {
+                self.emit_init_global(e.pos, gid);
                 let glob = self.ctxt.globals[gid].borrow();
 
                 let disp = self.masm.add_addr(glob.address_value);
                 let pos = self.masm.pos() as i32;
 
                 self.masm.emit_comment(Comment::LoadGlobal(gid));
-                self.masm.load_constpool(REG_TMP1, disp + pos);
 
-                self.masm
-                    .load_mem(glob.ty.mode(), dest, Mem::Base(REG_TMP1, 0));
+                if glob.ty.is_struct() {
+                    self.masm.load_constpool(dest.reg(), disp + pos);
+                } else {
+                    self.masm.load_constpool(REG_TMP1, disp + pos);
+
+                    self.masm
+                        .load_mem(glob.ty.mode(), dest, Mem::Base(REG_TMP1, 0));
+                }
             }
 
             IdentType::Field(cls, field) => {
                 self.emit_self(REG_RESULT);
-                self.emit_field_access(e.pos, cls, field, REG_RESULT, dest);
+                self.emit_field_access(e.id, e.pos, cls, field, REG_RESULT, dest);
             }
 
+            IdentType::StructField(_, _) => unreachable!(),
+
             IdentType::Struct(_) => {
                 unimplemented!();
             }
@@ -566,7 +778,12 @@ where
                 );
             }
 
-            BuiltinType::Byte | BuiltinType::Int | BuiltinType::Long => {
+            BuiltinType::Byte
+            | BuiltinType::Short
+            | BuiltinType::Int
+            | BuiltinType::UInt
+            | BuiltinType::Long
+            | BuiltinType::ULong => {
                 self.masm
                     .load_int_const(ty.mode(), dest.reg(), xconst.value.to_int());
             }
@@ -602,6 +819,12 @@ where
                     self.masm.int_neg(mode, dest, dest);
                 }
 
+                Intrinsic::ShortNeg => {
+                    let dest = dest.reg();
+                    self.masm.int_neg(MachineMode::Int32, dest, dest);
+                    self.masm.extend_short(dest, dest);
+                }
+
                 Intrinsic::FloatNeg | Intrinsic::DoubleNeg => {
                     let dest = dest.freg();
 
@@ -651,6 +874,12 @@ where
                 match intrinsic {
                     Intrinsic::GenericArraySet => {
                         let ty = self.ty(array.id);
+
+                        if ty.is_struct() {
+                            self.emit_array_set_struct(e.pos, ty, array, &e.rhs);
+                            return;
+                        }
+
                         self.emit_array_set(
                             e.pos,
                             ty,
@@ -684,16 +913,17 @@ where
         match ident_type {
             IdentType::Var(varid) => {
                 let ty = self.jit_info.ty(varid);
-                let dest = result_reg(ty.mode());
+                let dest = register_for_type(ty);
                 self.emit_expr(&e.rhs, dest);
 
                 self.masm.emit_comment(Comment::StoreVar(varid));
-                codegen::var_store(&mut self.masm, self.jit_info, dest, varid);
+                codegen::var_store(self.ctxt, &mut self.masm, self.jit_info, dest, varid);
             }
 
             IdentType::Global(gid) => {
+                self.emit_init_global(e.pos, gid);
                 let glob = self.ctxt.globals[gid].borrow();
-                let dest = result_reg(glob.ty.mode());
+                let dest = register_for_type(glob.ty);
                 self.emit_expr(&e.rhs, dest);
 
                 let disp = self.masm.add_addr(glob.address_value);
@@ -702,8 +932,14 @@ where
                 self.masm.emit_comment(Comment::StoreGlobal(gid));
                 self.masm.load_constpool(REG_TMP1, disp + pos);
 
-                self.masm
-                    .store_mem(glob.ty.mode(), Mem::Base(REG_TMP1, 0), dest);
+                if glob.ty.is_struct() {
+                    let size = glob.ty.size(self.ctxt);
+                    self.masm
+                        .copy_mem(Mem::Base(REG_TMP1, 0), Mem::Base(dest.reg(), 0), size, REG_TMP2);
+                } else {
+                    self.masm
+                        .store_mem(glob.ty.mode(), Mem::Base(REG_TMP1, 0), dest);
+                }
             }
 
             IdentType::Field(ty, fieldid) => {
@@ -726,25 +962,46 @@ where
                 self.masm
                     .store_mem(MachineMode::Ptr, Mem::Local(temp_offset), REG_RESULT.into());
 
-                let reg = result_reg(field.ty.mode());
+                let reg = register_for_type(field.ty);
                 self.emit_expr(&e.rhs, reg);
                 self.masm
                     .load_mem(MachineMode::Ptr, REG_TMP1.into(), Mem::Local(temp_offset));
 
                 self.masm.emit_comment(Comment::StoreField(cls_id, fieldid));
 
-                let write_barrier = self.ctxt.gc.needs_write_barrier() && field.ty.reference_type();
                 let card_table_offset = self.ctxt.gc.card_table_offset();
 
-                self.masm.store_field(
-                    field.ty.mode(),
-                    REG_TMP1,
-                    field.offset,
-                    reg,
-                    e.pos.line as i32,
-                    write_barrier,
-                    card_table_offset,
-                );
+                if field.ty.is_struct() {
+                    let size = field.ty.size(self.ctxt);
+                    let mut ref_fields = Vec::new();
+                    add_ref_fields(self.ctxt, &mut ref_fields, field.ty, 0);
+
+                    self.masm.test_if_nil_bailout(e.pos, REG_TMP1, Trap::NIL);
+                    self.masm.copy_mem(
+                        Mem::Base(REG_TMP1, field.offset),
+                        Mem::Base(reg.reg(), 0),
+                        size,
+                        REG_TMP2,
+                    );
+
+                    if self.ctxt.gc.needs_write_barrier() && !ref_fields.is_empty() {
+                        self.masm.emit_barrier(REG_TMP1, card_table_offset);
+                    }
+                } else {
+                    let write_barrier =
+                        self.ctxt.gc.needs_write_barrier() && field.ty.reference_type();
+
+                    self.masm.store_field(
+                        field.ty.mode(),
+                        REG_TMP1,
+                        field.offset,
+                        reg,
+                        e.pos.line as i32,
+                        write_barrier,
+                        card_table_offset,
+                    );
+                }
+
                 self.free_temp_for_node(temp, temp_offset);
             }
 
@@ -752,6 +1009,10 @@ where
                 unimplemented!();
             }
 
+            IdentType::StructField(_, _) => {
+                unreachable!();
+            }
+
             IdentType::Const(_) => {
                 unreachable!();
             }
@@ -760,7 +1021,7 @@ where
 
     fn emit_bin(&mut self, e: &'ast ExprBinType, dest: ExprStore) {
         if let Some(intrinsic) = self.intrinsic(e.id) {
-            self.emit_intrinsic_bin(&e.lhs, &e.rhs, dest, intrinsic, Some(e.op));
+            self.emit_intrinsic_bin(e.pos, &e.lhs, &e.rhs, dest, intrinsic, Some(e.op));
         } else if e.op == BinOp::Cmp(CmpOp::Is) || e.op == BinOp::Cmp(CmpOp::IsNot) {
             self.emit_expr(&e.lhs, REG_RESULT.into());
             let offset = self.reserve_temp_for_node(&e.lhs);
@@ -896,7 +1157,432 @@ where
         }
     }
 
+    fn emit_spawn(&mut self, e: &'ast ExprSpawnType, dest: Reg) {
+        self.emit_closure(&e.fct, e.pos, REG_PARAMS[0]);
+
+        let internal_fct = InternalFct {
+            ptr: stdlib::spawn_lambda as *mut u8,
+            args: &[BuiltinType::Ptr],
+            return_type: BuiltinType::Ptr,
+            throws: false,
+            id: FctId(0),
+        };
+
+        self.emit_native_call_insn(e.pos, internal_fct, dest.into());
+    }
+
+    fn emit_closure(&mut self, fct: &'ast Function, pos: Position, dest: Reg) {
+        let lambda = self.src.map_lambdas.get(fct.id).unwrap().clone();
+        let fct_id = lambda.fct_id.unwrap();
+
+        // lambda is compiled eagerly, its code is the only entry in the closure's vtable
+        let ptr = baseline::generate(
+            self.ctxt,
+            fct_id,
+            self.cls_type_params,
+            self.fct_type_params,
+        );
+
+        let captures: Vec<_> = lambda
+            .captures
+            .iter()
+            .map(|&var| self.specialize_type(self.src.vars[var].ty))
+            .collect();
+        let cls_def_id = specialize_lambda(self.ctxt, &captures, ptr);
+        let (field_offsets, _) = lambda_field_offsets(self.ctxt, &captures);
+
+        let offset = -(self.jit_info.localsize + self.jit_info.get_store(fct.id).offset());
+        self.emit_allocation(pos, &[], cls_def_id, offset, REG_RESULT);
+
+        let values = lambda
+            .captures
+            .iter()
+            .zip(captures)
+            .zip(field_offsets)
+            .map(|((&var, ty), field_offset)| (ty, self.jit_info.offset(var), field_offset))
+            .collect::<Vec<_>>();
+
+        self.store_fields_from_stack(pos, offset, &values);
+
+        self.masm
+            .load_mem(MachineMode::Ptr, dest.into(), Mem::Local(offset));
+    }
+
+    // copies (type, stack offset, field offset) values into the fields
+    // of the freshly allocated object stored at `obj_offset`
+    fn store_fields_from_stack(
+        &mut self,
+        pos: Position,
+        obj_offset: i32,
+        values: &[(BuiltinType, i32, i32)],
+    ) {
+        for &(ty, offset, field_offset) in values {
+            // the write barrier clobbers the object register, reload it every time
+            if ty.is_struct() {
+                let mut ref_fields = Vec::new();
+                add_ref_fields(self.ctxt, &mut ref_fields, ty, 0);
+
+                self.masm
+                    .load_mem(MachineMode::Ptr, REG_RESULT.into(), Mem::Local(obj_offset));
+                self.masm.copy_mem(
+                    Mem::Base(REG_RESULT, field_offset),
+                    Mem::Local(offset),
+                    ty.size(self.ctxt),
+                    REG_TMP1,
+                );
+
+                if self.ctxt.gc.needs_write_barrier() && !ref_fields.is_empty() {
+                    let card_table_offset = self.ctxt.gc.card_table_offset();
+                    self.masm.emit_barrier(REG_RESULT, card_table_offset);
+                }
+
+                continue;
+            }
+
+            let value: ExprStore = if ty.mode().is_float() {
+                FREG_TMP1.into()
+            } else {
+                REG_TMP1.into()
+            };
+
+            self.masm.load_mem(ty.mode(), value, Mem::Local(offset));
+            self.masm
+                .load_mem(MachineMode::Ptr, REG_RESULT.into(), Mem::Local(obj_offset));
+
+            let write_barrier = self.ctxt.gc.needs_write_barrier() && ty.reference_type();
+            let card_table_offset = self.ctxt.gc.card_table_offset();
+
+            self.masm.store_field(
+                ty.mode(),
+                REG_RESULT,
+                field_offset,
+                value,
+                pos.line as i32,
+                write_barrier,
+                card_table_offset,
+            );
+        }
+    }
+
+    fn emit_enum_variant(
+        &mut self,
+        id: NodeId,
+        pos: Position,
+        enum_id: EnumId,
+        variant: usize,
+        args: &'ast [Box<Expr>],
+        dest: Reg,
+    ) {
+        let cls_def_id = specialize_enum_variant(self.ctxt, enum_id, variant);
+        let fields = self.ctxt.class_defs[cls_def_id].borrow().fields.clone();
+        let types = self.ctxt.enums[enum_id].borrow().variants[variant]
+            .types
+            .clone();
+
+        let mut values = Vec::with_capacity(args.len());
+
+        for ((arg, &ty), field) in args.iter().zip(&types).zip(&fields[1..]) {
+            let offset = -(self.jit_info.localsize + self.jit_info.get_store(arg.id()).offset());
+            let reg = register_for_type(ty);
+
+            self.emit_expr(arg, reg);
+            self.store_arg(ty, offset, reg);
+
+            values.push((ty, offset, field.offset));
+        }
+
+        let offset = -(self.jit_info.localsize + self.jit_info.get_store(id).offset());
+        self.emit_allocation(pos, &[], cls_def_id, offset, REG_RESULT);
+
+        // the tag identifies the variant in match expressions
+        self.masm
+            .load_int_const(MachineMode::Int32, REG_TMP1, variant as i64);
+        self.masm.store_mem(
+            MachineMode::Int32,
+            Mem::Base(REG_RESULT, fields[0].offset),
+            REG_TMP1.into(),
+        );
+
+        self.store_fields_from_stack(pos, offset, &values);
+
+        for (ty, offset, _) in values {
+            self.free_temp_with_type(ty, offset);
+        }
+
+        self.masm
+            .load_mem(MachineMode::Ptr, dest.into(), Mem::Local(offset));
+    }
+
+    fn emit_path(&mut self, e: &'ast ExprPathType, dest: Reg) {
+        match **self.src.map_calls.get(e.id).unwrap() {
+            CallType::Enum(enum_id, variant) => {
+                self.emit_enum_variant(e.id, e.pos, enum_id, variant, &[], dest);
+            }
+
+            _ => unreachable!(),
+        }
+    }
+
+    fn emit_match(&mut self, e: &'ast ExprMatchType, dest: ExprStore) {
+        let enum_id = match self.ty(e.expr.id()) {
+            BuiltinType::Enum(enum_id) => enum_id,
+            _ => unreachable!(),
+        };
+
+        let offset = -(self.jit_info.localsize + self.jit_info.get_store(e.expr.id()).offset());
+
+        self.emit_expr(&e.expr, REG_RESULT.into());
+        self.masm.test_if_nil_bailout(e.pos, REG_RESULT, Trap::NIL);
+        self.masm
+            .store_mem(MachineMode::Ptr, Mem::Local(offset), REG_RESULT.into());
+        self.temps.insert(offset);
+
+        let lbl_end = self.masm.create_label();
+
+        for (idx, arm) in e.arms.iter().enumerate() {
+            let (path, params) = match arm.pattern {
+                // semck guarantees that `_` is the last arm
+                MatchPattern::Underscore => {
+                    self.emit_expr(&arm.value, dest);
+                    break;
+                }
+
+                MatchPattern::Variant(ref path, ref params) => (path, params),
+            };
+
+            let variant = {
+                let xenum = self.ctxt.enums[enum_id].borrow();
+                xenum.find_variant(path.path[path.len() - 1]).unwrap()
+            };
+
+            let cls_def_id = specialize_enum_variant(self.ctxt, enum_id, variant);
+            let fields = self.ctxt.class_defs[cls_def_id].borrow().fields.clone();
+
+            // match is exhaustive, no need to check the tag for the last arm
+            let lbl_next = if idx + 1 < e.arms.len() {
+                Some(self.masm.create_label())
+            } else {
+                None
+            };
+
+            self.masm
+                .load_mem(MachineMode::Ptr, REG_RESULT.into(), Mem::Local(offset));
+
+            if let Some(lbl_next) = lbl_next {
+                self.masm.cmp_mem_imm(
+                    MachineMode::Int32,
+                    Mem::Base(REG_RESULT, fields[0].offset),
+                    variant as i32,
+                );
+                self.masm.jump_if(CondCode::NotEqual, lbl_next);
+            }
+
+            self.scopes.push_scope();
+
+            for (param, field) in params.iter().zip(&fields[1..]) {
+                if param.name.is_none() {
+                    continue;
+                }
+
+                let var_id = *self.src.map_vars.get(param.id).unwrap();
+                let ty = self.jit_info.ty(var_id);
+                let var_offset = self.jit_info.offset(var_id);
+
+                if ty.is_struct() {
+                    self.masm.copy_mem(
+                        Mem::Local(var_offset),
+                        Mem::Base(REG_RESULT, field.offset),
+                        ty.size(self.ctxt),
+                        REG_TMP1,
+                    );
+                } else {
+                    let value: ExprStore = if ty.mode().is_float() {
+                        FREG_TMP1.into()
+                    } else {
+                        REG_TMP1.into()
+                    };
+
+                    self.masm
+                        .load_mem(ty.mode(), value, Mem::Base(REG_RESULT, field.offset));
+                    self.masm
+                        .store_mem(ty.mode(), Mem::Local(var_offset), value);
+                }
+
+                codegen::add_var_refs(self.ctxt, self.scopes, ty, var_offset);
+            }
+
+            self.emit_expr(&arm.value, dest);
+            self.scopes.pop_scope();
+
+            if let Some(lbl_next) = lbl_next {
+                self.masm.jump(lbl_end);
+                self.masm.bind_label(lbl_next);
+            }
+        }
+
+        self.masm.bind_label(lbl_end);
+        self.temps.remove(offset);
+    }
+
+    fn emit_call_lambda(&mut self, e: &'ast ExprCallType, callee: LambdaCallee, dest: ExprStore) {
+        // closures not held by a variable are evaluated first and kept in a temp
+        let closure = match callee {
+            LambdaCallee::Var(_) => None,
+
+            LambdaCallee::Field(ty, field_id) => {
+                let object = e.object.as_ref().unwrap();
+                let ty = self.specialize_type(ty);
+
+                self.emit_expr(object, REG_RESULT.into());
+                self.emit_field_access(e.id, e.pos, ty, field_id, REG_RESULT, REG_RESULT.into());
+
+                Some(self.store_closure(object))
+            }
+
+            LambdaCallee::Expr => {
+                let callee = e.callee.as_ref().unwrap();
+                self.emit_expr(callee, REG_RESULT.into());
+
+                Some(self.store_closure(callee))
+            }
+        };
+
+        let ty = match callee {
+            LambdaCallee::Var(var_id) => self.jit_info.ty(var_id),
+            _ => closure.unwrap().0,
+        };
+
+        let lambda = match ty.non_nullable(self.ctxt) {
+            BuiltinType::Lambda(lambda_id) => self.ctxt.lambda_types.borrow().get(lambda_id),
+            _ => unreachable!(),
+        };
+
+        let mut temps = Vec::with_capacity(e.args.len());
+
+        for arg in &e.args {
+            let (offset, ty) = match self.jit_info.get_store(arg.id()) {
+                Store::Temp(offset, ty) => (-(self.jit_info.localsize + offset), ty),
+                Store::Reg => unreachable!(),
+            };
+
+            let reg = register_for_type(ty);
+            self.emit_expr(arg, reg);
+            self.store_arg(ty, offset, reg);
+
+            temps.push((ty, offset));
+        }
+
+        let mut arg_offset = -self.jit_info.stacksize();
+        let mut reg_idx = 1;
+        let mut freg_idx = 0;
+
+        for &(ty, offset) in &temps {
+            if ty.is_struct() {
+                self.pass_address(offset, &mut reg_idx, &mut arg_offset);
+                continue;
+            }
+
+            let mode = ty.mode();
+
+            if mode.is_float() {
+                if freg_idx < FREG_PARAMS.len() {
+                    let freg = FREG_PARAMS[freg_idx];
+                    self.masm.load_mem(mode, freg.into(), Mem::Local(offset));
+
+                    freg_idx += 1;
+                } else {
+                    self.masm
+                        .load_mem(mode, FREG_TMP1.into(), Mem::Local(offset));
+                    self.masm
+                        .store_mem(mode, Mem::Local(arg_offset), FREG_TMP1.into());
+
+                    arg_offset += 8;
+                }
+            } else {
+                if reg_idx < REG_PARAMS.len() {
+                    let reg = REG_PARAMS[reg_idx];
+                    self.masm.load_mem(mode, reg.into(), Mem::Local(offset));
+
+                    reg_idx += 1;
+                } else {
+                    self.masm
+                        .load_mem(mode, REG_TMP1.into(), Mem::Local(offset));
+                    self.masm
+                        .store_mem(mode, Mem::Local(arg_offset), REG_TMP1.into());
+
+                    arg_offset += 8;
+                }
+            }
+        }
+
+        // storage for a returned struct is passed after all other arguments
+        if let Some(&offset) = self.jit_info.map_offsets.get(e.id) {
+            self.pass_address(offset, &mut reg_idx, &mut arg_offset);
+        }
+
+        match callee {
+            LambdaCallee::Var(var_id) => {
+                codegen::var_load(self.masm, self.jit_info, var_id, REG_PARAMS[0].into());
+            }
+
+            _ => {
+                let (ty, offset) = closure.unwrap();
+                self.masm
+                    .load_mem(ty.mode(), REG_PARAMS[0].into(), Mem::Local(offset));
+                self.temps.remove(offset);
+            }
+        }
+
+        // the closure's vtable only contains the lambda's code
+        self.masm.emit_comment(Comment::Lit("call lambda"));
+        self.emit_indirect_call_insn(0, e.pos, lambda.ret(), dest);
+
+        for (ty, offset) in temps {
+            self.free_temp_with_type(ty, offset);
+        }
+    }
+
+    // keeps the closure in REG_RESULT alive in the temp reserved for `node`
+    fn store_closure(&mut self, node: &'ast Expr) -> (BuiltinType, i32) {
+        let (offset, ty) = match self.jit_info.get_store(node.id()) {
+            Store::Temp(offset, ty) => (-(self.jit_info.localsize + offset), ty),
+            Store::Reg => unreachable!(),
+        };
+
+        self.store_arg(ty, offset, REG_RESULT.into());
+
+        (ty, offset)
+    }
+
+    // passes the address of the stack slot at `offset` as argument
+    fn pass_address(&mut self, offset: i32, reg_idx: &mut usize, arg_offset: &mut i32) {
+        if *reg_idx < REG_PARAMS.len() {
+            self.masm
+                .load_address(REG_PARAMS[*reg_idx], Mem::Local(offset));
+            *reg_idx += 1;
+        } else {
+            self.masm.load_address(REG_TMP1, Mem::Local(offset));
+            self.masm
+                .store_mem(MachineMode::Ptr, Mem::Local(*arg_offset), REG_TMP1.into());
+            *arg_offset += 8;
+        }
+    }
+
     fn emit_call(&mut self, e: &'ast ExprCallType, dest: ExprStore) {
+        match self.src.map_calls.get(e.id).map(|c| &**c) {
+            Some(&CallType::Lambda(callee)) => {
+                self.emit_call_lambda(e, callee, dest);
+                return;
+            }
+
+            Some(&CallType::Enum(enum_id, variant)) => {
+                self.emit_enum_variant(e.id, e.pos, enum_id, variant, &e.args, dest.reg());
+                return;
+            }
+
+            _ => {}
+        }
+
         if let Some(intrinsic) = self.intrinsic(e.id) {
             match intrinsic {
                 Intrinsic::GenericArrayLen => self.emit_intrinsic_len(e, dest.reg()),
@@ -915,10 +1601,28 @@ where
                 Intrinsic::BoolToInt | Intrinsic::ByteToInt => {
                     self.emit_intrinsic_byte_to_int(e, dest.reg())
                 }
+                Intrinsic::ByteToShort
+                | Intrinsic::ShortToInt
+                | Intrinsic::IntToUInt
+                | Intrinsic::UIntToInt
+                | Intrinsic::LongToULong
+                | Intrinsic::ULongToLong => {
+                    self.emit_expr(e.object.as_ref().unwrap(), dest);
+                }
                 Intrinsic::BoolToLong | Intrinsic::ByteToLong => {
                     self.emit_intrinsic_byte_to_long(e, dest.reg())
                 }
-                Intrinsic::LongToByte => self.emit_intrinsic_long_to_byte(e, dest.reg()),
+                Intrinsic::LongToByte => self.emit_intrinsic_long_to_byte(e, dest.reg()),
+                Intrinsic::IntToShort | Intrinsic::LongToShort => {
+                    self.emit_intrinsic_int_to_short(e, dest.reg())
+                }
+                Intrinsic::UIntToLong => self.emit_intrinsic_uint_to_long(e, dest.reg()),
+                Intrinsic::UIntToFloat
+                | Intrinsic::UIntToDouble
+                | Intrinsic::ULongToFloat
+                | Intrinsic::ULongToDouble => {
+                    self.emit_intrinsic_uint_to_float(e, dest.freg(), intrinsic)
+                }
                 Intrinsic::LongToChar | Intrinsic::LongToInt => {
                     self.emit_intrinsic_long_to_int(e, dest.reg())
                 }
@@ -975,6 +1679,16 @@ where
                 Intrinsic::IntDiv => self.emit_intrinsic_bin_call(e, dest, intrinsic),
                 Intrinsic::IntMod => self.emit_intrinsic_bin_call(e, dest, intrinsic),
 
+                Intrinsic::IntCheckedAdd => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+                Intrinsic::IntCheckedSub => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+                Intrinsic::IntCheckedMul => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+                Intrinsic::IntWrappingAdd => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+                Intrinsic::IntWrappingSub => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+                Intrinsic::IntWrappingMul => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+                Intrinsic::IntSaturatingAdd => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+                Intrinsic::IntSaturatingSub => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+                Intrinsic::IntSaturatingMul => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+
                 Intrinsic::IntOr => self.emit_intrinsic_bin_call(e, dest, intrinsic),
                 Intrinsic::IntAnd => self.emit_intrinsic_bin_call(e, dest, intrinsic),
                 Intrinsic::IntXor => self.emit_intrinsic_bin_call(e, dest, intrinsic),
@@ -983,6 +1697,16 @@ where
                 Intrinsic::IntSar => self.emit_intrinsic_bin_call(e, dest, intrinsic),
                 Intrinsic::IntShr => self.emit_intrinsic_bin_call(e, dest, intrinsic),
 
+                Intrinsic::ShortAdd => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+                Intrinsic::ShortSub => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+                Intrinsic::ShortMul => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+                Intrinsic::ShortDiv => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+                Intrinsic::ShortShl => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+
+                Intrinsic::UIntCmp => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+                Intrinsic::UIntDiv => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+                Intrinsic::UIntMod => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+
                 Intrinsic::LongEq => self.emit_intrinsic_bin_call(e, dest, intrinsic),
                 Intrinsic::LongCmp => self.emit_intrinsic_bin_call(e, dest, intrinsic),
 
@@ -992,6 +1716,16 @@ where
                 Intrinsic::LongDiv => self.emit_intrinsic_bin_call(e, dest, intrinsic),
                 Intrinsic::LongMod => self.emit_intrinsic_bin_call(e, dest, intrinsic),
 
+                Intrinsic::LongCheckedAdd => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+                Intrinsic::LongCheckedSub => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+                Intrinsic::LongCheckedMul => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+                Intrinsic::LongWrappingAdd => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+                Intrinsic::LongWrappingSub => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+                Intrinsic::LongWrappingMul => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+                Intrinsic::LongSaturatingAdd => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+                Intrinsic::LongSaturatingSub => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+                Intrinsic::LongSaturatingMul => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+
                 Intrinsic::LongOr => self.emit_intrinsic_bin_call(e, dest, intrinsic),
                 Intrinsic::LongAnd => self.emit_intrinsic_bin_call(e, dest, intrinsic),
                 Intrinsic::LongXor => self.emit_intrinsic_bin_call(e, dest, intrinsic),
@@ -1000,6 +1734,10 @@ where
                 Intrinsic::LongSar => self.emit_intrinsic_bin_call(e, dest, intrinsic),
                 Intrinsic::LongShr => self.emit_intrinsic_bin_call(e, dest, intrinsic),
 
+                Intrinsic::ULongCmp => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+                Intrinsic::ULongDiv => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+                Intrinsic::ULongMod => self.emit_intrinsic_bin_call(e, dest, intrinsic),
+
                 Intrinsic::FloatAdd => self.emit_intrinsic_bin_call(e, dest, intrinsic),
                 Intrinsic::FloatSub => self.emit_intrinsic_bin_call(e, dest, intrinsic),
                 Intrinsic::FloatMul => self.emit_intrinsic_bin_call(e, dest, intrinsic),
@@ -1029,12 +1767,21 @@ where
         match ty {
             BuiltinType::Bool
             | BuiltinType::Byte
+            | BuiltinType::Short
             | BuiltinType::Int
+            | BuiltinType::UInt
             | BuiltinType::Long
+            | BuiltinType::ULong
             | BuiltinType::Char => self.masm.load_int_const(ty.mode(), dest.reg(), 0),
             BuiltinType::Float | BuiltinType::Double => {
                 self.masm.load_float_const(ty.mode(), dest.freg(), 0.0)
             }
+            BuiltinType::Struct(_, _) | BuiltinType::Tuple(_) => {
+                let offset = *self.jit_info.map_offsets.get(e.id).unwrap();
+                self.masm
+                    .zero_mem(Mem::Local(offset), ty.size(self.ctxt), REG_TMP1);
+                self.masm.load_address(dest.reg(), Mem::Local(offset));
+            }
             _ => self.masm.load_nil(dest.reg()),
         }
     }
@@ -1150,6 +1897,107 @@ where
         }
     }
 
+    fn emit_array_get_struct(&mut self, e: &'ast ExprArrayType, ty: BuiltinType, dest: Reg) {
+        self.emit_expr(&e.object, REG_RESULT.into());
+        let offset = self.reserve_temp_for_node(&e.object);
+        self.masm
+            .store_mem(MachineMode::Ptr, Mem::Local(offset), REG_RESULT.into());
+
+        self.emit_expr(&e.index, REG_TMP1.into());
+        self.masm
+            .load_mem(MachineMode::Ptr, REG_RESULT.into(), Mem::Local(offset));
+
+        self.masm.test_if_nil_bailout(e.pos, REG_RESULT, Trap::NIL);
+
+        if !self.ctxt.args.flag_omit_bounds_check {
+            self.masm
+                .check_index_out_of_bounds(e.pos, REG_RESULT, REG_TMP1);
+        }
+
+        // copy element into the stack slot of this expression
+        let size = ty.size(self.ctxt);
+        let slot = *self.jit_info.map_offsets.get(e.id).unwrap();
+
+        self.masm
+            .array_elem_address(REG_TMP2, REG_RESULT, REG_TMP1, size);
+        self.masm
+            .copy_mem(Mem::Local(slot), Mem::Base(REG_TMP2, 0), size, REG_TMP1);
+        self.masm.load_address(dest, Mem::Local(slot));
+
+        self.free_temp_for_node(&e.object, offset);
+    }
+
+    fn emit_array_set_struct(
+        &mut self,
+        pos: Position,
+        element_type: BuiltinType,
+        array: &'ast ExprArrayType,
+        rhs: &'ast Expr,
+    ) {
+        self.emit_expr(&array.object, REG_RESULT.into());
+        let offset_object = self.reserve_temp_for_node(&array.object);
+        self.masm.store_mem(
+            MachineMode::Ptr,
+            Mem::Local(offset_object),
+            REG_RESULT.into(),
+        );
+
+        self.emit_expr(&array.index, REG_RESULT.into());
+        let offset_index = self.reserve_temp_for_node(&array.index);
+        self.masm.store_mem(
+            MachineMode::Int32,
+            Mem::Local(offset_index),
+            REG_RESULT.into(),
+        );
+
+        // only the address of the value is stored in the temporary
+        self.emit_expr(rhs, REG_RESULT.into());
+        let offset_value = self.reserve_temp_for_node(rhs);
+        self.masm.store_mem(
+            MachineMode::Ptr,
+            Mem::Local(offset_value),
+            REG_RESULT.into(),
+        );
+
+        self.masm
+            .load_mem(MachineMode::Ptr, REG_TMP1.into(), Mem::Local(offset_object));
+        self.masm.load_mem(
+            MachineMode::Int32,
+            REG_TMP2.into(),
+            Mem::Local(offset_index),
+        );
+
+        self.masm.test_if_nil_bailout(pos, REG_TMP1, Trap::NIL);
+
+        if !self.ctxt.args.flag_omit_bounds_check {
+            self.masm.check_index_out_of_bounds(pos, REG_TMP1, REG_TMP2);
+        }
+
+        let size = element_type.size(self.ctxt);
+        self.masm
+            .array_elem_address(REG_RESULT, REG_TMP1, REG_TMP2, size);
+        self.masm
+            .load_mem(MachineMode::Ptr, REG_TMP1.into(), Mem::Local(offset_value));
+        self.masm
+            .copy_mem(Mem::Base(REG_RESULT, 0), Mem::Base(REG_TMP1, 0), size, REG_TMP2);
+
+        let mut ref_fields = Vec::new();
+        add_ref_fields(self.ctxt, &mut ref_fields, element_type, 0);
+
+        // like for fields the card of the object start is marked,
+        // the GC visits all elements of struct arrays at once
+        if self.ctxt.gc.needs_write_barrier() && !ref_fields.is_empty() {
+            let card_table_offset = self.ctxt.gc.card_table_offset();
+            self.masm
+                .load_mem(MachineMode::Ptr, REG_TMP1.into(), Mem::Local(offset_object));
+            self.masm.emit_barrier(REG_TMP1, card_table_offset);
+        }
+
+        self.free_temp_for_node(&array.object, offset_object);
+        self.free_temp_for_node(&array.index, offset_index);
+        self.free_temp_for_node(rhs, offset_value);
+    }
+
     fn emit_set_uint8(&mut self, e: &'ast ExprCallType, _: Reg) {
         self.emit_expr(&e.args[0], REG_RESULT.into());
         let offset = self.reserve_temp_for_node(&e.args[0]);
@@ -1229,6 +2077,16 @@ where
         self.masm.extend_int_long(dest, REG_RESULT);
     }
 
+    fn emit_intrinsic_int_to_short(&mut self, e: &'ast ExprCallType, dest: Reg) {
+        self.emit_expr(e.object.as_ref().unwrap(), dest.into());
+        self.masm.extend_short(dest, dest);
+    }
+
+    fn emit_intrinsic_uint_to_long(&mut self, e: &'ast ExprCallType, dest: Reg) {
+        self.emit_expr(e.object.as_ref().unwrap(), REG_RESULT.into());
+        self.masm.extend_uint_long(dest, REG_RESULT);
+    }
+
     fn emit_intrinsic_float_to_double(&mut self, e: &'ast ExprCallType, dest: FReg) {
         self.emit_expr(e.object.as_ref().unwrap(), FREG_RESULT.into());
         self.masm.float_to_double(dest, FREG_RESULT);
@@ -1259,6 +2117,26 @@ where
             .int_to_float(dest_mode, dest, src_mode, REG_RESULT);
     }
 
+    fn emit_intrinsic_uint_to_float(
+        &mut self,
+        e: &'ast ExprCallType,
+        dest: FReg,
+        intrinsic: Intrinsic,
+    ) {
+        self.emit_expr(e.object.as_ref().unwrap(), REG_RESULT.into());
+
+        let (src_mode, dest_mode) = match intrinsic {
+            Intrinsic::UIntToFloat => (MachineMode::Int32, MachineMode::Float32),
+            Intrinsic::UIntToDouble => (MachineMode::Int32, MachineMode::Float64),
+            Intrinsic::ULongToFloat => (MachineMode::Int64, MachineMode::Float32),
+            Intrinsic::ULongToDouble => (MachineMode::Int64, MachineMode::Float64),
+            _ => unreachable!(),
+        };
+
+        self.masm
+            .uint_to_float(dest_mode, dest, src_mode, REG_RESULT);
+    }
+
     fn emit_intrinsic_float_to_int(
         &mut self,
         e: &'ast ExprCallType,
@@ -1293,11 +2171,12 @@ where
         let lhs = e.object.as_ref().unwrap();
         let rhs = &e.args[0];
 
-        self.emit_intrinsic_bin(lhs, rhs, dest, intr, None);
+        self.emit_intrinsic_bin(e.pos, lhs, rhs, dest, intr, None);
     }
 
     fn emit_intrinsic_bin(
         &mut self,
+        pos: Position,
         lhs: &'ast Expr,
         rhs: &'ast Expr,
         dest: ExprStore,
@@ -1330,7 +2209,7 @@ where
             let lhs_reg = lhs_reg.reg();
             let rhs_reg = rhs_reg.reg();
 
-            self.emit_intrinsic_int(dest.reg(), lhs_reg, rhs_reg, intr, op);
+            self.emit_intrinsic_int(pos, dest.reg(), lhs_reg, rhs_reg, intr, op);
         }
 
         self.free_temp_for_node(lhs, offset);
@@ -1338,6 +2217,7 @@ where
 
     fn emit_intrinsic_int(
         &mut self,
+        pos: Position,
         dest: Reg,
         lhs: Reg,
         rhs: Reg,
@@ -1382,11 +2262,86 @@ where
                 }
             }
 
-            Intrinsic::IntAdd => self.masm.int_add(MachineMode::Int32, dest, lhs, rhs),
-            Intrinsic::IntSub => self.masm.int_sub(MachineMode::Int32, dest, lhs, rhs),
-            Intrinsic::IntMul => self.masm.int_mul(MachineMode::Int32, dest, lhs, rhs),
-            Intrinsic::IntDiv => self.masm.int_div(MachineMode::Int32, dest, lhs, rhs),
-            Intrinsic::IntMod => self.masm.int_mod(MachineMode::Int32, dest, lhs, rhs),
+            Intrinsic::UIntCmp | Intrinsic::ULongCmp => {
+                let mode = if intr == Intrinsic::ULongCmp {
+                    MachineMode::Int64
+                } else {
+                    MachineMode::Int32
+                };
+
+                self.masm.cmp_reg(mode, lhs, rhs);
+
+                if let Some(BinOp::Cmp(op)) = op {
+                    self.masm.set(dest, to_unsigned_cond_code(op));
+                } else {
+                    // subtracting could overflow, compute (lhs > rhs) - (lhs < rhs)
+                    let tmp = if dest == rhs { lhs } else { rhs };
+
+                    self.masm.set(dest, CondCode::UnsignedGreater);
+                    self.masm.set(tmp, CondCode::UnsignedLess);
+                    self.masm.int_sub(MachineMode::Int32, dest, dest, tmp);
+                }
+            }
+
+            Intrinsic::ShortAdd => {
+                self.masm.int_add(MachineMode::Int32, dest, lhs, rhs);
+                self.masm.extend_short(dest, dest);
+            }
+            Intrinsic::ShortSub => {
+                self.masm.int_sub(MachineMode::Int32, dest, lhs, rhs);
+                self.masm.extend_short(dest, dest);
+            }
+            Intrinsic::ShortMul => {
+                self.masm.int_mul(MachineMode::Int32, dest, lhs, rhs);
+                self.masm.extend_short(dest, dest);
+            }
+            Intrinsic::ShortDiv => {
+                self.emit_div_check(pos, MachineMode::Int32, rhs);
+                self.masm.int_div(MachineMode::Int32, dest, lhs, rhs);
+                self.masm.extend_short(dest, dest);
+            }
+            Intrinsic::ShortShl => {
+                self.masm.int_shl(MachineMode::Int32, dest, lhs, rhs);
+                self.masm.extend_short(dest, dest);
+            }
+
+            Intrinsic::UIntDiv => {
+                self.emit_div_check(pos, MachineMode::Int32, rhs);
+                self.masm.uint_div(MachineMode::Int32, dest, lhs, rhs);
+            }
+            Intrinsic::UIntMod => {
+                self.emit_div_check(pos, MachineMode::Int32, rhs);
+                self.masm.uint_mod(MachineMode::Int32, dest, lhs, rhs);
+            }
+            Intrinsic::ULongDiv => {
+                self.emit_div_check(pos, MachineMode::Int64, rhs);
+                self.masm.uint_div(MachineMode::Int64, dest, lhs, rhs);
+            }
+            Intrinsic::ULongMod => {
+                self.emit_div_check(pos, MachineMode::Int64, rhs);
+                self.masm.uint_mod(MachineMode::Int64, dest, lhs, rhs);
+            }
+
+            Intrinsic::IntAdd
+            | Intrinsic::IntSub
+            | Intrinsic::IntMul
+            | Intrinsic::IntCheckedAdd
+            | Intrinsic::IntCheckedSub
+            | Intrinsic::IntCheckedMul
+            | Intrinsic::IntWrappingAdd
+            | Intrinsic::IntWrappingSub
+            | Intrinsic::IntWrappingMul
+            | Intrinsic::IntSaturatingAdd
+            | Intrinsic::IntSaturatingSub
+            | Intrinsic::IntSaturatingMul => self.emit_int_arith(pos, dest, lhs, rhs, intr),
+            Intrinsic::IntDiv => {
+                self.emit_div_check(pos, MachineMode::Int32, rhs);
+                self.masm.int_div(MachineMode::Int32, dest, lhs, rhs);
+            }
+            Intrinsic::IntMod => {
+                self.emit_div_check(pos, MachineMode::Int32, rhs);
+                self.masm.int_mod(MachineMode::Int32, dest, lhs, rhs);
+            }
 
             Intrinsic::IntOr => self.masm.int_or(MachineMode::Int32, dest, lhs, rhs),
             Intrinsic::IntAnd => self.masm.int_and(MachineMode::Int32, dest, lhs, rhs),
@@ -1396,11 +2351,26 @@ where
             Intrinsic::IntSar => self.masm.int_sar(MachineMode::Int32, dest, lhs, rhs),
             Intrinsic::IntShr => self.masm.int_shr(MachineMode::Int32, dest, lhs, rhs),
 
-            Intrinsic::LongAdd => self.masm.int_add(MachineMode::Int64, dest, lhs, rhs),
-            Intrinsic::LongSub => self.masm.int_sub(MachineMode::Int64, dest, lhs, rhs),
-            Intrinsic::LongMul => self.masm.int_mul(MachineMode::Int64, dest, lhs, rhs),
-            Intrinsic::LongDiv => self.masm.int_div(MachineMode::Int64, dest, lhs, rhs),
-            Intrinsic::LongMod => self.masm.int_mod(MachineMode::Int64, dest, lhs, rhs),
+            Intrinsic::LongAdd
+            | Intrinsic::LongSub
+            | Intrinsic::LongMul
+            | Intrinsic::LongCheckedAdd
+            | Intrinsic::LongCheckedSub
+            | Intrinsic::LongCheckedMul
+            | Intrinsic::LongWrappingAdd
+            | Intrinsic::LongWrappingSub
+            | Intrinsic::LongWrappingMul
+            | Intrinsic::LongSaturatingAdd
+            | Intrinsic::LongSaturatingSub
+            | Intrinsic::LongSaturatingMul => self.emit_int_arith(pos, dest, lhs, rhs, intr),
+            Intrinsic::LongDiv => {
+                self.emit_div_check(pos, MachineMode::Int64, rhs);
+                self.masm.int_div(MachineMode::Int64, dest, lhs, rhs);
+            }
+            Intrinsic::LongMod => {
+                self.emit_div_check(pos, MachineMode::Int64, rhs);
+                self.masm.int_mod(MachineMode::Int64, dest, lhs, rhs);
+            }
 
             Intrinsic::LongOr => self.masm.int_or(MachineMode::Int64, dest, lhs, rhs),
             Intrinsic::LongAnd => self.masm.int_and(MachineMode::Int64, dest, lhs, rhs),
@@ -1414,6 +2384,150 @@ where
         }
     }
 
+    fn emit_div_check(&mut self, pos: Position, mode: MachineMode, rhs: Reg) {
+        self.masm.cmp_zero(mode, rhs);
+
+        let lbl_zero = self.masm.create_label();
+        self.masm.jump_if(CondCode::Equal, lbl_zero);
+        self.masm.emit_bailout(lbl_zero, Trap::DIV0, pos);
+    }
+
+    fn emit_int_arith(&mut self, pos: Position, dest: Reg, lhs: Reg, rhs: Reg, intr: Intrinsic) {
+        use self::Overflow::{Saturate, Throw, Wrap};
+
+        let default = if self.ctxt.args.flag_check_overflow {
+            Throw
+        } else {
+            Wrap
+        };
+
+        let (mode, op, overflow) = match intr {
+            Intrinsic::IntAdd => (MachineMode::Int32, BinOp::Add, default),
+            Intrinsic::IntSub => (MachineMode::Int32, BinOp::Sub, default),
+            Intrinsic::IntMul => (MachineMode::Int32, BinOp::Mul, default),
+            Intrinsic::IntCheckedAdd => (MachineMode::Int32, BinOp::Add, Throw),
+            Intrinsic::IntCheckedSub => (MachineMode::Int32, BinOp::Sub, Throw),
+            Intrinsic::IntCheckedMul => (MachineMode::Int32, BinOp::Mul, Throw),
+            Intrinsic::IntWrappingAdd => (MachineMode::Int32, BinOp::Add, Wrap),
+            Intrinsic::IntWrappingSub => (MachineMode::Int32, BinOp::Sub, Wrap),
+            Intrinsic::IntWrappingMul => (MachineMode::Int32, BinOp::Mul, Wrap),
+            Intrinsic::IntSaturatingAdd => (MachineMode::Int32, BinOp::Add, Saturate),
+            Intrinsic::IntSaturatingSub => (MachineMode::Int32, BinOp::Sub, Saturate),
+            Intrinsic::IntSaturatingMul => (MachineMode::Int32, BinOp::Mul, Saturate),
+
+            Intrinsic::LongAdd => (MachineMode::Int64, BinOp::Add, default),
+            Intrinsic::LongSub => (MachineMode::Int64, BinOp::Sub, default),
+            Intrinsic::LongMul => (MachineMode::Int64, BinOp::Mul, default),
+            Intrinsic::LongCheckedAdd => (MachineMode::Int64, BinOp::Add, Throw),
+            Intrinsic::LongCheckedSub => (MachineMode::Int64, BinOp::Sub, Throw),
+            Intrinsic::LongCheckedMul => (MachineMode::Int64, BinOp::Mul, Throw),
+            Intrinsic::LongWrappingAdd => (MachineMode::Int64, BinOp::Add, Wrap),
+            Intrinsic::LongWrappingSub => (MachineMode::Int64, BinOp::Sub, Wrap),
+            Intrinsic::LongWrappingMul => (MachineMode::Int64, BinOp::Mul, Wrap),
+            Intrinsic::LongSaturatingAdd => (MachineMode::Int64, BinOp::Add, Saturate),
+            Intrinsic::LongSaturatingSub => (MachineMode::Int64, BinOp::Sub, Saturate),
+            Intrinsic::LongSaturatingMul => (MachineMode::Int64, BinOp::Mul, Saturate),
+
+            _ => unreachable!(),
+        };
+
+        match overflow {
+            Wrap => match op {
+                BinOp::Add => self.masm.int_add(mode, dest, lhs, rhs),
+                BinOp::Sub => self.masm.int_sub(mode, dest, lhs, rhs),
+                BinOp::Mul => self.masm.int_mul(mode, dest, lhs, rhs),
+                _ => unreachable!(),
+            },
+
+            Throw => {
+                let lbl_overflow = self.masm.create_label();
+                let lbl_done = self.masm.create_label();
+
+                self.emit_int_arith_checked(mode, op, dest, lhs, rhs, lbl_overflow);
+                self.masm.jump(lbl_done);
+
+                self.masm.bind_label(lbl_overflow);
+
+                let internal_fct = InternalFct {
+                    ptr: exception::throw_overflow as *mut u8,
+                    args: &[],
+                    return_type: BuiltinType::Unit,
+                    throws: true,
+                    id: FctId(0),
+                };
+
+                // the stub never returns but needs the usual call site information
+                // for building the stack trace and the GC
+                let ptr = ensure_native_stub(self.ctxt, FctId(0), internal_fct);
+                self.masm.direct_call_without_info(ptr);
+                self.masm.emit_lineno(pos.line as i32);
+
+                let gcpoint = codegen::create_gcpoint(self.scopes, &self.temps);
+                self.masm.emit_gcpoint(gcpoint);
+
+                self.masm.bind_label(lbl_done);
+            }
+
+            Saturate => {
+                // on overflow the exact result has the sign of lhs for additions
+                // and subtractions and the sign of lhs ^ rhs for multiplications
+                let sign = if dest != lhs && dest != rhs {
+                    dest
+                } else {
+                    REG_TMP2
+                };
+                assert!(sign != lhs && sign != rhs);
+
+                self.masm.copy_reg(mode, sign, lhs);
+
+                if op == BinOp::Mul {
+                    self.masm.int_xor(mode, sign, sign, rhs);
+                }
+
+                let lbl_overflow = self.masm.create_label();
+                let lbl_negative = self.masm.create_label();
+                let lbl_done = self.masm.create_label();
+
+                self.emit_int_arith_checked(mode, op, lhs, lhs, rhs, lbl_overflow);
+                self.masm.copy_reg(mode, dest, lhs);
+                self.masm.jump(lbl_done);
+
+                let (min, max) = match mode {
+                    MachineMode::Int32 => (i32::min_value() as i64, i32::max_value() as i64),
+                    _ => (i64::min_value(), i64::max_value()),
+                };
+
+                self.masm.bind_label(lbl_overflow);
+                self.masm.cmp_zero(mode, sign);
+                self.masm.jump_if(CondCode::Less, lbl_negative);
+                self.masm.load_int_const(mode, dest, max);
+                self.masm.jump(lbl_done);
+
+                self.masm.bind_label(lbl_negative);
+                self.masm.load_int_const(mode, dest, min);
+
+                self.masm.bind_label(lbl_done);
+            }
+        }
+    }
+
+    fn emit_int_arith_checked(
+        &mut self,
+        mode: MachineMode,
+        op: BinOp,
+        dest: Reg,
+        lhs: Reg,
+        rhs: Reg,
+        lbl_overflow: Label,
+    ) {
+        match op {
+            BinOp::Add => self.masm.int_add_checked(mode, dest, lhs, rhs, lbl_overflow),
+            BinOp::Sub => self.masm.int_sub_checked(mode, dest, lhs, rhs, lbl_overflow),
+            BinOp::Mul => self.masm.int_mul_checked(mode, dest, lhs, rhs, lbl_overflow),
+            _ => unreachable!(),
+        }
+    }
+
     fn emit_intrinsic_float(
         &mut self,
         dest: ExprStore,
@@ -1490,8 +2604,7 @@ where
         let fct = self.ctxt.fcts[fid].borrow();
 
         for (idx, arg) in csite.args.iter().enumerate() {
-            let mode = arg.ty().mode();
-            let dest = register_for_mode(mode);
+            let dest = register_for_type(arg.ty());
 
             match *arg {
                 Arg::Expr(ast, ty, _) => {
@@ -1517,6 +2630,13 @@ where
                     self.emit_self(dest.reg());
                 }
 
+                Arg::Varargs(exprs, ty, _, length_offset) => {
+                    let offset = self.reserve_temp_for_arg(arg);
+                    self.emit_varargs(pos, exprs, ty, offset, length_offset);
+                    temps.push((ty, offset, None));
+                    continue;
+                }
+
                 Arg::SelfieNew(ty, _) => {
                     let cls_id = specialize_class_ty(self.ctxt, ty);
                     // do NOT invoke `reserve_temp_for_arg` here, since
@@ -1530,8 +2650,7 @@ where
             }
 
             let offset = self.reserve_temp_for_arg(arg);
-            self.masm
-                .store_mem(arg.ty().mode(), Mem::Local(offset), dest);
+            self.store_arg(arg.ty(), offset, dest);
             temps.push((arg.ty(), offset, None));
         }
 
@@ -1542,9 +2661,16 @@ where
 
         for arg in &csite.args {
             let ty = arg.ty();
+            let offset = temps[idx].1;
+
+            if ty.is_struct() {
+                self.pass_address(offset, &mut reg_idx, &mut arg_offset);
+                idx += 1;
+                continue;
+            }
+
             let mode = ty.mode();
             let is_float = mode.is_float();
-            let offset = temps[idx].1;
 
             if idx == 0 {
                 if let Some(cls_id) = temps[idx].2 {
@@ -1595,6 +2721,11 @@ where
             idx += 1;
         }
 
+        // storage for a returned struct is passed after all other arguments
+        if let Some(offset) = csite.return_slot {
+            self.pass_address(offset, &mut reg_idx, &mut arg_offset);
+        }
+
         let return_type = self.specialize_type(csite.return_type);
         let cls_type_params: TypeParams = csite
             .cls_type_params
@@ -1662,6 +2793,79 @@ where
         }
     }
 
+    // allocates the array for a variadic parameter and stores it in `offset`
+    fn emit_varargs(
+        &mut self,
+        pos: Position,
+        exprs: &'ast [Box<Expr>],
+        ty: BuiltinType,
+        offset: i32,
+        length_offset: i32,
+    ) {
+        let cls_id = specialize_class_ty(self.ctxt, ty);
+        let element = ty.type_params(self.ctxt)[0];
+        let length_offset = -(self.jit_info.localsize + length_offset);
+
+        self.masm
+            .load_int_const(MachineMode::Int32, REG_RESULT, exprs.len() as i64);
+        self.masm.store_mem(
+            MachineMode::Int32,
+            Mem::Local(length_offset),
+            REG_RESULT.into(),
+        );
+
+        let temps = [
+            (ty, offset, Some(cls_id)),
+            (BuiltinType::Int, length_offset, None),
+        ];
+        self.emit_allocation(pos, &temps, cls_id, offset, REG_RESULT);
+        self.temps.insert(offset);
+
+        let write_barrier = self.ctxt.gc.needs_write_barrier();
+        let card_table_offset = self.ctxt.gc.card_table_offset();
+
+        for (idx, expr) in exprs.iter().enumerate() {
+            if element.is_struct() {
+                self.emit_expr(expr, REG_RESULT.into());
+
+                let size = element.size(self.ctxt);
+                let disp = offset_of_array_data() + idx as i32 * size;
+
+                self.masm
+                    .load_mem(MachineMode::Ptr, REG_TMP1.into(), Mem::Local(offset));
+                self.masm.copy_mem(
+                    Mem::Base(REG_TMP1, disp),
+                    Mem::Base(REG_RESULT, 0),
+                    size,
+                    REG_TMP2,
+                );
+
+                let mut ref_fields = Vec::new();
+                add_ref_fields(self.ctxt, &mut ref_fields, element, 0);
+
+                if write_barrier && !ref_fields.is_empty() {
+                    self.masm.emit_barrier(REG_TMP1, card_table_offset);
+                }
+            } else {
+                let value = result_reg(element.mode());
+                self.emit_expr(expr, value);
+
+                self.masm
+                    .load_mem(MachineMode::Ptr, REG_TMP1.into(), Mem::Local(offset));
+                self.masm
+                    .load_int_const(MachineMode::Int32, REG_TMP2, idx as i64);
+                self.masm.store_array_elem(
+                    element.mode(),
+                    REG_TMP1,
+                    REG_TMP2,
+                    value,
+                    write_barrier && element.reference_type(),
+                    card_table_offset,
+                );
+            }
+        }
+    }
+
     fn emit_allocation(
         &mut self,
         pos: Position,
@@ -1682,7 +2886,7 @@ where
                     .load_int_const(MachineMode::Int32, REG_PARAMS[0], size as i64);
             }
 
-            ClassSize::Array(esize) if temps.len() > 1 => {
+            ClassSize::Array(esize) | ClassSize::StructArray(esize) if temps.len() > 1 => {
                 self.masm
                     .load_mem(MachineMode::Int32, REG_TMP1.into(), Mem::Local(temps[1].1));
 
@@ -1712,7 +2916,10 @@ where
                 store_length = true;
             }
 
-            ClassSize::Array(_) | ClassSize::ObjArray | ClassSize::Str => {
+            ClassSize::Array(_)
+            | ClassSize::StructArray(_)
+            | ClassSize::ObjArray
+            | ClassSize::Str => {
                 let size = (Header::size() + mem::ptr_width()) as i64;
                 self.masm
                     .load_int_const(MachineMode::Int32, REG_PARAMS[0], size);
@@ -1787,7 +2994,7 @@ where
                 );
 
                 let element_size = match cls.size {
-                    ClassSize::Array(esize) => esize,
+                    ClassSize::Array(esize) | ClassSize::StructArray(esize) => esize,
                     ClassSize::ObjArray => mem::ptr_width(),
                     ClassSize::Str => 1,
                     ClassSize::Fixed(_) => unreachable!(),
@@ -1807,6 +3014,41 @@ where
             .load_mem(MachineMode::Ptr, dest.into(), Mem::Local(offset));
     }
 
+    fn emit_init_global(&mut self, pos: Position, gid: GlobalId) {
+        let glob = self.ctxt.globals[gid].borrow();
+
+        // the initializer itself stores into the global without checking
+        match glob.initializer {
+            Some(fct_id) if fct_id != self.fct.id => {}
+            _ => return,
+        }
+
+        let lbl_initialized = self.masm.create_label();
+
+        let disp = self.masm.add_addr(glob.address_init);
+        let pos_const = self.masm.pos() as i32;
+
+        self.masm.emit_comment(Comment::InitGlobal(gid));
+        self.masm.load_constpool(REG_TMP1, disp + pos_const);
+        self.masm
+            .cmp_mem_imm(MachineMode::Int8, Mem::Base(REG_TMP1, 0), 0);
+        self.masm.jump_if(CondCode::NotEqual, lbl_initialized);
+
+        self.masm
+            .load_int_const(MachineMode::Int32, REG_PARAMS[0], gid.idx() as i64);
+
+        let internal_fct = InternalFct {
+            ptr: globals::init_global as *mut u8,
+            args: &[BuiltinType::Int],
+            return_type: BuiltinType::Unit,
+            throws: false,
+            id: FctId(0),
+        };
+
+        self.emit_native_call_insn(pos, internal_fct, REG_RESULT.into());
+        self.masm.bind_label(lbl_initialized);
+    }
+
     fn emit_native_call_insn(&mut self, pos: Position, internal_fct: InternalFct, dest: ExprStore) {
         let ty = internal_fct.return_type;
         let ptr = ensure_native_stub(self.ctxt, FctId(0), internal_fct);
@@ -1850,6 +3092,15 @@ where
     }
 
     fn copy_result_to(&mut self, ty: BuiltinType, dest: ExprStore) {
+        // functions return the address of struct values
+        if ty.is_struct() {
+            if REG_RESULT != dest.reg() {
+                self.masm.copy_reg(MachineMode::Ptr, dest.reg(), REG_RESULT);
+            }
+
+            return;
+        }
+
         match dest {
             ExprStore::FReg(dest) => {
                 if FREG_RESULT != dest {
@@ -1922,12 +3173,12 @@ where
     fn specialize_type(&self, ty: BuiltinType) -> BuiltinType {
         match ty {
             BuiltinType::ClassTypeParam(cls_id, id) => {
-                assert!(self.fct.parent == FctParent::Class(cls_id));
+                assert!(self.fct.is_lambda() || self.fct.is_method_of(self.ctxt, cls_id));
                 self.cls_type_params[id.idx()]
             }
 
             BuiltinType::FctTypeParam(fct_id, id) => {
-                assert!(self.fct.id == fct_id);
+                assert!(self.fct.id == fct_id || self.fct.parent == FctParent::Fct(fct_id));
                 self.fct_type_params[id.idx()]
             }
 
@@ -1941,7 +3192,36 @@ where
                 BuiltinType::Class(cls_id, list_id)
             }
 
-            BuiltinType::Lambda(_) => unimplemented!(),
+            BuiltinType::Lambda(lambda_id) => {
+                let lambda = self.ctxt.lambda_types.borrow().get(lambda_id);
+                let params: Vec<_> = lambda
+                    .params()
+                    .iter()
+                    .map(|&t| self.specialize_type(t))
+                    .collect();
+                let ret = self.specialize_type(lambda.ret());
+
+                let lambda_id = self.ctxt.lambda_types.borrow_mut().insert(params, ret);
+
+                BuiltinType::Lambda(lambda_id)
+            }
+
+            BuiltinType::Tuple(tuple_id) => {
+                let elements = self.ctxt.tuples.borrow().get(tuple_id);
+                let elements: Vec<_> = elements
+                    .iter()
+                    .map(|&t| self.specialize_type(t))
+                    .collect();
+
+                let tuple_id = self.ctxt.tuples.borrow_mut().insert(elements);
+
+                BuiltinType::Tuple(tuple_id)
+            }
+
+            BuiltinType::Nullable(_) => {
+                let ty = self.specialize_type(ty.non_nullable(self.ctxt));
+                BuiltinType::nullable(self.ctxt, ty)
+            }
 
             _ => ty,
         }
@@ -1967,22 +3247,35 @@ fn check_for_nil(ty: BuiltinType) -> bool {
         BuiltinType::Unit => false,
         BuiltinType::Byte
         | BuiltinType::Char
+        | BuiltinType::Short
         | BuiltinType::Int
+        | BuiltinType::UInt
         | BuiltinType::Long
+        | BuiltinType::ULong
         | BuiltinType::Float
         | BuiltinType::Double
         | BuiltinType::Bool => false,
         BuiltinType::Nil | BuiltinType::Ptr => true,
         BuiltinType::Class(_, _) => true,
-        BuiltinType::Struct(_, _) => false,
-        BuiltinType::Trait(_) => false,
+        BuiltinType::Struct(_, _) | BuiltinType::Tuple(_) => false,
+        BuiltinType::Trait(_, _) => false,
         BuiltinType::This => unreachable!(),
         BuiltinType::ClassTypeParam(_, _) => unreachable!(),
         BuiltinType::FctTypeParam(_, _) => unreachable!(),
-        BuiltinType::Lambda(_) => true,
+        BuiltinType::TraitTypeParam(_, _) => unreachable!(),
+        BuiltinType::Lambda(_) | BuiltinType::Nullable(_) => true,
+        BuiltinType::Enum(_) => true,
     }
 }
 
+// how integer arithmetic handles results that do not fit into the type
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Overflow {
+    Wrap,
+    Throw,
+    Saturate,
+}
+
 fn ensure_native_stub(ctxt: &SemContext, fct_id: FctId, internal_fct: InternalFct) -> *const u8 {
     let mut native_fcts = ctxt.native_fcts.lock().unwrap();
     let ptr = internal_fct.ptr;
@@ -2053,3 +3346,13 @@ fn to_cond_code(cmp: CmpOp) -> CondCode {
         CmpOp::IsNot => CondCode::NotEqual,
     }
 }
+
+fn to_unsigned_cond_code(cmp: CmpOp) -> CondCode {
+    match cmp {
+        CmpOp::Gt => CondCode::UnsignedGreater,
+        CmpOp::Ge => CondCode::UnsignedGreaterEq,
+        CmpOp::Lt => CondCode::UnsignedLess,
+        CmpOp::Le => CondCode::UnsignedLessEq,
+        _ => to_cond_code(cmp),
+    }
+}
@@ -267,6 +267,7 @@ pub enum Comment {
     LoadSelf(VarId),
     LoadGlobal(GlobalId),
     StoreGlobal(GlobalId),
+    InitGlobal(GlobalId),
     ReadPollingPage,
 }
 
@@ -375,6 +376,13 @@ impl<'a, 'ast> fmt::Display for CommentFormat<'a, 'ast> {
                 write!(f, "store global {}", name)
             }
 
+            &Comment::InitGlobal(gid) => {
+                let glob = self.ctxt.globals[gid].borrow();
+                let name = self.ctxt.interner.str(glob.name);
+
+                write!(f, "init global {}", name)
+            }
+
             &Comment::LoadGlobal(gid) => {
                 let glob = &self.ctxt.globals[gid].borrow();
                 let name = self.ctxt.interner.str(glob.name);
@@ -414,7 +422,7 @@ impl LineNumberTable {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct ExHandler {
     pub try_start: usize,
     pub try_end: usize,
@@ -7,8 +7,8 @@ use dora_parser::ast::Expr::*;
 use dora_parser::ast::visit::*;
 use class::TypeParams;
 use cpu::*;
-use ctxt::{Arg, CallSite, CallType, Fct, FctId, FctKind, FctParent, FctSrc, Intrinsic, NodeMap,
-           SemContext, Store, TraitId, VarId};
+use ctxt::{Arg, CallSite, CallType, EnumId, Fct, FctId, FctKind, FctParent, FctSrc, Intrinsic,
+           LambdaCallee, NodeMap, SemContext, Store, TraitId, VarId};
 use mem;
 use ty::BuiltinType;
 
@@ -20,7 +20,12 @@ pub fn generate<'a, 'ast: 'a>(
     cls_type_params: &TypeParams,
     fct_type_params: &TypeParams,
 ) {
-    let start = if fct.has_self() { 1 } else { 0 };
+    // lambdas get their closure passed as first argument
+    let start = if fct.has_self() || fct.is_lambda() {
+        1
+    } else {
+        0
+    };
 
     let mut ig = InfoGenerator {
         ctxt: ctxt,
@@ -55,6 +60,8 @@ pub struct JitInfo<'ast> {
     pub argsize: i32,                 // size of arguments on stack (need to be on bottom)
     pub leaf: bool,                   // false if fct calls other functions
     pub eh_return_value: Option<i32>, // stack slot for return value storage
+    pub struct_return: Option<i32>,   // stack slot with address for returned struct
+    pub struct_slots: Vec<(i32, BuiltinType)>, // stack slots for struct values
 
     pub map_stores: NodeMap<Store>,
     pub map_csites: NodeMap<CallSite<'ast>>,
@@ -63,6 +70,7 @@ pub struct JitInfo<'ast> {
     pub map_var_types: HashMap<VarId, BuiltinType>,
     pub map_intrinsics: NodeMap<Intrinsic>,
     pub map_fors: NodeMap<ForInfo<'ast>>,
+    pub map_templates: NodeMap<TemplateInfo<'ast>>,
 }
 
 impl<'ast> JitInfo<'ast> {
@@ -96,6 +104,8 @@ impl<'ast> JitInfo<'ast> {
             argsize: 0,
             leaf: false,
             eh_return_value: None,
+            struct_return: None,
+            struct_slots: Vec::new(),
 
             map_stores: NodeMap::new(),
             map_csites: NodeMap::new(),
@@ -104,6 +114,7 @@ impl<'ast> JitInfo<'ast> {
             map_var_types: HashMap::new(),
             map_intrinsics: NodeMap::new(),
             map_fors: NodeMap::new(),
+            map_templates: NodeMap::new(),
         }
     }
 }
@@ -141,9 +152,21 @@ impl<'a, 'ast> Visitor<'ast> for InfoGenerator<'a, 'ast> {
 
         let is_float = ty.is_float();
 
+        // structs are passed as pointer to a copy of the value,
+        // the callee copies the value into its own variable
+        if ty.is_struct() {
+            self.reserve_stack_for_var(var);
+
+            if self.param_reg_idx < REG_PARAMS.len() {
+                self.param_reg_idx += 1;
+            } else {
+                self.jit_info.map_offsets.insert(p.id, self.param_offset);
+                self.param_offset = next_param_offset(self.param_offset, ty);
+            }
+
         // only some parameters are passed in registers
         // these registers need to be stored into local variables
-        if is_float && self.param_freg_idx < FREG_PARAMS.len() {
+        } else if is_float && self.param_freg_idx < FREG_PARAMS.len() {
             self.reserve_stack_for_var(var);
             self.param_freg_idx += 1;
         } else if !is_float && self.param_reg_idx < REG_PARAMS.len() {
@@ -170,10 +193,22 @@ impl<'a, 'ast> Visitor<'ast> for InfoGenerator<'a, 'ast> {
                 self.reserve_stack_for_var(var);
             }
 
+            &StmtVarTuple(ref stmt) => {
+                for var in &stmt.vars {
+                    if let Some(&var_id) = self.src.map_vars.get(var.id) {
+                        self.reserve_stack_for_var(var_id);
+                    }
+                }
+            }
+
             &StmtDo(ref try) => {
                 self.reserve_stmt_do(try);
             }
 
+            &StmtDefer(ref defer) => {
+                self.reserve_stmt_defer(defer);
+            }
+
             &StmtFor(ref sfor) => {
                 self.reserve_stmt_for(sfor);
             }
@@ -199,7 +234,15 @@ impl<'a, 'ast> Visitor<'ast> for InfoGenerator<'a, 'ast> {
             ExprBin(ref expr) => self.expr_bin(expr),
             ExprUn(ref expr) => self.expr_un(expr),
             ExprConv(ref expr) => self.expr_conv(expr),
+            ExprField(ref expr) => self.expr_field(expr),
+            ExprIdent(ref expr) => self.expr_ident(expr),
             ExprLitStruct(ref expr) => self.expr_lit_struct(expr),
+            ExprLambda(ref expr) => self.expr_closure(&expr.fct),
+            ExprSpawn(ref expr) => self.expr_closure(&expr.fct),
+            ExprPath(ref expr) => self.expr_path(expr),
+            ExprMatch(ref expr) => self.expr_match(expr),
+            ExprTuple(ref expr) => self.expr_tuple(expr),
+            ExprTemplate(ref expr) => self.expr_template(expr),
 
             _ => visit::walk_expr(self, e),
         }
@@ -212,8 +255,25 @@ impl<'a, 'ast> InfoGenerator<'a, 'ast> {
             self.reserve_stack_for_self();
         }
 
+        if self.fct.is_lambda() {
+            self.reserve_stack_for_closure();
+        }
+
         self.visit_fct(self.ast);
 
+        let return_type = self.specialize_type(self.fct.return_type);
+
+        // address for returned structs is passed after all other arguments
+        if return_type.is_struct() {
+            let offset = if self.param_reg_idx < REG_PARAMS.len() {
+                self.reserve_stack_for_type(BuiltinType::Ptr)
+            } else {
+                self.param_offset
+            };
+
+            self.jit_info.struct_return = Some(offset);
+        }
+
         self.jit_info.localsize = self.localsize;
         self.jit_info.tempsize = self.max_tempsize;
         self.jit_info.argsize = self.argsize;
@@ -222,14 +282,7 @@ impl<'a, 'ast> InfoGenerator<'a, 'ast> {
     }
 
     fn reserve_stmt_do(&mut self, try: &'ast StmtDoType) {
-        let ret = self.fct.return_type;
-
-        if !ret.is_unit() {
-            self.eh_return_value = Some(
-                self.eh_return_value
-                    .unwrap_or_else(|| self.reserve_stack_for_type(ret)),
-            );
-        }
+        self.reserve_eh_return_value();
 
         // we also need space for catch block parameters
         for catch in &try.catch_blocks {
@@ -243,6 +296,33 @@ impl<'a, 'ast> InfoGenerator<'a, 'ast> {
         }
     }
 
+    fn reserve_stmt_defer(&mut self, defer: &'ast StmtDeferType) {
+        self.reserve_eh_return_value();
+
+        // deferred expression is executed like a finally block,
+        // we need space for the exception object
+        let offset = self.reserve_stack_for_type(BuiltinType::Ptr);
+        self.jit_info.map_offsets.insert(defer.id, offset);
+    }
+
+    fn reserve_eh_return_value(&mut self) {
+        let ret = self.specialize_type(self.fct.return_type);
+
+        // for structs only the address of the returned value is stored
+        let ret = if ret.is_struct() {
+            BuiltinType::Ptr
+        } else {
+            ret
+        };
+
+        if !ret.is_unit() {
+            self.eh_return_value = Some(
+                self.eh_return_value
+                    .unwrap_or_else(|| self.reserve_stack_for_type(ret)),
+            );
+        }
+    }
+
     fn reserve_stmt_for(&mut self, stmt: &'ast StmtForType) {
         let for_type_info = self.src.map_fors.get(stmt.id).unwrap();
 
@@ -310,6 +390,18 @@ impl<'a, 'ast> InfoGenerator<'a, 'ast> {
         self.jit_info.map_var_offsets.insert(id, offset);
     }
 
+    fn reserve_stack_for_closure(&mut self) {
+        let src = self.src;
+        let lambda = src.map_lambdas.get(self.ast.id).unwrap();
+
+        self.reserve_stack_for_var(lambda.closure);
+
+        // captured variables are copied from the closure into local variables
+        for &var in &lambda.captures {
+            self.reserve_stack_for_var(var);
+        }
+    }
+
     fn reserve_stack_for_var(&mut self, id: VarId) -> i32 {
         let ty = self.src.vars[id].ty;
         let ty = self.specialize_type(ty);
@@ -323,17 +415,30 @@ impl<'a, 'ast> InfoGenerator<'a, 'ast> {
 
     fn reserve_stack_for_type(&mut self, ty: BuiltinType) -> i32 {
         let ty_size = ty.size(self.ctxt);
-        self.localsize = mem::align_i32(self.localsize + ty_size, ty_size);
+        let ty_align = ty.align(self.ctxt);
+        self.localsize = mem::align_i32(self.localsize + ty_size, ty_align);
 
         -self.localsize
     }
 
+    // struct values of expressions get their own stack slot, they are
+    // never shared with other expressions such that the GC can always scan them
+    fn reserve_struct_slot(&mut self, id: NodeId, ty: BuiltinType) -> i32 {
+        let offset = self.reserve_stack_for_type(ty);
+
+        self.jit_info.map_offsets.insert(id, offset);
+        self.jit_info.struct_slots.push((offset, ty));
+
+        offset
+    }
+
     fn expr_array(&mut self, expr: &'ast ExprArrayType) {
         if let Some(intrinsic) = self.get_intrinsic(expr.id) {
             self.visit_expr(&expr.object);
             self.visit_expr(&expr.index);
 
             self.reserve_temp_for_node(&expr.object);
+            self.reserve_struct_slot_for_intrinsic(expr.id);
             self.jit_info.map_intrinsics.insert(expr.id, intrinsic);
         } else {
             let args = vec![
@@ -355,13 +460,208 @@ impl<'a, 'ast> InfoGenerator<'a, 'ast> {
     }
 
     fn expr_lit_struct(&mut self, e: &'ast ExprLitStructType) {
-        self.reserve_temp_for_node_id(e.id);
+        let ty = self.ty(e.id);
+        self.reserve_struct_slot(e.id, ty);
 
         for arg in &e.args {
             self.visit_expr(&arg.expr);
         }
     }
 
+    fn expr_tuple(&mut self, e: &'ast ExprTupleType) {
+        let ty = self.ty(e.id);
+        self.reserve_struct_slot(e.id, ty);
+
+        for value in &e.values {
+            self.visit_expr(value);
+        }
+    }
+
+    fn expr_template(&mut self, e: &'ast ExprTemplateType) {
+        // empty string literals do not contribute to the result
+        let exprs = e.parts
+            .iter()
+            .filter(|part| match part.to_lit_str() {
+                Some(lit) => !lit.value.is_empty(),
+                None => true,
+            })
+            .collect::<Vec<_>>();
+
+        // all parts are stored in consecutive stack slots and passed as array
+        // to the runtime, the first part needs to be stored at the lowest address
+        let mut offsets = exprs
+            .iter()
+            .map(|_| self.reserve_temp_for_type(BuiltinType::Ptr))
+            .collect::<Vec<_>>();
+        offsets.reverse();
+
+        let mut parts = Vec::with_capacity(exprs.len());
+
+        for (expr, offset) in exprs.into_iter().zip(offsets) {
+            let to_string = if let Some(&fct_id) = self.src.map_templates.get(expr.id()) {
+                let object_type = self.ty(expr.id());
+
+                let callee_id = {
+                    let fct = self.ctxt.fcts[fct_id].borrow();
+
+                    if fct.in_trait() {
+                        self.find_trait_impl(fct_id, fct.trait_id(), object_type)
+                    } else {
+                        fct_id
+                    }
+                };
+
+                let ctype = CallType::Method(object_type, callee_id, TypeParams::empty());
+                let args = vec![Arg::Expr(expr, BuiltinType::Unit, 0)];
+
+                Some(self.build_call_site(&ctype, callee_id, args))
+            } else {
+                self.visit_expr(expr);
+
+                None
+            };
+
+            parts.push(TemplatePart {
+                expr: expr,
+                offset: offset,
+                to_string: to_string,
+            });
+        }
+
+        self.leaf = false;
+        self.jit_info
+            .map_templates
+            .insert(e.id, TemplateInfo { parts: parts });
+    }
+
+    fn expr_field(&mut self, e: &'ast ExprFieldType) {
+        self.visit_expr(&e.object);
+
+        // struct fields of objects are copied onto the stack
+        let ty = self.ty(e.id);
+
+        if ty.is_struct() && self.ty(e.object.id()).is_cls() {
+            self.reserve_struct_slot(e.id, ty);
+        }
+    }
+
+    fn expr_ident(&mut self, e: &'ast ExprIdentType) {
+        let ty = self.ty(e.id);
+        let field = self.src.map_idents.get(e.id).unwrap().is_field();
+
+        if ty.is_struct() && field {
+            self.reserve_struct_slot(e.id, ty);
+        }
+    }
+
+    fn expr_closure(&mut self, fct: &'ast Function) {
+        // creating the closure allocates an object, the closure itself
+        // is stored under the id of the lambda's function
+        self.leaf = false;
+        self.reserve_temp_for_node_with_type(fct.id, BuiltinType::Ptr);
+    }
+
+    fn expr_call_lambda(&mut self, expr: &'ast ExprCallType, callee: LambdaCallee) {
+        let ty = match callee {
+            LambdaCallee::Var(var_id) => self.specialize_type(self.src.vars[var_id].ty),
+
+            LambdaCallee::Field(cls_ty, field_id) => {
+                let cls_ty = self.specialize_type(cls_ty);
+                let cls_id = cls_ty.cls_id(self.ctxt).unwrap();
+                let field_ty = self.ctxt.classes[cls_id].borrow().fields[field_id].ty;
+
+                let cls_type_params = cls_ty.type_params(self.ctxt);
+
+                specialize_type(self.ctxt, field_ty, &cls_type_params, &TypeParams::empty())
+            }
+
+            LambdaCallee::Expr => self.ty(expr.callee.as_ref().unwrap().id()),
+        };
+
+        // a variable checked against nil is still declared nullable
+        let ty = ty.non_nullable(self.ctxt);
+
+        // the closure is evaluated before the arguments and kept in a temp,
+        // a field is loaded right after evaluating its object
+        if let Some(node) = expr.object.as_ref().or(expr.callee.as_ref()) {
+            self.visit_expr(node);
+            self.reserve_temp_for_node_with_type(node.id(), ty);
+        }
+
+        let lambda = match ty {
+            BuiltinType::Lambda(lambda_id) => self.ctxt.lambda_types.borrow().get(lambda_id),
+            _ => unreachable!(),
+        };
+
+        let mut args = expr.args
+            .iter()
+            .zip(lambda.params())
+            .map(|(arg, &ty)| {
+                let offset = self.reserve_temp_for_node_with_type(arg.id(), ty);
+                Arg::Expr(arg, ty, offset)
+            })
+            .collect::<Vec<_>>();
+
+        // closure is passed as first argument
+        args.insert(0, Arg::Stack(0, ty, 0));
+
+        let return_type = lambda.ret();
+
+        if return_type.is_struct() {
+            self.reserve_struct_slot(expr.id, return_type);
+        }
+
+        self.leaf = false;
+        self.determine_call_stack(&args, return_type);
+    }
+
+    fn expr_path(&mut self, expr: &'ast ExprPathType) {
+        match **self.src.map_calls.get(expr.id).unwrap() {
+            CallType::Enum(enum_id, variant) => {
+                self.expr_enum_variant(expr.id, enum_id, variant, &[]);
+            }
+
+            _ => unreachable!(),
+        }
+    }
+
+    fn expr_enum_variant(
+        &mut self,
+        id: NodeId,
+        enum_id: EnumId,
+        variant: usize,
+        args: &'ast [Box<Expr>],
+    ) {
+        let xenum = self.ctxt.enums[enum_id].borrow();
+
+        // arguments are evaluated into temporaries with the type of the variant's field
+        for (arg, &ty) in args.iter().zip(&xenum.variants[variant].types) {
+            self.visit_expr(arg);
+            self.reserve_temp_for_node_with_type(arg.id(), ty);
+        }
+
+        // the allocated object is stored under the id of the whole expression
+        self.leaf = false;
+        self.reserve_temp_for_node_with_type(id, BuiltinType::Ptr);
+    }
+
+    fn expr_match(&mut self, expr: &'ast ExprMatchType) {
+        self.visit_expr(&expr.expr);
+        self.reserve_temp_for_node_with_type(expr.expr.id(), BuiltinType::Ptr);
+
+        for arm in &expr.arms {
+            if let MatchPattern::Variant(_, ref params) = arm.pattern {
+                for param in params {
+                    if let Some(&var_id) = self.src.map_vars.get(param.id) {
+                        self.reserve_stack_for_var(var_id);
+                    }
+                }
+            }
+
+            self.visit_expr(&arm.value);
+        }
+    }
+
     fn get_intrinsic(&self, id: NodeId) -> Option<Intrinsic> {
         let fid = self.src.map_calls.get(id).unwrap().fct_id();
 
@@ -379,8 +679,23 @@ impl<'a, 'ast> InfoGenerator<'a, 'ast> {
     }
 
     fn expr_call(&mut self, expr: &'ast ExprCallType) {
+        match **self.src.map_calls.get(expr.id).unwrap() {
+            CallType::Lambda(callee) => {
+                self.expr_call_lambda(expr, callee);
+                return;
+            }
+
+            CallType::Enum(enum_id, variant) => {
+                self.expr_enum_variant(expr.id, enum_id, variant, &expr.args);
+                return;
+            }
+
+            _ => {}
+        }
+
         if let Some(intrinsic) = self.get_intrinsic(expr.id) {
             self.reserve_args(expr);
+            self.reserve_struct_slot_for_intrinsic(expr.id);
             self.jit_info.map_intrinsics.insert(expr.id, intrinsic);
             return;
         }
@@ -418,11 +733,13 @@ impl<'a, 'ast> InfoGenerator<'a, 'ast> {
             CallType::Fct(fid, _, _) => {
                 fct_id = fid;
             }
+
+            CallType::Lambda(_) | CallType::Enum(_, _) => unreachable!(),
         }
 
         let fct = self.ctxt.fcts[fct_id].borrow();
 
-        let callee_id = if fct.kind.is_definition() {
+        let callee_id = if fct.in_trait() {
             let trait_id = fct.trait_id();
             let object_type = match *call_type {
                 CallType::Method(ty, _, _) => ty,
@@ -440,13 +757,37 @@ impl<'a, 'ast> InfoGenerator<'a, 'ast> {
 
         if let FctKind::Builtin(intrinsic) = callee.kind {
             self.reserve_args(expr);
+            self.reserve_struct_slot_for_intrinsic(expr.id);
             self.jit_info.map_intrinsics.insert(expr.id, intrinsic);
             return;
         }
 
+        self.pack_varargs(&*fct, &expr.args, &mut args);
         self.universal_call(expr.id, args, Some(callee_id));
     }
 
+    fn pack_varargs(&self, fct: &Fct<'ast>, exprs: &'ast [Box<Expr>], args: &mut Vec<Arg<'ast>>) {
+        let arg_types = exprs
+            .iter()
+            .map(|arg| self.src.ty(arg.id()))
+            .collect::<Vec<_>>();
+
+        if let Some(start) = fct.variadic_start(self.ctxt, &arg_types) {
+            let len = args.len() - (exprs.len() - start);
+            args.truncate(len);
+            args.push(Arg::Varargs(&exprs[start..], BuiltinType::Unit, 0, 0));
+        }
+    }
+
+    // intrinsics like array accesses need storage for struct results
+    fn reserve_struct_slot_for_intrinsic(&mut self, id: NodeId) {
+        let ty = self.ty(id);
+
+        if ty.is_struct() {
+            self.reserve_struct_slot(id, ty);
+        }
+    }
+
     fn reserve_args(&mut self, expr: &'ast ExprCallType) {
         for arg in &expr.args {
             self.visit_expr(arg);
@@ -491,6 +832,10 @@ impl<'a, 'ast> InfoGenerator<'a, 'ast> {
         let cls = self.ty(expr.id);
         args.insert(0, Arg::Selfie(cls, 0));
 
+        let call_type = self.src.map_calls.get(expr.id).unwrap().clone();
+        let ctor = self.ctxt.fcts[call_type.fct_id()].borrow();
+        self.pack_varargs(&*ctor, &expr.args, &mut args);
+
         self.universal_call(expr.id, args, None);
     }
 
@@ -524,7 +869,16 @@ impl<'a, 'ast> InfoGenerator<'a, 'ast> {
             self.determine_call_args_and_types(&*call_type, &*callee, args);
         let (cls_type_params, fct_type_params) = self.determine_call_type_params(&*call_type);
 
-        self.determine_call_stack(&args);
+        self.determine_call_stack(&args, return_type);
+
+        let return_slot = if return_type.is_struct() {
+            let offset = self.reserve_stack_for_type(return_type);
+            self.jit_info.struct_slots.push((offset, return_type));
+
+            Some(offset)
+        } else {
+            None
+        };
 
         CallSite {
             callee: callee_id,
@@ -533,6 +887,7 @@ impl<'a, 'ast> InfoGenerator<'a, 'ast> {
             fct_type_params: fct_type_params,
             super_call: super_call,
             return_type: return_type,
+            return_slot: return_slot,
         }
     }
 
@@ -565,6 +920,11 @@ impl<'a, 'ast> InfoGenerator<'a, 'ast> {
                     Arg::Stack(soffset, _, _) => Arg::Stack(soffset, ty, offset),
                     Arg::SelfieNew(cid, _) => Arg::SelfieNew(cid, offset),
                     Arg::Selfie(cid, _) => Arg::Selfie(cid, offset),
+
+                    Arg::Varargs(exprs, _, _, _) => {
+                        let length_offset = self.reserve_temp_for_type(BuiltinType::Int);
+                        Arg::Varargs(exprs, ty, offset, length_offset)
+                    }
                 }
             })
             .collect::<Vec<_>>();
@@ -595,13 +955,16 @@ impl<'a, 'ast> InfoGenerator<'a, 'ast> {
                 cls_type_params = cls_tps.clone();
                 fct_type_params = fct_tps.clone();
             }
+
+            CallType::Lambda(_) | CallType::Enum(_, _) => unreachable!(),
         }
 
         (cls_type_params, fct_type_params)
     }
 
-    fn determine_call_stack(&mut self, args: &[Arg<'ast>]) {
-        let mut reg_args: i32 = 0;
+    fn determine_call_stack(&mut self, args: &[Arg<'ast>], return_type: BuiltinType) {
+        // address for struct result is passed as last argument
+        let mut reg_args: i32 = if return_type.is_struct() { 1 } else { 0 };
         let mut freg_args: i32 = 0;
 
         for arg in args {
@@ -623,6 +986,14 @@ impl<'a, 'ast> InfoGenerator<'a, 'ast> {
                         reg_args += 1;
                     }
                 }
+
+                Arg::Varargs(exprs, _, _, _) => {
+                    for expr in exprs {
+                        self.visit_expr(expr);
+                    }
+
+                    reg_args += 1;
+                }
             }
         }
 
@@ -751,7 +1122,9 @@ impl<'a, 'ast> InfoGenerator<'a, 'ast> {
 
     fn reserve_temp_for_type(&mut self, ty: BuiltinType) -> i32 {
         let ty_size = ty.size(self.ctxt);
-        self.cur_tempsize = mem::align_i32(self.cur_tempsize + ty_size, ty_size);
+        let ty_align = ty.align(self.ctxt);
+        self.cur_tempsize = mem::align_i32(self.cur_tempsize + ty_size, ty_align);
+        self.max_tempsize = max(self.cur_tempsize, self.max_tempsize);
 
         self.cur_tempsize
     }
@@ -767,7 +1140,7 @@ impl<'a, 'ast> InfoGenerator<'a, 'ast> {
                 specialize_type(self.ctxt, ty, cls_type_params, fct_type_params)
             }
 
-            CallType::Method(cls_ty, _, ref type_params) => match cls_ty {
+            CallType::Method(cls_ty, _, ref type_params) => match self.specialize_type(cls_ty) {
                 BuiltinType::Class(_, list_id) => {
                     let params = self.ctxt.lists.borrow().get(list_id);
                     specialize_type(self.ctxt, ty, &params, type_params)
@@ -780,6 +1153,8 @@ impl<'a, 'ast> InfoGenerator<'a, 'ast> {
                 let empty = TypeParams::empty();
                 specialize_type(self.ctxt, ty, type_params, &empty)
             }
+
+            CallType::Lambda(_) | CallType::Enum(_, _) => unreachable!(),
         };
 
         self.specialize_type(ty)
@@ -788,12 +1163,12 @@ impl<'a, 'ast> InfoGenerator<'a, 'ast> {
     fn specialize_type(&self, ty: BuiltinType) -> BuiltinType {
         match ty {
             BuiltinType::ClassTypeParam(cls_id, id) => {
-                debug_assert!(self.fct.parent == FctParent::Class(cls_id));
+                debug_assert!(self.fct.is_lambda() || self.fct.is_method_of(self.ctxt, cls_id));
                 self.cls_type_params[id.idx()]
             }
 
             BuiltinType::FctTypeParam(fct_id, id) => {
-                debug_assert!(self.fct.id == fct_id);
+                debug_assert!(self.fct.id == fct_id || self.fct.parent == FctParent::Fct(fct_id));
                 self.fct_type_params[id.idx()]
             }
 
@@ -806,7 +1181,36 @@ impl<'a, 'ast> InfoGenerator<'a, 'ast> {
                 BuiltinType::Class(cls_id, list_id)
             }
 
-            BuiltinType::Lambda(_) => unimplemented!(),
+            BuiltinType::Lambda(lambda_id) => {
+                let lambda = self.ctxt.lambda_types.borrow().get(lambda_id);
+                let params: Vec<_> = lambda
+                    .params()
+                    .iter()
+                    .map(|&t| self.specialize_type(t))
+                    .collect();
+                let ret = self.specialize_type(lambda.ret());
+
+                let lambda_id = self.ctxt.lambda_types.borrow_mut().insert(params, ret);
+
+                BuiltinType::Lambda(lambda_id)
+            }
+
+            BuiltinType::Tuple(tuple_id) => {
+                let elements = self.ctxt.tuples.borrow().get(tuple_id);
+                let elements: Vec<_> = elements
+                    .iter()
+                    .map(|&t| self.specialize_type(t))
+                    .collect();
+
+                let tuple_id = self.ctxt.tuples.borrow_mut().insert(elements);
+
+                BuiltinType::Tuple(tuple_id)
+            }
+
+            BuiltinType::Nullable(_) => {
+                let ty = self.specialize_type(ty.non_nullable(self.ctxt));
+                BuiltinType::nullable(self.ctxt, ty)
+            }
 
             _ => ty,
         }
@@ -837,7 +1241,38 @@ fn specialize_type(
             BuiltinType::Class(cls_id, list_id)
         }
 
-        BuiltinType::Lambda(_) => unimplemented!(),
+        BuiltinType::Lambda(lambda_id) => {
+            let lambda = ctxt.lambda_types.borrow().get(lambda_id);
+
+            let params: Vec<_> = lambda
+                .params()
+                .iter()
+                .map(|&t| specialize_type(ctxt, t, cls_type_params, fct_type_params))
+                .collect();
+            let ret = specialize_type(ctxt, lambda.ret(), cls_type_params, fct_type_params);
+
+            let lambda_id = ctxt.lambda_types.borrow_mut().insert(params, ret);
+
+            BuiltinType::Lambda(lambda_id)
+        }
+
+        BuiltinType::Tuple(tuple_id) => {
+            let elements = ctxt.tuples.borrow().get(tuple_id);
+
+            let elements: Vec<_> = elements
+                .iter()
+                .map(|&t| specialize_type(ctxt, t, cls_type_params, fct_type_params))
+                .collect();
+
+            let tuple_id = ctxt.tuples.borrow_mut().insert(elements);
+
+            BuiltinType::Tuple(tuple_id)
+        }
+
+        BuiltinType::Nullable(_) => {
+            let ty = specialize_type(ctxt, ty.non_nullable(ctxt), cls_type_params, fct_type_params);
+            BuiltinType::nullable(ctxt, ty)
+        }
 
         _ => ty,
     }
@@ -850,6 +1285,18 @@ pub struct ForInfo<'ast> {
     pub next: CallSite<'ast>,
 }
 
+#[derive(Clone)]
+pub struct TemplateInfo<'ast> {
+    pub parts: Vec<TemplatePart<'ast>>,
+}
+
+#[derive(Clone)]
+pub struct TemplatePart<'ast> {
+    pub expr: &'ast Expr,
+    pub offset: i32,
+    pub to_string: Option<CallSite<'ast>>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,7 @@
+extern crate dora;
+
+use std::process::exit;
+
+fn main() {
+    exit(dora::start_lsp());
+}
@@ -5,7 +5,7 @@ use std::iter::Iterator;
 use std::ops::{Index, IndexMut};
 use std::rc::Rc;
 
-use ctxt::{FctId, ImplId, SemContext, TraitId, TypeParam};
+use ctxt::{FctId, ImplId, ModuleId, SemContext, TraitId, TypeParam};
 use dora_parser::interner::Name;
 use dora_parser::lexer::position::Position;
 use vtable::VTableBox;
@@ -48,6 +48,7 @@ pub struct Class {
     pub id: ClassId,
     pub pos: Position,
     pub name: Name,
+    pub module: ModuleId,
     pub ty: BuiltinType,
     pub parent_class: Option<ClassId>,
     pub has_open: bool,
@@ -260,10 +261,15 @@ impl From<usize> for FieldId {
 #[derive(Debug)]
 pub struct Field {
     pub id: FieldId,
+    pub pos: Position,
     pub name: Name,
     pub ty: BuiltinType,
     pub offset: i32,
     pub reassignable: bool,
+    pub is_pub: bool,
+
+    // alias the type of the field was declared with
+    pub alias: Option<String>,
 }
 
 impl Index<FieldId> for Vec<Field> {
@@ -302,6 +308,7 @@ pub enum ClassSize {
     Fixed(i32),
     Array(i32),
     ObjArray,
+    StructArray(i32),
     Str,
 }
 
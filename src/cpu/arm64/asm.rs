@@ -253,6 +253,11 @@ pub fn ldrh_ind(rt: Reg, rn: Reg, rm: Reg, extend: LdStExtend, amount: u32) -> u
     cls_ldst_regoffset(0b01, 0, 0b01, rm, extend, amount, rn, rt.asm())
 }
 
+pub fn ldrsh_ind(rt: Reg, rn: Reg, rm: Reg, extend: LdStExtend, amount: u32) -> u32 {
+    assert!(rt.is_gpr());
+    cls_ldst_regoffset(0b01, 0, 0b11, rm, extend, amount, rn, rt.asm())
+}
+
 pub fn ldrw_ind(rt: Reg, rn: Reg, rm: Reg, extend: LdStExtend, amount: u32) -> u32 {
     assert!(rt.is_gpr());
     cls_ldst_regoffset(0b10, 0, 0b01, rm, extend, amount, rn, rt.asm())
@@ -569,6 +574,14 @@ pub fn mul(sf: u32, rd: Reg, rn: Reg, rm: Reg) -> u32 {
     madd(sf, rd, rn, rm, REG_ZERO)
 }
 
+pub fn smull(rd: Reg, rn: Reg, rm: Reg) -> u32 {
+    cls_dataproc3(1, 0, 0b001, rm, 0, REG_ZERO, rn, rd)
+}
+
+pub fn smulh(rd: Reg, rn: Reg, rm: Reg) -> u32 {
+    cls_dataproc3(1, 0, 0b010, rm, 0, REG_ZERO, rn, rd)
+}
+
 fn cls_dataproc3(
     sf: u32,
     op54: u32,
@@ -642,6 +655,10 @@ pub fn uxtb(rd: Reg, rn: Reg) -> u32 {
     ubfm(0, rd, rn, 0, 7)
 }
 
+pub fn sxth(rd: Reg, rn: Reg) -> u32 {
+    sbfm(0, rd, rn, 0, 15)
+}
+
 pub fn sxtw(rd: Reg, rn: Reg) -> u32 {
     sbfm(1, rd, rn, 0, 31)
 }
@@ -815,6 +832,10 @@ pub fn scvtf(sf: u32, ty: u32, rd: FReg, rn: Reg) -> u32 {
     cls_fp_int(sf, 0, ty, 0b00, 0b010, rn.asm(), rd.asm())
 }
 
+pub fn ucvtf(sf: u32, ty: u32, rd: FReg, rn: Reg) -> u32 {
+    cls_fp_int(sf, 0, ty, 0b00, 0b011, rn.asm(), rd.asm())
+}
+
 pub fn fcvtzs(sf: u32, ty: u32, rd: Reg, rn: FReg) -> u32 {
     cls_fp_int(sf, 0, ty, 0b11, 0b000, rn.asm(), rd.asm())
 }
@@ -886,6 +907,7 @@ impl From<CondCode> for Cond {
             CondCode::UnsignedGreaterEq => Cond::HS,
             CondCode::UnsignedLess => Cond::LO,
             CondCode::UnsignedLessEq => Cond::LS,
+            CondCode::Overflow => Cond::VS,
         }
     }
 }
@@ -1316,6 +1338,9 @@ mod tests {
         assert_emit!(0x78626820; ldrh_ind(R0, R1, R2, LdStExtend::LSL, 0));
         assert_emit!(0x78656883; ldrh_ind(R3, R4, R5, LdStExtend::LSL, 0));
 
+        assert_emit!(0x78e26820; ldrsh_ind(R0, R1, R2, LdStExtend::LSL, 0));
+        assert_emit!(0x78e56883; ldrsh_ind(R3, R4, R5, LdStExtend::LSL, 0));
+
         assert_emit!(0xb8626820; ldrw_ind(R0, R1, R2, LdStExtend::LSL, 0));
         assert_emit!(0xb8657883; ldrw_ind(R3, R4, R5, LdStExtend::LSL, 1));
         assert_emit!(0xb86858e6; ldrw_ind(R6, R7, R8, LdStExtend::UXTW, 1));
@@ -1525,6 +1550,12 @@ mod tests {
         assert_emit!(0x1b067ca4; mul(0, R4, R5, R6));
     }
 
+    #[test]
+    fn test_smull_smulh() {
+        assert_emit!(0x9b237c41; smull(R1, R2, R3));
+        assert_emit!(0x9b467ca4; smulh(R4, R5, R6));
+    }
+
     #[test]
     fn test_bfm() {
         assert_emit!(0x53010820; ubfm(0, R0, R1, 1, 2));
@@ -1642,12 +1673,26 @@ mod tests {
         assert_eq!(0x9e620083, scvtf(1, 1, F3, R4));
     }
 
+    #[test]
+    fn test_ucvtf() {
+        assert_eq!(0x1e230041, ucvtf(0, 0, F1, R2));
+        assert_eq!(0x1e630041, ucvtf(0, 1, F1, R2));
+        assert_eq!(0x9e230083, ucvtf(1, 0, F3, R4));
+        assert_eq!(0x9e630083, ucvtf(1, 1, F3, R4));
+    }
+
     #[test]
     fn test_uxtw() {
         assert_eq!(0xD3407c00, uxtw(R0, R0));
         assert_eq!(0xD3407d8f, uxtw(R15, R12));
     }
 
+    #[test]
+    fn test_sxth() {
+        assert_eq!(0x13003c20, sxth(R0, R1));
+        assert_eq!(0x13003d8f, sxth(R15, R12));
+    }
+
     #[test]
     fn test_sxtw() {
         assert_eq!(0x93407c00, sxtw(R0, R0));
@@ -43,3 +43,14 @@ pub enum Mem {
     // reg1 * val1 + val2
     Offset(Reg, i32, i32),
 }
+
+impl Mem {
+    pub fn offset(&self, disp: i32) -> Mem {
+        match *self {
+            Mem::Local(offset) => Mem::Local(offset + disp),
+            Mem::Base(base, offset) => Mem::Base(base, offset + disp),
+            Mem::Index(base, index, scale, offset) => Mem::Index(base, index, scale, offset + disp),
+            Mem::Offset(index, scale, offset) => Mem::Offset(index, scale, offset + disp),
+        }
+    }
+}
@@ -73,6 +73,18 @@ pub fn emit_movzbl_memq_reg(buf: &mut MacroAssembler, src: Reg, disp: i32, dest:
     emit_membase(buf, src, disp, dest);
 }
 
+pub fn emit_movswl_memq_reg(buf: &mut MacroAssembler, src: Reg, disp: i32, dest: Reg) {
+    let src_msb = if src == RIP { 0 } else { src.msb() };
+
+    if dest.msb() != 0 || src_msb != 0 {
+        emit_rex(buf, 0, dest.msb(), 0, src_msb);
+    }
+
+    emit_op(buf, 0x0F);
+    emit_op(buf, 0xBF);
+    emit_membase(buf, src, disp, dest);
+}
+
 pub fn emit_movq_memq_reg(buf: &mut MacroAssembler, src: Reg, disp: i32, dest: Reg) {
     emit_mov_memq_reg(buf, 0, 1, 0x8b, src, disp, dest);
 }
@@ -104,6 +116,11 @@ pub fn emit_movl_reg_memq(buf: &mut MacroAssembler, src: Reg, dest: Reg, disp: i
     emit_mov_reg_memq(buf, 0x89, 0, src, dest, disp);
 }
 
+pub fn emit_movw_reg_memq(buf: &mut MacroAssembler, src: Reg, dest: Reg, disp: i32) {
+    emit_op(buf, 0x66);
+    emit_mov_reg_memq(buf, 0x89, 0, src, dest, disp);
+}
+
 pub fn emit_movb_reg_memq(buf: &mut MacroAssembler, src: Reg, dest: Reg, disp: i32) {
     let dest_msb =
         if dest == RIP { 0 } else { dest.msb() };
@@ -239,14 +256,36 @@ fn emit_membase(buf: &mut MacroAssembler, base: Reg, disp: i32, dest: Reg) {
 pub fn emit_cmp_imm_reg(buf: &mut MacroAssembler, mode: MachineMode, imm: i32, reg: Reg) {
     let x64 = match mode {
         MachineMode::Int8 | MachineMode::Int32 => 0,
-        MachineMode::Int64 => unimplemented!(),
+        MachineMode::Int16 => return emit_cmpw_imm_reg(buf, imm, reg),
         MachineMode::Float32 | MachineMode::Float64 => unreachable!(),
-        MachineMode::Ptr => 1,
+        MachineMode::Int64 | MachineMode::Ptr => 1,
     };
 
     emit_aluq_imm_reg(buf, x64, imm, reg, 0x3d, 0b111);
 }
 
+fn emit_cmpw_imm_reg(buf: &mut MacroAssembler, imm: i32, reg: Reg) {
+    assert!(fits_i16(imm));
+    emit_op(buf, 0x66);
+
+    if reg.msb() != 0 {
+        emit_rex(buf, 0, 0, 0, reg.msb());
+    }
+
+    if fits_i8(imm) {
+        emit_op(buf, 0x83);
+        emit_modrm(buf, 0b11, 0b111, reg.and7());
+        emit_u8(buf, imm as u8);
+    } else if reg == RAX {
+        emit_op(buf, 0x3d);
+        emit_u16(buf, imm as u16);
+    } else {
+        emit_op(buf, 0x81);
+        emit_modrm(buf, 0b11, 0b111, reg.and7());
+        emit_u16(buf, imm as u16);
+    }
+}
+
 pub fn emit_subq_imm_reg(buf: &mut MacroAssembler, imm: i32, reg: Reg) {
     emit_aluq_imm_reg(buf, 1, imm, reg, 0x2d, 0b101);
 }
@@ -349,13 +388,16 @@ fn emit_alub_imm_reg(
 
 pub fn emit_sub_imm_mem(buf: &mut MacroAssembler, mode: MachineMode, base: Reg, imm: u8) {
     let (x64, opcode) = match mode {
-        MachineMode::Ptr => (1, 0x83),
-        MachineMode::Int32 => (0, 0x83),
-        MachineMode::Int64 => unimplemented!(),
+        MachineMode::Int64 | MachineMode::Ptr => (1, 0x83),
+        MachineMode::Int16 | MachineMode::Int32 => (0, 0x83),
         MachineMode::Float32 | MachineMode::Float64 => unreachable!(),
         MachineMode::Int8 => (0, 0x80),
     };
 
+    if mode == MachineMode::Int16 {
+        emit_op(buf, 0x66);
+    }
+
     if x64 != 0 || base.msb() != 0 {
         emit_rex(buf, x64, 0, 0, base.msb());
     }
@@ -397,6 +439,10 @@ pub fn emit_u32(buf: &mut MacroAssembler, val: u32) {
     buf.emit_u32(val)
 }
 
+pub fn emit_u16(buf: &mut MacroAssembler, val: u16) {
+    buf.emit_u16(val)
+}
+
 pub fn emit_u8(buf: &mut MacroAssembler, val: u8) {
     buf.emit_u8(val)
 }
@@ -434,6 +480,10 @@ pub fn fits_i8(imm: i32) -> bool {
     imm == (imm as i8) as i32
 }
 
+pub fn fits_i16(imm: i32) -> bool {
+    imm == (imm as i16) as i32
+}
+
 pub fn emit_jcc(buf: &mut MacroAssembler, cond: CondCode, lbl: Label) {
     let opcode = match cond {
         CondCode::Zero | CondCode::Equal => 0x84,
@@ -446,6 +496,7 @@ pub fn emit_jcc(buf: &mut MacroAssembler, cond: CondCode, lbl: Label) {
         CondCode::UnsignedGreaterEq => 0x83, // above or equal
         CondCode::UnsignedLess => 0x82,      // below
         CondCode::UnsignedLessEq => 0x86,    // below or equal
+        CondCode::Overflow => 0x80,
     };
 
     emit_op(buf, 0x0f);
@@ -571,6 +622,15 @@ pub fn emit_idiv_reg_reg(buf: &mut MacroAssembler, x64: u8, reg: Reg) {
     emit_modrm(buf, 0b11, 0b111, reg.and7());
 }
 
+pub fn emit_div_reg_reg(buf: &mut MacroAssembler, x64: u8, reg: Reg) {
+    if reg.msb() != 0 || x64 != 0 {
+        emit_rex(buf, x64, 0, 0, reg.msb());
+    }
+
+    emit_op(buf, 0xf7);
+    emit_modrm(buf, 0b11, 0b110, reg.and7());
+}
+
 pub fn emit_cmp_reg_reg(buf: &mut MacroAssembler, x64: u8, src: Reg, dest: Reg) {
     emit_alu_reg_reg(buf, x64, 0x39, src, dest);
 }
@@ -587,12 +647,15 @@ pub fn emit_cmp_mem_reg(
 
     let (x64, opcode) = match mode {
         MachineMode::Int8 => (0, 0x38),
-        MachineMode::Int32 => (0, 0x39),
-        MachineMode::Int64 => unimplemented!(),
+        MachineMode::Int16 | MachineMode::Int32 => (0, 0x39),
         MachineMode::Float32 | MachineMode::Float64 => unreachable!(),
-        MachineMode::Ptr => (1, 0x39),
+        MachineMode::Int64 | MachineMode::Ptr => (1, 0x39),
     };
 
+    if mode == MachineMode::Int16 {
+        emit_op(buf, 0x66);
+    }
+
     if x64 != 0 || dest.msb() != 0 || base_msb != 0 {
         emit_rex(buf, x64, dest.msb(), 0, base_msb);
     }
@@ -615,11 +678,15 @@ pub fn emit_mov_memindex_reg(
 
     let (x64, opcode) = match mode {
         MachineMode::Int8 => (0, 0x8a),
-        MachineMode::Int32 => (0, 0x8b),
+        MachineMode::Int16 | MachineMode::Int32 => (0, 0x8b),
         MachineMode::Int64 | MachineMode::Ptr => (1, 0x8b),
         MachineMode::Float32 | MachineMode::Float64 => unreachable!(),
     };
 
+    if mode == MachineMode::Int16 {
+        emit_op(buf, 0x66);
+    }
+
     if x64 != 0 || dest.msb() != 0 || index.msb() != 0 || base.msb() != 0 {
         emit_rex(buf, x64, dest.msb(), index.msb(), base.msb());
     }
@@ -645,6 +712,22 @@ pub fn emit_movzx_memindex_byte_reg(
     emit_membase_with_index_and_scale(buf, base, index, 1, disp, dest);
 }
 
+pub fn emit_movsx_memindex_word_reg(
+    buf: &mut MacroAssembler,
+    base: Reg,
+    index: Reg,
+    disp: i32,
+    dest: Reg,
+) {
+    if dest.msb() != 0 || index.msb() != 0 || base.msb() != 0 {
+        emit_rex(buf, 0, dest.msb(), index.msb(), base.msb());
+    }
+
+    emit_op(buf, 0x0f);
+    emit_op(buf, 0xbf);
+    emit_membase_with_index_and_scale(buf, base, index, 2, disp, dest);
+}
+
 pub fn emit_mov_reg_memindex(
     buf: &mut MacroAssembler,
     mode: MachineMode,
@@ -658,11 +741,15 @@ pub fn emit_mov_reg_memindex(
 
     let (x64, opcode) = match mode {
         MachineMode::Int8 => (0, 0x88),
-        MachineMode::Int32 => (0, 0x89),
+        MachineMode::Int16 | MachineMode::Int32 => (0, 0x89),
         MachineMode::Int64 | MachineMode::Ptr => (1, 0x89),
         MachineMode::Float32 | MachineMode::Float64 => unreachable!(),
     };
 
+    if mode == MachineMode::Int16 {
+        emit_op(buf, 0x66);
+    }
+
     if x64 != 0 || src.msb() != 0 || index.msb() != 0 || base.msb() != 0 {
         emit_rex(buf, x64, src.msb(), index.msb(), base.msb());
     }
@@ -685,12 +772,15 @@ pub fn emit_cmp_mem_imm(
 
     let (x64, opcode) = match mode {
         MachineMode::Int8 => (0, 0x80),
-        MachineMode::Int32 => (0, opcode),
-        MachineMode::Int64 => unimplemented!(),
-        MachineMode::Ptr => (1, opcode),
+        MachineMode::Int16 | MachineMode::Int32 => (0, opcode),
+        MachineMode::Int64 | MachineMode::Ptr => (1, opcode),
         MachineMode::Float32 | MachineMode::Float64 => unreachable!(),
     };
 
+    if mode == MachineMode::Int16 {
+        emit_op(buf, 0x66);
+    }
+
     if x64 != 0 || base_msb != 0 {
         emit_rex(buf, x64, 0, 0, base_msb);
     }
@@ -700,6 +790,9 @@ pub fn emit_cmp_mem_imm(
 
     if fits_i8(imm) {
         emit_u8(buf, imm as u8);
+    } else if mode == MachineMode::Int16 {
+        assert!(fits_i16(imm));
+        emit_u16(buf, imm as u16);
     } else {
         if mode == MachineMode::Int8 {
             panic!("Int8 does not support 32 bit values");
@@ -722,12 +815,15 @@ pub fn emit_cmp_memindex_reg(
 
     let (x64, opcode) = match mode {
         MachineMode::Int8 => (0, 0x38),
-        MachineMode::Int32 => (0, 0x39),
-        MachineMode::Int64 => unimplemented!(),
-        MachineMode::Ptr => (1, 0x39),
+        MachineMode::Int16 | MachineMode::Int32 => (0, 0x39),
+        MachineMode::Int64 | MachineMode::Ptr => (1, 0x39),
         MachineMode::Float32 | MachineMode::Float64 => unreachable!(),
     };
 
+    if mode == MachineMode::Int16 {
+        emit_op(buf, 0x66);
+    }
+
     if x64 != 0 || dest.msb() != 0 || index.msb() != 0 || base.msb() != 0 {
         emit_rex(buf, x64, dest.msb(), index.msb(), base.msb());
     }
@@ -813,6 +909,7 @@ pub fn emit_setb_reg(buf: &mut MacroAssembler, op: CondCode, reg: Reg) {
         CondCode::UnsignedLessEq => 0x96,    // below or equal
         CondCode::Zero | CondCode::Equal => 0x94,
         CondCode::NonZero | CondCode::NotEqual => 0x95,
+        CondCode::Overflow => 0x90,
     };
 
     emit_op(buf, 0x0f);
@@ -880,6 +977,7 @@ pub fn cmov(buf: &mut MacroAssembler, x64: u8, dest: Reg, src: Reg, cond: CondCo
         CondCode::UnsignedGreaterEq => 0x43, // above or equal
         CondCode::UnsignedLess => 0x42,      // below
         CondCode::UnsignedLessEq => 0x46,    // below or equal
+        CondCode::Overflow => 0x40,
     };
 
     if src.msb() != 0 || dest.msb() != 0 || x64 != 0 {
@@ -967,6 +1065,16 @@ pub fn emit_movzx_byte(buf: &mut MacroAssembler, x64: u8, src: Reg, dest: Reg) {
     emit_modrm(buf, 0b11, dest.and7(), src.and7());
 }
 
+pub fn emit_movsx_word(buf: &mut MacroAssembler, x64: u8, src: Reg, dest: Reg) {
+    if src.msb() != 0 || dest.msb() != 0 || x64 != 0 {
+        emit_rex(buf, x64, dest.msb(), 0, src.msb());
+    }
+
+    emit_op(buf, 0x0f);
+    emit_op(buf, 0xbf);
+    emit_modrm(buf, 0b11, dest.and7(), src.and7());
+}
+
 pub fn addss(buf: &mut MacroAssembler, dest: FReg, src: FReg) {
     sse_float_freg_freg(buf, false, 0x58, dest, src);
 }
@@ -1343,6 +1451,16 @@ mod tests {
         assert_eq!(vec![0x0f, 0x85, 1, 0, 0, 0, 0x90], buf.data());
     }
 
+    #[test]
+    fn test_emit_jcc_overflow() {
+        let mut buf = MacroAssembler::new();
+        let lbl = buf.create_label();
+        emit_jcc(&mut buf, CondCode::Overflow, lbl);
+        emit_nop(&mut buf);
+        buf.bind_label(lbl);
+        assert_eq!(vec![0x0f, 0x80, 1, 0, 0, 0, 0x90], buf.data());
+    }
+
     #[test]
     fn test_emit_jcc_greater() {
         let mut buf = MacroAssembler::new();
@@ -1771,6 +1889,12 @@ mod tests {
 
         // cmp [rsp+rbp],rax
         assert_emit!(0x48, 0x39, 0x04, 0x2c; emit_cmp_memindex_reg(p, RSP, RBP, 1, 0, RAX));
+
+        let w = MachineMode::Int16;
+
+        // cmp [rax+rcx*2+12],dx
+        assert_emit!(0x66, 0x39, 0x54, 0x48, 0x0c;
+                     emit_cmp_memindex_reg(w, RAX, RCX, 2, 12, RDX));
     }
 
     #[test]
@@ -1823,6 +1947,17 @@ mod tests {
 
         // cmp [rbx+1], r10d
         assert_emit!(0x44, 0x39, 0x53, 1; emit_cmp_mem_reg(i, RBX, 1, R10));
+
+        let w = MachineMode::Int16;
+
+        // cmp [rbx+1], ax
+        assert_emit!(0x66, 0x39, 0x43, 1; emit_cmp_mem_reg(w, RBX, 1, RAX));
+
+        // cmp [rbx+1], r10w
+        assert_emit!(0x66, 0x44, 0x39, 0x53, 1; emit_cmp_mem_reg(w, RBX, 1, R10));
+
+        // cmp [rbx+1], rax
+        assert_emit!(0x48, 0x39, 0x43, 1; emit_cmp_mem_reg(MachineMode::Int64, RBX, 1, RAX));
     }
 
     #[test]
@@ -1849,6 +1984,14 @@ mod tests {
         // cmp [rbx+1], 256
         assert_emit!(0x81, 0x7B, 1, 0, 1, 0, 0; emit_cmp_mem_imm(i, RBX, 1, 256));
 
+        let w = MachineMode::Int16;
+
+        // cmp [rbx+1], 2
+        assert_emit!(0x66, 0x83, 0x7B, 1, 2; emit_cmp_mem_imm(w, RBX, 1, 2));
+
+        // cmp [rbx+1], 256
+        assert_emit!(0x66, 0x81, 0x7B, 1, 0, 1; emit_cmp_mem_imm(w, RBX, 1, 256));
+
         let b = MachineMode::Int8;
 
         // cmp [rbx+1], 2
@@ -1879,6 +2022,8 @@ mod tests {
         assert_emit!(0x49, 0x83, 0x29, 1; emit_sub_imm_mem(MachineMode::Ptr, R9, 1));
         assert_emit!(0x49, 0x83, 0x28, 1; emit_sub_imm_mem(MachineMode::Ptr, R8, 1));
         assert_emit!(0x41, 0x80, 0x28, 1; emit_sub_imm_mem(MachineMode::Int8, R8, 1));
+        assert_emit!(0x66, 0x83, 0x28, 1; emit_sub_imm_mem(MachineMode::Int16, RAX, 1));
+        assert_emit!(0x48, 0x83, 0x28, 1; emit_sub_imm_mem(MachineMode::Int64, RAX, 1));
     }
 
     #[test]
@@ -1887,6 +2032,8 @@ mod tests {
             emit_mov_memindex_reg(MachineMode::Ptr, RAX, RCX, 8, 12, RDX));
         assert_emit!(0x8b, 0x54, 0x88, 0x0c;
             emit_mov_memindex_reg(MachineMode::Int32, RAX, RCX, 4, 12, RDX));
+        assert_emit!(0x66, 0x8b, 0x54, 0x48, 0x0c;
+            emit_mov_memindex_reg(MachineMode::Int16, RAX, RCX, 2, 12, RDX));
         assert_emit!(0x8a, 0x54, 0x08, 0x0c;
             emit_mov_memindex_reg(MachineMode::Int8, RAX, RCX, 1, 12, RDX));
         assert_emit!(0x8a, 0x44, 0x08, 0x20;
@@ -1925,6 +2072,19 @@ mod tests {
         assert_emit!(0x41, 0x83, 0xff, 0; emit_cmp_imm_reg(MachineMode::Int32, 0, R15));
         assert_emit!(0x49, 0x83, 0xf9, 0; emit_cmp_imm_reg(MachineMode::Ptr, 0, R9));
         assert_emit!(0x41, 0x83, 0xf9, 0; emit_cmp_imm_reg(MachineMode::Int32, 0, R9));
+
+        let w = MachineMode::Int16;
+        assert_emit!(0x66, 0x83, 0xf8, 1; emit_cmp_imm_reg(w, 1, RAX));
+        assert_emit!(0x66, 0x3d, 0, 1; emit_cmp_imm_reg(w, 256, RAX));
+        assert_emit!(0x66, 0x41, 0x81, 0xf9, 0, 1; emit_cmp_imm_reg(w, 256, R9));
+        assert_emit!(0x66, 0x41, 0x83, 0xf9, 0xff; emit_cmp_imm_reg(w, -1, R9));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_cmp_reg_imm_i32_for_i16() {
+        let mut buf = MacroAssembler::new();
+        emit_cmp_imm_reg(&mut buf, MachineMode::Int16, 1 << 16, RAX);
     }
 
     #[test]
@@ -1961,6 +2121,31 @@ mod tests {
         assert_emit!(0x4d, 0x0f, 0xb6, 0xf9; emit_movzx_byte(1, R9, R15));
     }
 
+    #[test]
+    fn test_emit_movsx_word() {
+        assert_emit!(0x0f, 0xbf, 0xc0; emit_movsx_word(0, RAX, RAX));
+        assert_emit!(0x48, 0x0f, 0xbf, 0xc1; emit_movsx_word(1, RCX, RAX));
+        assert_emit!(0x45, 0x0f, 0xbf, 0xf9; emit_movsx_word(0, R9, R15));
+    }
+
+    #[test]
+    fn test_emit_movswl_memq_reg() {
+        assert_emit!(0x0f, 0xbf, 0x45, 0xf8; emit_movswl_memq_reg(RBP, -8, RAX));
+        assert_emit!(0x44, 0x0f, 0xbf, 0x51, 0x11; emit_movswl_memq_reg(RCX, 0x11, R10));
+    }
+
+    #[test]
+    fn test_emit_movw_reg_memq() {
+        assert_emit!(0x66, 0x89, 0x45, 0xf8; emit_movw_reg_memq(RAX, RBP, -8));
+        assert_emit!(0x66, 0x44, 0x89, 0x51, 0x11; emit_movw_reg_memq(R10, RCX, 0x11));
+    }
+
+    #[test]
+    fn test_emit_div_reg_reg() {
+        assert_emit!(0xf7, 0xf1; emit_div_reg_reg(0, RCX));
+        assert_emit!(0x49, 0xf7, 0xf0; emit_div_reg_reg(1, R8));
+    }
+
     #[test]
     fn test_addss() {
         assert_emit!(0xf3, 0x0f, 0x58, 0xc1; addss(XMM0, XMM1));
@@ -1,7 +1,6 @@
 use std::cell::{Cell, RefCell};
 use std::collections::hash_map::Iter;
 use std::collections::HashMap;
-use std::collections::HashSet;
 use std::ops::{Index, IndexMut};
 use std::ptr;
 use std::rc::Rc;
@@ -18,15 +17,16 @@ use baseline::stub::Stub;
 use class::{Class, ClassDef, ClassDefId, ClassId, FieldId, TypeParams};
 use exception::DoraToNativeInfo;
 use gc::Gc;
+use globals::GlobalInits;
 use dora_parser::interner::*;
 use dora_parser::lexer::position::Position;
 use handle::HandleMemory;
 use opt::util::LlvmJit;
 use safepoint::PollingPage;
-use semck::specialize::{specialize_class_id, specialize_class_id_params};
+use semck::specialize::{specialize_class_id, specialize_class_id_params, specialize_for_trait};
 use sym::*;
 use sym::Sym::*;
-use ty::{BuiltinType, LambdaTypes, TypeLists};
+use ty::{BuiltinType, LambdaTypes, NullableTypes, TupleTypes, TypeLists};
 use utils::GrowableVec;
 
 pub static mut CTXT: Option<*const u8> = None;
@@ -68,6 +68,8 @@ pub struct SemContext<'ast> {
     pub consts: GrowableVec<ConstData<'ast>>, // stores all const definitions
     pub structs: GrowableVec<StructData>,     // stores all struct source definitions
     pub struct_defs: GrowableVec<StructDef>,  // stores all struct definitions
+    pub enums: GrowableVec<EnumData>,         // stores all enum definitions
+    pub aliases: GrowableVec<AliasData<'ast>>, // stores all type aliases
     pub classes: GrowableVec<Class>,          // stores all class source definitions
     pub class_defs: GrowableVec<ClassDef>,    // stores all class definitions
     pub fcts: GrowableVec<Fct<'ast>>,         // stores all function definitions
@@ -76,6 +78,9 @@ pub struct SemContext<'ast> {
     pub impls: Vec<RefCell<ImplData>>,        // stores all impl definitions
     pub code_map: Mutex<CodeMap>,             // stores all compiled functions
     pub globals: GrowableVec<GlobalData<'ast>>, // stores all global variables
+    pub modules: Vec<ModuleData>,             // stores all modules, the root module first
+    pub global_inits: GlobalInits,            // runtime state of global initializers
+    pub thread_roots: Mutex<Vec<usize>>,      // thread objects not yet picked up by their OS thread
     pub gc: Gc,                               // garbage collector
     pub dtn: RefCell<*const DoraToNativeInfo>,
     pub native_fcts: Mutex<NativeFcts>,
@@ -83,6 +88,8 @@ pub struct SemContext<'ast> {
     pub polling_page: PollingPage,
     pub lists: RefCell<TypeLists>,
     pub lambda_types: RefCell<LambdaTypes>,
+    pub tuples: RefCell<TupleTypes>,
+    pub nullables: RefCell<NullableTypes>,
     pub handles: HandleMemory,
     pub llvm_jit: LlvmJit,
 }
@@ -98,18 +105,26 @@ impl<'ast> SemContext<'ast> {
             consts: GrowableVec::new(),
             structs: GrowableVec::new(),
             struct_defs: GrowableVec::new(),
+            enums: GrowableVec::new(),
+            aliases: GrowableVec::new(),
             classes: GrowableVec::new(),
             class_defs: GrowableVec::new(),
             traits: Vec::new(),
             impls: Vec::new(),
             globals: GrowableVec::new(),
+            modules: vec![ModuleData::new(ModuleId::root(), None, None)],
+            global_inits: GlobalInits::new(),
+            thread_roots: Mutex::new(Vec::new()),
             interner: interner,
             vips: KnownElements {
                 bool_class: empty_class_id,
                 byte_class: empty_class_id,
                 char_class: empty_class_id,
+                short_class: empty_class_id,
                 int_class: empty_class_id,
+                uint_class: empty_class_id,
                 long_class: empty_class_id,
+                ulong_class: empty_class_id,
                 float_class: empty_class_id,
                 double_class: empty_class_id,
                 object_class: empty_class_id,
@@ -119,10 +134,18 @@ impl<'ast> SemContext<'ast> {
 
                 testing_class: empty_class_id,
                 exception_class: empty_class_id,
+                arithmetic_exception_class: empty_class_id,
+                index_out_of_bounds_exception_class: empty_class_id,
+                nil_pointer_exception_class: empty_class_id,
+                cast_exception_class: empty_class_id,
+                out_of_memory_error_class: empty_class_id,
+                thread_class: empty_class_id,
+                spawn_thread_class: empty_class_id,
                 stack_trace_element_class: empty_class_id,
 
                 equals_trait: empty_trait_id,
                 comparable_trait: empty_trait_id,
+                stringable_trait: empty_trait_id,
                 iterator_trait: Cell::new(None),
 
                 int_array_def: Cell::new(None),
@@ -143,6 +166,8 @@ impl<'ast> SemContext<'ast> {
             polling_page: PollingPage::new(),
             lists: RefCell::new(TypeLists::new()),
             lambda_types: RefCell::new(LambdaTypes::new()),
+            tuples: RefCell::new(TupleTypes::new()),
+            nullables: RefCell::new(NullableTypes::new()),
             handles: HandleMemory::new(),
             llvm_jit: LlvmJit::new(),
         }
@@ -192,20 +217,104 @@ impl<'ast> SemContext<'ast> {
 
     pub fn add_fct_to_sym(&mut self, fct: Fct<'ast>) -> Result<FctId, Sym> {
         let name = fct.name;
+        let is_pub = fct.is_pub;
         let fctid = self.add_fct(fct);
 
         let mut sym = self.sym.borrow_mut();
 
-        match sym.get(name) {
+        match sym.get_local(name) {
             Some(sym) => Err(sym),
             None => {
                 assert!(sym.insert(name, SymFct(fctid)).is_none());
 
+                if is_pub {
+                    sym.export(name, SymFct(fctid));
+                }
+
                 Ok(fctid)
             }
         }
     }
 
+    pub fn find_module(&self, path: &[Name]) -> Option<ModuleId> {
+        let mut module = ModuleId::root();
+
+        for name in path {
+            module = *self.modules[module].children.get(name)?;
+        }
+
+        Some(module)
+    }
+
+    // returns the module for the path, missing modules are created
+    pub fn add_module(&mut self, path: &[Name]) -> ModuleId {
+        let mut module = ModuleId::root();
+
+        for &name in path {
+            let child = self.modules[module].children.get(&name).cloned();
+
+            module = match child {
+                Some(child) => child,
+                None => {
+                    let child = self.sym.borrow_mut().add_module();
+                    assert_eq!(child.idx(), self.modules.len());
+
+                    self.modules.push(ModuleData::new(child, Some(name), Some(module)));
+                    self.modules[module].children.insert(name, child);
+
+                    child
+                }
+            };
+        }
+
+        module
+    }
+
+    pub fn file_module(&self, file: &ast::File) -> ModuleId {
+        self.find_module(&file.module).expect("module of file missing")
+    }
+
+    pub fn module_name(&self, module: ModuleId) -> String {
+        let mut names = Vec::new();
+        let mut module = Some(module);
+
+        while let Some(id) = module {
+            let data = &self.modules[id];
+
+            if let Some(name) = data.name {
+                names.push(self.interner.str(name).to_string());
+            }
+
+            module = data.parent;
+        }
+
+        if names.is_empty() {
+            return "<root>".into();
+        }
+
+        names.reverse();
+        names.join("::")
+    }
+
+    // methods of classes are only visible outside of their module when
+    // marked `pub`, trait methods are as visible as their trait and
+    // overriding methods as visible as the method they override
+    pub fn fct_visible_in(&self, fct_id: FctId, module: ModuleId) -> bool {
+        let fct = self.fcts[fct_id].borrow();
+
+        if fct.module == module || fct.is_pub {
+            return true;
+        }
+
+        match fct.parent {
+            FctParent::Trait(_) | FctParent::Impl(_) => true,
+            _ => match fct.overrides {
+                Some(overrides) => self.fct_visible_in(overrides, module),
+                None => false,
+            },
+        }
+    }
+
     #[cfg(test)]
     pub fn cls_by_name(&self, name: &'static str) -> ClassId {
         let name = self.interner.intern(name);
@@ -265,6 +374,12 @@ pub struct StructFieldDef {
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct GlobalId(u32);
 
+impl GlobalId {
+    pub fn idx(self) -> usize {
+        self.0 as usize
+    }
+}
+
 impl From<u32> for GlobalId {
     fn from(data: u32) -> GlobalId {
         GlobalId(data)
@@ -279,7 +394,7 @@ pub struct GlobalData<'ast> {
     pub ty: BuiltinType,
     pub reassignable: bool,
     pub name: Name,
-    pub getter: Option<FctId>,
+    pub initializer: Option<FctId>,
     pub address_init: *const u8,
     pub address_value: *const u8,
 }
@@ -292,6 +407,58 @@ impl<'ast> Index<GlobalId> for GrowableVec<GlobalData<'ast>> {
     }
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ModuleId(u32);
+
+impl ModuleId {
+    pub fn root() -> ModuleId {
+        ModuleId(0)
+    }
+
+    pub fn idx(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl From<u32> for ModuleId {
+    fn from(data: u32) -> ModuleId {
+        ModuleId(data)
+    }
+}
+
+#[derive(Debug)]
+pub struct ModuleData {
+    pub id: ModuleId,
+    pub name: Option<Name>,
+    pub parent: Option<ModuleId>,
+    pub children: HashMap<Name, ModuleId>,
+}
+
+impl ModuleData {
+    fn new(id: ModuleId, name: Option<Name>, parent: Option<ModuleId>) -> ModuleData {
+        ModuleData {
+            id: id,
+            name: name,
+            parent: parent,
+            children: HashMap::new(),
+        }
+    }
+}
+
+impl Index<ModuleId> for Vec<ModuleData> {
+    type Output = ModuleData;
+
+    fn index(&self, index: ModuleId) -> &ModuleData {
+        &self[index.0 as usize]
+    }
+}
+
+impl IndexMut<ModuleId> for Vec<ModuleData> {
+    fn index_mut(&mut self, index: ModuleId) -> &mut ModuleData {
+        &mut self[index.0 as usize]
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct ImplId(u32);
 
@@ -305,8 +472,14 @@ impl From<u32> for ImplId {
 pub struct ImplData {
     pub id: ImplId,
     pub pos: Position,
+    pub module: ModuleId,
+    pub type_params: Vec<Name>,
     pub trait_id: Option<TraitId>,
     pub class_id: Option<ClassId>,
+
+    // type arguments of the implemented trait, may refer to
+    // type params of the class
+    pub trait_type_params: TypeParams,
     pub methods: Vec<FctId>,
 }
 
@@ -354,23 +527,28 @@ pub struct TraitData {
     pub id: TraitId,
     pub pos: Position,
     pub name: Name,
+    pub module: ModuleId,
+    pub type_params: Vec<TypeParam>,
+    pub aliases: Vec<AliasId>,
     pub methods: Vec<FctId>,
 }
 
 impl TraitData {
+    // `type_params` are the type arguments for the type params of the trait
     pub fn find_method(
         &self,
         ctxt: &SemContext,
         is_static: bool,
         name: Name,
         replace: Option<BuiltinType>,
+        type_params: &TypeParams,
         args: &[BuiltinType],
     ) -> Option<FctId> {
         for &method in &self.methods {
             let method = ctxt.fcts[method].borrow();
 
             if method.name == name && method.is_static == is_static
-                && params_match(replace, method.params_without_self(), args)
+                && params_match(ctxt, replace, type_params, method.params_without_self(), args)
             {
                 return Some(method.id);
             }
@@ -381,7 +559,9 @@ impl TraitData {
 }
 
 fn params_match(
+    ctxt: &SemContext,
     replace: Option<BuiltinType>,
+    type_params: &TypeParams,
     trait_args: &[BuiltinType],
     args: &[BuiltinType],
 ) -> bool {
@@ -390,6 +570,7 @@ fn params_match(
     }
 
     for (ind, &ty) in trait_args.iter().enumerate() {
+        let ty = specialize_for_trait(ctxt, ty, type_params);
         let other = args[ind];
 
         let found = if ty == BuiltinType::This {
@@ -443,6 +624,12 @@ pub struct StructData {
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct StructFieldId(u32);
 
+impl StructFieldId {
+    pub fn idx(self) -> usize {
+        self.0 as usize
+    }
+}
+
 impl From<u32> for StructFieldId {
     fn from(data: u32) -> StructFieldId {
         StructFieldId(data)
@@ -458,13 +645,99 @@ pub struct StructFieldData {
     pub offset: i32,
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct EnumId(u32);
+
+impl Index<EnumId> for GrowableVec<EnumData> {
+    type Output = RefCell<EnumData>;
+
+    fn index(&self, index: EnumId) -> &RefCell<EnumData> {
+        &self[index.0 as usize]
+    }
+}
+
+impl From<u32> for EnumId {
+    fn from(data: u32) -> EnumId {
+        EnumId(data)
+    }
+}
+
+#[derive(Debug)]
+pub struct EnumData {
+    pub id: EnumId,
+    pub pos: Position,
+    pub name: Name,
+    pub variants: Vec<EnumVariantData>,
+}
+
+impl EnumData {
+    pub fn find_variant(&self, name: Name) -> Option<usize> {
+        self.variants.iter().position(|v| v.name == name)
+    }
+}
+
+#[derive(Debug)]
+pub struct EnumVariantData {
+    pub pos: Position,
+    pub name: Name,
+    pub types: Vec<BuiltinType>,
+
+    // object layout of this variant, created on first use
+    pub cls_def: RefCell<Option<ClassDefId>>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AliasId(u32);
+
+impl<'ast> Index<AliasId> for GrowableVec<AliasData<'ast>> {
+    type Output = RefCell<AliasData<'ast>>;
+
+    fn index(&self, index: AliasId) -> &RefCell<AliasData<'ast>> {
+        &self[index.0 as usize]
+    }
+}
+
+impl From<u32> for AliasId {
+    fn from(data: u32) -> AliasId {
+        AliasId(data)
+    }
+}
+
+#[derive(Debug)]
+pub struct AliasData<'ast> {
+    pub id: AliasId,
+    pub pos: Position,
+    pub name: Name,
+    pub ast: &'ast ast::Alias,
+    pub module: ModuleId,
+    pub trait_id: Option<TraitId>,
+
+    // aliased type of an alias without type params, resolved on first use
+    pub ty: Cell<Option<BuiltinType>>,
+
+    // set while the aliased type is read, detects recursive aliases
+    pub expanding: Cell<bool>,
+}
+
+impl<'ast> AliasData<'ast> {
+    pub fn type_params(&self) -> &'ast [ast::TypeParam] {
+        match self.ast.type_params {
+            Some(ref params) => params,
+            None => &[],
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct KnownElements {
     pub bool_class: ClassId,
     pub byte_class: ClassId,
     pub char_class: ClassId,
+    pub short_class: ClassId,
     pub int_class: ClassId,
+    pub uint_class: ClassId,
     pub long_class: ClassId,
+    pub ulong_class: ClassId,
     pub float_class: ClassId,
     pub double_class: ClassId,
     pub object_class: ClassId,
@@ -473,10 +746,18 @@ pub struct KnownElements {
 
     pub testing_class: ClassId,
     pub exception_class: ClassId,
+    pub arithmetic_exception_class: ClassId,
+    pub index_out_of_bounds_exception_class: ClassId,
+    pub nil_pointer_exception_class: ClassId,
+    pub cast_exception_class: ClassId,
+    pub out_of_memory_error_class: ClassId,
+    pub thread_class: ClassId,
+    pub spawn_thread_class: ClassId,
     pub stack_trace_element_class: ClassId,
 
     pub equals_trait: TraitId,
     pub comparable_trait: TraitId,
+    pub stringable_trait: TraitId,
     pub iterator_trait: Cell<Option<TraitId>>,
 
     int_array_def: Cell<Option<ClassDefId>>,
@@ -490,6 +771,11 @@ impl KnownElements {
         self.iterator_trait.get().expect("iterator trait not set")
     }
 
+    pub fn array_ty(&self, ctxt: &SemContext, element: BuiltinType) -> BuiltinType {
+        let list_id = ctxt.lists.borrow_mut().insert(vec![element].into());
+        BuiltinType::Class(self.array_class, list_id)
+    }
+
     pub fn int_array(&self, ctxt: &SemContext) -> ClassDefId {
         let cls_id = self.int_array_def.get();
 
@@ -544,8 +830,11 @@ impl KnownElements {
             BuiltinType::Bool => Some(self.bool_class),
             BuiltinType::Byte => Some(self.byte_class),
             BuiltinType::Char => Some(self.char_class),
+            BuiltinType::Short => Some(self.short_class),
             BuiltinType::Int => Some(self.int_class),
+            BuiltinType::UInt => Some(self.uint_class),
             BuiltinType::Long => Some(self.long_class),
+            BuiltinType::ULong => Some(self.ulong_class),
             BuiltinType::Float => Some(self.float_class),
             BuiltinType::Double => Some(self.double_class),
             _ => None,
@@ -567,6 +856,7 @@ pub enum FctParent {
     Class(ClassId),
     Trait(TraitId),
     Impl(ImplId),
+    Fct(FctId),
     None,
 }
 
@@ -590,7 +880,9 @@ impl FctParent {
 pub struct TypeParam {
     pub name: Name,
     pub class_bound: Option<ClassId>,
-    pub trait_bounds: HashSet<TraitId>,
+
+    // bounds with the type arguments of the trait
+    pub trait_bounds: HashMap<TraitId, TypeParams>,
 }
 
 impl TypeParam {
@@ -598,7 +890,7 @@ impl TypeParam {
         TypeParam {
             name: name,
             class_bound: None,
-            trait_bounds: HashSet::new(),
+            trait_bounds: HashMap::new(),
         }
     }
 }
@@ -609,6 +901,7 @@ pub struct Fct<'ast> {
     pub ast: &'ast ast::Function,
     pub pos: Position,
     pub name: Name,
+    pub module: ModuleId,
     pub parent: FctParent,
     pub has_open: bool,
     pub has_override: bool,
@@ -625,6 +918,8 @@ pub struct Fct<'ast> {
 
     pub vtable_index: Option<u32>,
     pub impl_for: Option<FctId>,
+    // impl method that was copied from the default body of a trait method
+    pub is_default: bool,
     pub initialized: bool,
     pub throws: bool,
 
@@ -651,6 +946,13 @@ impl<'ast> Fct<'ast> {
         }
     }
 
+    pub fn in_impl(&self) -> bool {
+        match self.parent {
+            FctParent::Impl(_) => true,
+            _ => false,
+        }
+    }
+
     pub fn cls_id(&self) -> ClassId {
         match self.parent {
             FctParent::Class(clsid) => clsid,
@@ -665,6 +967,28 @@ impl<'ast> Fct<'ast> {
         }
     }
 
+    pub fn is_lambda(&self) -> bool {
+        match self.parent {
+            FctParent::Fct(_) => true,
+            _ => false,
+        }
+    }
+
+    // the default body of a trait method is checked for the trait first,
+    // its copies in impls are skipped then so errors are only reported once
+    pub fn skip_default_check(&self, ctxt: &SemContext) -> bool {
+        self.is_default && ctxt.diag.borrow().has_errors()
+    }
+
+    // methods in an impl also use the type params of the class
+    pub fn is_method_of(&self, ctxt: &SemContext, cls_id: ClassId) -> bool {
+        match self.parent {
+            FctParent::Class(id) => id == cls_id,
+            FctParent::Impl(impl_id) => ctxt.impls[impl_id].borrow().class_id == Some(cls_id),
+            _ => false,
+        }
+    }
+
     pub fn full_name(&self, ctxt: &SemContext) -> String {
         let mut repr = String::new();
 
@@ -752,6 +1076,58 @@ impl<'ast> Fct<'ast> {
             &self.param_types
         }
     }
+
+    pub fn is_variadic(&self) -> bool {
+        self.ast.params.last().map_or(false, |param| param.variadic)
+    }
+
+    // arguments of a variadic function are packed into a new array from
+    // the returned index on, unless an array that fits the variadic
+    // parameter is passed in its place
+    pub fn variadic_start(&self, ctxt: &SemContext, args: &[BuiltinType]) -> Option<usize> {
+        if !self.is_variadic() {
+            return None;
+        }
+
+        let start = self.params_without_self().len() - 1;
+
+        if args.len() == start + 1 {
+            let param = self.params_without_self()[start];
+            let arg = args[start];
+
+            // arrays are invariant, for `T...` the element type is only
+            // known after inferring it from the forwarded array
+            let forward = if param.type_params(ctxt)[0].is_type_param() {
+                match arg {
+                    BuiltinType::Class(cls_id, _) => cls_id == ctxt.vips.array_class,
+                    _ => false,
+                }
+            } else {
+                arg == param
+            };
+
+            if forward {
+                return None;
+            }
+        }
+
+        Some(start)
+    }
+
+    // parameter types `args` are checked against when calling this function
+    pub fn params_for_args(&self, ctxt: &SemContext, args: &[BuiltinType]) -> Vec<BuiltinType> {
+        let mut params = self.params_without_self().to_vec();
+
+        if self.variadic_start(ctxt, args).is_some() {
+            let element = params.pop().unwrap().type_params(ctxt)[0];
+
+            while params.len() < args.len() {
+                params.push(element);
+            }
+        }
+
+        params
+    }
 }
 
 #[derive(Debug)]
@@ -812,6 +1188,7 @@ pub enum Intrinsic {
     ByteEq,
     ByteCmp,
     ByteNot,
+    ByteToShort,
     ByteToInt,
     ByteToLong,
 
@@ -820,7 +1197,17 @@ pub enum Intrinsic {
     CharToInt,
     CharToLong,
 
+    ShortToInt,
+    ShortAdd,
+    ShortSub,
+    ShortMul,
+    ShortDiv,
+    ShortShl,
+    ShortNeg,
+
     IntToByte,
+    IntToShort,
+    IntToUInt,
     IntToChar,
     IntToLong,
     IntToFloat,
@@ -833,6 +1220,16 @@ pub enum Intrinsic {
     IntDiv,
     IntMod,
 
+    IntCheckedAdd,
+    IntCheckedSub,
+    IntCheckedMul,
+    IntWrappingAdd,
+    IntWrappingSub,
+    IntWrappingMul,
+    IntSaturatingAdd,
+    IntSaturatingSub,
+    IntSaturatingMul,
+
     IntOr,
     IntAnd,
     IntXor,
@@ -845,9 +1242,19 @@ pub enum Intrinsic {
     IntNeg,
     IntPlus,
 
+    UIntToInt,
+    UIntToLong,
+    UIntToFloat,
+    UIntToDouble,
+    UIntCmp,
+    UIntDiv,
+    UIntMod,
+
     LongToInt,
     LongToChar,
     LongToByte,
+    LongToShort,
+    LongToULong,
     LongToFloat,
     LongToDouble,
     LongEq,
@@ -858,6 +1265,16 @@ pub enum Intrinsic {
     LongDiv,
     LongMod,
 
+    LongCheckedAdd,
+    LongCheckedSub,
+    LongCheckedMul,
+    LongWrappingAdd,
+    LongWrappingSub,
+    LongWrappingMul,
+    LongSaturatingAdd,
+    LongSaturatingSub,
+    LongSaturatingMul,
+
     LongOr,
     LongAnd,
     LongXor,
@@ -870,6 +1287,13 @@ pub enum Intrinsic {
     LongNeg,
     LongPlus,
 
+    ULongToLong,
+    ULongToFloat,
+    ULongToDouble,
+    ULongCmp,
+    ULongDiv,
+    ULongMod,
+
     FloatToInt,
     FloatToLong,
     FloatToDouble,
@@ -920,6 +1344,8 @@ pub struct FctSrc {
     pub map_convs: NodeMap<ConvInfo>,
     pub map_cls: NodeMap<ClassId>,
     pub map_fors: NodeMap<ForTypeInfo>,
+    pub map_templates: NodeMap<FctId>, // maps template part to its toString()
+    pub map_lambdas: NodeMap<LambdaInfo>,
 
     pub always_returns: bool, // true if function is always exited via return statement
     // false if function execution could reach the closing } of this function
@@ -937,6 +1363,8 @@ impl Clone for FctSrc {
             map_convs: self.map_convs.clone(),
             map_cls: self.map_cls.clone(),
             map_fors: self.map_fors.clone(),
+            map_templates: self.map_templates.clone(),
+            map_lambdas: self.map_lambdas.clone(),
 
             vars: self.vars.clone(),
             always_returns: self.always_returns,
@@ -955,6 +1383,8 @@ impl FctSrc {
             map_convs: NodeMap::new(),
             map_cls: NodeMap::new(),
             map_fors: NodeMap::new(),
+            map_templates: NodeMap::new(),
+            map_lambdas: NodeMap::new(),
 
             vars: Vec::new(),
             always_returns: false,
@@ -1028,6 +1458,17 @@ where
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct LambdaInfo {
+    pub fct_id: Option<FctId>,
+
+    // hidden variable that stores the closure object inside the lambda
+    pub closure: VarId,
+
+    // variables of enclosing functions that are copied into the closure
+    pub captures: Vec<VarId>,
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct ConvInfo {
     pub cls_id: ClassId,
@@ -1054,6 +1495,7 @@ pub enum IdentType {
     Var(VarId),
     Global(GlobalId),
     Field(BuiltinType, FieldId),
+    StructField(BuiltinType, StructFieldId),
     Struct(StructId),
     Const(ConstId),
 }
@@ -1102,6 +1544,20 @@ pub enum CallType {
     Method(BuiltinType, FctId, TypeParams),
     CtorNew(ClassId, FctId, TypeParams),
     Ctor(ClassId, FctId, TypeParams),
+    Lambda(LambdaCallee),
+    Enum(EnumId, usize),
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum LambdaCallee {
+    // `f(x)` calls the closure stored in a local variable
+    Var(VarId),
+
+    // `obj.f(x)` calls the closure stored in a field of `obj`
+    Field(BuiltinType, FieldId),
+
+    // `e(x)` calls the closure `e` evaluates to
+    Expr,
 }
 
 impl CallType {
@@ -1126,12 +1582,21 @@ impl CallType {
         }
     }
 
+    pub fn is_lambda(&self) -> bool {
+        match *self {
+            CallType::Lambda(_) => true,
+            _ => false,
+        }
+    }
+
     pub fn fct_id(&self) -> FctId {
         match *self {
             CallType::Fct(fctid, _, _) => fctid,
             CallType::Method(_, fctid, _) => fctid,
             CallType::CtorNew(_, fctid, _) => fctid,
             CallType::Ctor(_, fctid, _) => fctid,
+            CallType::Lambda(_) => panic!("lambda call has no callee"),
+            CallType::Enum(_, _) => panic!("enum variant has no callee"),
         }
     }
 }
@@ -1144,6 +1609,7 @@ pub struct CallSite<'ast> {
     pub args: Vec<Arg<'ast>>,
     pub super_call: bool,
     pub return_type: BuiltinType,
+    pub return_slot: Option<i32>, // stack slot receiving a returned struct value
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
@@ -1168,6 +1634,7 @@ pub struct ConstData<'ast> {
     pub id: ConstId,
     pub pos: Position,
     pub name: Name,
+    pub module: ModuleId,
     pub ty: BuiltinType,
     pub expr: &'ast ast::Expr,
     pub value: ConstValue,
@@ -1218,6 +1685,9 @@ pub enum Arg<'ast> {
     Stack(i32, BuiltinType, i32),
     SelfieNew(BuiltinType, i32),
     Selfie(BuiltinType, i32),
+    // arguments packed into a new array for a variadic parameter, the
+    // array length is kept in the second offset while allocating it
+    Varargs(&'ast [Box<ast::Expr>], BuiltinType, i32, i32),
 }
 
 impl<'ast> Arg<'ast> {
@@ -1227,6 +1697,7 @@ impl<'ast> Arg<'ast> {
             Arg::Stack(_, _, offset) => offset,
             Arg::Selfie(_, offset) => offset,
             Arg::SelfieNew(_, offset) => offset,
+            Arg::Varargs(_, _, offset, _) => offset,
         }
     }
 
@@ -1236,6 +1707,7 @@ impl<'ast> Arg<'ast> {
             Arg::Stack(_, ty, _) => ty,
             Arg::Selfie(ty, _) => ty,
             Arg::SelfieNew(ty, _) => ty,
+            Arg::Varargs(_, ty, _, _) => ty,
         }
     }
 }
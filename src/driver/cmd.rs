@@ -27,7 +27,15 @@ Options:
     --emit-debug=<fct>      Emits debug instruction at beginning of functions
     --opt=<fct>             Use optimizing compiler for given functions
     --omit-bounds-check     Omit array index out of bounds checks
+    --check-overflow        Throw ArithmeticException on int and long overflow
     --check                 Only type check given program
+    --allow=<lints>         Do not report given lints (comma-separated or `all`)
+    --warn=<lints>          Report given lints as warnings with --check (default)
+    --deny=<lints>          Report given lints as errors (comma-separated or `all`)
+                            Lints: unused-variable, unused-parameter, unused-function,
+                            unreachable-code, unused-result, shadowed-field
+    --error-format=<fmt>    Format of errors and warnings
+                            Possible values: human (default), json
     --asm-syntax TYPE       Emits assembly with Intel or AT&T syntax
                             Allowed values: intel, att
     --enable-perf           Enable dump for perf
@@ -55,6 +63,7 @@ pub struct Args {
     pub flag_enable_perf: bool,
     pub flag_opt: Option<String>,
     pub flag_omit_bounds_check: bool,
+    pub flag_check_overflow: bool,
     pub flag_version: bool,
     pub flag_emit_debug: Option<String>,
     pub flag_asm_syntax: Option<AsmSyntax>,
@@ -67,6 +76,10 @@ pub struct Args {
     pub flag_gc: Option<CollectorName>,
     pub flag_heap_size: Option<MemSize>,
     pub flag_check: bool,
+    pub flag_allow: Option<String>,
+    pub flag_warn: Option<String>,
+    pub flag_deny: Option<String>,
+    pub flag_error_format: Option<ErrorFormat>,
 
     pub cmd_test: bool,
 }
@@ -75,6 +88,10 @@ impl Args {
     pub fn heap_size(&self) -> usize {
         self.flag_heap_size.map(|s| *s).unwrap_or(32 * 1024 * 1024)
     }
+
+    pub fn error_format(&self) -> ErrorFormat {
+        self.flag_error_format.unwrap_or(ErrorFormat::Human)
+    }
 }
 
 impl Default for Args {
@@ -92,6 +109,7 @@ impl Default for Args {
             flag_enable_perf: false,
             flag_opt: None,
             flag_omit_bounds_check: false,
+            flag_check_overflow: false,
             flag_version: false,
             flag_asm_syntax: None,
             flag_gc_events: false,
@@ -103,6 +121,10 @@ impl Default for Args {
             flag_gc: None,
             flag_heap_size: None,
             flag_check: false,
+            flag_allow: None,
+            flag_warn: None,
+            flag_deny: None,
+            flag_error_format: None,
 
             cmd_test: false,
         }
@@ -116,6 +138,12 @@ pub enum CollectorName {
     Swiper,
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, Debug, RustcDecodable)]
+pub enum ErrorFormat {
+    Human,
+    Json,
+}
+
 #[derive(Copy, Clone, Debug, RustcDecodable)]
 pub enum AsmSyntax {
     Intel,
@@ -0,0 +1,69 @@
+use std::collections::BTreeMap;
+
+use rustc_serialize::json::Json;
+
+use ctxt::SemContext;
+use dora_parser::error::msg::MsgWithPos;
+use exception::{StackFrame, Stacktrace};
+
+// with `--error-format=json` every diagnostic is printed as a single line:
+//
+// {"code":"UnknownIdentifier","column":13,"file":"foo.dora","frames":[],
+//  "line":2,"message":"unknown identifier `x`.","severity":"error"}
+pub fn diagnostic(ctxt: &SemContext, severity: &str, err: &MsgWithPos) -> String {
+    let file = ctxt.ast
+        .files
+        .get(err.pos.file.0 as usize)
+        .map(|file| Json::String(file.path.clone()))
+        .unwrap_or(Json::Null);
+
+    let mut obj = BTreeMap::new();
+    obj.insert("file".into(), file);
+    obj.insert("line".into(), Json::U64(err.pos.line as u64));
+    obj.insert("column".into(), Json::U64(err.pos.column as u64));
+    obj.insert("severity".into(), Json::String(severity.into()));
+    obj.insert("code".into(), Json::String(err.msg.code()));
+    obj.insert("message".into(), Json::String(err.msg.message()));
+    obj.insert("frames".into(), Json::Array(Vec::new()));
+
+    Json::Object(obj).to_string()
+}
+
+// runtime errors are located at the innermost frame of the stack trace,
+// there is no column information for them
+pub fn runtime_error(ctxt: &SemContext, code: &str, msg: &str, stacktrace: &Stacktrace) -> String {
+    let frames = stacktrace.frames(ctxt);
+
+    let (file, line) = match frames.first() {
+        Some(frame) if frame.lineno != 0 => {
+            (Json::String(frame.path.clone()), Json::U64(frame.lineno as u64))
+        }
+        _ => (Json::Null, Json::Null),
+    };
+
+    let mut obj = BTreeMap::new();
+    obj.insert("file".into(), file);
+    obj.insert("line".into(), line);
+    obj.insert("column".into(), Json::Null);
+    obj.insert("severity".into(), Json::String("error".into()));
+    obj.insert("code".into(), Json::String(code.into()));
+    obj.insert("message".into(), Json::String(msg.into()));
+    obj.insert("frames".into(), Json::Array(frames.iter().map(frame_to_json).collect()));
+
+    Json::Object(obj).to_string()
+}
+
+fn frame_to_json(frame: &StackFrame) -> Json {
+    let line = if frame.lineno == 0 {
+        Json::Null
+    } else {
+        Json::U64(frame.lineno as u64)
+    };
+
+    let mut obj = BTreeMap::new();
+    obj.insert("function".into(), Json::String(frame.name.clone()));
+    obj.insert("file".into(), Json::String(frame.path.clone()));
+    obj.insert("line".into(), line);
+
+    Json::Object(obj)
+}
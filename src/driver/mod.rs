@@ -1,4 +1,5 @@
 pub use self::start::*;
 
 pub mod cmd;
+pub mod json;
 pub mod start;
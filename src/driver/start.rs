@@ -6,18 +6,21 @@ use baseline;
 use class::TypeParams;
 use ctxt::{exception_get_and_clear, Fct, FctId, SemContext};
 use dora_parser::ast::{self, Ast};
+use dora_parser::error::diag::Diagnostic;
 use dora_parser::error::msg::Msg;
 
-use dora_parser::interner::Interner;
+use dora_parser::interner::{Interner, Name};
 use dora_parser::lexer::reader::Reader;
-use dora_parser::lexer::position::Position;
-use driver::cmd;
+use dora_parser::lexer::position::{FileId, Position};
+use driver::cmd::{self, ErrorFormat};
+use driver::json;
 use exception::DoraToNativeInfo;
 use object::{self, Handle, Testing};
 use os;
 
 use dora_parser::parser::{NodeIdGenerator, Parser};
 use semck;
+use semck::lintck;
 use semck::specialize::specialize_class_id;
 use ty::BuiltinType;
 
@@ -29,17 +32,35 @@ pub fn start() -> i32 {
         return 0;
     }
 
+    let lint_lists = [&args.flag_allow, &args.flag_warn, &args.flag_deny];
+
+    if let Some(name) = lintck::unknown_lint(&lint_lists) {
+        println!("unknown lint `{}`.", name);
+        return 1;
+    }
+
     let mut interner = Interner::new();
     let id_generator = NodeIdGenerator::new();
     let mut ast = Ast::new();
-
-    if let Err(code) = parse_dir("stdlib", &id_generator, &mut ast, &mut interner).and_then(|_| {
+    let mut diag = Diagnostic::new();
+
+    // the stdlib is module `std`, the program itself is the root module
+    let std_module = vec![interner.intern("std")];
+
+    if let Err(code) = parse_dir(
+        "stdlib",
+        &std_module,
+        &id_generator,
+        &mut ast,
+        &mut interner,
+        &mut diag,
+    ).and_then(|_| {
         let path = Path::new(&args.arg_file);
 
         if path.is_file() {
-            parse_file(&args.arg_file, &id_generator, &mut ast, &mut interner)
+            parse_file(&args.arg_file, &[], &id_generator, &mut ast, &mut interner, &mut diag)
         } else if path.is_dir() {
-            parse_dir(&args.arg_file, &id_generator, &mut ast, &mut interner)
+            parse_dir(&args.arg_file, &[], &id_generator, &mut ast, &mut interner, &mut diag)
         } else {
             println!("file or directory `{}` does not exist.", &args.arg_file);
             Err(1)
@@ -54,6 +75,9 @@ pub fn start() -> i32 {
 
     let mut ctxt = SemContext::new(args, &ast, interner);
 
+    // syntax errors are reported together with the errors of semck
+    *ctxt.diag.borrow_mut() = diag;
+
     semck::check(&mut ctxt);
 
     // register signal handler
@@ -66,10 +90,12 @@ pub fn start() -> i32 {
     };
 
     if ctxt.diag.borrow().has_errors() {
-        ctxt.diag.borrow().dump();
+        dump_diagnostics(&ctxt);
         let no_errors = ctxt.diag.borrow().errors().len();
 
-        if no_errors == 1 {
+        if ctxt.args.error_format() == ErrorFormat::Json {
+            // only diagnostics are printed in JSON
+        } else if no_errors == 1 {
             println!("{} error found.", no_errors);
         } else {
             println!("{} errors found.", no_errors);
@@ -78,15 +104,44 @@ pub fn start() -> i32 {
         return 1;
     }
 
-    // if --check given, stop after type/semantic check
+    // if --check given, stop after type/semantic check, warnings are only
+    // reported then so they don't mix with the output of the program
     if ctxt.args.flag_check {
+        if !ctxt.diag.borrow().warnings().is_empty() {
+            dump_diagnostics(&ctxt);
+        }
+
         return 0;
     }
 
-    if ctxt.args.cmd_test {
+    let exit_code = if ctxt.args.cmd_test {
         run_tests(&ctxt)
     } else {
         run_main(&ctxt, main.unwrap())
+    };
+
+    // threads that were never joined may still run dora code, keep heap and
+    // code space mapped until the process exits
+    mem::forget(ctxt);
+
+    exit_code
+}
+
+fn dump_diagnostics<'ast>(ctxt: &SemContext<'ast>) {
+    let diag = ctxt.diag.borrow();
+
+    match ctxt.args.error_format() {
+        ErrorFormat::Human => diag.dump(ctxt.ast),
+
+        ErrorFormat::Json => {
+            for err in diag.errors() {
+                println!("{}", json::diagnostic(ctxt, "error", err));
+            }
+
+            for warning in diag.warnings() {
+                println!("{}", json::diagnostic(ctxt, "warning", warning));
+            }
+        }
     }
 }
 
@@ -153,7 +208,7 @@ fn run_test<'ast>(ctxt: &SemContext<'ast>, fct: FctId) -> bool {
     exception.is_null() && !testing.has_failed()
 }
 
-fn is_test_fct<'ast>(ctxt: &SemContext<'ast>, fct: &Fct<'ast>) -> bool {
+pub fn is_test_fct<'ast>(ctxt: &SemContext<'ast>, fct: &Fct<'ast>) -> bool {
     // tests need to be standalone functions, with no return type and a single parameter
     if !fct.parent.is_none() || !fct.return_type.is_unit() || fct.param_types.len() != 1 {
         return false;
@@ -195,11 +250,14 @@ fn run_main<'ast>(ctxt: &SemContext<'ast>, main: FctId) -> i32 {
     }
 }
 
-fn parse_dir(
+// every subdirectory is a child module of the directory's module
+pub fn parse_dir(
     dirname: &str,
+    module: &[Name],
     id_generator: &NodeIdGenerator,
     ast: &mut Ast,
     interner: &mut Interner,
+    diag: &mut Diagnostic,
 ) -> Result<(), i32> {
     let path = Path::new(dirname);
 
@@ -207,8 +265,13 @@ fn parse_dir(
         for entry in fs::read_dir(path).unwrap() {
             let path = entry.unwrap().path();
 
-            if path.is_file() && path.extension().unwrap() == "dora" {
-                parse_file(path.to_str().unwrap(), id_generator, ast, interner)?;
+            if path.is_file() && path.extension().map_or(false, |ext| ext == "dora") {
+                parse_file(path.to_str().unwrap(), module, id_generator, ast, interner, diag)?;
+            } else if path.is_dir() {
+                let mut module = module.to_vec();
+                module.push(interner.intern(path.file_name().unwrap().to_str().unwrap()));
+
+                parse_dir(path.to_str().unwrap(), &module, id_generator, ast, interner, diag)?;
             }
         }
 
@@ -222,9 +285,11 @@ fn parse_dir(
 
 fn parse_file(
     filename: &str,
+    module: &[Name],
     id_generator: &NodeIdGenerator,
     ast: &mut Ast,
     interner: &mut Interner,
+    diag: &mut Diagnostic,
 ) -> Result<(), i32> {
     let reader = if filename == "-" {
         match Reader::from_input() {
@@ -246,11 +311,9 @@ fn parse_file(
         }
     };
 
-    if let Err(error) = Parser::new(reader, id_generator, ast, interner).parse() {
-        println!("{}", error);
-        println!("1 error found.");
-        return Err(1);
-    }
+    Parser::new(reader, id_generator, ast, interner).parse(diag);
+
+    ast.files.last_mut().unwrap().module = module.to_vec();
 
     Ok(())
 }
@@ -260,9 +323,10 @@ fn find_main<'ast>(ctxt: &SemContext<'ast>) -> Option<FctId> {
     let fctid = match ctxt.sym.borrow().get_fct(name) {
         Some(id) => id,
         None => {
-            ctxt.diag
-                .borrow_mut()
-                .report(Position::new(1, 1), Msg::MainNotFound);
+            // report at the beginning of the program's last file
+            let file = FileId(ctxt.ast.files.len() as u32 - 1);
+            let pos = Position { file: file, ..Position::new(1, 1) };
+            ctxt.diag.borrow_mut().report(pos, Msg::MainNotFound);
             return None;
         }
     };
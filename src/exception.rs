@@ -1,11 +1,13 @@
 use std::ptr;
 
-use baseline::fct::{CatchType, JitFctId};
+use baseline::fct::{CatchType, ExHandler, JitFctId};
 use baseline::map::CodeData;
 use cpu::{fp_from_execstate, get_exception_object, resume_with_handler};
-use ctxt::{get_ctxt, SemContext};
+use class::{ClassDef, ClassDefId, ClassId};
+use ctxt::{exception_set, get_ctxt, FctKind, SemContext};
 use object::{alloc, Array, Exception, Handle, IntArray, Obj, StackTraceElement, Str};
 use execstate::ExecState;
+use semck::specialize::specialize_class_id;
 
 pub struct Stacktrace {
     elems: Vec<StackElem>,
@@ -27,18 +29,32 @@ impl Stacktrace {
         });
     }
 
+    pub fn frames(&self, ctxt: &SemContext) -> Vec<StackFrame> {
+        self.elems
+            .iter()
+            .map(|elem| {
+                let jit_fct = ctxt.jit_fcts[elem.fct_id].borrow();
+                let fct_id = jit_fct.fct_id();
+                let fct = ctxt.fcts[fct_id].borrow();
+                let file = &ctxt.ast.files[fct.ast.pos.file.0 as usize];
+
+                StackFrame {
+                    name: fct.full_name(ctxt),
+                    path: file.path.clone(),
+                    lineno: elem.lineno,
+                }
+            })
+            .collect()
+    }
+
     pub fn dump(&self, ctxt: &SemContext) {
-        for (ind, elem) in self.elems.iter().enumerate() {
-            let jit_fct = ctxt.jit_fcts[elem.fct_id].borrow();
-            let fct_id = jit_fct.fct_id();
-            let fct = ctxt.fcts[fct_id].borrow();
-            let name = fct.full_name(ctxt);
-            print!("{}: {}: ", ind, name);
-
-            if elem.lineno == 0 {
+        for (ind, frame) in self.frames(ctxt).iter().enumerate() {
+            print!("{}: {}: ", ind, frame.name);
+
+            if frame.lineno == 0 {
                 println!("?");
             } else {
-                println!("{}", elem.lineno);
+                println!("{}:{}", frame.path, frame.lineno);
             }
         }
     }
@@ -49,6 +65,12 @@ struct StackElem {
     lineno: i32,
 }
 
+pub struct StackFrame {
+    pub name: String,
+    pub path: String,
+    pub lineno: i32,
+}
+
 pub struct DoraToNativeInfo {
     // pointer to previous DTN-Info
     pub last: *const DoraToNativeInfo,
@@ -84,6 +106,33 @@ pub fn stacktrace_from_last_dtn(ctxt: &SemContext) -> Stacktrace {
     return stacktrace;
 }
 
+// stack trace recorded on creation of the exception, empty for thrown
+// objects that are not exceptions
+pub fn stacktrace_from_exception(ctxt: &SemContext, obj: Handle<Obj>) -> Stacktrace {
+    let mut stacktrace = Stacktrace::new();
+
+    let cls_id = obj.header().vtbl().class().cls_id;
+    let cls = ctxt.classes[cls_id].borrow();
+
+    if !cls.subclass_from(ctxt, ctxt.vips.exception_class) {
+        return stacktrace;
+    }
+
+    let obj: Handle<Exception> = obj.cast();
+    let array = obj.backtrace;
+
+    if !array.raw().is_null() {
+        for ind in 0..array.len() / 2 {
+            let lineno = array.get_at(ind * 2);
+            let fct_id = array.get_at(ind * 2 + 1);
+
+            stacktrace.push_entry(JitFctId::from(fct_id as usize), lineno);
+        }
+    }
+
+    stacktrace
+}
+
 fn frames_from_dtns(stacktrace: &mut Stacktrace, ctxt: &SemContext) {
     let mut dtn_ptr = *ctxt.dtn.borrow();
 
@@ -140,7 +189,10 @@ fn determine_stack_entry(stacktrace: &mut Stacktrace, ctxt: &SemContext, pc: usi
             let jit_fct = ctxt.jit_fcts[fct_id].borrow();
             let fct = ctxt.fcts[jit_fct.fct_id()].borrow();
 
-            stacktrace.push_entry(fct_id, fct.ast.pos.line as i32);
+            // runtime helpers like gc_alloc have no function of their own
+            if let FctKind::Native(_) = fct.kind {
+                stacktrace.push_entry(fct_id, fct.ast.pos.line as i32);
+            }
 
             true
         }
@@ -150,24 +202,121 @@ fn determine_stack_entry(stacktrace: &mut Stacktrace, ctxt: &SemContext, pc: usi
 }
 
 pub fn handle_exception(es: &mut ExecState) -> bool {
+    let exception = get_exception_object(es);
+    let clsptr = exception.header().vtbl().classptr();
+    let unchecked = is_runtime_exception(exception);
+
+    match find_handler(es, clsptr, unchecked) {
+        Some(handler) => {
+            resume_with_handler(es, &handler.entry, handler.fp, exception, handler.stacksize);
+            true
+        }
+
+        None => false,
+    }
+}
+
+// turns a trap of generated code into a runtime exception of the given class,
+// returns false if there is no handler for it
+pub fn handle_trap(es: &mut ExecState, cls_id: ClassId, msg: &str) -> bool {
+    let ctxt = get_ctxt();
+    let cls_def_id = specialize_class_id(ctxt, cls_id);
+    let clsptr = {
+        let cls_def = ctxt.class_defs[cls_def_id].borrow();
+        &*cls_def as *const ClassDef
+    };
+
+    let handler = match find_handler(es, clsptr, true) {
+        Some(handler) => handler,
+        None => return false,
+    };
+
+    let stacktrace = stacktrace_from_es(ctxt, es);
+
+    // the frames between trap and handler are dropped anyway, so the exception
+    // is allocated as if the handler's frame had called into the runtime
+    if let Some(offset) = handler.entry.offset {
+        let slot = (handler.fp as isize + offset as isize) as *mut usize;
+        unsafe {
+            *slot = 0;
+        }
+    }
+
+    let mut dtn = DoraToNativeInfo {
+        last: ptr::null(),
+        fp: handler.fp,
+        pc: handler.entry.catch,
+    };
+
+    let exception = ctxt.use_dtn(&mut dtn, || {
+        ctxt.handles.push_border();
+
+        let msg = Str::from_buffer(ctxt, msg.as_bytes());
+        let msg = ctxt.handles.root(msg);
+        let exception = alloc_exception_of(ctxt, cls_def_id, msg.direct(), &stacktrace);
+
+        ctxt.handles.pop_border();
+
+        exception
+    });
+
+    resume_with_handler(
+        es,
+        &handler.entry,
+        handler.fp,
+        exception.cast(),
+        handler.stacksize,
+    );
+
+    true
+}
+
+struct Handler {
+    entry: ExHandler,
+    fp: usize,
+    stacksize: usize,
+}
+
+enum HandlerFound {
+    Yes(Handler),
+    No,
+    Stop,
+}
+
+// exceptions raised by the runtime itself are not declared with `throws`
+// and may therefore unwind any function
+fn is_runtime_exception(exception: Handle<Obj>) -> bool {
+    let ctxt = get_ctxt();
+    let vtable = exception.header().vtbl();
+
+    let runtime_exceptions = [
+        ctxt.vips.arithmetic_exception_class,
+        ctxt.vips.index_out_of_bounds_exception_class,
+        ctxt.vips.nil_pointer_exception_class,
+        ctxt.vips.cast_exception_class,
+        ctxt.vips.out_of_memory_error_class,
+    ];
+
+    runtime_exceptions.iter().any(|&cls_id| {
+        let cls_def_id = specialize_class_id(ctxt, cls_id);
+        let cls_def = ctxt.class_defs[cls_def_id].borrow();
+        let runtime_vtable = cls_def.vtable.as_ref().unwrap();
+
+        vtable.subclass_from(runtime_vtable)
+    })
+}
+
+fn find_handler(es: &ExecState, clsptr: *const ClassDef, unchecked: bool) -> Option<Handler> {
     let mut pc: usize = es.pc;
     let mut fp: usize = fp_from_execstate(es);
 
-    let exception = get_exception_object(es);
-
     loop {
-        let found = find_handler(exception, es, pc, fp);
-
-        match found {
-            HandlerFound::Yes => {
-                return true;
-            }
-            HandlerFound::Stop => {
-                return false;
-            }
+        match find_handler_in_frame(clsptr, pc, fp, unchecked) {
+            HandlerFound::Yes(handler) => return Some(handler),
+            HandlerFound::Stop => return None,
             HandlerFound::No => {
                 if fp == 0 {
-                    return false;
+                    return None;
                 }
             }
         }
@@ -177,14 +326,25 @@ pub fn handle_exception(es: &mut ExecState) -> bool {
     }
 }
 
-#[derive(PartialEq, Eq, Debug)]
-enum HandlerFound {
-    Yes,
-    No,
-    Stop,
+fn catches(catch_type: CatchType, clsptr: *const ClassDef) -> bool {
+    match catch_type {
+        CatchType::Any => true,
+
+        CatchType::Class(catch_clsptr) => {
+            let vtable = unsafe { &**(*clsptr).vtable.as_ref().unwrap() };
+            let catch_vtable = unsafe { &**(*catch_clsptr).vtable.as_ref().unwrap() };
+
+            vtable.subclass_from(catch_vtable)
+        }
+    }
 }
 
-fn find_handler(exception: Handle<Obj>, es: &mut ExecState, pc: usize, fp: usize) -> HandlerFound {
+fn find_handler_in_frame(
+    clsptr: *const ClassDef,
+    pc: usize,
+    fp: usize,
+    unchecked: bool,
+) -> HandlerFound {
     let ctxt = get_ctxt();
     let data = {
         let code_map = ctxt.code_map.lock().unwrap();
@@ -195,36 +355,32 @@ fn find_handler(exception: Handle<Obj>, es: &mut ExecState, pc: usize, fp: usize
         Some(CodeData::Fct(fct_id)) | Some(CodeData::NativeStub(fct_id)) => {
             let jit_fct = ctxt.jit_fcts[fct_id].borrow();
             let jit_fct = jit_fct.to_base().expect("baseline expected");
-            let clsptr = exception.header().vtbl().classptr();
 
             for entry in &jit_fct.exception_handlers {
                 // println!("entry = {:x} to {:x} for {:?}",
                 //          entry.try_start, entry.try_end, entry.catch_type);
 
                 if entry.try_start < pc && pc <= entry.try_end
-                    && (entry.catch_type == CatchType::Any
-                        || entry.catch_type == CatchType::Class(clsptr))
+                    && catches(entry.catch_type, clsptr)
                 {
-                    let stacksize = jit_fct.framesize as usize;
-                    resume_with_handler(es, entry, fp, exception, stacksize);
-
-                    return HandlerFound::Yes;
-                } else if pc > entry.try_end {
-                    // exception handlers are sorted, no more possible handlers
-                    // in this function
-
-                    return HandlerFound::No;
+                    return HandlerFound::Yes(Handler {
+                        entry: entry.clone(),
+                        fp: fp,
+                        stacksize: jit_fct.framesize as usize,
+                    });
                 }
             }
 
             // exception can only bubble up in stacktrace if current function
             // is allowed to throw exceptions
-            if !jit_fct.throws {
+            if !jit_fct.throws && !unchecked {
                 return HandlerFound::Stop;
             }
         }
 
-        _ => {}
+        // never unwind native frames that called into dora code, this includes
+        // checked exceptions leaving a `throws` function like main
+        _ => return HandlerFound::Stop,
     }
 
     HandlerFound::No
@@ -259,22 +415,50 @@ pub extern "C" fn stack_element(obj: Handle<Exception>, ind: i32) -> Handle<Stac
     ste.direct()
 }
 
+pub extern "C" fn throw_overflow() {
+    let ctxt = get_ctxt();
+    let msg = Str::from_buffer(ctxt, b"arithmetic overflow");
+    let msg = ctxt.handles.root(msg);
+
+    let cls_id = specialize_class_id(ctxt, ctxt.vips.arithmetic_exception_class);
+    let stacktrace = stacktrace_from_last_dtn(ctxt);
+    let obj = alloc_exception_of(ctxt, cls_id, msg.direct(), &stacktrace);
+
+    exception_set(obj.raw() as *const u8);
+}
+
 pub fn alloc_exception(ctxt: &SemContext, msg: Handle<Str>) -> Handle<Exception> {
     let cls_id = ctxt.vips.exception(ctxt);
+    let stacktrace = stacktrace_from_last_dtn(ctxt);
+    alloc_exception_of(ctxt, cls_id, msg, &stacktrace)
+}
+
+fn alloc_exception_of(
+    ctxt: &SemContext,
+    cls_id: ClassDefId,
+    msg: Handle<Str>,
+    stacktrace: &Stacktrace,
+) -> Handle<Exception> {
+    let msg = ctxt.handles.root(msg);
     let obj: Handle<Exception> = alloc(ctxt, cls_id).cast();
     let mut obj = ctxt.handles.root(obj);
 
-    obj.msg = msg;
-    set_exception_backtrace(ctxt, obj.direct(), false);
+    obj.msg = msg.direct();
+    set_backtrace(ctxt, obj.direct(), stacktrace, 0);
 
     obj.direct()
 }
 
 fn set_exception_backtrace(ctxt: &SemContext, obj: Handle<Exception>, via_retrieve: bool) {
     let stacktrace = stacktrace_from_last_dtn(ctxt);
+    let skip = if via_retrieve { 2 } else { 0 };
+
+    set_backtrace(ctxt, obj, &stacktrace, skip);
+}
+
+fn set_backtrace(ctxt: &SemContext, obj: Handle<Exception>, stacktrace: &Stacktrace, skip: usize) {
     let mut obj = ctxt.handles.root(obj);
 
-    let skip = if via_retrieve { 2 } else { 0 };
     let len = stacktrace.len() - skip;
 
     let cls_id = ctxt.vips.int_array(ctxt);
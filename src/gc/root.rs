@@ -3,6 +3,7 @@ use std::convert::From;
 use baseline::map::CodeData;
 use ctxt::SemContext;
 use object::Obj;
+use semck::specialize::add_ref_fields;
 use exception::DoraToNativeInfo;
 use gc::Address;
 
@@ -12,10 +13,17 @@ pub fn get_rootset(ctxt: &SemContext) -> Vec<IndirectObj> {
     determine_rootset_from_stack(&mut rootset, ctxt);
     determine_rootset_from_globals(&mut rootset, ctxt);
     determine_rootset_from_handles(&mut rootset, ctxt);
+    determine_rootset_from_threads(&mut rootset, ctxt);
 
     rootset
 }
 
+fn determine_rootset_from_threads(rootset: &mut Vec<IndirectObj>, ctxt: &SemContext) {
+    for &root in ctxt.thread_roots.lock().unwrap().iter() {
+        rootset.push(root.into());
+    }
+}
+
 fn determine_rootset_from_handles(rootset: &mut Vec<IndirectObj>, ctxt: &SemContext) {
     for rooted in ctxt.handles.iter() {
         rootset.push((rooted.raw() as usize).into());
@@ -26,11 +34,12 @@ fn determine_rootset_from_globals(rootset: &mut Vec<IndirectObj>, ctxt: &SemCont
     for glob in ctxt.globals.iter() {
         let glob = glob.borrow();
 
-        if !glob.ty.reference_type() {
-            continue;
-        }
+        let mut ref_fields = Vec::new();
+        add_ref_fields(ctxt, &mut ref_fields, glob.ty, 0);
 
-        rootset.push((glob.address_value as usize).into());
+        for offset in ref_fields {
+            rootset.push((glob.address_value as usize + offset as usize).into());
+        }
     }
 }
 
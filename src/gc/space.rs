@@ -6,6 +6,7 @@ use gc::arena;
 use gc::Address;
 use gc::swiper::Region;
 use mem;
+use os;
 
 /// Configuration for a space.
 /// This makes it possible to use `Space` both for the
@@ -135,3 +136,9 @@ impl Space {
         self.total.contains(addr)
     }
 }
+
+impl Drop for Space {
+    fn drop(&mut self) {
+        os::munmap(self.total.start.to_ptr(), self.total.size());
+    }
+}
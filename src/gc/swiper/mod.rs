@@ -39,6 +39,8 @@ pub const CARD_SIZE_BITS: usize = 9;
 pub const LARGE_OBJECT_SIZE: usize = 16 * 1024;
 
 pub struct Swiper {
+    // heap, card table and crossing map are reserved together
+    reserved_area: Region,
     heap: Region,
 
     young: YoungGen,
@@ -131,6 +133,7 @@ impl Swiper {
         }
 
         Swiper {
+            reserved_area: Region::new(ptr, ptr.offset(heap_reserve_size)),
             heap: Region::new(heap_start, heap_end),
 
             young: young,
@@ -216,6 +219,12 @@ impl Swiper {
     }
 }
 
+impl Drop for Swiper {
+    fn drop(&mut self) {
+        os::munmap(self.reserved_area.start.to_ptr(), self.reserved_area.size());
+    }
+}
+
 impl Collector for Swiper {
     fn alloc(&self, ctxt: &SemContext, size: usize, array_ref: bool) -> *const u8 {
         if ctxt.args.flag_gc_stress_minor {
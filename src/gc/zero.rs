@@ -5,6 +5,7 @@ use ctxt::SemContext;
 use driver::cmd::Args;
 use gc::Address;
 use gc::{arena, Collector};
+use os;
 
 pub struct ZeroCollector {
     start: Address,
@@ -27,6 +28,12 @@ impl ZeroCollector {
     }
 }
 
+impl Drop for ZeroCollector {
+    fn drop(&mut self) {
+        os::munmap(self.start.to_ptr(), self.end.offset_from(self.start));
+    }
+}
+
 impl Collector for ZeroCollector {
     fn alloc(&self, _ctxt: &SemContext, size: usize, _array_ref: bool) -> *const u8 {
         let mut old = self.next.load(Ordering::Relaxed);
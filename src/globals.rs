@@ -0,0 +1,164 @@
+use libc;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ptr;
+use std::sync::{Condvar, Mutex};
+use std::thread::{self, ThreadId};
+
+use baseline;
+use class::TypeParams;
+use ctxt::{get_ctxt, GlobalId, SemContext};
+use exception::stacktrace_from_last_dtn;
+
+thread_local! {
+    // globals whose initializers currently run on this thread, innermost last
+    static INITIALIZING: RefCell<Vec<GlobalId>> = RefCell::new(Vec::new());
+}
+
+pub struct GlobalInits {
+    state: Mutex<InitState>,
+    finished: Condvar,
+}
+
+struct InitState {
+    // thread that currently runs the initializer of the global
+    running: HashMap<GlobalId, ThreadId>,
+
+    // global a thread waits for while another thread initializes it
+    waiting: HashMap<ThreadId, GlobalId>,
+}
+
+impl GlobalInits {
+    pub fn new() -> GlobalInits {
+        GlobalInits {
+            state: Mutex::new(InitState {
+                running: HashMap::new(),
+                waiting: HashMap::new(),
+            }),
+            finished: Condvar::new(),
+        }
+    }
+}
+
+// called from generated code when the init flag of the global is not set yet
+pub extern "C" fn init_global(id: u32) {
+    let ctxt = get_ctxt();
+    let id: GlobalId = id.into();
+    let me = thread::current().id();
+
+    let inits = &ctxt.global_inits;
+    let mut state = inits.state.lock().unwrap();
+
+    loop {
+        if is_initialized(ctxt, id) {
+            return;
+        }
+
+        if !state.running.contains_key(&id) {
+            break;
+        }
+
+        if is_cycle(&state, id, me) {
+            drop(state);
+            report_cycle(ctxt, id);
+        }
+
+        state.waiting.insert(me, id);
+        state = inits.finished.wait(state).unwrap();
+        state.waiting.remove(&me);
+    }
+
+    state.running.insert(id, me);
+    INITIALIZING.with(|globals| globals.borrow_mut().push(id));
+    drop(state);
+
+    let _guard = RunningGuard { inits: inits, id: id };
+
+    let fct_id = ctxt.globals[id].borrow().initializer.unwrap();
+    let type_params = TypeParams::empty();
+    let fct_ptr = baseline::generate(ctxt, fct_id, &type_params, &type_params);
+    let fct: extern "C" fn() = unsafe { ::std::mem::transmute(fct_ptr) };
+    fct();
+
+    let address_init = ctxt.globals[id].borrow().address_init as *mut u8;
+    unsafe {
+        ptr::write_volatile(address_init, 1);
+    }
+}
+
+// releases the global however the initializer is left, threads waiting
+// for it would otherwise block forever
+struct RunningGuard<'a> {
+    inits: &'a GlobalInits,
+    id: GlobalId,
+}
+
+impl<'a> Drop for RunningGuard<'a> {
+    fn drop(&mut self) {
+        let mut state = self.inits.state.lock().unwrap();
+        state.running.remove(&self.id);
+        INITIALIZING.with(|globals| globals.borrow_mut().pop());
+
+        self.inits.finished.notify_all();
+    }
+}
+
+// names the global whose initializer the current thread is running in the
+// message of an uncaught exception, the exception cannot unwind through
+// the runtime frame that called the initializer
+pub fn uncaught_message(ctxt: &SemContext, msg: &str) -> String {
+    let id = INITIALIZING.with(|globals| globals.borrow().last().cloned());
+
+    match id {
+        Some(id) => {
+            let glob = ctxt.globals[id].borrow();
+            let name = ctxt.interner.str(glob.name);
+
+            format!("{} in initializer of global `{}`", msg, name)
+        }
+
+        None => msg.to_string(),
+    }
+}
+
+fn is_initialized(ctxt: &SemContext, id: GlobalId) -> bool {
+    let address_init = ctxt.globals[id].borrow().address_init;
+    unsafe { ptr::read_volatile(address_init) != 0 }
+}
+
+// follows the chain of threads waiting for each other, starting with the
+// thread running the initializer of `id`. reaching `me` means no thread
+// in the chain can ever make progress.
+fn is_cycle(state: &InitState, mut id: GlobalId, me: ThreadId) -> bool {
+    loop {
+        let owner = match state.running.get(&id) {
+            Some(&owner) => owner,
+            None => return false,
+        };
+
+        if owner == me {
+            return true;
+        }
+
+        id = match state.waiting.get(&owner) {
+            Some(&id) => id,
+            None => return false,
+        };
+    }
+}
+
+fn report_cycle(ctxt: &SemContext, id: GlobalId) -> ! {
+    let name = {
+        let glob = ctxt.globals[id].borrow();
+        ctxt.interner.str(glob.name).to_string()
+    };
+
+    println!("initialization cycle for global `{}`", name);
+    let stacktrace = stacktrace_from_last_dtn(ctxt);
+    stacktrace.dump(ctxt);
+
+    unsafe {
+        libc::_exit(108);
+    }
+}
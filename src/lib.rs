@@ -0,0 +1,76 @@
+#![feature(asm)]
+#![feature(alloc)]
+#![feature(box_syntax)]
+#![allow(dead_code)]
+#![allow(non_camel_case_types)]
+#![feature(allocator_api)]
+
+extern crate alloc;
+extern crate byteorder;
+extern crate capstone;
+extern crate docopt;
+extern crate dora_parser;
+extern crate libc;
+extern crate llvm_sys as llvm;
+extern crate rustc_serialize;
+extern crate time;
+
+#[cfg(target_os = "windows")]
+extern crate winapi;
+
+#[cfg(target_os = "windows")]
+extern crate kernel32;
+
+macro_rules! offset_of {
+    ($ty: ty, $field: ident) => {
+        unsafe { &(*(0 as *const $ty)).$field as *const _ as usize }
+    };
+}
+
+mod baseline;
+mod bytecode;
+mod class;
+mod cpu;
+mod ctxt;
+mod driver;
+mod dseg;
+mod exception;
+mod execstate;
+mod gc;
+mod globals;
+mod handle;
+mod masm;
+mod mem;
+mod mir;
+mod object;
+mod opt;
+mod os;
+mod os_cpu;
+mod semck;
+mod stdlib;
+mod safepoint;
+mod sym;
+mod threads;
+mod timer;
+mod ty;
+mod utils;
+mod vtable;
+
+mod lsp;
+
+#[cfg(test)]
+mod test;
+
+// entry point of `dora`
+pub fn start() -> i32 {
+    os::mem::init_page_size();
+
+    driver::start()
+}
+
+// entry point of `dora-lsp`
+pub fn start_lsp() -> i32 {
+    os::mem::init_page_size();
+
+    lsp::start()
+}
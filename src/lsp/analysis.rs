@@ -0,0 +1,717 @@
+use std::fs;
+
+use class::{ClassId, FieldId};
+use ctxt::{CallType, Fct, FctId, FctSrc, IdentType, LambdaCallee, SemContext, VarId};
+use driver;
+use driver::cmd::Args;
+use dora_parser::ast::*;
+use dora_parser::ast::Elem::*;
+use dora_parser::ast::Expr::*;
+use dora_parser::ast::Stmt::*;
+use dora_parser::ast::visit::*;
+use dora_parser::error::diag::Diagnostic;
+use dora_parser::interner::{Interner, Name};
+use dora_parser::lexer::position::{FileId, Position};
+use dora_parser::lexer::reader::Reader;
+use dora_parser::parser::{NodeIdGenerator, Parser};
+use lsp::path_to_uri;
+use lsp::rpc::object;
+use rustc_serialize::json::Json;
+use semck;
+use ty::BuiltinType;
+
+// the parsed stdlib, every analysis starts from a copy of it
+pub struct Stdlib {
+    id_generator: NodeIdGenerator,
+    interner: Interner,
+    ast: Ast,
+    diag: Diagnostic,
+}
+
+impl Stdlib {
+    pub fn parse(dir: &str) -> Result<Stdlib, String> {
+        // file paths in the ast need to be absolute to be turned into uris
+        let dir = match fs::canonicalize(dir) {
+            Ok(ref path) if path.is_dir() => path.to_string_lossy().into_owned(),
+            _ => return Err(format!("directory `{}` does not exist.", dir)),
+        };
+
+        let id_generator = NodeIdGenerator::new();
+        let mut interner = Interner::new();
+        let mut ast = Ast::new();
+        let mut diag = Diagnostic::new();
+
+        let std_module = vec![interner.intern("std")];
+
+        driver::parse_dir(
+            &dir,
+            &std_module,
+            &id_generator,
+            &mut ast,
+            &mut interner,
+            &mut diag,
+        ).map_err(|_| format!("could not read stdlib in `{}`.", dir))?;
+
+        Ok(Stdlib {
+            id_generator: id_generator,
+            interner: interner,
+            ast: ast,
+            diag: diag,
+        })
+    }
+}
+
+// checks the document together with the stdlib and runs all semantic checks,
+// `f` gets the id of the document's file
+pub fn analyze<F, T>(stdlib: &Stdlib, path: &str, text: &str, f: F) -> T
+where
+    F: FnOnce(&SemContext, FileId) -> T,
+{
+    let id_generator = stdlib.id_generator.clone();
+    let mut interner = stdlib.interner.clone();
+    let mut ast = stdlib.ast.clone();
+    let mut diag = stdlib.diag.clone();
+
+    let file = FileId(ast.files.len() as u32);
+
+    {
+        let reader = Reader::from_named_string(path, text);
+        Parser::new(reader, &id_generator, &mut ast, &mut interner).parse(&mut diag);
+    }
+
+    let args: Args = Default::default();
+    let mut ctxt = SemContext::new(args, &ast, interner);
+    *ctxt.diag.borrow_mut() = diag;
+
+    semck::check(&mut ctxt);
+
+    f(&ctxt, file)
+}
+
+pub fn diagnostics(ctxt: &SemContext, file: FileId) -> Json {
+    let src = &ctxt.ast.files[file.0 as usize].src;
+    let diag = ctxt.diag.borrow();
+    let mut result = Vec::new();
+
+    // severities 1 and 2 are error and warning
+    for &(severity, msgs) in &[(1, diag.errors()), (2, diag.warnings())] {
+        for msg in msgs.iter().filter(|msg| msg.pos.file == file) {
+            result.push(object(vec![
+                ("range", range(src, msg.pos.start, msg.pos.end)),
+                ("severity", Json::U64(severity)),
+                ("code", Json::String(msg.msg.code())),
+                ("source", Json::String("dora".into())),
+                ("message", Json::String(msg.msg.message())),
+            ]));
+        }
+    }
+
+    Json::Array(result)
+}
+
+// location of the declaration of the variable, field, global or function
+// referenced at `offset`
+pub fn definition(ctxt: &SemContext, file: FileId, offset: u32) -> Json {
+    let (fct_id, e) = match expr_at(ctxt, file, offset) {
+        Some(found) => found,
+        None => return Json::Null,
+    };
+
+    let fct = ctxt.fcts[fct_id].borrow();
+    let src = fct.src();
+    let src = src.borrow();
+
+    let pos = if let Some(call) = src.map_calls.get(e.id()) {
+        match **call {
+            CallType::Fct(fct_id, _, _) |
+            CallType::Method(_, fct_id, _) |
+            CallType::CtorNew(_, fct_id, _) |
+            CallType::Ctor(_, fct_id, _) => Some(ctxt.fcts[fct_id].borrow().pos),
+            CallType::Lambda(LambdaCallee::Var(var_id)) => var_pos(&fct, &src, var_id),
+            CallType::Lambda(LambdaCallee::Field(ty, field_id)) => {
+                ty.cls_id(ctxt).map(|cls_id| ctxt.classes[cls_id].borrow().fields[field_id].pos)
+            }
+            CallType::Lambda(LambdaCallee::Expr) => None,
+            CallType::Enum(enum_id, idx) => Some(ctxt.enums[enum_id].borrow().variants[idx].pos),
+        }
+    } else {
+        match src.map_idents.get(e.id()) {
+            Some(&IdentType::Var(var_id)) => var_pos(&fct, &src, var_id),
+            Some(&IdentType::Global(id)) => Some(ctxt.globals[id].borrow().pos),
+            Some(&IdentType::Field(ty, field_id)) => {
+                ty.cls_id(ctxt).map(|cls_id| ctxt.classes[cls_id].borrow().fields[field_id].pos)
+            }
+            Some(&IdentType::StructField(ty, field_id)) => match ty {
+                BuiltinType::Struct(id, _) => {
+                    Some(ctxt.structs[id].borrow().fields[field_id.idx()].pos)
+                }
+                _ => None,
+            },
+            Some(&IdentType::Struct(id)) => Some(ctxt.structs[id].borrow().pos),
+            Some(&IdentType::Const(id)) => Some(ctxt.consts[id].borrow().pos),
+            None => None,
+        }
+    };
+
+    match pos {
+        Some(pos) => {
+            let file = &ctxt.ast.files[pos.file.0 as usize];
+
+            object(vec![
+                ("uri", Json::String(path_to_uri(&file.path))),
+                ("range", range(&file.src, pos.start, pos.end)),
+            ])
+        }
+
+        None => Json::Null,
+    }
+}
+
+// type of the expression at `offset`, functions are shown with their signature
+pub fn hover(ctxt: &SemContext, file: FileId, offset: u32) -> Json {
+    let (fct_id, e) = match expr_at(ctxt, file, offset) {
+        Some(found) => found,
+        None => return Json::Null,
+    };
+
+    let fct = ctxt.fcts[fct_id].borrow();
+    let src = fct.src();
+    let src = src.borrow();
+
+    let text = if let Some(call) = src.map_calls.get(e.id()) {
+        match **call {
+            CallType::Fct(fct_id, _, _) |
+            CallType::Method(_, fct_id, _) |
+            CallType::CtorNew(_, fct_id, _) |
+            CallType::Ctor(_, fct_id, _) => {
+                format!("fun {}", ctxt.fcts[fct_id].borrow().full_name(ctxt))
+            }
+            CallType::Lambda(LambdaCallee::Var(var_id)) => var_decl(ctxt, &src, var_id),
+            CallType::Lambda(LambdaCallee::Field(ty, field_id)) => match ty.cls_id(ctxt) {
+                Some(cls_id) => field_decl(ctxt, cls_id, field_id),
+                None => return Json::Null,
+            },
+            CallType::Lambda(LambdaCallee::Expr) => match src.map_tys.get(e.id()) {
+                Some(ty) => ty.name(ctxt),
+                None => return Json::Null,
+            },
+            CallType::Enum(enum_id, idx) => {
+                let xenum = ctxt.enums[enum_id].borrow();
+                format!("{}::{}",
+                        ctxt.interner.str(xenum.name),
+                        ctxt.interner.str(xenum.variants[idx].name))
+            }
+        }
+    } else {
+        match src.map_idents.get(e.id()) {
+            Some(&IdentType::Var(var_id)) => var_decl(ctxt, &src, var_id),
+            Some(&IdentType::Global(id)) => {
+                let glob = ctxt.globals[id].borrow();
+                let name = ctxt.interner.str(glob.name).to_string();
+                decl(ctxt, glob.reassignable, name, glob.ty)
+            }
+            Some(&IdentType::Field(ty, field_id)) => match ty.cls_id(ctxt) {
+                Some(cls_id) => field_decl(ctxt, cls_id, field_id),
+                None => return Json::Null,
+            },
+            Some(&IdentType::StructField(ty, field_id)) => match ty {
+                BuiltinType::Struct(id, _) => {
+                    let struc = ctxt.structs[id].borrow();
+                    let field = &struc.fields[field_id.idx()];
+                    format!("{}: {}", ctxt.interner.str(field.name), field.ty.name(ctxt))
+                }
+                _ => return Json::Null,
+            },
+            Some(&IdentType::Struct(id)) => {
+                format!("struct {}", ctxt.interner.str(ctxt.structs[id].borrow().name))
+            }
+            Some(&IdentType::Const(id)) => {
+                let xconst = ctxt.consts[id].borrow();
+                format!("const {}: {}", ctxt.interner.str(xconst.name), xconst.ty.name(ctxt))
+            }
+            None => match src.map_tys.get(e.id()) {
+                Some(ty) => ty.name(ctxt),
+                None => return Json::Null,
+            },
+        }
+    };
+
+    let (start, end) = span(ctxt, e);
+    let src = &ctxt.ast.files[file.0 as usize].src;
+
+    let contents = object(vec![
+        ("kind", Json::String("plaintext".into())),
+        ("value", Json::String(text)),
+    ]);
+
+    object(vec![("contents", contents), ("range", range(src, start, end))])
+}
+
+fn decl(ctxt: &SemContext, reassignable: bool, name: String, ty: BuiltinType) -> String {
+    let keyword = if reassignable { "var" } else { "let" };
+
+    format!("{} {}: {}", keyword, name, ty.name(ctxt))
+}
+
+fn var_decl(ctxt: &SemContext, src: &FctSrc, var_id: VarId) -> String {
+    let var = &src.vars[var_id];
+    let name = ctxt.interner.str(var.name).to_string();
+
+    decl(ctxt, var.reassignable, name, var.ty)
+}
+
+fn field_decl(ctxt: &SemContext, cls_id: ClassId, field_id: FieldId) -> String {
+    let cls = ctxt.classes[cls_id].borrow();
+    let field = &cls.fields[field_id];
+    let name = ctxt.interner.str(field.name).to_string();
+
+    decl(ctxt, field.reassignable, name, field.ty)
+}
+
+// the innermost expression at `offset` and the function it belongs to
+fn expr_at<'ast>(ctxt: &SemContext<'ast>, file: FileId, offset: u32) -> Option<(FctId, &'ast Expr)> {
+    for fct in ctxt.fcts.iter() {
+        let fct = fct.borrow();
+
+        if fct.pos.file != file || !fct.is_src() {
+            continue;
+        }
+
+        let mut finder = ExprFinder {
+            ctxt: ctxt,
+            offset: offset,
+            found: None,
+        };
+
+        finder.visit_stmt(fct.ast.block());
+
+        if let Some(e) = finder.found {
+            return Some((fct.id, e));
+        }
+    }
+
+    None
+}
+
+// fields and method calls are positioned at the `.`, their span is extended
+// to the name following it
+fn span(ctxt: &SemContext, e: &Expr) -> (u32, u32) {
+    let pos = e.pos();
+
+    let name = match *e {
+        ExprField(ref field) => Some(field.name),
+        ExprCall(ref call) if call.object.is_some() => Some(call.path.name()),
+        _ => None,
+    };
+
+    let len = name.map_or(0, |name| ctxt.interner.str(name).len() as u32);
+
+    (pos.start, pos.end + len)
+}
+
+struct ExprFinder<'a, 'ast: 'a> {
+    ctxt: &'a SemContext<'ast>,
+    offset: u32,
+    found: Option<&'ast Expr>,
+}
+
+impl<'a, 'ast> Visitor<'ast> for ExprFinder<'a, 'ast> {
+    fn visit_expr(&mut self, e: &'ast Expr) {
+        // lambdas are functions of their own
+        if e.is_lambda() {
+            return;
+        }
+
+        let (start, end) = span(self.ctxt, e);
+
+        if start <= self.offset && self.offset <= end {
+            self.found = Some(e);
+        }
+
+        visit::walk_expr(self, e);
+    }
+}
+
+fn var_pos(fct: &Fct, src: &FctSrc, var_id: VarId) -> Option<Position> {
+    let var = &src.vars[var_id];
+
+    // `self` and the lambda itself are declared by the function
+    if var.node_id == fct.ast.id {
+        return Some(fct.pos);
+    }
+
+    for param in &fct.ast.params {
+        if param.id == var.node_id {
+            return Some(param.pos);
+        }
+    }
+
+    let mut finder = DeclFinder {
+        id: var.node_id,
+        name: var.name,
+        found: None,
+    };
+
+    finder.visit_stmt(fct.ast.block());
+
+    finder.found
+}
+
+struct DeclFinder {
+    id: NodeId,
+    name: Name,
+    found: Option<Position>,
+}
+
+impl<'ast> Visitor<'ast> for DeclFinder {
+    fn visit_stmt(&mut self, s: &'ast Stmt) {
+        match *s {
+            StmtVar(ref stmt) if stmt.id == self.id => self.found = Some(stmt.pos),
+            StmtFor(ref stmt) if stmt.id == self.id => self.found = Some(stmt.pos),
+
+            StmtVarTuple(ref stmt) => {
+                for var in &stmt.vars {
+                    if var.id == self.id {
+                        self.found = Some(var.pos);
+                    }
+                }
+            }
+
+            // all variables of catch blocks are declared by the `do`
+            StmtDo(ref stmt) if stmt.id == self.id => {
+                for catch in &stmt.catch_blocks {
+                    if catch.name == self.name {
+                        self.found = Some(catch.pos);
+                    }
+                }
+            }
+
+            _ => {}
+        }
+
+        visit::walk_stmt(self, s);
+    }
+}
+
+// symbols of the document, only the document itself needs to be parsed
+pub fn symbols(path: &str, text: &str) -> Json {
+    let id_generator = NodeIdGenerator::new();
+    let mut interner = Interner::new();
+    let mut ast = Ast::new();
+    let mut diag = Diagnostic::new();
+
+    {
+        let reader = Reader::from_named_string(path, text);
+        Parser::new(reader, &id_generator, &mut ast, &mut interner).parse(&mut diag);
+    }
+
+    let src = &ast.files[0].src;
+    let interner = &interner;
+    let symbol = |name: String, kind: u64, pos: Position, children: Vec<Json>| {
+        let range = range(src, pos.start, pos.end);
+
+        object(vec![
+            ("name", Json::String(name)),
+            ("kind", Json::U64(kind)),
+            ("range", range.clone()),
+            ("selectionRange", range),
+            ("children", Json::Array(children)),
+        ])
+    };
+    let name = |name: Name| interner.str(name).to_string();
+    let methods = |methods: &[Function], kind: u64| {
+        methods
+            .iter()
+            .map(|m| symbol(name(m.name), kind, m.pos, Vec::new()))
+            .collect::<Vec<_>>()
+    };
+
+    // kinds as defined by the protocol
+    const CLASS: u64 = 5;
+    const METHOD: u64 = 6;
+    const FIELD: u64 = 8;
+    const CONSTRUCTOR: u64 = 9;
+    const ENUM: u64 = 10;
+    const INTERFACE: u64 = 11;
+    const FUNCTION: u64 = 12;
+    const VARIABLE: u64 = 13;
+    const CONSTANT: u64 = 14;
+    const OBJECT: u64 = 19;
+    const ENUM_MEMBER: u64 = 22;
+    const STRUCT: u64 = 23;
+    const TYPE_PARAMETER: u64 = 26;
+
+    let mut result = Vec::new();
+
+    for elem in &ast.files[0].elements {
+        let sym = match *elem {
+            ElemFunction(ref fct) => symbol(name(fct.name), FUNCTION, fct.pos, Vec::new()),
+
+            ElemClass(ref cls) => {
+                let mut children: Vec<_> = cls.fields
+                    .iter()
+                    .map(|field| symbol(name(field.name), FIELD, field.pos, Vec::new()))
+                    .collect();
+
+                children.extend(
+                    cls.ctors
+                        .iter()
+                        .filter(|ctor| ctor.ctor == CtorType::Secondary)
+                        .map(|ctor| symbol(name(cls.name), CONSTRUCTOR, ctor.pos, Vec::new())),
+                );
+                children.extend(methods(&cls.methods, METHOD));
+
+                symbol(name(cls.name), CLASS, cls.pos, children)
+            }
+
+            ElemStruct(ref struc) => {
+                let children = struc.fields
+                    .iter()
+                    .map(|field| symbol(name(field.name), FIELD, field.pos, Vec::new()))
+                    .collect();
+
+                symbol(name(struc.name), STRUCT, struc.pos, children)
+            }
+
+            ElemEnum(ref xenum) => {
+                let children = xenum.variants
+                    .iter()
+                    .map(|variant| symbol(name(variant.name), ENUM_MEMBER, variant.pos, Vec::new()))
+                    .collect();
+
+                symbol(name(xenum.name), ENUM, xenum.pos, children)
+            }
+
+            ElemTrait(ref xtrait) => {
+                symbol(name(xtrait.name), INTERFACE, xtrait.pos, methods(&xtrait.methods, METHOD))
+            }
+
+            ElemImpl(ref ximpl) => {
+                let impl_name = format!("impl {} for {}",
+                                        ximpl.trait_type.to_string(interner),
+                                        ximpl.class_type.to_string(interner));
+
+                symbol(impl_name, OBJECT, ximpl.pos, methods(&ximpl.methods, METHOD))
+            }
+
+            ElemGlobal(ref glob) => symbol(name(glob.name), VARIABLE, glob.pos, Vec::new()),
+            ElemConst(ref xconst) => symbol(name(xconst.name), CONSTANT, xconst.pos, Vec::new()),
+            ElemAlias(ref alias) => symbol(name(alias.name), TYPE_PARAMETER, alias.pos, Vec::new()),
+            ElemImport(_) => continue,
+        };
+
+        result.push(sym);
+    }
+
+    Json::Array(result)
+}
+
+fn range(src: &str, start: u32, end: u32) -> Json {
+    object(vec![
+        ("start", position(src, start)),
+        ("end", position(src, end)),
+    ])
+}
+
+fn position(src: &str, offset: u32) -> Json {
+    let (line, character) = to_lsp(src, offset as usize);
+
+    object(vec![
+        ("line", Json::U64(line as u64)),
+        ("character", Json::U64(character as u64)),
+    ])
+}
+
+// the protocol counts lines from 0 and characters in UTF-16 code units
+pub fn to_lsp(src: &str, offset: usize) -> (u32, u32) {
+    let before = &src[..offset.min(src.len())];
+    let line_start = before.rfind('\n').map_or(0, |ind| ind + 1);
+
+    let line = before.matches('\n').count();
+    let character = before[line_start..].encode_utf16().count();
+
+    (line as u32, character as u32)
+}
+
+pub fn from_lsp(src: &str, line: u32, character: u32) -> u32 {
+    let mut offset = 0;
+
+    for _ in 0..line {
+        match src[offset..].find('\n') {
+            Some(ind) => offset += ind + 1,
+            None => return src.len() as u32,
+        }
+    }
+
+    let mut units = 0;
+
+    for (ind, ch) in src[offset..].char_indices() {
+        if ch == '\n' || units >= character as usize {
+            return (offset + ind) as u32;
+        }
+
+        units += ch.len_utf16();
+    }
+
+    src.len() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use os;
+
+    #[test]
+    fn test_to_lsp() {
+        let src = "fun main() {\n\tlet ä = 1;\n}";
+
+        assert_eq!((0, 0), to_lsp(src, 0));
+        assert_eq!((0, 4), to_lsp(src, 4));
+        assert_eq!((1, 0), to_lsp(src, 13));
+        assert_eq!((1, 1), to_lsp(src, 14));
+        assert_eq!((1, 6), to_lsp(src, 20));
+        assert_eq!((2, 1), to_lsp(src, 100));
+    }
+
+    #[test]
+    fn test_from_lsp() {
+        let src = "fun main() {\n\tlet ä = 1;\n}";
+
+        assert_eq!(0, from_lsp(src, 0, 0));
+        assert_eq!(4, from_lsp(src, 0, 4));
+        assert_eq!(12, from_lsp(src, 0, 100));
+        assert_eq!(14, from_lsp(src, 1, 1));
+        assert_eq!(20, from_lsp(src, 1, 6));
+        assert_eq!(src.len() as u32, from_lsp(src, 5, 0));
+    }
+
+    fn analyze_code<F, T>(code: &str, f: F) -> T
+    where
+        F: FnOnce(&SemContext, FileId, &str) -> T,
+    {
+        os::mem::init_page_size();
+
+        let stdlib = Stdlib::parse("stdlib").unwrap();
+
+        analyze(&stdlib, "/tmp/test.dora", code, |ctxt, file| f(ctxt, file, code))
+    }
+
+    fn offset_of(code: &str, pattern: &str) -> u32 {
+        code.find(pattern).unwrap() as u32
+    }
+
+    fn start_of(range: &Json) -> (u64, u64) {
+        let line = range.find_path(&["start", "line"]).unwrap().as_u64().unwrap();
+        let character = range.find_path(&["start", "character"]).unwrap().as_u64().unwrap();
+
+        (line, character)
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let code = "fun main() {\n  let x: int = \"a\";\n}";
+
+        analyze_code(code, |ctxt, file, _| {
+            let diagnostics = diagnostics(ctxt, file);
+            let diagnostics = diagnostics.as_array().unwrap();
+            assert_eq!(1, diagnostics.len());
+
+            assert_eq!(Some(1), diagnostics[0].find("severity").unwrap().as_u64());
+            assert_eq!(Some("AssignType"), diagnostics[0].find("code").unwrap().as_string());
+            assert_eq!((1, 2), start_of(diagnostics[0].find("range").unwrap()));
+        });
+
+        let code = "fun main() {\n  let y = 1;\n}";
+
+        analyze_code(code, |ctxt, file, _| {
+            let diagnostics = diagnostics(ctxt, file);
+            let diagnostics = diagnostics.as_array().unwrap();
+            assert_eq!(1, diagnostics.len());
+
+            assert_eq!(Some(2), diagnostics[0].find("severity").unwrap().as_u64());
+            assert_eq!(Some("UnusedVariable"), diagnostics[0].find("code").unwrap().as_string());
+            assert_eq!((1, 2), start_of(diagnostics[0].find("range").unwrap()));
+        });
+    }
+
+    #[test]
+    fn test_definition() {
+        let code = "fun main() {\n  let a = A(1);\n  foo(a.x);\n}\n\
+                    fun foo(x: int) -> int { return x; }\n\
+                    class A(let x: int)";
+
+        analyze_code(code, |ctxt, file, code| {
+            let location = definition(ctxt, file, offset_of(code, "foo(a"));
+            assert_eq!(Some("file:///tmp/test.dora"), location.find("uri").unwrap().as_string());
+            assert_eq!((4, 0), start_of(location.find("range").unwrap()));
+
+            let location = definition(ctxt, file, offset_of(code, "a.x") + 2);
+            assert_eq!((5, 12), start_of(location.find("range").unwrap()));
+
+            let location = definition(ctxt, file, offset_of(code, "a.x"));
+            assert_eq!((1, 2), start_of(location.find("range").unwrap()));
+
+            let location = definition(ctxt, file, offset_of(code, "x; }"));
+            assert_eq!((4, 8), start_of(location.find("range").unwrap()));
+
+            let location = definition(ctxt, file, offset_of(code, "A(1)"));
+            assert_eq!((5, 0), start_of(location.find("range").unwrap()));
+
+            assert_eq!(Json::Null, definition(ctxt, file, offset_of(code, "int")));
+        });
+    }
+
+    #[test]
+    fn test_definition_in_stdlib() {
+        let code = "fun main() {\n  println(\"a\");\n}";
+
+        analyze_code(code, |ctxt, file, code| {
+            let location = definition(ctxt, file, offset_of(code, "println"));
+            let uri = location.find("uri").unwrap().as_string().unwrap();
+            assert!(uri.ends_with("stdlib/prelude.dora"));
+        });
+    }
+
+    #[test]
+    fn test_hover() {
+        let code = "fun main() {\n  var a = A(1);\n  foo(a.x);\n}\n\
+                    fun foo(x: int) -> int { return x; }\n\
+                    class A(let x: int)";
+
+        analyze_code(code, |ctxt, file, code| {
+            let value = |offset| {
+                let hover = hover(ctxt, file, offset);
+                hover.find_path(&["contents", "value"]).unwrap().as_string().unwrap().to_string()
+            };
+
+            assert_eq!("fun foo(int) -> int", value(offset_of(code, "foo(a")));
+            assert_eq!("var a: A", value(offset_of(code, "a.x")));
+            assert_eq!("let x: int", value(offset_of(code, "a.x") + 2));
+            assert_eq!("int", value(offset_of(code, "1)")));
+            assert_eq!(Json::Null, hover(ctxt, file, 0));
+        });
+    }
+
+    #[test]
+    fn test_symbols() {
+        let code = "fun main() {}\n\
+                    class A(let x: int) {\n  fun foo() {}\n}\n\
+                    struct B { y: int }\n\
+                    const C: int = 1;";
+
+        let symbols = symbols("/tmp/test.dora", code);
+        let symbols = symbols.as_array().unwrap();
+
+        let names: Vec<_> = symbols
+            .iter()
+            .map(|sym| sym.find("name").unwrap().as_string().unwrap())
+            .collect();
+        assert_eq!(vec!["main", "A", "B", "C"], names);
+
+        let children = symbols[1].find("children").unwrap().as_array().unwrap();
+        assert_eq!(2, children.len());
+        assert_eq!(Some("x"), children[0].find("name").unwrap().as_string());
+        assert_eq!(Some("foo"), children[1].find("name").unwrap().as_string());
+        assert_eq!(Some(6), children[1].find("kind").unwrap().as_u64());
+    }
+}
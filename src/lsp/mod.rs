@@ -0,0 +1,409 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use ctxt::SemContext;
+use docopt::Docopt;
+use dora_parser::lexer::position::FileId;
+use rustc_serialize::json::Json;
+
+use self::analysis::Stdlib;
+use self::rpc::object;
+
+pub mod analysis;
+pub mod rpc;
+
+// Write the Docopt usage string.
+static USAGE: &'static str = "
+Usage: dora-lsp [options]
+       dora-lsp --help
+
+Speaks the Language Server Protocol over stdin and stdout. Every document
+is checked as a program of its own, like `dora <file>` does.
+
+Options:
+    -h, --help              Shows this text
+    --stdlib=<dir>          Directory of the standard library [default: stdlib]
+";
+
+// error codes defined by JSON-RPC and the protocol
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+
+pub fn start() -> i32 {
+    let args = Docopt::new(USAGE)
+        .and_then(|d| d.parse())
+        .unwrap_or_else(|e| e.exit());
+
+    // parsed only once, a missing stdlib is reported to the client
+    let stdlib = Stdlib::parse(args.get_str("--stdlib"));
+
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let stdout = io::stdout();
+    let mut output = stdout.lock();
+
+    let mut server = Server::new(stdlib);
+
+    loop {
+        let msg = match rpc::read_message(&mut input) {
+            Ok(Some(msg)) => msg,
+
+            // client went away without `exit`
+            Ok(None) => return 1,
+
+            Err(err) => {
+                writeln!(&mut io::stderr(), "invalid message: {}", err)
+                    .expect("could not print to stderr");
+                return 1;
+            }
+        };
+
+        for reply in server.handle(&msg) {
+            if rpc::write_message(&mut output, &reply).is_err() {
+                return 1;
+            }
+        }
+
+        if let Some(code) = server.exit_code {
+            return code;
+        }
+    }
+}
+
+struct Server {
+    stdlib: Result<Stdlib, String>,
+
+    // contents of all open documents by uri
+    documents: HashMap<String, String>,
+
+    shutdown: bool,
+    exit_code: Option<i32>,
+}
+
+impl Server {
+    fn new(stdlib: Result<Stdlib, String>) -> Server {
+        Server {
+            stdlib: stdlib,
+            documents: HashMap::new(),
+            shutdown: false,
+            exit_code: None,
+        }
+    }
+
+    // returns the messages to send back to the client
+    fn handle(&mut self, msg: &Json) -> Vec<Json> {
+        let null = Json::Null;
+        let params = msg.find("params").unwrap_or(&null);
+
+        let method = match msg.find("method").and_then(|method| method.as_string()) {
+            Some(method) => method,
+
+            // responses to requests of the server, there are none
+            None => return Vec::new(),
+        };
+
+        match msg.find("id") {
+            Some(id) => {
+                let reply = match self.request(method, params) {
+                    Ok(result) => rpc::response(id.clone(), result),
+                    Err((code, msg)) => rpc::error_response(id.clone(), code, msg),
+                };
+
+                let mut replies = vec![reply];
+
+                // documents can't be checked without the stdlib
+                if let ("initialize", &Err(ref msg)) = (method, &self.stdlib) {
+                    replies.push(show_error(msg));
+                }
+
+                replies
+            }
+
+            None => self.notification(method, params),
+        }
+    }
+
+    fn request(&mut self, method: &str, params: &Json) -> Result<Json, (i64, &'static str)> {
+        if self.shutdown {
+            return Err((INVALID_REQUEST, "server is shutting down"));
+        }
+
+        match method {
+            "initialize" => Ok(capabilities()),
+
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Json::Null)
+            }
+
+            "textDocument/definition" => {
+                Ok(self.analyze_at(params, |ctxt, file, offset| {
+                    analysis::definition(ctxt, file, offset)
+                }))
+            }
+
+            "textDocument/hover" => {
+                Ok(self.analyze_at(params, |ctxt, file, offset| {
+                    analysis::hover(ctxt, file, offset)
+                }))
+            }
+
+            "textDocument/documentSymbol" => {
+                let uri = document_uri(params);
+
+                Ok(match self.documents.get(uri) {
+                    Some(text) => analysis::symbols(&uri_to_path(uri), text),
+                    None => Json::Null,
+                })
+            }
+
+            _ => Err((METHOD_NOT_FOUND, "method not found")),
+        }
+    }
+
+    fn notification(&mut self, method: &str, params: &Json) -> Vec<Json> {
+        let uri = document_uri(params).to_string();
+
+        match method {
+            "exit" => {
+                self.exit_code = Some(if self.shutdown { 0 } else { 1 });
+                Vec::new()
+            }
+
+            "textDocument/didOpen" => {
+                let text = params.find_path(&["textDocument", "text"]).and_then(|t| t.as_string());
+                self.documents.insert(uri.clone(), text.unwrap_or("").to_string());
+
+                vec![self.publish_diagnostics(&uri)]
+            }
+
+            // documents are synced in full, the last change has the current text
+            "textDocument/didChange" => {
+                let text = params
+                    .find("contentChanges")
+                    .and_then(|changes| changes.as_array())
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change.find("text"))
+                    .and_then(|text| text.as_string());
+
+                if let Some(text) = text {
+                    self.documents.insert(uri, text.to_string());
+                }
+
+                Vec::new()
+            }
+
+            "textDocument/didSave" => {
+                if let Some(text) = params.find("text").and_then(|text| text.as_string()) {
+                    self.documents.insert(uri.clone(), text.to_string());
+                }
+
+                vec![self.publish_diagnostics(&uri)]
+            }
+
+            // clears the diagnostics of the document
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                vec![diagnostics_notification(&uri, Json::Array(Vec::new()))]
+            }
+
+            _ => Vec::new(),
+        }
+    }
+
+    fn publish_diagnostics(&self, uri: &str) -> Json {
+        let diagnostics = match (self.documents.get(uri), &self.stdlib) {
+            (Some(text), &Ok(ref stdlib)) => {
+                analysis::analyze(stdlib, &uri_to_path(uri), text, |ctxt, file| {
+                    analysis::diagnostics(ctxt, file)
+                })
+            }
+
+            _ => Json::Array(Vec::new()),
+        };
+
+        diagnostics_notification(uri, diagnostics)
+    }
+
+    // checks the document of a request with `position`, `f` gets
+    // the byte offset of that position
+    fn analyze_at<F>(&self, params: &Json, f: F) -> Json
+    where
+        F: FnOnce(&SemContext, FileId, u32) -> Json,
+    {
+        let uri = document_uri(params);
+
+        let (text, stdlib) = match (self.documents.get(uri), &self.stdlib) {
+            (Some(text), &Ok(ref stdlib)) => (text, stdlib),
+            _ => return Json::Null,
+        };
+
+        let line = params.find_path(&["position", "line"]).and_then(|l| l.as_u64());
+        let character = params.find_path(&["position", "character"]).and_then(|c| c.as_u64());
+        let offset = analysis::from_lsp(text, line.unwrap_or(0) as u32, character.unwrap_or(0) as u32);
+
+        analysis::analyze(stdlib, &uri_to_path(uri), text, |ctxt, file| {
+            f(ctxt, file, offset)
+        })
+    }
+}
+
+fn capabilities() -> Json {
+    // documents are always sent in full
+    let sync = object(vec![
+        ("openClose", Json::Boolean(true)),
+        ("change", Json::U64(1)),
+        ("save", object(vec![("includeText", Json::Boolean(false))])),
+    ]);
+
+    let capabilities = object(vec![
+        ("textDocumentSync", sync),
+        ("definitionProvider", Json::Boolean(true)),
+        ("hoverProvider", Json::Boolean(true)),
+        ("documentSymbolProvider", Json::Boolean(true)),
+    ]);
+
+    object(vec![
+        ("capabilities", capabilities),
+        ("serverInfo", object(vec![("name", Json::String("dora-lsp".into()))])),
+    ])
+}
+
+fn diagnostics_notification(uri: &str, diagnostics: Json) -> Json {
+    let params = object(vec![
+        ("uri", Json::String(uri.into())),
+        ("diagnostics", diagnostics),
+    ]);
+
+    rpc::notification("textDocument/publishDiagnostics", params)
+}
+
+// message type 1 is shown as error
+fn show_error(msg: &str) -> Json {
+    let params = object(vec![
+        ("type", Json::U64(1)),
+        ("message", Json::String(msg.into())),
+    ]);
+
+    rpc::notification("window/showMessage", params)
+}
+
+fn document_uri(params: &Json) -> &str {
+    params
+        .find_path(&["textDocument", "uri"])
+        .and_then(|uri| uri.as_string())
+        .unwrap_or("")
+}
+
+pub fn path_to_uri(path: &str) -> String {
+    let mut uri = String::from("file://");
+
+    for &byte in path.as_bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-_.~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+
+    uri
+}
+
+pub fn uri_to_path(uri: &str) -> String {
+    let path = uri.trim_left_matches("file://").as_bytes();
+    let mut bytes = Vec::new();
+    let mut ind = 0;
+
+    while ind < path.len() {
+        let decoded = if path[ind] == b'%' && ind + 2 < path.len() {
+            ::std::str::from_utf8(&path[ind + 1..ind + 3])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        } else {
+            None
+        };
+
+        match decoded {
+            Some(byte) => {
+                bytes.push(byte);
+                ind += 3;
+            }
+
+            None => {
+                bytes.push(path[ind]);
+                ind += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uri() {
+        assert_eq!("file:///tmp/foo.dora", path_to_uri("/tmp/foo.dora"));
+        assert_eq!("file:///tmp/a%20b/%C3%A4.dora", path_to_uri("/tmp/a b/ä.dora"));
+
+        assert_eq!("/tmp/foo.dora", uri_to_path("file:///tmp/foo.dora"));
+        assert_eq!("/tmp/a b/ä.dora", uri_to_path("file:///tmp/a%20b/%C3%A4.dora"));
+        assert_eq!("/tmp/100%", uri_to_path("file:///tmp/100%"));
+    }
+
+    #[test]
+    fn test_requests() {
+        let mut server = Server::new(Stdlib::parse("stdlib"));
+        let request = |id: u64, method: &str| {
+            object(vec![("id", Json::U64(id)), ("method", Json::String(method.into()))])
+        };
+
+        let reply = server.handle(&request(1, "initialize"));
+        assert_eq!(1, reply.len());
+        assert!(reply[0].find_path(&["result", "capabilities"]).is_some());
+
+        let reply = server.handle(&request(2, "foo"));
+        assert_eq!(Some(METHOD_NOT_FOUND),
+                   reply[0].find_path(&["error", "code"]).and_then(|c| c.as_i64()));
+
+        let reply = server.handle(&request(3, "shutdown"));
+        assert_eq!(Some(&Json::Null), reply[0].find("result"));
+
+        let reply = server.handle(&request(4, "initialize"));
+        assert_eq!(Some(INVALID_REQUEST),
+                   reply[0].find_path(&["error", "code"]).and_then(|c| c.as_i64()));
+
+        let exit = object(vec![("method", Json::String("exit".into()))]);
+        assert!(server.handle(&exit).is_empty());
+        assert_eq!(Some(0), server.exit_code);
+    }
+
+    #[test]
+    fn test_missing_stdlib() {
+        let mut server = Server::new(Stdlib::parse("/nonexistent/stdlib"));
+        let initialize = object(vec![
+            ("id", Json::U64(1)),
+            ("method", Json::String("initialize".into())),
+        ]);
+
+        let reply = server.handle(&initialize);
+        assert_eq!(2, reply.len());
+        assert!(reply[0].find_path(&["result", "capabilities"]).is_some());
+        assert_eq!(Some("window/showMessage"), reply[1].find("method").and_then(|m| m.as_string()));
+        assert_eq!(Some(1), reply[1].find_path(&["params", "type"]).and_then(|t| t.as_u64()));
+
+        let document = object(vec![
+            ("uri", Json::String("file:///tmp/foo.dora".into())),
+            ("text", Json::String("fun main() { foo(); }".into())),
+        ]);
+        let open = object(vec![
+            ("method", Json::String("textDocument/didOpen".into())),
+            ("params", object(vec![("textDocument", document)])),
+        ]);
+
+        let reply = server.handle(&open);
+        assert_eq!(Some(&Json::Array(Vec::new())), reply[0].find_path(&["params", "diagnostics"]));
+    }
+}
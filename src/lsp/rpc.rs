@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, Error, ErrorKind, Write};
+
+use rustc_serialize::json::Json;
+
+// messages are JSON objects prefixed with headers:
+//
+// Content-Length: 44\r\n
+// \r\n
+// {"jsonrpc":"2.0","id":1,"method":"shutdown"}
+//
+// returns None at the end of input
+pub fn read_message<R: BufRead>(input: &mut R) -> io::Result<Option<Json>> {
+    let mut len = None;
+
+    loop {
+        let mut line = String::new();
+
+        if input.read_line(&mut line)? == 0 {
+            return if len.is_none() {
+                Ok(None)
+            } else {
+                Err(invalid_data("unexpected end of input"))
+            };
+        }
+
+        let line = line.trim_right();
+
+        if line.is_empty() {
+            break;
+        }
+
+        let mut parts = line.splitn(2, ':');
+        let name = parts.next().unwrap().trim();
+        let value = parts.next().unwrap_or("").trim();
+
+        if name.eq_ignore_ascii_case("Content-Length") {
+            len = Some(value.parse::<usize>().map_err(|_| invalid_data("invalid Content-Length"))?);
+        }
+    }
+
+    let len = match len {
+        Some(len) => len,
+        None => return Err(invalid_data("missing Content-Length")),
+    };
+
+    let mut content = vec![0; len];
+    input.read_exact(&mut content)?;
+
+    let content = String::from_utf8(content).map_err(|_| invalid_data("content is not UTF-8"))?;
+
+    match Json::from_str(&content) {
+        Ok(msg) => Ok(Some(msg)),
+        Err(_) => Err(invalid_data("content is not JSON")),
+    }
+}
+
+pub fn write_message<W: Write>(output: &mut W, msg: &Json) -> io::Result<()> {
+    let content = msg.to_string();
+
+    write!(output, "Content-Length: {}\r\n\r\n{}", content.len(), content)?;
+    output.flush()
+}
+
+fn invalid_data(msg: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, msg)
+}
+
+pub fn object(fields: Vec<(&str, Json)>) -> Json {
+    let mut obj = BTreeMap::new();
+
+    for (name, value) in fields {
+        obj.insert(name.to_string(), value);
+    }
+
+    Json::Object(obj)
+}
+
+pub fn response(id: Json, result: Json) -> Json {
+    object(vec![
+        ("jsonrpc", Json::String("2.0".into())),
+        ("id", id),
+        ("result", result),
+    ])
+}
+
+pub fn error_response(id: Json, code: i64, msg: &str) -> Json {
+    let error = object(vec![
+        ("code", Json::I64(code)),
+        ("message", Json::String(msg.into())),
+    ]);
+
+    object(vec![
+        ("jsonrpc", Json::String("2.0".into())),
+        ("id", id),
+        ("error", error),
+    ])
+}
+
+pub fn notification(method: &str, params: Json) -> Json {
+    object(vec![
+        ("jsonrpc", Json::String("2.0".into())),
+        ("method", Json::String(method.into())),
+        ("params", params),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn test_read_message() {
+        let input = "Content-Length: 14\r\n\r\n{\"id\":1,\"a\":2}";
+        let mut input = Cursor::new(input.as_bytes());
+
+        let msg = read_message(&mut input).unwrap().unwrap();
+        assert_eq!(Some(1), msg.find("id").and_then(|id| id.as_u64()));
+        assert_eq!(None, read_message(&mut input).unwrap());
+    }
+
+    #[test]
+    fn test_read_message_with_content_type() {
+        let input = "Content-Length: 2\r\nContent-Type: application/vscode-jsonrpc\r\n\r\n{}";
+        let mut input = Cursor::new(input.as_bytes());
+
+        assert!(read_message(&mut input).unwrap().unwrap().is_object());
+    }
+
+    #[test]
+    fn test_read_invalid_message() {
+        let mut input = Cursor::new("Content-Type: foo\r\n\r\n{}".as_bytes());
+        assert!(read_message(&mut input).is_err());
+
+        let mut input = Cursor::new("Content-Length: 3\r\n\r\n{\"".as_bytes());
+        assert!(read_message(&mut input).is_err());
+    }
+
+    #[test]
+    fn test_write_message() {
+        let mut output = Vec::new();
+        let msg = response(Json::U64(1), Json::Null);
+        write_message(&mut output, &msg).unwrap();
+
+        assert_eq!("Content-Length: 38\r\n\r\n{\"id\":1,\"jsonrpc\":\"2.0\",\"result\":null}",
+                   String::from_utf8(output).unwrap());
+    }
+}
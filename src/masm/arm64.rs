@@ -262,6 +262,28 @@ impl MacroAssembler {
         self.emit_u32(asm::msub(x64, dest, *scratch, rhs, lhs));
     }
 
+    pub fn uint_div(&mut self, mode: MachineMode, dest: Reg, lhs: Reg, rhs: Reg) {
+        let x64 = match mode {
+            MachineMode::Int32 => 0,
+            MachineMode::Int64 => 1,
+            _ => panic!("unimplemented mode {:?}", mode),
+        };
+
+        self.emit_u32(asm::udiv(x64, dest, lhs, rhs));
+    }
+
+    pub fn uint_mod(&mut self, mode: MachineMode, dest: Reg, lhs: Reg, rhs: Reg) {
+        let scratch = self.get_scratch();
+        let x64 = match mode {
+            MachineMode::Int32 => 0,
+            MachineMode::Int64 => 1,
+            _ => panic!("unimplemented mode {:?}", mode),
+        };
+
+        self.emit_u32(asm::udiv(x64, *scratch, lhs, rhs));
+        self.emit_u32(asm::msub(x64, dest, *scratch, rhs, lhs));
+    }
+
     pub fn int_mul(&mut self, mode: MachineMode, dest: Reg, lhs: Reg, rhs: Reg) {
         let x64 = match mode {
             MachineMode::Int32 => 0,
@@ -292,6 +314,41 @@ impl MacroAssembler {
         self.emit_u32(asm::sub_reg(x64, dest, lhs, rhs));
     }
 
+    // the checked variants jump to `lbl` when the signed result overflows
+    pub fn int_add_checked(&mut self, mode: MachineMode, dest: Reg, lhs: Reg, rhs: Reg, lbl: Label) {
+        self.emit_u32(asm::adds_reg(size_flag(mode), dest, lhs, rhs));
+        self.jump_if(CondCode::Overflow, lbl);
+    }
+
+    pub fn int_sub_checked(&mut self, mode: MachineMode, dest: Reg, lhs: Reg, rhs: Reg, lbl: Label) {
+        self.emit_u32(asm::subs_reg(size_flag(mode), dest, lhs, rhs));
+        self.jump_if(CondCode::Overflow, lbl);
+    }
+
+    pub fn int_mul_checked(&mut self, mode: MachineMode, dest: Reg, lhs: Reg, rhs: Reg, lbl: Label) {
+        let scratch = self.get_scratch();
+
+        match mode {
+            MachineMode::Int32 => {
+                // the 64-bit product needs to survive sign-extending its lower half
+                self.emit_u32(asm::smull(dest, lhs, rhs));
+                self.emit_u32(asm::sxtw(*scratch, dest));
+                self.emit_u32(asm::cmp_reg(1, dest, *scratch));
+            }
+
+            MachineMode::Int64 => {
+                // the upper half needs to consist of sign bits of the lower half
+                self.emit_u32(asm::smulh(*scratch, lhs, rhs));
+                self.emit_u32(asm::mul(1, dest, lhs, rhs));
+                self.emit_u32(asm::cmp_shreg(1, *scratch, dest, Shift::ASR, 63));
+            }
+
+            _ => panic!("unimplemented mode {:?}", mode),
+        }
+
+        self.jump_if(CondCode::NotEqual, lbl);
+    }
+
     pub fn int_shl(&mut self, mode: MachineMode, dest: Reg, lhs: Reg, rhs: Reg) {
         let x64 = match mode {
             MachineMode::Int32 => 0,
@@ -374,6 +431,28 @@ impl MacroAssembler {
         self.emit_u32(asm::scvtf(x64, flt, dest, src));
     }
 
+    pub fn uint_to_float(
+        &mut self,
+        dest_mode: MachineMode,
+        dest: FReg,
+        src_mode: MachineMode,
+        src: Reg,
+    ) {
+        let x64 = match src_mode {
+            MachineMode::Int32 => 0,
+            MachineMode::Int64 => 1,
+            _ => unreachable!(),
+        };
+
+        let flt = match dest_mode {
+            MachineMode::Float32 => 0,
+            MachineMode::Float64 => 1,
+            _ => unreachable!(),
+        };
+
+        self.emit_u32(asm::ucvtf(x64, flt, dest, src));
+    }
+
     pub fn float_to_int(
         &mut self,
         dest_mode: MachineMode,
@@ -408,7 +487,7 @@ impl MacroAssembler {
         let dbl = match mode {
             MachineMode::Float32 => 0,
             MachineMode::Float64 => 1,
-            _ => unimplemented!(),
+            _ => unreachable!("{:?} is not a float mode", mode),
         };
 
         self.emit_u32(asm::fadd(dbl, dest, lhs, rhs));
@@ -418,7 +497,7 @@ impl MacroAssembler {
         let dbl = match mode {
             MachineMode::Float32 => 0,
             MachineMode::Float64 => 1,
-            _ => unimplemented!(),
+            _ => unreachable!("{:?} is not a float mode", mode),
         };
 
         self.emit_u32(asm::fsub(dbl, dest, lhs, rhs));
@@ -428,7 +507,7 @@ impl MacroAssembler {
         let dbl = match mode {
             MachineMode::Float32 => 0,
             MachineMode::Float64 => 1,
-            _ => unimplemented!(),
+            _ => unreachable!("{:?} is not a float mode", mode),
         };
 
         self.emit_u32(asm::fmul(dbl, dest, lhs, rhs));
@@ -438,7 +517,7 @@ impl MacroAssembler {
         let dbl = match mode {
             MachineMode::Float32 => 0,
             MachineMode::Float64 => 1,
-            _ => unimplemented!(),
+            _ => unreachable!("{:?} is not a float mode", mode),
         };
 
         self.emit_u32(asm::fdiv(dbl, dest, lhs, rhs));
@@ -448,7 +527,7 @@ impl MacroAssembler {
         let dbl = match mode {
             MachineMode::Float32 => 0,
             MachineMode::Float64 => 1,
-            _ => unimplemented!(),
+            _ => unreachable!("{:?} is not a float mode", mode),
         };
 
         self.emit_u32(asm::fneg(dbl, dest, src));
@@ -458,7 +537,7 @@ impl MacroAssembler {
         let dbl = match mode {
             MachineMode::Float32 => 0,
             MachineMode::Float64 => 1,
-            _ => unimplemented!(),
+            _ => unreachable!("{:?} is not a float mode", mode),
         };
 
         self.emit_u32(asm::fsqrt(dbl, dest, src));
@@ -475,7 +554,7 @@ impl MacroAssembler {
         let dbl = match mode {
             MachineMode::Float32 => 0,
             MachineMode::Float64 => 1,
-            _ => unimplemented!(),
+            _ => unreachable!("{:?} is not a float mode", mode),
         };
 
         match cond {
@@ -497,7 +576,7 @@ impl MacroAssembler {
         let dbl = match mode {
             MachineMode::Float32 => 0,
             MachineMode::Float64 => 1,
-            _ => unimplemented!(),
+            _ => unreachable!("{:?} is not a float mode", mode),
         };
 
         self.emit_u32(asm::fcmp(dbl, src, src));
@@ -588,6 +667,9 @@ impl MacroAssembler {
                     MachineMode::Int8 => {
                         asm::ldrb_ind(dest.reg(), REG_FP, *scratch, LdStExtend::LSL, 0)
                     }
+                    MachineMode::Int16 => {
+                        asm::ldrsh_ind(dest.reg(), REG_FP, *scratch, LdStExtend::LSL, 0)
+                    }
                     MachineMode::Int32 => {
                         asm::ldrw_ind(dest.reg(), REG_FP, *scratch, LdStExtend::LSL, 0)
                     }
@@ -620,6 +702,9 @@ impl MacroAssembler {
                     MachineMode::Int8 => {
                         asm::ldrb_ind(dest.reg(), *scratch, index, LdStExtend::LSL, 0)
                     }
+                    MachineMode::Int16 => {
+                        asm::ldrsh_ind(dest.reg(), *scratch, index, LdStExtend::LSL, 1)
+                    }
                     MachineMode::Int32 => {
                         asm::ldrw_ind(dest.reg(), *scratch, index, LdStExtend::LSL, 1)
                     }
@@ -659,6 +744,7 @@ impl MacroAssembler {
 
         let inst = match mode {
             MachineMode::Int8 => asm::ldrb_ind(dest.reg(), base, reg, LdStExtend::LSL, 0),
+            MachineMode::Int16 => asm::ldrsh_ind(dest.reg(), base, reg, LdStExtend::LSL, 0),
             MachineMode::Int32 => asm::ldrw_ind(dest.reg(), base, reg, LdStExtend::LSL, 0),
             MachineMode::Int64 | MachineMode::Ptr => {
                 asm::ldrx_ind(dest.reg(), base, reg, LdStExtend::LSL, 0)
@@ -704,6 +790,7 @@ impl MacroAssembler {
 
         let inst = match mode {
             MachineMode::Int8 => asm::strb_ind(src.reg(), base, reg, LdStExtend::LSL, 0),
+            MachineMode::Int16 => asm::strh_ind(src.reg(), base, reg, LdStExtend::LSL, 0),
             MachineMode::Int32 => asm::strw_ind(src.reg(), base, reg, LdStExtend::LSL, 0),
             MachineMode::Int64 | MachineMode::Ptr => {
                 asm::strx_ind(src.reg(), base, reg, LdStExtend::LSL, 0)
@@ -730,6 +817,9 @@ impl MacroAssembler {
                     MachineMode::Int8 => {
                         asm::strb_ind(src.reg(), REG_FP, *scratch, LdStExtend::LSL, 0)
                     }
+                    MachineMode::Int16 => {
+                        asm::strh_ind(src.reg(), REG_FP, *scratch, LdStExtend::LSL, 0)
+                    }
                     MachineMode::Int32 => {
                         asm::strw_ind(src.reg(), REG_FP, *scratch, LdStExtend::LSL, 0)
                     }
@@ -762,6 +852,9 @@ impl MacroAssembler {
                     MachineMode::Int8 => {
                         asm::strb_ind(src.reg(), *scratch, index, LdStExtend::LSL, 0)
                     }
+                    MachineMode::Int16 => {
+                        asm::strh_ind(src.reg(), *scratch, index, LdStExtend::LSL, 1)
+                    }
                     MachineMode::Int32 => {
                         asm::strw_ind(src.reg(), *scratch, index, LdStExtend::LSL, 1)
                     }
@@ -812,6 +905,14 @@ impl MacroAssembler {
         self.emit_u32(asm::sxtw(dest, src));
     }
 
+    pub fn extend_uint_long(&mut self, dest: Reg, src: Reg) {
+        self.emit_u32(asm::uxtw(dest, src));
+    }
+
+    pub fn extend_short(&mut self, dest: Reg, src: Reg) {
+        self.emit_u32(asm::sxth(dest, src));
+    }
+
     pub fn extend_byte(&mut self, mode: MachineMode, dest: Reg, src: Reg) {
         match mode {
             MachineMode::Int32 => {}
@@ -834,9 +935,16 @@ impl MacroAssembler {
     }
 
     pub fn load_int_const(&mut self, mode: MachineMode, dest: Reg, imm: i64) {
+        let imm = if mode == MachineMode::Int16 {
+            imm as i16 as i64
+        } else {
+            imm
+        };
+
         let sf = size_flag(mode);
         let register_size = match mode {
             MachineMode::Int8 => 32,
+            MachineMode::Int16 => 32,
             MachineMode::Int32 => 32,
             MachineMode::Int64 => 64,
             MachineMode::Ptr => 64,
@@ -969,7 +1077,7 @@ enum JumpType {
 
 fn size_flag(mode: MachineMode) -> u32 {
     match mode {
-        MachineMode::Int8 | MachineMode::Int32 => 0,
+        MachineMode::Int8 | MachineMode::Int16 | MachineMode::Int32 => 0,
         MachineMode::Ptr | MachineMode::Int64 => 1,
         MachineMode::Float32 | MachineMode::Float64 => unimplemented!(),
     }
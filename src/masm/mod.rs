@@ -33,7 +33,7 @@ pub struct MacroAssembler {
     data: Vec<u8>,
     labels: Vec<Option<usize>>,
     jumps: Vec<ForwardJump>,
-    bailouts: Vec<(Label, Trap, Position)>,
+    bailouts: Vec<(Label, Trap, Position, usize)>,
     bailout_infos: Bailouts,
     nil_checks: HashSet<i32>,
     dseg: DSeg,
@@ -105,11 +105,27 @@ impl MacroAssembler {
         let bailouts = self.bailouts.drain(0..).collect::<Vec<_>>();
 
         for bailout in &bailouts {
-            let (lbl, trap, pos) = *bailout;
+            let (lbl, trap, pos, origin) = *bailout;
 
             self.bind_label(lbl);
+            let trap_pos = self.pos();
             self.emit_lineno(pos.line as i32);
             self.trap(trap);
+
+            // the trap is placed after the function body, so it needs to be covered
+            // by the same exception handlers as the jump into it (handlers cover
+            // all program counters in `(try_start, try_end]`)
+            let handlers = self.exception_handlers
+                .iter()
+                .filter(|handler| handler.try_start < origin && origin <= handler.try_end)
+                .map(|handler| ExHandler {
+                    try_start: trap_pos - 1,
+                    try_end: trap_pos,
+                    ..handler.clone()
+                })
+                .collect::<Vec<_>>();
+
+            self.exception_handlers.extend(handlers);
         }
 
         self.fix_forward_jumps();
@@ -194,7 +210,8 @@ impl MacroAssembler {
     }
 
     pub fn emit_bailout(&mut self, lbl: Label, trap: Trap, pos: Position) {
-        self.bailouts.push((lbl, trap, pos));
+        let origin = self.pos();
+        self.bailouts.push((lbl, trap, pos, origin));
     }
 
     pub fn emit_bailout_inplace(&mut self, trap: Trap, pos: Position) {
@@ -230,6 +247,10 @@ impl MacroAssembler {
         self.data[pos as usize] = value;
     }
 
+    pub fn emit_u16(&mut self, value: u16) {
+        self.data.write_u16::<LittleEndian>(value).unwrap();
+    }
+
     pub fn emit_u32(&mut self, value: u32) {
         self.data.write_u32::<LittleEndian>(value).unwrap();
     }
@@ -253,6 +274,40 @@ impl MacroAssembler {
         }
     }
 
+    // copies `size` bytes from `src` to `dest`, used for struct values
+    pub fn copy_mem(&mut self, dest: Mem, src: Mem, size: i32, tmp: Reg) {
+        let mut offset = 0;
+
+        while offset < size {
+            let mode = match size - offset {
+                rest if rest >= 8 => MachineMode::Int64,
+                rest if rest >= 4 => MachineMode::Int32,
+                _ => MachineMode::Int8,
+            };
+
+            self.load_mem(mode, tmp.into(), src.offset(offset));
+            self.store_mem(mode, dest.offset(offset), tmp.into());
+
+            offset += mode.size();
+        }
+    }
+
+    pub fn zero_mem(&mut self, dest: Mem, size: i32, tmp: Reg) {
+        let mut offset = 0;
+        self.load_int_const(MachineMode::Ptr, tmp, 0);
+
+        while offset < size {
+            let mode = match size - offset {
+                rest if rest >= 8 => MachineMode::Int64,
+                rest if rest >= 4 => MachineMode::Int32,
+                _ => MachineMode::Int8,
+            };
+
+            self.store_mem(mode, dest.offset(offset), tmp.into());
+            offset += mode.size();
+        }
+    }
+
     pub fn fill_zero(&mut self, obj: Reg, size: usize) {
         debug_assert!(size >= (Header::size() as usize));
         debug_assert!(size % mem::ptr_width_usize() == 0);
@@ -123,6 +123,33 @@ impl MacroAssembler {
         }
     }
 
+    // computes the address of the array element, needed for struct elements
+    // which can't be loaded into a register
+    pub fn array_elem_address(&mut self, dest: Reg, array: Reg, index: Reg, element_size: i32) {
+        match element_size {
+            1 | 2 | 4 | 8 => {
+                asm::lea(
+                    self,
+                    dest,
+                    Mem::Index(array, index, element_size, offset_of_array_data()),
+                );
+            }
+
+            _ => {
+                assert!(dest != array && dest != index);
+
+                self.load_int_const(MachineMode::Ptr, dest, element_size as i64);
+                asm::emit_imul_reg_reg(self, 1, index, dest);
+                asm::emit_add_reg_reg(self, 1, array, dest);
+                asm::emit_addq_imm_reg(self, offset_of_array_data(), dest);
+            }
+        }
+    }
+
+    pub fn load_address(&mut self, dest: Reg, mem: Mem) {
+        asm::lea(self, dest, mem);
+    }
+
     pub fn set(&mut self, dest: Reg, op: CondCode) {
         asm::emit_setb_reg(self, op, dest);
         asm::emit_movzbl_reg_reg(self, dest, dest);
@@ -150,7 +177,7 @@ impl MacroAssembler {
 
     pub fn cmp_reg(&mut self, mode: MachineMode, lhs: Reg, rhs: Reg) {
         let x64 = match mode {
-            MachineMode::Int8 | MachineMode::Int32 => 0,
+            MachineMode::Int8 | MachineMode::Int16 | MachineMode::Int32 => 0,
             MachineMode::Int64 | MachineMode::Ptr => 1,
             MachineMode::Float32 | MachineMode::Float64 => unreachable!(),
         };
@@ -243,14 +270,30 @@ impl MacroAssembler {
     }
 
     pub fn int_div(&mut self, mode: MachineMode, dest: Reg, lhs: Reg, rhs: Reg) {
-        self.div_common(mode, dest, lhs, rhs, RAX);
+        self.div_common(mode, dest, lhs, rhs, RAX, true);
     }
 
     pub fn int_mod(&mut self, mode: MachineMode, dest: Reg, lhs: Reg, rhs: Reg) {
-        self.div_common(mode, dest, lhs, rhs, RDX);
+        self.div_common(mode, dest, lhs, rhs, RDX, true);
+    }
+
+    pub fn uint_div(&mut self, mode: MachineMode, dest: Reg, lhs: Reg, rhs: Reg) {
+        self.div_common(mode, dest, lhs, rhs, RAX, false);
     }
 
-    fn div_common(&mut self, mode: MachineMode, dest: Reg, lhs: Reg, rhs: Reg, result: Reg) {
+    pub fn uint_mod(&mut self, mode: MachineMode, dest: Reg, lhs: Reg, rhs: Reg) {
+        self.div_common(mode, dest, lhs, rhs, RDX, false);
+    }
+
+    fn div_common(
+        &mut self,
+        mode: MachineMode,
+        dest: Reg,
+        lhs: Reg,
+        rhs: Reg,
+        result: Reg,
+        signed: bool,
+    ) {
         let x64 = match mode {
             MachineMode::Int32 => 0,
             MachineMode::Int64 => 1,
@@ -262,13 +305,36 @@ impl MacroAssembler {
             asm::emit_mov_reg_reg(self, x64, lhs, RAX);
         }
 
-        if x64 != 0 {
-            asm::emit_cqo(self);
+        if !signed {
+            assert!(rhs != RDX);
+            asm::emit_movl_imm_reg(self, 0, RDX);
+            asm::emit_div_reg_reg(self, x64, rhs);
         } else {
-            asm::emit_cdq(self);
-        }
+            // idiv faults on MIN / -1, the quotient wraps around to MIN instead
+            let lbl_div = self.create_label();
+            let lbl_done = self.create_label();
+
+            asm::emit_cmp_imm_reg(self, mode, -1, rhs);
+            self.jump_if(CondCode::NotEqual, lbl_div);
 
-        asm::emit_idiv_reg_reg(self, x64, rhs);
+            if result == RAX {
+                asm::emit_neg_reg(self, x64, RAX);
+            } else {
+                asm::emit_movl_imm_reg(self, 0, RDX);
+            }
+
+            self.jump(lbl_done);
+            self.bind_label(lbl_div);
+
+            if x64 != 0 {
+                asm::emit_cqo(self);
+            } else {
+                asm::emit_cdq(self);
+            }
+
+            asm::emit_idiv_reg_reg(self, x64, rhs);
+            self.bind_label(lbl_done);
+        }
 
         if dest != result {
             asm::emit_mov_reg_reg(self, x64, result, dest);
@@ -303,6 +369,22 @@ impl MacroAssembler {
         }
     }
 
+    // the checked variants jump to `lbl` when the signed result overflows
+    pub fn int_add_checked(&mut self, mode: MachineMode, dest: Reg, lhs: Reg, rhs: Reg, lbl: Label) {
+        self.int_add(mode, dest, lhs, rhs);
+        self.jump_if(CondCode::Overflow, lbl);
+    }
+
+    pub fn int_sub_checked(&mut self, mode: MachineMode, dest: Reg, lhs: Reg, rhs: Reg, lbl: Label) {
+        self.int_sub(mode, dest, lhs, rhs);
+        self.jump_if(CondCode::Overflow, lbl);
+    }
+
+    pub fn int_mul_checked(&mut self, mode: MachineMode, dest: Reg, lhs: Reg, rhs: Reg, lbl: Label) {
+        self.int_mul(mode, dest, lhs, rhs);
+        self.jump_if(CondCode::Overflow, lbl);
+    }
+
     pub fn int_add_imm(&mut self, mode: MachineMode, dest: Reg, lhs: Reg, value: i32) {
         let x64 = match mode {
             MachineMode::Int64 | MachineMode::Ptr => 1,
@@ -451,6 +533,44 @@ impl MacroAssembler {
         }
     }
 
+    pub fn uint_to_float(
+        &mut self,
+        dest_mode: MachineMode,
+        dest: FReg,
+        src_mode: MachineMode,
+        src: Reg,
+    ) {
+        if src_mode == MachineMode::Int32 {
+            // every zero-extended uint is a positive long
+            self.extend_uint_long(src, src);
+            self.int_to_float(dest_mode, dest, MachineMode::Int64, src);
+            return;
+        }
+
+        assert_eq!(MachineMode::Int64, src_mode);
+
+        let lbl_large = self.create_label();
+        let lbl_done = self.create_label();
+
+        asm::emit_testq_reg_reg(self, src, src);
+        self.jump_if(CondCode::Less, lbl_large);
+        self.int_to_float(dest_mode, dest, MachineMode::Int64, src);
+        self.jump(lbl_done);
+
+        // values with the top bit set are halved for the signed conversion and
+        // doubled afterwards, the lost bit is kept to round the same way
+        self.bind_label(lbl_large);
+        let scratch = self.get_scratch();
+        asm::emit_mov_reg_reg(self, 1, src, *scratch);
+        asm::emit_shr_reg_imm(self, 1, *scratch, 1);
+        asm::emit_andq_imm_reg(self, 1, src);
+        asm::emit_or_reg_reg(self, 1, src, *scratch);
+        self.int_to_float(dest_mode, dest, MachineMode::Int64, *scratch);
+        self.float_add(dest_mode, dest, dest, dest);
+
+        self.bind_label(lbl_done);
+    }
+
     pub fn float_to_int(
         &mut self,
         dest_mode: MachineMode,
@@ -486,8 +606,6 @@ impl MacroAssembler {
         element_size: i32,
         with_header: bool,
     ) {
-        assert!(element_size == 1 || element_size == 2 || element_size == 4 || element_size == 8);
-
         let header_size = if with_header {
             Header::size() + ptr_width()
         } else {
@@ -500,7 +618,25 @@ impl MacroAssembler {
             0
         };
 
-        asm::lea(self, dest, Mem::Offset(length, element_size, size));
+        match element_size {
+            1 | 2 | 4 | 8 => {
+                asm::lea(self, dest, Mem::Offset(length, element_size, size));
+            }
+
+            // arrays of structs can have arbitrary element sizes
+            _ => {
+                let scratch = self.get_scratch();
+                assert!(*scratch != dest && *scratch != length);
+                self.load_int_const(MachineMode::Ptr, *scratch, element_size as i64);
+
+                if dest != length {
+                    asm::emit_mov_reg_reg(self, 1, length, dest);
+                }
+
+                asm::emit_imul_reg_reg(self, 1, *scratch, dest);
+                asm::emit_addq_imm_reg(self, size, dest);
+            }
+        }
 
         if element_size != ptr_width() {
             asm::emit_andq_imm_reg(self, -ptr_width(), dest);
@@ -542,6 +678,7 @@ impl MacroAssembler {
         match mem {
             Mem::Local(offset) => match mode {
                 MachineMode::Int8 => asm::emit_movzbl_memq_reg(self, RBP, offset, dest.reg()),
+                MachineMode::Int16 => asm::emit_movswl_memq_reg(self, RBP, offset, dest.reg()),
                 MachineMode::Int32 => asm::emit_movl_memq_reg(self, RBP, offset, dest.reg()),
                 MachineMode::Int64 | MachineMode::Ptr => {
                     asm::emit_movq_memq_reg(self, RBP, offset, dest.reg())
@@ -552,6 +689,7 @@ impl MacroAssembler {
 
             Mem::Base(base, disp) => match mode {
                 MachineMode::Int8 => asm::emit_movzbl_memq_reg(self, base, disp, dest.reg()),
+                MachineMode::Int16 => asm::emit_movswl_memq_reg(self, base, disp, dest.reg()),
                 MachineMode::Int32 => asm::emit_movl_memq_reg(self, base, disp, dest.reg()),
                 MachineMode::Int64 | MachineMode::Ptr => {
                     asm::emit_movq_memq_reg(self, base, disp, dest.reg())
@@ -566,6 +704,11 @@ impl MacroAssembler {
                     asm::emit_movzx_memindex_byte_reg(self, 0, base, index, disp, dest.reg())
                 }
 
+                MachineMode::Int16 => {
+                    assert!(scale == 2);
+                    asm::emit_movsx_memindex_word_reg(self, base, index, disp, dest.reg())
+                }
+
                 MachineMode::Int32 | MachineMode::Int64 | MachineMode::Ptr => {
                     asm::emit_mov_memindex_reg(self, mode, base, index, scale, disp, dest.reg())
                 }
@@ -597,7 +740,7 @@ impl MacroAssembler {
         }
     }
 
-    fn emit_barrier(&mut self, src: Reg, card_table_offset: usize) {
+    pub fn emit_barrier(&mut self, src: Reg, card_table_offset: usize) {
         asm::emit_shr_reg_imm(self, 1, src, CARD_SIZE_BITS as u8);
 
         // test if card table offset fits into displacement of memory store
@@ -615,6 +758,7 @@ impl MacroAssembler {
         match mem {
             Mem::Local(offset) => match mode {
                 MachineMode::Int8 => asm::emit_movb_reg_memq(self, src.reg(), RBP, offset),
+                MachineMode::Int16 => asm::emit_movw_reg_memq(self, src.reg(), RBP, offset),
                 MachineMode::Int32 => asm::emit_movl_reg_memq(self, src.reg(), RBP, offset),
                 MachineMode::Int64 | MachineMode::Ptr => {
                     asm::emit_movq_reg_memq(self, src.reg(), RBP, offset)
@@ -625,6 +769,7 @@ impl MacroAssembler {
 
             Mem::Base(base, disp) => match mode {
                 MachineMode::Int8 => asm::emit_movb_reg_memq(self, src.reg(), base, disp),
+                MachineMode::Int16 => asm::emit_movw_reg_memq(self, src.reg(), base, disp),
                 MachineMode::Int32 => asm::emit_movl_reg_memq(self, src.reg(), base, disp),
                 MachineMode::Int64 | MachineMode::Ptr => {
                     asm::emit_movq_reg_memq(self, src.reg(), base, disp)
@@ -634,7 +779,11 @@ impl MacroAssembler {
             },
 
             Mem::Index(base, index, scale, disp) => match mode {
-                MachineMode::Int8 | MachineMode::Int32 | MachineMode::Int64 | MachineMode::Ptr => {
+                MachineMode::Int8
+                | MachineMode::Int16
+                | MachineMode::Int32
+                | MachineMode::Int64
+                | MachineMode::Ptr => {
                     asm::emit_mov_reg_memindex(self, mode, src.reg(), base, index, scale, disp)
                 }
 
@@ -648,7 +797,7 @@ impl MacroAssembler {
 
     pub fn copy_reg(&mut self, mode: MachineMode, dest: Reg, src: Reg) {
         let x64 = match mode {
-            MachineMode::Int8 | MachineMode::Int32 => 0,
+            MachineMode::Int8 | MachineMode::Int16 | MachineMode::Int32 => 0,
             MachineMode::Int64 | MachineMode::Ptr => 1,
             MachineMode::Float32 | MachineMode::Float64 => unreachable!(),
         };
@@ -672,6 +821,15 @@ impl MacroAssembler {
         asm::emit_movsx(self, src, dest);
     }
 
+    pub fn extend_uint_long(&mut self, dest: Reg, src: Reg) {
+        // writing a 32-bit register clears the upper half
+        asm::emit_mov_reg_reg(self, 0, src, dest);
+    }
+
+    pub fn extend_short(&mut self, dest: Reg, src: Reg) {
+        asm::emit_movsx_word(self, 0, src, dest);
+    }
+
     pub fn extend_byte(&mut self, mode: MachineMode, dest: Reg, src: Reg) {
         let x64 = match mode {
             MachineMode::Int32 => 0,
@@ -704,6 +862,7 @@ impl MacroAssembler {
             MachineMode::Int8 | MachineMode::Int32 => {
                 asm::emit_movl_imm_reg(self, imm as i32, dest)
             }
+            MachineMode::Int16 => asm::emit_movl_imm_reg(self, imm as i16 as i32, dest),
             MachineMode::Int64 | MachineMode::Ptr => {
                 if fits_i32(imm) {
                     asm::emit_movq_imm_reg(self, imm as i32, dest);
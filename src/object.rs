@@ -6,8 +6,9 @@ use std::ops::{Deref, DerefMut};
 use std::ptr;
 use std::slice;
 use std::str;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use class::{ClassDefId, ClassSize};
+use class::{ClassDef, ClassDefId, ClassSize};
 use ctxt::SemContext;
 use gc::Address;
 use gc::root::IndirectObj;
@@ -86,7 +87,9 @@ impl Obj {
 
             ClassSize::ObjArray => determine_array_size(self, mem::ptr_width()),
 
-            ClassSize::Array(element_size) => determine_array_size(self, element_size),
+            ClassSize::Array(element_size) | ClassSize::StructArray(element_size) => {
+                determine_array_size(self, element_size)
+            }
 
             ClassSize::Str => {
                 let handle: Handle<Str> = Handle {
@@ -120,6 +123,11 @@ impl Obj {
             return;
         }
 
+        if let ClassSize::StructArray(element_size) = cls.size {
+            visit_struct_array_elements(self, cls, element_size, f);
+            return;
+        }
+
         for &offset in &cls.ref_fields {
             let obj = (self as *mut Obj as usize) + offset as usize;
             f(obj.into());
@@ -152,6 +160,11 @@ impl Obj {
             return;
         }
 
+        if let ClassSize::StructArray(element_size) = cls.size {
+            visit_struct_array_elements(self, cls, element_size, f);
+            return;
+        }
+
         // visit the whole object all the time
         for &offset in &cls.ref_fields {
             let obj = (self as *mut Obj as usize) + offset as usize;
@@ -170,6 +183,26 @@ impl Obj {
     }
 }
 
+// struct elements are embedded into the array, `ref_fields` stores
+// the offsets of references relative to the start of each element
+fn visit_struct_array_elements<F>(obj: &mut Obj, cls: &ClassDef, element_size: i32, mut f: F)
+where
+    F: FnMut(IndirectObj),
+{
+    let array = unsafe { &*(obj as *const _ as *const ByteArray) };
+
+    let mut element = array.data() as usize;
+    let last = element + array.len() * element_size as usize;
+
+    while element < last {
+        for &offset in &cls.ref_fields {
+            f((element + offset as usize).into());
+        }
+
+        element += element_size as usize;
+    }
+}
+
 fn determine_array_size(obj: &Obj, element_size: i32) -> usize {
     let handle: Handle<ByteArray> = Handle {
         ptr: obj as *const Obj as *const ByteArray,
@@ -367,6 +400,29 @@ impl Str {
         handle
     }
 
+    pub fn concat_all(ctxt: &SemContext, parts: &[Rooted<Str>]) -> Rooted<Str> {
+        let len = parts.iter().map(|part| part.len()).sum();
+        let mut handle = ctxt.handles.root(str_alloc_heap(ctxt, len));
+
+        unsafe {
+            handle.length = len;
+
+            let mut offset = 0;
+
+            for part in parts {
+                ptr::copy_nonoverlapping(
+                    part.data(),
+                    handle.data().offset(offset as isize) as *mut u8,
+                    part.len(),
+                );
+
+                offset += part.len();
+            }
+        }
+
+        handle
+    }
+
     // duplicate string into a new object
     pub fn dup(&self, ctxt: &SemContext) -> Handle<Str> {
         let len = self.len();
@@ -546,6 +602,13 @@ pub fn alloc(ctxt: &SemContext, clsid: ClassDefId) -> Handle<Obj> {
     let size = mem::align_usize(size, mem::ptr_width() as usize);
 
     let ptr = ctxt.gc.alloc(ctxt, size, false) as usize;
+
+    // fields are initialized one by one and the GC might run in between,
+    // so reference fields must not contain garbage
+    unsafe {
+        ptr::write_bytes(ptr as *mut u8, 0, size);
+    }
+
     let vtable: *const VTable = &**cls_def.vtable.as_ref().unwrap();
     let mut handle: Handle<Obj> = ptr.into();
     handle.header_mut().vtable = vtable as *mut VTable;
@@ -565,3 +628,29 @@ pub struct StackTraceElement {
     pub name: Handle<Str>,
     pub line: i32,
 }
+
+pub struct Thread {
+    pub header: Header,
+    pub handle: usize,
+}
+
+impl Thread {
+    // the handle is shared by all dora threads that reference this object,
+    // only one of them may take the handle out to join the thread
+    fn handle_atomic(&self) -> &AtomicUsize {
+        unsafe { &*(&self.handle as *const usize as *const AtomicUsize) }
+    }
+
+    pub fn set_handle(&self, handle: usize) {
+        self.handle_atomic().store(handle, Ordering::SeqCst);
+    }
+
+    pub fn take_handle(&self) -> usize {
+        self.handle_atomic().swap(0, Ordering::SeqCst)
+    }
+}
+
+pub struct SpawnThread {
+    pub thread: Thread,
+    pub fct: Handle<Obj>,
+}
@@ -6,7 +6,7 @@ use libc;
 
 use baseline::fct::JitFct;
 use class::TypeParams;
-use ctxt::{Fct, FctKind, FctParent, FctSrc, IdentType, Intrinsic, SemContext, VarId};
+use ctxt::{Fct, FctKind, FctSrc, IdentType, Intrinsic, SemContext, VarId};
 use opt::fct::JitOptFct;
 use ty::{BuiltinType, MachineMode};
 
@@ -275,10 +275,11 @@ where
             StmtContinue(_) => fail(),
             StmtBlock(ref stmt) => self.emit_block(stmt),
             StmtVar(ref stmt) => self.emit_var(stmt),
+            StmtVarTuple(_) => fail(),
             StmtThrow(_) => fail(),
             StmtDefer(_) => fail(),
             StmtDo(_) => fail(),
-            StmtSpawn(_) => fail(),
+            StmtError(_) => fail(),
         }
     }
 
@@ -425,6 +426,7 @@ where
             ExprLitFloat(ref lit) => self.emit_lit_float(lit),
             ExprLitBool(ref lit) => self.emit_lit_bool(lit),
             ExprLitStr(_) => fail(),
+            ExprTemplate(_) => fail(),
             ExprLitStruct(_) => fail(),
             ExprUn(_) => fail(),
             ExprIdent(ref ident) => self.emit_ident(ident),
@@ -440,6 +442,11 @@ where
             ExprConv(_) => fail(),
             ExprTry(_) => fail(),
             ExprLambda(_) => fail(),
+            ExprSpawn(_) => fail(),
+            ExprPath(_) => fail(),
+            ExprMatch(_) => fail(),
+            ExprTuple(_) => fail(),
+            ExprTupleElement(_) => fail(),
         }
     }
 
@@ -455,8 +462,9 @@ where
         unsafe {
             let ty = match e.suffix {
                 IntSuffix::Byte => LLVMInt8TypeInContext(self.context),
-                IntSuffix::Int => LLVMInt32TypeInContext(self.context),
-                IntSuffix::Long => LLVMInt64TypeInContext(self.context),
+                IntSuffix::Short => LLVMInt16TypeInContext(self.context),
+                IntSuffix::Int | IntSuffix::UInt => LLVMInt32TypeInContext(self.context),
+                IntSuffix::Long | IntSuffix::ULong => LLVMInt64TypeInContext(self.context),
             };
 
             let value = LLVMConstInt(ty, e.value, 0);
@@ -704,7 +712,7 @@ where
     fn specialize_type(&self, ty: BuiltinType) -> BuiltinType {
         match ty {
             BuiltinType::ClassTypeParam(cls_id, id) => {
-                assert!(self.fct.parent == FctParent::Class(cls_id));
+                assert!(self.fct.is_method_of(self.ctxt, cls_id));
                 self.cls_type_params[id.idx()]
             }
 
@@ -725,6 +733,11 @@ where
 
             BuiltinType::Lambda(_) => unimplemented!(),
 
+            BuiltinType::Nullable(_) => {
+                let ty = self.specialize_type(ty.non_nullable(self.ctxt));
+                BuiltinType::nullable(self.ctxt, ty)
+            }
+
             _ => ty,
         }
     }
@@ -758,6 +771,7 @@ where
                 MachineMode::Int64 => LLVMInt64TypeInContext(self.context),
                 MachineMode::Int32 => LLVMInt32TypeInContext(self.context),
                 MachineMode::Int8 => LLVMInt8TypeInContext(self.context),
+                MachineMode::Int16 => LLVMInt16TypeInContext(self.context),
                 MachineMode::Float32 => LLVMFloatTypeInContext(self.context),
                 MachineMode::Float64 => LLVMDoubleTypeInContext(self.context),
                 MachineMode::Ptr => {
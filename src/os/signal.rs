@@ -8,8 +8,11 @@ use baseline::map::CodeData;
 use class::TypeParams;
 use cpu;
 use ctxt::{get_ctxt, FctId, SemContext, CTXT};
-use exception::{handle_exception, stacktrace_from_es};
+use driver::cmd::ErrorFormat;
+use driver::json;
+use exception::{handle_exception, handle_trap, stacktrace_from_es, stacktrace_from_exception};
 use execstate::ExecState;
+use globals;
 use object::{Handle, Obj};
 use os_cpu::*;
 use safepoint;
@@ -90,91 +93,53 @@ fn handler(signo: libc::c_int, info: *const siginfo_t, ucontext: *const u8) {
         match trap {
             Trap::COMPILER => compile_request(ctxt, &mut es, ucontext),
 
-            Trap::DIV0 => {
-                println!("division by 0");
-                let stacktrace = stacktrace_from_es(ctxt, &es);
-                stacktrace.dump(ctxt);
-                unsafe {
-                    libc::_exit(101);
-                }
+            Trap::DIV0 | Trap::INDEX_OUT_OF_BOUNDS | Trap::NIL | Trap::CAST | Trap::OOM => {
+                throw_runtime_exception(ctxt, &mut es, ucontext, trap)
             }
 
             Trap::ASSERT => {
-                println!("assert failed");
-                let stacktrace = stacktrace_from_es(ctxt, &es);
-                stacktrace.dump(ctxt);
+                report_runtime_error(ctxt, &es, "AssertFailed", "assert failed");
                 unsafe {
                     libc::_exit(101);
                 }
             }
 
-            Trap::INDEX_OUT_OF_BOUNDS => {
-                println!("array index out of bounds");
-                let stacktrace = stacktrace_from_es(ctxt, &es);
-                stacktrace.dump(ctxt);
-                unsafe {
-                    libc::_exit(102);
-                }
-            }
-
-            Trap::NIL => {
-                println!("nil check failed");
-                let stacktrace = stacktrace_from_es(ctxt, &es);
-                stacktrace.dump(ctxt);
-                unsafe {
-                    libc::_exit(103);
-                }
-            }
-
             Trap::THROW => {
                 let handler_found = handle_exception(&mut es);
 
                 if handler_found {
                     write_execstate(&es, ucontext as *mut u8);
                 } else {
-                    println!("uncaught exception");
+                    let msg = globals::uncaught_message(ctxt, "uncaught exception");
+
+                    if ctxt.args.error_format() == ErrorFormat::Json {
+                        let exception = cpu::get_exception_object(&es);
+                        let stacktrace = stacktrace_from_exception(ctxt, exception);
+                        println!(
+                            "{}",
+                            json::runtime_error(ctxt, "UncaughtException", &msg, &stacktrace)
+                        );
+                    } else {
+                        println!("{}", msg);
+                    }
+
                     unsafe {
                         libc::_exit(104);
                     }
                 }
             }
 
-            Trap::CAST => {
-                println!("cast failed");
-                let stacktrace = stacktrace_from_es(ctxt, &es);
-                stacktrace.dump(ctxt);
-                unsafe {
-                    libc::_exit(105);
-                }
-            }
-
             Trap::UNEXPECTED => {
-                println!("unexpected exception");
-                let stacktrace = stacktrace_from_es(ctxt, &es);
-                stacktrace.dump(ctxt);
+                report_runtime_error(ctxt, &es, "UnexpectedException", "unexpected exception");
                 unsafe {
                     libc::_exit(106);
                 }
             }
-
-            Trap::OOM => {
-                println!("out of memory");
-                let stacktrace = stacktrace_from_es(ctxt, &es);
-                stacktrace.dump(ctxt);
-                unsafe {
-                    libc::_exit(107);
-                }
-            }
         }
 
     // is this is a failed nil check?
     } else if detect_nil_check(ctxt, es.pc) {
-        println!("nil check failed");
-        let stacktrace = stacktrace_from_es(ctxt, &es);
-        stacktrace.dump(ctxt);
-        unsafe {
-            libc::_exit(103);
-        }
+        throw_runtime_exception(ctxt, &mut es, ucontext, Trap::NIL);
     } else if detect_polling_page_check(ctxt, signo, addr) {
         // polling page read failed => enter safepoint
         safepoint::enter(&es);
@@ -200,6 +165,59 @@ fn handler(signo: libc::c_int, info: *const siginfo_t, ucontext: *const u8) {
     }
 }
 
+fn throw_runtime_exception(ctxt: &SemContext, es: &mut ExecState, ucontext: *const u8, trap: Trap) {
+    let (cls_id, msg, error, code) = match trap {
+        Trap::DIV0 => (
+            ctxt.vips.arithmetic_exception_class,
+            "division by 0",
+            "DivisionByZero",
+            101,
+        ),
+        Trap::INDEX_OUT_OF_BOUNDS => (
+            ctxt.vips.index_out_of_bounds_exception_class,
+            "array index out of bounds",
+            "IndexOutOfBounds",
+            102,
+        ),
+        Trap::NIL => (
+            ctxt.vips.nil_pointer_exception_class,
+            "nil check failed",
+            "NilCheckFailed",
+            103,
+        ),
+        Trap::CAST => (ctxt.vips.cast_exception_class, "cast failed", "CastFailed", 105),
+        Trap::OOM => (
+            ctxt.vips.out_of_memory_error_class,
+            "out of memory",
+            "OutOfMemory",
+            107,
+        ),
+        _ => unreachable!(),
+    };
+
+    if handle_trap(es, cls_id, msg) {
+        write_execstate(es, ucontext as *mut u8);
+    } else {
+        let msg = globals::uncaught_message(ctxt, msg);
+        report_runtime_error(ctxt, es, error, &msg);
+        unsafe {
+            libc::_exit(code);
+        }
+    }
+}
+
+// prints message and stack trace of a runtime error without handler
+fn report_runtime_error(ctxt: &SemContext, es: &ExecState, code: &str, msg: &str) {
+    let stacktrace = stacktrace_from_es(ctxt, es);
+
+    if ctxt.args.error_format() == ErrorFormat::Json {
+        println!("{}", json::runtime_error(ctxt, code, msg, &stacktrace));
+    } else {
+        println!("{}", msg);
+        stacktrace.dump(ctxt);
+    }
+}
+
 fn detect_nil_check(ctxt: &SemContext, pc: usize) -> bool {
     let code_map = ctxt.code_map.lock().unwrap();
 
@@ -0,0 +1,104 @@
+use std::collections::HashSet;
+
+use ctxt::{SemContext, TraitId};
+use dora_parser::error::msg::Msg;
+use semck;
+use sym::Sym::SymAlias;
+use ty::BuiltinType;
+
+pub fn check<'ast>(ctxt: &SemContext<'ast>) {
+    for alias in ctxt.aliases.iter() {
+        let alias = alias.borrow();
+        let mut names = HashSet::new();
+
+        for param in alias.type_params() {
+            if !names.insert(param.name) {
+                let name = ctxt.interner.str(param.name).to_string();
+                let msg = Msg::TypeParamNameNotUnique(name);
+                ctxt.diag.borrow_mut().report(param.pos, msg);
+            }
+
+            for bound in &param.bounds {
+                ctxt.diag.borrow_mut().report_unimplemented(bound.pos());
+            }
+        }
+
+        ctxt.sym.borrow_mut().enter_module(alias.module);
+        ctxt.sym.borrow_mut().push_level();
+
+        if let Some(trait_id) = alias.trait_id {
+            add_trait_aliases(ctxt, trait_id);
+        }
+
+        // type params are not known here, check the aliased type
+        // with placeholders to report unknown types only once
+        let args = vec![BuiltinType::Error; alias.type_params().len()];
+        semck::expand_alias(ctxt, &*alias, &args, alias.pos);
+
+        ctxt.sym.borrow_mut().pop_level();
+    }
+}
+
+pub fn add_trait_aliases<'ast>(ctxt: &SemContext<'ast>, trait_id: TraitId) {
+    let xtrait = ctxt.traits[trait_id].borrow();
+    let mut sym = ctxt.sym.borrow_mut();
+
+    for &alias_id in &xtrait.aliases {
+        let alias = ctxt.aliases[alias_id].borrow();
+        sym.insert(alias.name, SymAlias(alias_id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dora_parser::error::msg::Msg;
+    use semck::tests::*;
+
+    #[test]
+    fn alias_unknown_type() {
+        err(
+            "type Foo = Unknown;",
+            pos(1, 12),
+            Msg::UnknownType("Unknown".into()),
+        );
+        err(
+            "type Foo<T> = Array<Unknown>;",
+            pos(1, 21),
+            Msg::UnknownType("Unknown".into()),
+        );
+    }
+
+    #[test]
+    fn alias_recursive() {
+        err(
+            "type Foo = Foo;",
+            pos(1, 12),
+            Msg::RecursiveAlias("Foo".into()),
+        );
+        err(
+            "type Foo = Bar; type Bar = Array<Foo>;",
+            pos(1, 34),
+            Msg::RecursiveAlias("Foo".into()),
+        );
+    }
+
+    #[test]
+    fn alias_type_params() {
+        ok("type Foo<T> = Array<T>; fun f(x: Foo<int>) -> Array<int> { return x; }");
+        err(
+            "type Foo<T, T> = Array<T>;",
+            pos(1, 13),
+            Msg::TypeParamNameNotUnique("T".into()),
+        );
+        err(
+            "type Foo<T> = Array<T>; fun f(x: Foo) {}",
+            pos(1, 34),
+            Msg::WrongNumberTypeParams(1, 0),
+        );
+        err(
+            "type Foo = int; fun f(x: Foo<int>) {}",
+            pos(1, 26),
+            Msg::NoTypeParamsExpected,
+        );
+    }
+}
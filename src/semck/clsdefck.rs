@@ -6,7 +6,6 @@ use dora_parser::ast::visit::{self, Visitor};
 use class::*;
 use ctxt::{Fct, FctId, FctKind, FctParent, FctSrc, NodeMap, SemContext};
 use dora_parser::error::msg::Msg;
-use dora_parser::interner::Name;
 use dora_parser::lexer::position::Position;
 use semck;
 use sym::Sym;
@@ -36,22 +35,25 @@ impl<'x, 'ast> ClsCheck<'x, 'ast> {
         self.visit_ast(self.ast);
     }
 
-    fn add_field(&mut self, pos: Position, name: Name, ty: BuiltinType, reassignable: bool) {
+    fn add_field(&mut self, f: &ast::Field, ty: BuiltinType) {
         let mut cls = self.ctxt.classes[self.cls_id.unwrap()].borrow_mut();
 
         for field in &cls.fields {
-            if field.name == name {
-                let name = self.ctxt.interner.str(name).to_string();
-                report(self.ctxt, pos, Msg::ShadowField(name));
+            if field.name == f.name {
+                let name = self.ctxt.interner.str(f.name).to_string();
+                report(self.ctxt, f.pos, Msg::ShadowField(name));
             }
         }
 
         let field = Field {
             id: cls.fields.len().into(),
-            name: name,
+            pos: f.pos,
+            name: f.name,
             ty: ty,
             offset: 0,
-            reassignable: reassignable,
+            reassignable: f.reassignable,
+            is_pub: f.is_pub,
+            alias: semck::alias_name(self.ctxt, &f.data_type),
         };
 
         cls.fields.push(field);
@@ -59,6 +61,13 @@ impl<'x, 'ast> ClsCheck<'x, 'ast> {
 }
 
 impl<'x, 'ast> Visitor<'ast> for ClsCheck<'x, 'ast> {
+    fn visit_file(&mut self, f: &'ast ast::File) {
+        let module = self.ctxt.file_module(f);
+        self.ctxt.sym.borrow_mut().enter_module(module);
+
+        visit::walk_file(self, f);
+    }
+
     fn visit_class(&mut self, c: &'ast ast::Class) {
         self.cls_id = Some(*self.map_cls_defs.get(c.id).unwrap());
 
@@ -93,8 +102,11 @@ impl<'x, 'ast> Visitor<'ast> for ClsCheck<'x, 'ast> {
                                 }
                             }
 
-                            Some(BuiltinType::Trait(trait_id)) => {
-                                if !cls.type_params[type_param_id].trait_bounds.insert(trait_id) {
+                            Some(BuiltinType::Trait(trait_id, list_id)) => {
+                                let params = self.ctxt.lists.borrow().get(list_id);
+                                let bounds = &mut cls.type_params[type_param_id].trait_bounds;
+
+                                if bounds.insert(trait_id, params).is_some() {
                                     let msg = Msg::DuplicateTraitBound;
                                     self.ctxt.diag.borrow_mut().report(type_param.pos, msg);
                                 }
@@ -179,7 +191,7 @@ impl<'x, 'ast> Visitor<'ast> for ClsCheck<'x, 'ast> {
 
     fn visit_field(&mut self, f: &'ast ast::Field) {
         let ty = semck::read_type(self.ctxt, &f.data_type).unwrap_or(BuiltinType::Unit);
-        self.add_field(f.pos, f.name, ty, f.reassignable);
+        self.add_field(f, ty);
 
         if !f.reassignable && !f.primary_ctor && f.expr.is_none() {
             self.ctxt
@@ -203,6 +215,7 @@ impl<'x, 'ast> Visitor<'ast> for ClsCheck<'x, 'ast> {
             pos: f.pos,
             ast: f,
             name: f.name,
+            module: self.ctxt.classes[self.cls_id.unwrap()].borrow().module,
             param_types: Vec::new(),
             return_type: BuiltinType::Unit,
             parent: FctParent::Class(clsid),
@@ -220,6 +233,7 @@ impl<'x, 'ast> Visitor<'ast> for ClsCheck<'x, 'ast> {
             vtable_index: None,
             initialized: false,
             impl_for: None,
+            is_default: false,
 
             type_params: Vec::new(),
             kind: kind,
@@ -247,6 +261,7 @@ impl<'x, 'ast> Visitor<'ast> for ClsCheck<'x, 'ast> {
             ast: f,
             pos: f.pos,
             name: f.name,
+            module: self.ctxt.classes[self.cls_id.unwrap()].borrow().module,
             param_types: Vec::new(),
             return_type: BuiltinType::Unit,
             parent: FctParent::Class(self.cls_id.unwrap()),
@@ -267,6 +282,7 @@ impl<'x, 'ast> Visitor<'ast> for ClsCheck<'x, 'ast> {
             vtable_index: None,
             initialized: false,
             impl_for: None,
+            is_default: false,
 
             type_params: Vec::new(),
             kind: kind,
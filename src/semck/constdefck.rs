@@ -1,5 +1,5 @@
 use dora_parser::ast;
-use dora_parser::ast::visit::Visitor;
+use dora_parser::ast::visit::{self, Visitor};
 use ctxt::{ConstId, NodeMap, SemContext};
 use dora_parser::error::msg::Msg;
 use dora_parser::lexer::position::Position;
@@ -32,6 +32,13 @@ impl<'x, 'ast> ConstCheck<'x, 'ast> {
 }
 
 impl<'x, 'ast> Visitor<'ast> for ConstCheck<'x, 'ast> {
+    fn visit_file(&mut self, f: &'ast ast::File) {
+        let module = self.ctxt.file_module(f);
+        self.ctxt.sym.borrow_mut().enter_module(module);
+
+        visit::walk_file(self, f);
+    }
+
     fn visit_const(&mut self, c: &'ast ast::Const) {
         let const_id = *self.map_const_defs.get(c.id).unwrap();
 
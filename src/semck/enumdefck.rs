@@ -0,0 +1,90 @@
+use std::cell::RefCell;
+
+use dora_parser::ast;
+use dora_parser::ast::visit::{self, Visitor};
+use ctxt::{EnumId, EnumVariantData, NodeMap, SemContext};
+use dora_parser::error::msg::Msg;
+use semck;
+use ty::BuiltinType;
+
+pub fn check<'ast>(ctxt: &mut SemContext<'ast>, map_enum_defs: &NodeMap<EnumId>) {
+    let mut enumck = EnumCheck {
+        ctxt: ctxt,
+        ast: ctxt.ast,
+        map_enum_defs: map_enum_defs,
+    };
+
+    enumck.check();
+}
+
+struct EnumCheck<'x, 'ast: 'x> {
+    ctxt: &'x mut SemContext<'ast>,
+    ast: &'ast ast::Ast,
+    map_enum_defs: &'x NodeMap<EnumId>,
+}
+
+impl<'x, 'ast> EnumCheck<'x, 'ast> {
+    fn check(&mut self) {
+        self.visit_ast(self.ast);
+    }
+}
+
+impl<'x, 'ast> Visitor<'ast> for EnumCheck<'x, 'ast> {
+    fn visit_file(&mut self, f: &'ast ast::File) {
+        let module = self.ctxt.file_module(f);
+        self.ctxt.sym.borrow_mut().enter_module(module);
+
+        visit::walk_file(self, f);
+    }
+
+    fn visit_enum(&mut self, e: &'ast ast::Enum) {
+        let id = *self.map_enum_defs.get(e.id).unwrap();
+
+        for variant in &e.variants {
+            let types = variant
+                .types
+                .iter()
+                .map(|ty| semck::read_type(self.ctxt, ty).unwrap_or(BuiltinType::Error))
+                .collect::<Vec<_>>();
+
+            let mut xenum = self.ctxt.enums[id].borrow_mut();
+
+            if xenum.find_variant(variant.name).is_some() {
+                let xenum = self.ctxt.interner.str(e.name).to_string();
+                let name = self.ctxt.interner.str(variant.name).to_string();
+                let msg = Msg::ShadowEnumVariant(xenum, name);
+                self.ctxt.diag.borrow_mut().report(variant.pos, msg);
+                continue;
+            }
+
+            xenum.variants.push(EnumVariantData {
+                pos: variant.pos,
+                name: variant.name,
+                types: types,
+                cls_def: RefCell::new(None),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dora_parser::error::msg::Msg;
+    use semck::tests::*;
+
+    #[test]
+    fn enum_variants() {
+        ok("enum Foo { A, B(int), C(Str, Foo) }");
+        ok("struct Bar { a: int } enum Foo { A(Bar) }");
+        err(
+            "enum Foo { A(Unknown) }",
+            pos(1, 14),
+            Msg::UnknownType("Unknown".into()),
+        );
+        err(
+            "enum Foo { A, B, A(int) }",
+            pos(1, 18),
+            Msg::ShadowEnumVariant("Foo".into(), "A".into()),
+        );
+    }
+}
@@ -3,9 +3,12 @@ use std::collections::HashSet;
 use dora_parser::ast::*;
 use dora_parser::ast::Stmt::*;
 use dora_parser::ast::visit::*;
+use class::TypeParams;
 use ctxt::{self, Fct, FctId, FctParent, FctSrc, SemContext};
 use dora_parser::error::msg::Msg;
 use semck;
+use semck::aliasdefck;
+use semck::specialize::specialize_self;
 use sym::Sym;
 use ty::BuiltinType;
 
@@ -20,10 +23,11 @@ pub fn check<'a, 'ast>(ctxt: &SemContext<'ast>) {
         check_abstract(ctxt, &*fct);
         check_static(ctxt, &*fct);
 
-        if !(fct.is_src() || fct.kind.is_definition()) {
+        if !(fct.is_src() || fct.kind.is_definition()) || fct.skip_default_check(ctxt) {
             continue;
         }
 
+        ctxt.sym.borrow_mut().enter_module(fct.module);
         ctxt.sym.borrow_mut().push_level();
 
         match fct.parent {
@@ -46,18 +50,44 @@ pub fn check<'a, 'ast>(ctxt: &SemContext<'ast>) {
                 let ximpl = ctxt.impls[impl_id].borrow();
                 let cls = ctxt.classes[ximpl.cls_id()].borrow();
 
+                // type params of the impl are the type params of the class
+                for (id, type_param) in ximpl.type_params.iter().enumerate() {
+                    let sym = Sym::SymClassTypeParam(cls.id, id.into());
+                    ctxt.sym.borrow_mut().insert(*type_param, sym);
+                }
+
+                // default methods refer to the type params of their trait
+                if fct.is_default {
+                    let xtrait = ctxt.traits[ximpl.trait_id()].borrow();
+                    let type_params = xtrait.type_params.iter();
+
+                    for (type_param, ty) in type_params.zip(ximpl.trait_type_params.iter()) {
+                        let sym = Sym::SymAliasTypeParam(ty);
+                        ctxt.sym.borrow_mut().insert(type_param.name, sym);
+                    }
+                }
+
                 if fct.has_self() {
                     fct.param_types.push(cls.ty);
                 }
             }
 
-            FctParent::Trait(_) => {
+            FctParent::Trait(trait_id) => {
+                aliasdefck::add_trait_aliases(ctxt, trait_id);
+
+                let xtrait = ctxt.traits[trait_id].borrow();
+
+                for (id, type_param) in xtrait.type_params.iter().enumerate() {
+                    let sym = Sym::SymTraitTypeParam(trait_id, id.into());
+                    ctxt.sym.borrow_mut().insert(type_param.name, sym);
+                }
+
                 if fct.has_self() {
                     fct.param_types.push(BuiltinType::This);
                 }
             }
 
-            FctParent::None => {}
+            FctParent::Fct(_) | FctParent::None => {}
         }
 
         if let Some(ref type_params) = ast.type_params {
@@ -87,8 +117,11 @@ pub fn check<'a, 'ast>(ctxt: &SemContext<'ast>) {
                                 }
                             }
 
-                            Some(BuiltinType::Trait(trait_id)) => {
-                                if !fct.type_params[type_param_id].trait_bounds.insert(trait_id) {
+                            Some(BuiltinType::Trait(trait_id, list_id)) => {
+                                let params = ctxt.lists.borrow().get(list_id);
+                                let bounds = &mut fct.type_params[type_param_id].trait_bounds;
+
+                                if bounds.insert(trait_id, params).is_some() {
                                     let msg = Msg::DuplicateTraitBound;
                                     ctxt.diag.borrow_mut().report(type_param.pos, msg);
                                 }
@@ -115,8 +148,9 @@ pub fn check<'a, 'ast>(ctxt: &SemContext<'ast>) {
             }
         }
 
-        for p in &ast.params {
+        for (ind, p) in ast.params.iter().enumerate() {
             let ty = semck::read_type(ctxt, &p.data_type).unwrap_or(BuiltinType::Unit);
+            let ty = default_self(ctxt, &*fct, ty);
 
             if ty == BuiltinType::This && !fct.in_trait() {
                 ctxt.diag
@@ -124,6 +158,18 @@ pub fn check<'a, 'ast>(ctxt: &SemContext<'ast>) {
                     .report(p.data_type.pos(), Msg::SelfTypeUnavailable);
             }
 
+            let ty = if p.variadic {
+                if ind + 1 != ast.params.len() {
+                    ctxt.diag
+                        .borrow_mut()
+                        .report(p.pos, Msg::VariadicParameterNeedsToBeLast);
+                }
+
+                ctxt.vips.array_ty(ctxt, ty)
+            } else {
+                ty
+            };
+
             fct.param_types.push(ty);
 
             if fct.is_src() {
@@ -137,6 +183,7 @@ pub fn check<'a, 'ast>(ctxt: &SemContext<'ast>) {
 
         if let Some(ret) = ast.return_type.as_ref() {
             let ty = semck::read_type(ctxt, ret).unwrap_or(BuiltinType::Unit);
+            let ty = default_self(ctxt, &*fct, ty);
 
             if ty == BuiltinType::This && !fct.in_trait() {
                 ctxt.diag
@@ -157,13 +204,15 @@ pub fn check<'a, 'ast>(ctxt: &SemContext<'ast>) {
 
             FctParent::Trait(traitid) => {
                 let xtrait = ctxt.traits[traitid].borrow();
-                let ty = BuiltinType::Trait(traitid);
+                let list_id = ctxt.lists.borrow_mut().insert(TypeParams::empty());
+                let ty = BuiltinType::Trait(traitid, list_id);
                 check_against_methods(ctxt, ty, &*fct, &xtrait.methods);
             }
 
             FctParent::Impl(implid) => {
                 let ximpl = ctxt.impls[implid].borrow();
-                let ty = BuiltinType::Trait(ximpl.trait_id());
+                let list_id = ctxt.lists.borrow_mut().insert(ximpl.trait_type_params.clone());
+                let ty = BuiltinType::Trait(ximpl.trait_id(), list_id);
                 check_against_methods(ctxt, ty, &*fct, &ximpl.methods);
             }
 
@@ -193,6 +242,23 @@ pub fn check<'a, 'ast>(ctxt: &SemContext<'ast>) {
     debug_assert!(ctxt.sym.borrow().levels() == 1);
 }
 
+// `Self` in a copied default method is the class of the impl
+fn default_self<'ast>(ctxt: &SemContext<'ast>, fct: &Fct<'ast>, ty: BuiltinType) -> BuiltinType {
+    if !fct.is_default {
+        return ty;
+    }
+
+    let impl_id = match fct.parent {
+        FctParent::Impl(impl_id) => impl_id,
+        _ => unreachable!(),
+    };
+
+    let ximpl = ctxt.impls[impl_id].borrow();
+    let cls_ty = ctxt.classes[ximpl.cls_id()].borrow().ty;
+
+    specialize_self(ctxt, ty, cls_ty)
+}
+
 fn check_abstract<'ast>(ctxt: &SemContext<'ast>, fct: &Fct<'ast>) {
     if !fct.is_abstract {
         return;
@@ -264,6 +330,37 @@ impl<'a, 'ast> FctDefCheck<'a, 'ast> {
     fn check(&mut self) {
         self.visit_fct(self.ast);
     }
+
+    fn check_unreachable_catches(&mut self, try: &'ast StmtDoType) {
+        for (ind, catch) in try.catch_blocks.iter().enumerate() {
+            let ty = self.src.ty(catch.data_type.id());
+
+            if ty.is_error() {
+                continue;
+            }
+
+            for prev in &try.catch_blocks[..ind] {
+                let prev_ty = self.src.ty(prev.data_type.id());
+
+                // `Foo<A>` must not shadow `Foo<B>`, so only non-generic
+                // classes catch their subclasses.
+                let caught = ty == prev_ty
+                    || (prev_ty.type_params(self.ctxt).len() == 0
+                        && ty.subclass_from(self.ctxt, prev_ty));
+
+                if caught {
+                    let name = ty.name(self.ctxt);
+                    let prev_name = prev_ty.name(self.ctxt);
+                    let msg = Msg::UnreachableCatch(name, prev_name);
+                    self.ctxt
+                        .diag
+                        .borrow_mut()
+                        .report(catch.data_type.pos(), msg);
+                    break;
+                }
+            }
+        }
+    }
 }
 
 impl<'a, 'ast> Visitor<'ast> for FctDefCheck<'a, 'ast> {
@@ -282,7 +379,7 @@ impl<'a, 'ast> Visitor<'ast> for FctDefCheck<'a, 'ast> {
                 }
 
                 if let Some(ref expr) = var.expr {
-                    visit::walk_expr(self, expr);
+                    self.visit_expr(expr);
                 }
             }
 
@@ -304,6 +401,8 @@ impl<'a, 'ast> Visitor<'ast> for FctDefCheck<'a, 'ast> {
                     }
                 }
 
+                self.check_unreachable_catches(try);
+
                 if try.catch_blocks.is_empty() && try.finally_block.is_none() {
                     self.ctxt
                         .diag
@@ -316,6 +415,19 @@ impl<'a, 'ast> Visitor<'ast> for FctDefCheck<'a, 'ast> {
         }
     }
 
+    fn visit_expr(&mut self, e: &'ast Expr) {
+        visit::walk_expr(self, e);
+
+        if let Expr::ExprLambda(ref lambda) = *e {
+            for p in &lambda.fct.params {
+                let ty = self.src.ty(p.data_type.id());
+
+                let var = *self.src.map_vars.get(p.id).unwrap();
+                self.src.vars[var].ty = ty;
+            }
+        }
+    }
+
     fn visit_type(&mut self, t: &'ast Type) {
         self.current_type = semck::read_type(self.ctxt, t).unwrap_or(BuiltinType::Unit);
         self.src.set_ty(t.id(), self.current_type);
@@ -9,7 +9,7 @@ pub fn check<'ast>(ctxt: &SemContext<'ast>) {
     for fct in ctxt.fcts.iter() {
         let fct = fct.borrow();
 
-        if !fct.is_src() {
+        if !fct.is_src() || fct.skip_default_check(ctxt) {
             continue;
         }
 
@@ -72,6 +72,15 @@ impl<'a, 'ast> Visitor<'ast> for FlowCheck<'a, 'ast> {
             _ => visit::walk_stmt(self, s),
         }
     }
+
+    fn visit_expr(&mut self, e: &'ast Expr) {
+        match *e {
+            // lambdas and spawn blocks are checked on their own
+            Expr::ExprLambda(_) | Expr::ExprSpawn(_) => {}
+
+            _ => visit::walk_expr(self, e),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1,5 +1,5 @@
-use std::cell::RefCell;
-use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
 use std::ptr;
 
 use dora_parser::ast::*;
@@ -7,16 +7,18 @@ use dora_parser::ast::visit::*;
 use dora_parser::error::msg::Msg;
 use dora_parser::interner::Name;
 use dora_parser::lexer::position::Position;
-use class::{self, ClassId};
+use class::{self, ClassId, TypeParams};
 use ctxt;
 use ctxt::*;
-use sym::Sym::{self, SymClass, SymConst, SymFct, SymGlobal, SymStruct, SymTrait};
+use sym::Sym::{self, SymAlias, SymClass, SymConst, SymEnum, SymFct, SymGlobal, SymStruct,
+                SymTrait};
 use ty::BuiltinType;
 
 pub fn check<'ast>(
     ctxt: &mut SemContext<'ast>,
     map_cls_defs: &mut NodeMap<ClassId>,
     map_struct_defs: &mut NodeMap<StructId>,
+    map_enum_defs: &mut NodeMap<EnumId>,
     map_trait_defs: &mut NodeMap<TraitId>,
     map_impl_defs: &mut NodeMap<ImplId>,
     map_global_defs: &mut NodeMap<GlobalId>,
@@ -26,47 +28,152 @@ pub fn check<'ast>(
         ctxt: ctxt,
         map_cls_defs: map_cls_defs,
         map_struct_defs: map_struct_defs,
+        map_enum_defs: map_enum_defs,
         map_trait_defs: map_trait_defs,
         map_impl_defs: map_impl_defs,
         map_global_defs: map_global_defs,
         map_const_defs: map_const_defs,
+        module: ModuleId::root(),
     };
 
     gdef.visit_ast(ctxt.ast);
+
+    let std_name = ctxt.interner.intern("std");
+
+    if let Some(std) = ctxt.find_module(&[std_name]) {
+        ctxt.sym.borrow_mut().set_prelude(std);
+    }
+
+    ctxt.sym.borrow_mut().enter_module(ModuleId::root());
 }
 
 struct GlobalDef<'x, 'ast: 'x> {
     ctxt: &'x mut SemContext<'ast>,
     map_cls_defs: &'x mut NodeMap<ClassId>,
     map_struct_defs: &'x mut NodeMap<StructId>,
+    map_enum_defs: &'x mut NodeMap<EnumId>,
     map_trait_defs: &'x mut NodeMap<TraitId>,
     map_impl_defs: &'x mut NodeMap<ImplId>,
     map_global_defs: &'x mut NodeMap<GlobalId>,
     map_const_defs: &'x mut NodeMap<ConstId>,
+    module: ModuleId,
+}
+
+impl<'x, 'ast> GlobalDef<'x, 'ast> {
+    fn insert(&mut self, name: Name, pos: Position, sym: Sym, is_pub: bool) {
+        let old = self.ctxt.sym.borrow_mut().insert(name, sym.clone());
+
+        if let Some(old) = old {
+            report(self.ctxt, name, pos, old);
+        } else if is_pub {
+            self.ctxt.sym.borrow_mut().export(name, sym);
+        }
+    }
+
+    fn add_alias(&mut self, a: &'ast Alias, trait_id: Option<TraitId>) -> AliasId {
+        let id: AliasId = (self.ctxt.aliases.len() as u32).into();
+        let alias = AliasData {
+            id: id,
+            pos: a.pos,
+            name: a.name,
+            ast: a,
+            module: self.module,
+            trait_id: trait_id,
+            ty: Cell::new(None),
+            expanding: Cell::new(false),
+        };
+
+        self.ctxt.aliases.push(alias);
+
+        id
+    }
 }
 
 impl<'x, 'ast> Visitor<'ast> for GlobalDef<'x, 'ast> {
+    fn visit_file(&mut self, f: &'ast File) {
+        self.module = self.ctxt.add_module(&f.module);
+        self.ctxt.sym.borrow_mut().enter_module(self.module);
+
+        walk_file(self, f);
+    }
+
     fn visit_trait(&mut self, t: &'ast Trait) {
         let id: TraitId = (self.ctxt.traits.len() as u32).into();
-        let xtrait = TraitData {
+        let mut xtrait = TraitData {
             id: id,
+            module: self.module,
             pos: t.pos,
             name: t.name,
+            type_params: Vec::new(),
+            aliases: Vec::new(),
             methods: Vec::new(),
         };
 
+        // bounds are added in traitdefck, but the number of type params
+        // needs to be known before any type is read
+        if let Some(ref type_params) = t.type_params {
+            for type_param in type_params {
+                xtrait.type_params.push(ctxt::TypeParam::new(type_param.name));
+            }
+        }
+
+        // aliases in traits are only visible inside the trait
+        let mut names = HashSet::new();
+
+        for alias in &t.aliases {
+            if !names.insert(alias.name) {
+                let name = self.ctxt.interner.str(alias.name).to_string();
+                let msg = Msg::ShadowAlias(name);
+                self.ctxt.diag.borrow_mut().report(alias.pos, msg);
+            }
+
+            xtrait.aliases.push(self.add_alias(alias, Some(id)));
+        }
+
         self.ctxt.traits.push(RefCell::new(xtrait));
         let sym = SymTrait(id);
 
         self.map_trait_defs.insert(t.id, id);
 
-        if let Some(sym) = self.ctxt.sym.borrow_mut().insert(t.name, sym) {
-            report(self.ctxt, t.name, t.pos, sym);
-        }
+        self.insert(t.name, t.pos, sym, t.is_pub);
     }
 
     fn visit_global(&mut self, g: &'ast Global) {
         let id: GlobalId = (self.ctxt.globals.len() as u32).into();
+
+        let initializer = g.initializer.as_ref().map(|f| {
+            let fct = Fct {
+                id: FctId(0),
+                pos: f.pos,
+                ast: f,
+                name: f.name,
+                module: self.module,
+                param_types: Vec::new(),
+                return_type: BuiltinType::Unit,
+                parent: FctParent::None,
+                has_override: false,
+                has_open: false,
+                has_final: false,
+                is_pub: false,
+                is_static: false,
+                is_abstract: false,
+                internal: false,
+                internal_resolved: false,
+                overrides: None,
+                throws: false,
+                ctor: CtorType::None,
+                vtable_index: None,
+                initialized: false,
+                impl_for: None,
+                is_default: false,
+
+                type_params: Vec::new(),
+                kind: FctKind::Source(RefCell::new(FctSrc::new())),
+            };
+
+            self.ctxt.add_fct(fct)
+        });
+
         let global = GlobalData {
             id: id,
             ast: g,
@@ -74,7 +181,7 @@ impl<'x, 'ast> Visitor<'ast> for GlobalDef<'x, 'ast> {
             name: g.name,
             ty: BuiltinType::Unit,
             reassignable: g.reassignable,
-            getter: None,
+            initializer: initializer,
             address_init: ptr::null(),
             address_value: ptr::null(),
         };
@@ -84,9 +191,7 @@ impl<'x, 'ast> Visitor<'ast> for GlobalDef<'x, 'ast> {
         let sym = SymGlobal(id);
         self.map_global_defs.insert(g.id, id);
 
-        if let Some(sym) = self.ctxt.sym.borrow_mut().insert(g.name, sym) {
-            report(self.ctxt, g.name, g.pos, sym);
-        }
+        self.insert(g.name, g.pos, sym, g.is_pub);
     }
 
     fn visit_impl(&mut self, i: &'ast Impl) {
@@ -94,8 +199,11 @@ impl<'x, 'ast> Visitor<'ast> for GlobalDef<'x, 'ast> {
         let ximpl = ImplData {
             id: id,
             pos: i.pos,
+            module: self.module,
+            type_params: Vec::new(),
             trait_id: None,
             class_id: None,
+            trait_type_params: TypeParams::empty(),
             methods: Vec::new(),
         };
 
@@ -109,6 +217,7 @@ impl<'x, 'ast> Visitor<'ast> for GlobalDef<'x, 'ast> {
             id: id,
             pos: c.pos,
             name: c.name,
+            module: self.module,
             ty: BuiltinType::Unit,
             expr: &c.expr,
             value: ConstValue::None,
@@ -119,9 +228,7 @@ impl<'x, 'ast> Visitor<'ast> for GlobalDef<'x, 'ast> {
 
         let sym = SymConst(id);
 
-        if let Some(sym) = self.ctxt.sym.borrow_mut().insert(c.name, sym) {
-            report(self.ctxt, c.name, c.pos, sym);
-        }
+        self.insert(c.name, c.pos, sym, c.is_pub);
     }
 
     fn visit_class(&mut self, c: &'ast Class) {
@@ -130,6 +237,7 @@ impl<'x, 'ast> Visitor<'ast> for GlobalDef<'x, 'ast> {
             id: id,
             name: c.name,
             pos: c.pos,
+            module: self.module,
             ty: self.ctxt.cls(id),
             parent_class: None,
             has_open: c.has_open,
@@ -164,9 +272,7 @@ impl<'x, 'ast> Visitor<'ast> for GlobalDef<'x, 'ast> {
 
         self.map_cls_defs.insert(c.id, id);
 
-        if let Some(sym) = self.ctxt.sym.borrow_mut().insert(c.name, sym) {
-            report(self.ctxt, c.name, c.pos, sym);
-        }
+        self.insert(c.name, c.pos, sym, c.is_pub);
     }
 
     fn visit_struct(&mut self, s: &'ast Struct) {
@@ -184,9 +290,31 @@ impl<'x, 'ast> Visitor<'ast> for GlobalDef<'x, 'ast> {
 
         self.map_struct_defs.insert(s.id, id);
 
-        if let Some(sym) = self.ctxt.sym.borrow_mut().insert(s.name, sym) {
-            report(self.ctxt, s.name, s.pos, sym);
-        }
+        self.insert(s.name, s.pos, sym, s.is_pub);
+    }
+
+    fn visit_enum(&mut self, e: &'ast Enum) {
+        let id: EnumId = (self.ctxt.enums.len() as u32).into();
+        let xenum = EnumData {
+            id: id,
+            pos: e.pos,
+            name: e.name,
+            variants: Vec::new(),
+        };
+
+        self.ctxt.enums.push(xenum);
+        let sym = SymEnum(id);
+
+        self.map_enum_defs.insert(e.id, id);
+
+        self.insert(e.name, e.pos, sym, e.is_pub);
+    }
+
+    fn visit_alias(&mut self, a: &'ast Alias) {
+        let id = self.add_alias(a, None);
+        let sym = SymAlias(id);
+
+        self.insert(a.name, a.pos, sym, a.is_pub);
     }
 
     fn visit_fct(&mut self, f: &'ast Function) {
@@ -201,13 +329,14 @@ impl<'x, 'ast> Visitor<'ast> for GlobalDef<'x, 'ast> {
             pos: f.pos,
             ast: f,
             name: f.name,
+            module: self.module,
             param_types: Vec::new(),
             return_type: BuiltinType::Unit,
             parent: FctParent::None,
             has_override: f.has_override,
             has_open: f.has_open,
             has_final: f.has_final,
-            is_pub: true,
+            is_pub: f.is_pub,
             is_static: false,
             is_abstract: false,
             internal: f.internal,
@@ -218,6 +347,7 @@ impl<'x, 'ast> Visitor<'ast> for GlobalDef<'x, 'ast> {
             vtable_index: None,
             initialized: false,
             impl_for: None,
+            is_default: false,
 
             type_params: Vec::new(),
             kind: kind,
@@ -235,6 +365,8 @@ fn report(ctxt: &SemContext, name: Name, pos: Position, sym: Sym) {
     let msg = match sym {
         SymClass(_) => Msg::ShadowClass(name),
         SymStruct(_) => Msg::ShadowStruct(name),
+        SymEnum(_) => Msg::ShadowEnum(name),
+        SymAlias(_) => Msg::ShadowAlias(name),
         SymFct(_) => Msg::ShadowFunction(name),
         SymTrait(_) => Msg::ShadowTrait(name),
         SymGlobal(_) => Msg::ShadowGlobal(name),
@@ -270,6 +402,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_enum() {
+        ok("enum Foo { A, B }");
+        err(
+            "enum Foo { A } class Foo {}",
+            pos(1, 16),
+            Msg::ShadowEnum("Foo".into()),
+        );
+        err(
+            "struct Foo {} enum Foo { A }",
+            pos(1, 15),
+            Msg::ShadowStruct("Foo".into()),
+        );
+    }
+
+    #[test]
+    fn test_alias() {
+        ok("type Foo = int;");
+        ok("trait Foo { type Item = int; }");
+        err(
+            "type Foo = int; class Foo {}",
+            pos(1, 17),
+            Msg::ShadowAlias("Foo".into()),
+        );
+        err(
+            "class Foo {} type Foo = int;",
+            pos(1, 14),
+            Msg::ShadowClass("Foo".into()),
+        );
+        err(
+            "trait Foo { type Item = int; type Item = bool; }",
+            pos(1, 30),
+            Msg::ShadowAlias("Item".into()),
+        );
+    }
+
     #[test]
     fn test_trait() {
         ok("trait Foo {}");
@@ -24,6 +24,9 @@ struct GlobalDefCheck<'a, 'ast: 'a> {
 
 impl<'a, 'ast> Visitor<'ast> for GlobalDefCheck<'a, 'ast> {
     fn visit_file(&mut self, f: &'ast File) {
+        let module = self.ctxt.file_module(f);
+        self.ctxt.sym.borrow_mut().enter_module(module);
+
         for e in &f.elements {
             match *e {
                 ElemGlobal(ref g) => self.visit_global(g),
@@ -38,11 +41,12 @@ impl<'a, 'ast> Visitor<'ast> for GlobalDefCheck<'a, 'ast> {
         let ty = semck::read_type(self.ctxt, &g.data_type).unwrap_or(BuiltinType::Unit);
         self.ctxt.globals[global_id].borrow_mut().ty = ty;
 
-        if g.expr.is_some() {
-            self.ctxt
-                .diag
-                .borrow_mut()
-                .report(g.pos, Msg::GlobalInitializerNotSupported);
+        if g.expr.is_none() && !ty.has_default_value(self.ctxt) {
+            let name = self.ctxt.interner.str(g.name).to_string();
+            let ty =
+                semck::alias_name(self.ctxt, &g.data_type).unwrap_or_else(|| ty.name(self.ctxt));
+            let msg = Msg::MissingInitialization(name, ty);
+            self.ctxt.diag.borrow_mut().report(g.pos, msg);
         }
     }
 }
@@ -54,10 +58,36 @@ mod tests {
 
     #[test]
     fn check_initializer() {
+        ok("let a: int = 0;");
+        ok("var a: int = 0; let b: int = a + 1;");
+        ok("let a: Str = \"abc\"; fun f() -> Str { return a; }");
+        ok("var a: int = f(); fun f() -> int { return 1; }");
         err(
-            "let a: int = 0;",
+            "let a: int = \"abc\";",
             pos(1, 1),
-            Msg::GlobalInitializerNotSupported,
+            Msg::AssignType("a".into(), "int".into(), "Str".into()),
+        );
+        err(
+            "let a: int = b;",
+            pos(1, 14),
+            Msg::UnknownIdentifier("b".into()),
+        );
+    }
+
+    #[test]
+    fn check_missing_initializer() {
+        ok("var a: int;");
+        ok("var a: Str?;");
+        ok("var a: Str = \"abc\";");
+        err(
+            "var a: Str;",
+            pos(1, 1),
+            Msg::MissingInitialization("a".into(), "Str".into()),
+        );
+        err(
+            "class Foo let a: Foo;",
+            pos(1, 11),
+            Msg::MissingInitialization("a".into(), "Foo".into()),
         );
     }
 
@@ -3,6 +3,8 @@ use std::collections::HashSet;
 use ctxt::SemContext;
 use dora_parser::error::msg::Msg;
 use dora_parser::lexer::position::Position;
+use semck::specialize::specialize_for_trait;
+use ty::BuiltinType;
 
 pub fn check<'ast>(ctxt: &mut SemContext<'ast>) {
     for ximpl in &ctxt.impls {
@@ -21,8 +23,23 @@ pub fn check<'ast>(ctxt: &mut SemContext<'ast>) {
                 method.is_static,
                 method.name,
                 Some(cls),
+                &ximpl.trait_type_params,
                 method.params_without_self(),
             ) {
+                let trait_method = ctxt.fcts[fid].borrow();
+                let trait_return_type = match trait_method.return_type {
+                    BuiltinType::This => cls,
+                    ty => specialize_for_trait(ctxt, ty, &ximpl.trait_type_params),
+                };
+
+                if method.return_type != trait_return_type {
+                    let msg = Msg::ReturnTypeMismatch(
+                        method.return_type.name(ctxt),
+                        trait_return_type.name(ctxt),
+                    );
+                    report(ctxt, method.pos, msg);
+                }
+
                 method.impl_for = Some(fid);
                 defined.insert(fid);
             } else {
@@ -143,4 +160,52 @@ mod tests {
             Msg::StaticMethodMissingFromTrait("Foo".into(), "bar".into(), vec![]),
         );
     }
+
+    #[test]
+    fn method_of_generic_trait() {
+        ok("trait Foo<T> { fun foo(a: T) -> T; }
+            class A
+            impl Foo<int> for A {
+                fun foo(a: int) -> int { return a; }
+            }");
+
+        err(
+            "trait Foo<T> { fun foo() -> T; }
+            class A
+            impl Foo<int> for A {
+                fun foo() -> bool { return false; }
+            }",
+            pos(4, 17),
+            Msg::ReturnTypeMismatch("bool".into(), "int".into()),
+        );
+    }
+
+    #[test]
+    fn default_method_in_trait() {
+        ok("trait Foo {
+                fun foo() -> int { return 1; }
+            }
+            class A
+            impl Foo for A {}
+            fun f() -> int { return A().foo(); }");
+
+        ok("trait Foo {
+                fun foo() -> int { return 1; }
+            }
+            class A
+            impl Foo for A {
+                fun foo() -> int { return 2; }
+            }");
+
+        err(
+            "trait Foo {
+                fun foo() -> int { return 1; }
+                fun bar() -> int;
+            }
+            class A
+            impl Foo for A {}",
+            pos(6, 13),
+            Msg::MethodMissingFromTrait("Foo".into(), "bar".into(), vec![]),
+        );
+    }
 }
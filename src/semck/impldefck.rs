@@ -2,9 +2,11 @@ use std::cell::RefCell;
 
 use dora_parser::ast;
 use dora_parser::ast::visit::{self, Visitor};
-use ctxt::{Fct, FctId, FctKind, FctParent, FctSrc, ImplId, NodeMap, SemContext};
+use class::{ClassId, TypeParams};
+use ctxt::{Fct, FctId, FctKind, FctParent, FctSrc, ImplId, NodeMap, SemContext, TraitId};
 use dora_parser::error::msg::Msg;
 use dora_parser::lexer::position::Position;
+use semck;
 use sym::Sym;
 use ty::BuiltinType;
 
@@ -31,34 +33,218 @@ impl<'x, 'ast> ImplCheck<'x, 'ast> {
     fn check(&mut self) {
         self.visit_ast(self.ast);
     }
+
+    // every impl gets its own copy of the trait's default methods it doesn't
+    // define itself, the copy is then checked and compiled for the impl's class
+    fn add_default_methods(&mut self, i: &'ast ast::Impl, trait_id: TraitId) {
+        let impl_id = self.impl_id.unwrap();
+        let defaults: Vec<&'ast ast::Function> = {
+            let xtrait = self.ctxt.traits[trait_id].borrow();
+
+            xtrait
+                .methods
+                .iter()
+                .map(|&method_id| self.ctxt.fcts[method_id].borrow().ast)
+                .filter(|f| f.block.is_some())
+                .filter(|f| {
+                    !i.methods
+                        .iter()
+                        .any(|m| m.name == f.name && m.is_static == f.is_static)
+                })
+                .collect()
+        };
+
+        for f in defaults {
+            let fct = Fct {
+                id: FctId(0),
+                ast: f,
+                pos: f.pos,
+                name: f.name,
+                module: self.ctxt.traits[trait_id].borrow().module,
+                param_types: Vec::new(),
+                return_type: BuiltinType::Unit,
+                parent: FctParent::Impl(impl_id),
+                has_override: f.has_override,
+                has_open: f.has_open,
+                has_final: f.has_final,
+                is_pub: f.is_pub,
+                is_static: f.is_static,
+                is_abstract: false,
+                internal: false,
+                internal_resolved: false,
+                overrides: None,
+                throws: f.throws,
+                ctor: ast::CtorType::None,
+                vtable_index: None,
+                initialized: false,
+                impl_for: None,
+                is_default: true,
+
+                type_params: Vec::new(),
+                kind: FctKind::Source(RefCell::new(FctSrc::new())),
+            };
+
+            let fctid = self.ctxt.add_fct(fct);
+
+            let mut ximpl = self.ctxt.impls[impl_id].borrow_mut();
+            ximpl.methods.push(fctid);
+        }
+    }
+
+    fn check_class(&mut self, i: &'ast ast::Impl) -> Option<ClassId> {
+        let basic = match i.class_type {
+            ast::Type::TypeBasic(ref basic) => basic,
+            _ => {
+                let name = i.class_type.to_string(&self.ctxt.interner);
+                report(self.ctxt, i.pos, Msg::ExpectedClass(name));
+                return None;
+            }
+        };
+
+        let class_id = match self.ctxt.sym.borrow().get(basic.name) {
+            Some(Sym::SymClass(class_id)) => class_id,
+            _ => {
+                let name = self.ctxt.interner.str(basic.name).to_string();
+                report(self.ctxt, i.pos, Msg::ExpectedClass(name));
+                return None;
+            }
+        };
+
+        let mut names = Vec::new();
+
+        if let Some(ref type_params) = i.type_params {
+            if type_params.is_empty() {
+                report(self.ctxt, i.pos, Msg::TypeParamsExpected);
+            }
+
+            for type_param in type_params {
+                if names.contains(&type_param.name) {
+                    let name = self.ctxt.interner.str(type_param.name).to_string();
+                    report(self.ctxt, type_param.pos, Msg::TypeParamNameNotUnique(name));
+                }
+
+                // bounds are taken from the class
+                if !type_param.bounds.is_empty() {
+                    self.ctxt
+                        .diag
+                        .borrow_mut()
+                        .report_unimplemented(type_param.pos);
+                }
+
+                names.push(type_param.name);
+            }
+        }
+
+        let cls_type_params = self.ctxt.classes[class_id].borrow().type_params.len();
+
+        if cls_type_params != basic.params.len() {
+            let msg = Msg::WrongNumberTypeParams(cls_type_params, basic.params.len());
+            report(self.ctxt, basic.pos, msg);
+            return None;
+        }
+
+        // only impls for all instances of a generic class are supported:
+        // the class needs to be applied to the impl's type params in order
+        let generic = basic.params.len() == names.len()
+            && basic.params.iter().zip(&names).all(|(ty, &name)| match **ty {
+                ast::Type::TypeBasic(ref param) => param.name == name && param.params.is_empty(),
+                _ => false,
+            });
+
+        if !generic {
+            self.ctxt.diag.borrow_mut().report_unimplemented(basic.pos);
+            return None;
+        }
+
+        self.ctxt.impls[self.impl_id.unwrap()].borrow_mut().type_params = names;
+
+        Some(class_id)
+    }
+
+    fn check_trait(&mut self, i: &'ast ast::Impl) -> Option<(TraitId, TypeParams)> {
+        let is_trait = match i.trait_type {
+            ast::Type::TypeBasic(ref basic) => match self.ctxt.sym.borrow().get(basic.name) {
+                Some(Sym::SymTrait(_)) => true,
+                _ => false,
+            },
+
+            _ => false,
+        };
+
+        if !is_trait {
+            let name = i.trait_type.to_string(&self.ctxt.interner);
+            report(self.ctxt, i.pos, Msg::ExpectedTrait(name));
+            return None;
+        }
+
+        match semck::read_type(self.ctxt, &i.trait_type) {
+            Some(BuiltinType::Trait(trait_id, list_id)) => {
+                let params = self.ctxt.lists.borrow().get(list_id);
+                Some((trait_id, params))
+            }
+
+            _ => None,
+        }
+    }
 }
 
 impl<'x, 'ast> Visitor<'ast> for ImplCheck<'x, 'ast> {
+    fn visit_file(&mut self, f: &'ast ast::File) {
+        let module = self.ctxt.file_module(f);
+        self.ctxt.sym.borrow_mut().enter_module(module);
+
+        visit::walk_file(self, f);
+    }
+
     fn visit_impl(&mut self, i: &'ast ast::Impl) {
         self.impl_id = Some(*self.map_impl_defs.get(i.id).unwrap());
 
         visit::walk_impl(self, i);
 
-        let mut ximpl = self.ctxt.impls[self.impl_id.unwrap()].borrow_mut();
+        let class_id = self.check_class(i);
 
-        if let Some(Sym::SymTrait(trait_id)) = self.ctxt.sym.borrow().get(i.trait_name) {
-            ximpl.trait_id = Some(trait_id);
-        } else {
-            let name = self.ctxt.interner.str(i.trait_name).to_string();
-            report(self.ctxt, i.pos, Msg::ExpectedTrait(name));
+        // the trait's type arguments may refer to the type params of the impl
+        self.ctxt.sym.borrow_mut().push_level();
+
+        if let Some(class_id) = class_id {
+            let ximpl = self.ctxt.impls[self.impl_id.unwrap()].borrow();
+
+            for (id, &name) in ximpl.type_params.iter().enumerate() {
+                let sym = Sym::SymClassTypeParam(class_id, id.into());
+                self.ctxt.sym.borrow_mut().insert(name, sym);
+            }
         }
 
-        if let Some(Sym::SymClass(class_id)) = self.ctxt.sym.borrow().get(i.class_name) {
-            ximpl.class_id = Some(class_id);
+        // without a class the impl's type params are unknown, skip the trait
+        // to avoid follow-up errors
+        let trait_type = if class_id.is_none() && i.type_params.is_some() {
+            None
         } else {
-            let name = self.ctxt.interner.str(i.class_name).to_string();
-            report(self.ctxt, i.pos, Msg::ExpectedClass(name));
+            self.check_trait(i)
+        };
+
+        self.ctxt.sym.borrow_mut().pop_level();
+
+        {
+            let mut ximpl = self.ctxt.impls[self.impl_id.unwrap()].borrow_mut();
+            ximpl.class_id = class_id;
+
+            if let Some((trait_id, ref trait_type_params)) = trait_type {
+                ximpl.trait_id = Some(trait_id);
+                ximpl.trait_type_params = trait_type_params.clone();
+            }
+
+            if ximpl.trait_id.is_some() && ximpl.class_id.is_some() {
+                let mut cls = self.ctxt.classes[ximpl.cls_id()].borrow_mut();
+                cls.traits.push(ximpl.trait_id());
+                cls.impls.push(ximpl.id);
+            }
         }
 
-        if ximpl.trait_id.is_some() && ximpl.class_id.is_some() {
-            let mut cls = self.ctxt.classes[ximpl.cls_id()].borrow_mut();
-            cls.traits.push(ximpl.trait_id());
-            cls.impls.push(ximpl.id);
+        if let Some((trait_id, _)) = trait_type {
+            if class_id.is_some() {
+                self.add_default_methods(i, trait_id);
+            }
         }
 
         self.impl_id = None;
@@ -84,6 +270,7 @@ impl<'x, 'ast> Visitor<'ast> for ImplCheck<'x, 'ast> {
             ast: f,
             pos: f.pos,
             name: f.name,
+            module: self.ctxt.impls[self.impl_id.unwrap()].borrow().module,
             param_types: Vec::new(),
             return_type: BuiltinType::Unit,
             parent: FctParent::Impl(self.impl_id.unwrap()),
@@ -101,6 +288,7 @@ impl<'x, 'ast> Visitor<'ast> for ImplCheck<'x, 'ast> {
             vtable_index: None,
             initialized: false,
             impl_for: None,
+            is_default: false,
 
             type_params: Vec::new(),
             kind: kind,
@@ -178,4 +366,26 @@ mod tests {
             class A {}
             impl Foo for A { fun toBool() -> bool { return false; } }");
     }
+
+    #[test]
+    fn impl_generic_trait() {
+        ok("trait Foo<T> {} class A impl Foo<int> for A {}");
+        ok("trait Foo<T> {} class A<T> impl<T> Foo<T> for A<T> {}");
+
+        err(
+            "trait Foo<T> {} class A impl Foo for A {}",
+            pos(1, 30),
+            Msg::WrongNumberTypeParams(1, 0),
+        );
+        err(
+            "trait Foo {} class A impl Foo<int> for A {}",
+            pos(1, 27),
+            Msg::NoTypeParamsExpected,
+        );
+        err(
+            "trait Foo<T> {} class A<T> impl<T> Foo<T> for A {}",
+            pos(1, 47),
+            Msg::WrongNumberTypeParams(1, 0),
+        );
+    }
 }
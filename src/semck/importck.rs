@@ -0,0 +1,188 @@
+use ctxt::{ModuleId, SemContext};
+use dora_parser::ast::{File, Import};
+use dora_parser::ast::visit::{self, Visitor};
+use dora_parser::error::msg::Msg;
+use dora_parser::interner::Name;
+
+pub fn check<'ast>(ctxt: &SemContext<'ast>) {
+    let mut importck = ImportCheck {
+        ctxt: ctxt,
+        module: ModuleId::root(),
+    };
+
+    importck.visit_ast(ctxt.ast);
+}
+
+struct ImportCheck<'x, 'ast: 'x> {
+    ctxt: &'x SemContext<'ast>,
+    module: ModuleId,
+}
+
+impl<'x, 'ast> ImportCheck<'x, 'ast> {
+    fn path_str(&self, path: &[Name]) -> String {
+        let names: Vec<String> = path
+            .iter()
+            .map(|&name| self.ctxt.interner.str(name).to_string())
+            .collect();
+
+        names.join("::")
+    }
+
+    fn import_glob(&mut self, module: ModuleId) {
+        let mut sym = self.ctxt.sym.borrow_mut();
+
+        // items imported by glob never replace local items or explicit imports
+        for (name, item) in sym.exports(module) {
+            if sym.get_item(self.module, name).is_none() {
+                if let Some(old) = sym.import(name, item) {
+                    sym.import(name, old);
+                }
+            }
+        }
+    }
+
+    fn import_item(&mut self, import: &'ast Import, module: ModuleId) {
+        let name = *import.path.path.last().unwrap();
+        let name_str = self.ctxt.interner.str(name).to_string();
+
+        let item = self.ctxt.sym.borrow().get_export(module, name);
+
+        let item = match item {
+            Some(item) => item,
+            None => {
+                let msg = if self.ctxt.sym.borrow().get_item(module, name).is_some() {
+                    Msg::NotPublic(name_str)
+                } else {
+                    Msg::UnknownImport(name_str, self.ctxt.module_name(module))
+                };
+
+                self.ctxt.diag.borrow_mut().report(import.pos, msg);
+                return;
+            }
+        };
+
+        let mut sym = self.ctxt.sym.borrow_mut();
+
+        if sym.get_item(self.module, name).is_some() || sym.import(name, item).is_some() {
+            let msg = Msg::ShadowImport(name_str);
+            self.ctxt.diag.borrow_mut().report(import.pos, msg);
+        }
+    }
+}
+
+impl<'x, 'ast> Visitor<'ast> for ImportCheck<'x, 'ast> {
+    fn visit_file(&mut self, f: &'ast File) {
+        self.module = self.ctxt.file_module(f);
+        self.ctxt.sym.borrow_mut().enter_module(self.module);
+
+        visit::walk_file(self, f);
+    }
+
+    fn visit_import(&mut self, import: &'ast Import) {
+        // for `import a::b::C;` the module is `a::b`, globs name the module itself
+        let module_path = if import.glob {
+            &import.path.path[..]
+        } else {
+            &import.path.path[..import.path.len() - 1]
+        };
+
+        let module = match self.ctxt.find_module(module_path) {
+            Some(module) => module,
+            None => {
+                let msg = Msg::UnknownModule(self.path_str(module_path));
+                self.ctxt.diag.borrow_mut().report(import.pos, msg);
+                return;
+            }
+        };
+
+        if import.glob {
+            self.import_glob(module);
+        } else {
+            self.import_item(import, module);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dora_parser::error::msg::Msg;
+    use semck::tests::*;
+
+    #[test]
+    fn import_from_std() {
+        ok("import std::println; fun f() { println(\"hello\"); }");
+        ok("import std::*; fun f() { println(\"hello\"); }");
+    }
+
+    #[test]
+    fn import_unknown_module() {
+        err(
+            "import foo::bar;",
+            pos(1, 1),
+            Msg::UnknownModule("foo".into()),
+        );
+        err(
+            "import foo::*;",
+            pos(1, 1),
+            Msg::UnknownModule("foo".into()),
+        );
+    }
+
+    #[test]
+    fn import_unknown_item() {
+        err(
+            "import std::unknown;",
+            pos(1, 1),
+            Msg::UnknownImport("unknown".into(), "std".into()),
+        );
+    }
+
+    #[test]
+    fn import_private_item() {
+        err(
+            "import std::IntRangeIter;",
+            pos(1, 1),
+            Msg::NotPublic("IntRangeIter".into()),
+        );
+    }
+
+    #[test]
+    fn access_private_member_of_imported_class() {
+        err(
+            "fun f(e: Exception) { e.retrieveStackTrace(); }",
+            pos(1, 24),
+            Msg::NotPublic("retrieveStackTrace".into()),
+        );
+        err(
+            "fun f(e: Exception) -> Array<int>? { return e.backtrace; }",
+            pos(1, 46),
+            Msg::NotPublic("backtrace".into()),
+        );
+        err(
+            "fun f(e: Exception) { e.backtrace = nil; }",
+            pos(1, 24),
+            Msg::NotPublic("backtrace".into()),
+        );
+        ok("fun f(e: Exception) -> Str? { e.printStackTrace(); return e.msg; }");
+    }
+
+    #[test]
+    fn import_twice() {
+        err(
+            "import std::println; import std::println;",
+            pos(1, 22),
+            Msg::ShadowImport("println".into()),
+        );
+        err(
+            "import std::println; fun println() {}",
+            pos(1, 1),
+            Msg::ShadowImport("println".into()),
+        );
+    }
+
+    #[test]
+    fn shadow_std_items() {
+        ok("fun print(a: Str) {} fun f() { print(\"hello\"); }");
+        ok("class Str fun f() -> Str { return Str(); }");
+    }
+}
@@ -0,0 +1,428 @@
+use std::collections::HashSet;
+
+use class::ClassId;
+use ctxt::{CallType, Fct, FctId, FctKind, FctParent, FctSrc, IdentType, LambdaCallee, SemContext,
+           VarId};
+use dora_parser::ast::*;
+use dora_parser::ast::Expr::*;
+use dora_parser::ast::Stmt::*;
+use dora_parser::ast::visit::*;
+use dora_parser::error::msg::Msg;
+use dora_parser::interner::Name;
+use dora_parser::lexer::position::Position;
+use semck::returnck;
+use stdlib;
+use ty::BuiltinType;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Lint {
+    UnusedVariable,
+    UnusedParameter,
+    UnusedFunction,
+    UnreachableCode,
+    UnusedResult,
+    ShadowedField,
+}
+
+impl Lint {
+    pub fn all() -> &'static [Lint] {
+        static LINTS: [Lint; 6] = [
+            Lint::UnusedVariable,
+            Lint::UnusedParameter,
+            Lint::UnusedFunction,
+            Lint::UnreachableCode,
+            Lint::UnusedResult,
+            Lint::ShadowedField,
+        ];
+
+        &LINTS
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Lint::UnusedVariable => "unused-variable",
+            Lint::UnusedParameter => "unused-parameter",
+            Lint::UnusedFunction => "unused-function",
+            Lint::UnreachableCode => "unreachable-code",
+            Lint::UnusedResult => "unused-result",
+            Lint::ShadowedField => "shadowed-field",
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+// returns the first name in `--allow`, `--warn` or `--deny` that is not a lint
+pub fn unknown_lint(lists: &[&Option<String>]) -> Option<String> {
+    for list in lists.iter().filter_map(|list| list.as_ref()) {
+        for name in list.split(',') {
+            if name != "all" && !Lint::all().iter().any(|lint| lint.name() == name) {
+                return Some(name.to_string());
+            }
+        }
+    }
+
+    None
+}
+
+fn level(ctxt: &SemContext, lint: Lint) -> LintLevel {
+    let listed = |list: &Option<String>| {
+        list.as_ref().map_or(false, |list| {
+            list.split(',').any(|name| name == "all" || name == lint.name())
+        })
+    };
+
+    // lints are warnings unless escalated with `--deny` or silenced with `--allow`
+    if listed(&ctxt.args.flag_deny) {
+        LintLevel::Deny
+    } else if listed(&ctxt.args.flag_warn) {
+        LintLevel::Warn
+    } else if listed(&ctxt.args.flag_allow) {
+        LintLevel::Allow
+    } else {
+        LintLevel::Warn
+    }
+}
+
+pub fn check<'ast>(ctxt: &SemContext<'ast>) {
+    if Lint::all().iter().all(|&lint| level(ctxt, lint) == LintLevel::Allow) {
+        return;
+    }
+
+    let mut lints = Vec::new();
+    let mut called = HashSet::new();
+    let mut called_by_name = HashSet::new();
+
+    for fct in ctxt.fcts.iter() {
+        let fct = fct.borrow();
+
+        if !fct.is_src() {
+            continue;
+        }
+
+        let src = fct.src();
+        let src = src.borrow();
+
+        for (_, call) in src.map_calls.iter() {
+            if let CallType::Fct(fct_id, _, _) = **call {
+                called.insert(fct_id);
+            }
+        }
+
+        // lambdas are checked as part of their enclosing function and
+        // default methods in impls as part of their trait
+        if fct.is_lambda() || fct.is_default || in_std(ctxt, &fct) {
+            continue;
+        }
+
+        let mut lintck = LintCheck {
+            ctxt: ctxt,
+            fct: &fct,
+            src: &src,
+            fields: fields(ctxt, &fct),
+            vars: Vec::new(),
+            assigned: HashSet::new(),
+            called_by_name: &mut called_by_name,
+            lints: &mut lints,
+        };
+
+        lintck.check();
+    }
+
+    check_unused_fcts(ctxt, &called, &called_by_name, &mut lints);
+
+    // report in source order
+    lints.sort_by_key(|&(_, pos, _)| (pos.file.0, pos.line, pos.column));
+
+    for (lint, pos, msg) in lints {
+        match level(ctxt, lint) {
+            LintLevel::Allow => {}
+            LintLevel::Warn => ctxt.diag.borrow_mut().warn(pos, msg),
+            LintLevel::Deny => ctxt.diag.borrow_mut().report(pos, msg),
+        }
+    }
+}
+
+fn check_unused_fcts<'ast>(
+    ctxt: &SemContext<'ast>,
+    called: &HashSet<FctId>,
+    called_by_name: &HashSet<Name>,
+    lints: &mut Vec<(Lint, Position, Msg)>,
+) {
+    let initializers: HashSet<FctId> = ctxt.globals
+        .iter()
+        .filter_map(|glob| glob.borrow().initializer)
+        .collect();
+
+    let main = ctxt.interner.intern("main");
+
+    for fct in ctxt.fcts.iter() {
+        let fct = fct.borrow();
+
+        if !fct.parent.is_none()
+            || !fct.is_src()
+            || fct.is_pub
+            || fct.name == main
+            || called.contains(&fct.id)
+            || called_by_name.contains(&fct.name)
+            || initializers.contains(&fct.id)
+            || in_std(ctxt, &fct)
+            || ::driver::is_test_fct(ctxt, &fct)
+        {
+            continue;
+        }
+
+        let name = ctxt.interner.str(fct.name).to_string();
+        lints.push((Lint::UnusedFunction, fct.pos, Msg::UnusedFunction(name)));
+    }
+}
+
+fn in_std(ctxt: &SemContext, fct: &Fct) -> bool {
+    let std = ctxt.interner.intern("std");
+    let file = &ctxt.ast.files[fct.pos.file.0 as usize];
+
+    file.module.first() == Some(&std)
+}
+
+// names of all fields of the class a method belongs to
+fn fields(ctxt: &SemContext, fct: &Fct) -> Vec<Name> {
+    let mut cls_id: Option<ClassId> = match fct.parent {
+        _ if !fct.has_self() => None,
+        FctParent::Class(cls_id) => Some(cls_id),
+        FctParent::Impl(impl_id) => Some(ctxt.impls[impl_id].borrow().cls_id()),
+        _ => None,
+    };
+
+    let mut fields = Vec::new();
+
+    while let Some(id) = cls_id {
+        let cls = ctxt.classes[id].borrow();
+        fields.extend(cls.fields.iter().map(|field| field.name));
+        cls_id = cls.parent_class;
+    }
+
+    fields
+}
+
+struct LintCheck<'a, 'ast: 'a> {
+    ctxt: &'a SemContext<'ast>,
+    fct: &'a Fct<'ast>,
+    src: &'a FctSrc,
+    fields: Vec<Name>,
+
+    // declared variables: ast id, position, name and whether it is a parameter
+    vars: Vec<(NodeId, Position, Name, bool)>,
+
+    // left-hand sides of assignments, storing into a variable does not use it
+    assigned: HashSet<NodeId>,
+
+    // functions called through `call("name")`
+    called_by_name: &'a mut HashSet<Name>,
+    lints: &'a mut Vec<(Lint, Position, Msg)>,
+}
+
+impl<'a, 'ast> LintCheck<'a, 'ast> {
+    fn check(&mut self) {
+        // the signature of these methods is given by their super method or trait
+        let skip_params = self.fct.is_virtual()
+            || self.fct.is_abstract
+            || self.fct.in_trait()
+            || self.fct.in_impl()
+            || self.fct.ctor == CtorType::Primary;
+
+        if !skip_params {
+            for param in &self.fct.ast.params {
+                self.vars.push((param.id, param.pos, param.name, true));
+            }
+        }
+
+        self.visit_stmt(self.fct.ast.block());
+        self.check_unused_vars();
+    }
+
+    fn lint(&mut self, lint: Lint, pos: Position, msg: Msg) {
+        self.lints.push((lint, pos, msg));
+    }
+
+    fn check_unused_vars(&mut self) {
+        let mut used: HashSet<VarId> = self.src
+            .map_idents
+            .iter()
+            .filter(|&(id, _)| !self.assigned.contains(id))
+            .filter_map(|(_, ident)| match *ident {
+                IdentType::Var(var_id) => Some(var_id),
+                _ => None,
+            })
+            .collect();
+
+        // calling a lambda uses the variable holding it
+        for (_, call) in self.src.map_calls.iter() {
+            if let CallType::Lambda(LambdaCallee::Var(var_id)) = **call {
+                used.insert(var_id);
+            }
+        }
+
+        let vars = ::std::mem::replace(&mut self.vars, Vec::new());
+
+        for (id, pos, name, is_param) in vars {
+            let var_id = match self.src.map_vars.get(id) {
+                Some(&var_id) => var_id,
+                None => continue,
+            };
+
+            let name = self.ctxt.interner.str(name).to_string();
+
+            if used.contains(&var_id) || name.starts_with('_') {
+                continue;
+            }
+
+            if is_param {
+                self.lint(Lint::UnusedParameter, pos, Msg::UnusedParameter(name));
+            } else {
+                self.lint(Lint::UnusedVariable, pos, Msg::UnusedVariable(name));
+            }
+        }
+    }
+
+    fn declare_var(&mut self, id: NodeId, pos: Position, name: Name) {
+        self.vars.push((id, pos, name, false));
+
+        if self.fields.contains(&name) {
+            let name = self.ctxt.interner.str(name).to_string();
+            self.lint(Lint::ShadowedField, pos, Msg::ShadowedField(name));
+        }
+    }
+
+    fn check_unreachable(&mut self, block: &'ast StmtBlockType) {
+        for (ind, stmt) in block.stmts.iter().enumerate() {
+            // a syntax error counts as returning but doesn't end the block
+            if stmt.is_error() {
+                continue;
+            }
+
+            if returnck::returns_value(stmt).is_ok() {
+                if let Some(next) = block.stmts.get(ind + 1) {
+                    self.lint(Lint::UnreachableCode, next.pos(), Msg::UnreachableCode);
+                }
+
+                break;
+            }
+        }
+    }
+
+    // `call` looks up the function by its name at runtime
+    fn check_call_by_name(&mut self, call: &'ast ExprCallType) {
+        let name = match call.args.first() {
+            Some(arg) if call.args.len() == 1 => match **arg {
+                ExprLitStr(ref lit) => &lit.value,
+                _ => return,
+            },
+
+            _ => return,
+        };
+
+        let fct_id = match self.src.map_calls.get(call.id) {
+            Some(call) => match **call {
+                CallType::Fct(fct_id, _, _) => fct_id,
+                _ => return,
+            },
+
+            None => return,
+        };
+
+        if let FctKind::Native(ptr) = self.ctxt.fcts[fct_id].borrow().kind {
+            if ptr == stdlib::call as *const u8 {
+                self.called_by_name.insert(self.ctxt.interner.intern(name));
+            }
+        }
+    }
+
+    fn check_unused_result(&mut self, stmt: &'ast StmtExprType) {
+        if !stmt.expr.is_call() {
+            return;
+        }
+
+        let ty = match self.src.map_tys.get(stmt.expr.id()) {
+            Some(&ty) => ty,
+            None => return,
+        };
+
+        if ty.is_unit() || ty == BuiltinType::Error {
+            return;
+        }
+
+        let name = ty.name(self.ctxt);
+        self.lint(Lint::UnusedResult, stmt.expr.pos(), Msg::UnusedResult(name));
+    }
+}
+
+impl<'a, 'ast> Visitor<'ast> for LintCheck<'a, 'ast> {
+    fn visit_stmt(&mut self, s: &'ast Stmt) {
+        match *s {
+            StmtVar(ref stmt) => self.declare_var(stmt.id, stmt.pos, stmt.name),
+
+            StmtVarTuple(ref stmt) => {
+                for var in &stmt.vars {
+                    if let Some(name) = var.name {
+                        self.declare_var(var.id, var.pos, name);
+                    }
+                }
+            }
+
+            StmtBlock(ref block) => self.check_unreachable(block),
+            StmtExpr(ref stmt) => self.check_unused_result(stmt),
+
+            _ => {}
+        }
+
+        visit::walk_stmt(self, s);
+    }
+
+    fn visit_expr(&mut self, e: &'ast Expr) {
+        match *e {
+            ExprLambda(ref lambda) => {
+                for param in &lambda.fct.params {
+                    self.vars.push((param.id, param.pos, param.name, true));
+                }
+            }
+
+            ExprAssign(ref assign) if assign.lhs.is_ident() => {
+                self.assigned.insert(assign.lhs.id());
+            }
+
+            ExprCall(ref call) => self.check_call_by_name(call),
+
+            _ => {}
+        }
+
+        visit::walk_expr(self, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::unknown_lint;
+
+    #[test]
+    fn test_unknown_lint() {
+        let list = |s: &str| Some(s.to_string());
+
+        assert_eq!(None, unknown_lint(&[&None, &None, &None]));
+        assert_eq!(None, unknown_lint(&[&None, &list("all"), &list("unused-result")]));
+        assert_eq!(
+            None,
+            unknown_lint(&[&list("unused-parameter"), &list("unused-variable,shadowed-field")])
+        );
+        assert_eq!(
+            Some("foo".to_string()),
+            unknown_lint(&[&None, &list("unused-variable,foo"), &None])
+        );
+        assert_eq!(Some("bar".to_string()), unknown_lint(&[&None, &None, &list("bar")]));
+        assert_eq!(Some("baz".to_string()), unknown_lint(&[&list("baz")]));
+    }
+}
@@ -1,21 +1,28 @@
 use class::TypeParams;
-use ctxt::{NodeMap, SemContext};
-use dora_parser::ast::{Stmt, Type};
-use dora_parser::ast::Type::{TypeBasic, TypeLambda, TypeSelf, TypeTuple};
+use ctxt::{AliasData, AliasId, ModuleId, NodeMap, SemContext, TypeParam};
+use dora_parser::ast::{Stmt, Type, TypeBasicType};
+use dora_parser::ast::Type::{TypeBasic, TypeLambda, TypeNullable, TypeSelf, TypeTuple};
+use dora_parser::error::diag::Diagnostic;
 use dora_parser::error::msg::Msg;
+use dora_parser::lexer::position::Position;
 use mem;
-use sym::Sym::{SymClass, SymClassTypeParam, SymFctTypeParam, SymStruct, SymTrait};
+use sym::Sym::{SymAlias, SymAliasTypeParam, SymClass, SymClassTypeParam, SymEnum,
+               SymFctTypeParam, SymStruct, SymTrait, SymTraitTypeParam};
 use ty::BuiltinType;
 
 mod abstractck;
+mod aliasdefck;
 mod constdefck;
 mod clsdefck;
+mod enumdefck;
 mod fctdefck;
 mod flowck;
 mod globaldef;
 mod globaldefck;
 mod implck;
 mod impldefck;
+mod importck;
+pub mod lintck;
 mod nameck;
 mod prelude;
 mod typeck;
@@ -34,8 +41,24 @@ macro_rules! return_on_error {
 }
 
 pub fn check<'ast>(ctxt: &mut SemContext<'ast>) {
+    // syntax errors are set aside during the checks, the ast is still
+    // well-formed so errors in the remaining code are reported as well
+    let syntax_errors = ::std::mem::replace(&mut *ctxt.diag.borrow_mut(), Diagnostic::new());
+
+    check_modules(ctxt);
+
+    let errors = ::std::mem::replace(&mut *ctxt.diag.borrow_mut(), syntax_errors);
+    ctxt.diag.borrow_mut().append(errors);
+
+    // passes switch between modules, names are looked up in the root
+    // module afterwards
+    ctxt.sym.borrow_mut().enter_module(ModuleId::root());
+}
+
+fn check_modules<'ast>(ctxt: &mut SemContext<'ast>) {
     let mut map_cls_defs = NodeMap::new(); // get ClassId from ast node
     let mut map_struct_defs = NodeMap::new(); // get StructId from ast node
+    let mut map_enum_defs = NodeMap::new(); // get EnumId from ast node
     let mut map_trait_defs = NodeMap::new(); // get TraitId from ast node
     let mut map_impl_defs = NodeMap::new(); // get ImplId from ast node
     let mut map_global_defs = NodeMap::new(); // get GlobalId from ast node
@@ -47,6 +70,7 @@ pub fn check<'ast>(ctxt: &mut SemContext<'ast>) {
         ctxt,
         &mut map_cls_defs,
         &mut map_struct_defs,
+        &mut map_enum_defs,
         &mut map_trait_defs,
         &mut map_impl_defs,
         &mut map_global_defs,
@@ -54,12 +78,18 @@ pub fn check<'ast>(ctxt: &mut SemContext<'ast>) {
     );
     return_on_error!(ctxt);
 
+    // resolve imports of all modules
+    importck::check(ctxt);
+    return_on_error!(ctxt);
+
     // define internal classes
     prelude::internal_classes(ctxt);
 
-    // checks class/struct/trait definitions/bodies
+    // checks class/struct/enum/alias/trait definitions/bodies
     clsdefck::check(ctxt, &map_cls_defs);
     structdefck::check(ctxt, &map_struct_defs);
+    enumdefck::check(ctxt, &map_enum_defs);
+    aliasdefck::check(ctxt);
     traitdefck::check(ctxt, &map_trait_defs);
     impldefck::check(ctxt, &map_impl_defs);
     globaldefck::check(ctxt, &map_global_defs);
@@ -108,6 +138,9 @@ pub fn check<'ast>(ctxt: &mut SemContext<'ast>) {
 
     // initialize addresses for global variables
     init_global_addresses(ctxt);
+
+    // report lints enabled with --warn or --deny
+    lintck::check(ctxt);
 }
 
 fn internalck<'ast>(ctxt: &SemContext<'ast>) {
@@ -166,10 +199,15 @@ fn init_global_addresses<'ast>(ctxt: &SemContext<'ast>) {
         let ty_size = glob.ty.size(ctxt);
         let ty_align = glob.ty.align(ctxt);
 
-        offsets.push(mem::align_i32(size, ty_align));
-        size += ty_size;
+        let offset = mem::align_i32(size, ty_align);
+        offsets.push(offset);
+        size = offset + ty_size;
     }
 
+    // one byte per global after all values: set to 1 once the initializer has run
+    let init_offset = size;
+    size += ctxt.globals.len() as i32;
+
     let ptr = ctxt.gc.alloc_perm(size as usize);
 
     for (ind, glob) in ctxt.globals.iter().enumerate() {
@@ -177,6 +215,7 @@ fn init_global_addresses<'ast>(ctxt: &SemContext<'ast>) {
         let offset = offsets[ind];
 
         glob.address_value = unsafe { ptr.offset(offset as isize) };
+        glob.address_init = unsafe { ptr.offset((init_offset + ind as i32) as isize) };
     }
 }
 
@@ -187,22 +226,13 @@ pub fn read_type<'ast>(ctxt: &SemContext<'ast>, t: &'ast Type) -> Option<Builtin
         }
 
         TypeBasic(ref basic) => {
-            if let Some(sym) = ctxt.sym.borrow().get(basic.name) {
+            let sym = ctxt.sym.borrow().get(basic.name);
+
+            if let Some(sym) = sym {
                 match sym {
                     SymClass(cls_id) => {
                         let ty = if basic.params.len() > 0 {
-                            let mut type_params = Vec::new();
-
-                            for param in &basic.params {
-                                let param = read_type(ctxt, param);
-
-                                if let Some(param) = param {
-                                    type_params.push(param);
-                                } else {
-                                    return None;
-                                }
-                            }
-
+                            let type_params = read_type_params(ctxt, basic)?;
                             let cls = ctxt.classes[cls_id].borrow();
 
                             if cls.type_params.len() != type_params.len() {
@@ -214,39 +244,9 @@ pub fn read_type<'ast>(ctxt: &SemContext<'ast>, t: &'ast Type) -> Option<Builtin
                                 return None;
                             }
 
-                            for (tp, ty) in cls.type_params.iter().zip(type_params.iter()) {
-                                if let Some(cls_id) = tp.class_bound {
-                                    let cls = ctxt.cls(cls_id);
-
-                                    if !ty.subclass_from(ctxt, cls) {
-                                        let name = ty.name(ctxt);
-                                        let cls = cls.name(ctxt);
-
-                                        let msg = Msg::ClassBoundNotSatisfied(name, cls);
-                                        ctxt.diag.borrow_mut().report(basic.pos, msg);
-                                    }
-                                }
-
-                                let cls_id = if let Some(cls_id) = ty.cls_id(ctxt) {
-                                    cls_id
-                                } else {
-                                    continue;
-                                };
-
-                                let cls = ctxt.classes[cls_id].borrow();
-
-                                for &trait_bound in &tp.trait_bounds {
-                                    if !cls.traits.contains(&trait_bound) {
-                                        let bound = ctxt.traits[trait_bound].borrow();
-                                        let name = ty.name(ctxt);
-                                        let trait_name = ctxt.interner.str(bound.name).to_string();
-                                        let msg = Msg::TraitBoundNotSatisfied(name, trait_name);
-                                        ctxt.diag.borrow_mut().report(bound.pos, msg);
-                                    }
-                                }
-                            }
+                            check_bounds(ctxt, &cls.type_params, &type_params, basic.pos);
 
-                            let list_id = ctxt.lists.borrow_mut().insert(type_params.into());
+                            let list_id = ctxt.lists.borrow_mut().insert(type_params);
                             BuiltinType::Class(cls.id, list_id)
                         } else {
                             let cls = ctxt.classes[cls_id].borrow();
@@ -258,12 +258,27 @@ pub fn read_type<'ast>(ctxt: &SemContext<'ast>, t: &'ast Type) -> Option<Builtin
                     }
 
                     SymTrait(trait_id) => {
-                        if basic.params.len() > 0 {
-                            let msg = Msg::NoTypeParamsExpected;
+                        let type_params = read_type_params(ctxt, basic)?;
+                        let xtrait = ctxt.traits[trait_id].borrow();
+
+                        if xtrait.type_params.len() != type_params.len() {
+                            let msg = if xtrait.type_params.is_empty() {
+                                Msg::NoTypeParamsExpected
+                            } else {
+                                Msg::WrongNumberTypeParams(
+                                    xtrait.type_params.len(),
+                                    type_params.len(),
+                                )
+                            };
+
                             ctxt.diag.borrow_mut().report(basic.pos, msg);
+                            return None;
                         }
 
-                        return Some(BuiltinType::Trait(trait_id));
+                        check_bounds(ctxt, &xtrait.type_params, &type_params, basic.pos);
+
+                        let list_id = ctxt.lists.borrow_mut().insert(type_params);
+                        return Some(BuiltinType::Trait(trait_id, list_id));
                     }
 
                     SymStruct(struct_id) => {
@@ -276,6 +291,28 @@ pub fn read_type<'ast>(ctxt: &SemContext<'ast>, t: &'ast Type) -> Option<Builtin
                         return Some(BuiltinType::Struct(struct_id, list_id));
                     }
 
+                    SymEnum(enum_id) => {
+                        if basic.params.len() > 0 {
+                            let msg = Msg::NoTypeParamsExpected;
+                            ctxt.diag.borrow_mut().report(basic.pos, msg);
+                        }
+
+                        return Some(BuiltinType::Enum(enum_id));
+                    }
+
+                    SymAlias(alias_id) => {
+                        return read_alias(ctxt, basic, alias_id);
+                    }
+
+                    SymAliasTypeParam(ty) => {
+                        if basic.params.len() > 0 {
+                            let msg = Msg::NoTypeParamsExpected;
+                            ctxt.diag.borrow_mut().report(basic.pos, msg);
+                        }
+
+                        return Some(ty);
+                    }
+
                     SymClassTypeParam(cls_id, type_param_id) => {
                         if basic.params.len() > 0 {
                             let msg = Msg::NoTypeParamsExpected;
@@ -294,6 +331,15 @@ pub fn read_type<'ast>(ctxt: &SemContext<'ast>, t: &'ast Type) -> Option<Builtin
                         return Some(BuiltinType::FctTypeParam(fct_id, type_param_id));
                     }
 
+                    SymTraitTypeParam(trait_id, type_param_id) => {
+                        if basic.params.len() > 0 {
+                            let msg = Msg::NoTypeParamsExpected;
+                            ctxt.diag.borrow_mut().report(basic.pos, msg);
+                        }
+
+                        return Some(BuiltinType::TraitTypeParam(trait_id, type_param_id));
+                    }
+
                     _ => {
                         let name = ctxt.interner.str(basic.name).to_string();
                         let msg = Msg::ExpectedType(name);
@@ -311,6 +357,26 @@ pub fn read_type<'ast>(ctxt: &SemContext<'ast>, t: &'ast Type) -> Option<Builtin
             return Some(BuiltinType::Unit);
         }
 
+        // `(T)` is just a parenthesized type
+        TypeTuple(ref tuple) if tuple.subtypes.len() == 1 => {
+            return read_type(ctxt, &tuple.subtypes[0]);
+        }
+
+        TypeTuple(ref tuple) => {
+            let mut elements = Vec::with_capacity(tuple.subtypes.len());
+
+            for subtype in &tuple.subtypes {
+                if let Some(ty) = read_type(ctxt, subtype) {
+                    elements.push(ty);
+                } else {
+                    return None;
+                }
+            }
+
+            let ty = ctxt.tuples.borrow_mut().insert(elements);
+            return Some(BuiltinType::Tuple(ty));
+        }
+
         TypeLambda(ref lambda) => {
             let mut params = vec![];
 
@@ -334,12 +400,189 @@ pub fn read_type<'ast>(ctxt: &SemContext<'ast>, t: &'ast Type) -> Option<Builtin
             return Some(ty);
         }
 
+        TypeNullable(ref nullable) => {
+            let ty = read_type(ctxt, &nullable.subtype)?;
+
+            match ty {
+                BuiltinType::Class(_, _) | BuiltinType::Lambda(_) | BuiltinType::Nullable(_) => {
+                    return Some(BuiltinType::nullable(ctxt, ty));
+                }
+
+                // type params are rejected as well, they could be value types,
+                // their fct may still be borrowed here so name the type from the ast
+                _ => {
+                    let name = nullable.subtype.to_string(&ctxt.interner);
+                    let msg = Msg::ReferenceTypeExpected(name);
+                    ctxt.diag.borrow_mut().report(nullable.pos, msg);
+                }
+            }
+        }
+
         _ => ctxt.diag.borrow_mut().report_unimplemented(t.pos()),
     }
 
     None
 }
 
+fn read_type_params<'ast>(
+    ctxt: &SemContext<'ast>,
+    basic: &'ast TypeBasicType,
+) -> Option<TypeParams> {
+    let mut type_params = Vec::with_capacity(basic.params.len());
+
+    for param in &basic.params {
+        type_params.push(read_type(ctxt, param)?);
+    }
+
+    Some(type_params.into())
+}
+
+// checks the type arguments of a class or trait against the bounds
+// of its type params
+fn check_bounds<'ast>(
+    ctxt: &SemContext<'ast>,
+    type_params: &[TypeParam],
+    args: &TypeParams,
+    pos: Position,
+) {
+    for (tp, ty) in type_params.iter().zip(args.iter()) {
+        if ty.is_error() {
+            continue;
+        }
+
+        if let Some(cls_id) = tp.class_bound {
+            let cls = ctxt.cls(cls_id);
+
+            if !ty.subclass_from(ctxt, cls) {
+                let name = ty.name(ctxt);
+                let cls = cls.name(ctxt);
+
+                let msg = Msg::ClassBoundNotSatisfied(name, cls);
+                ctxt.diag.borrow_mut().report(pos, msg);
+            }
+        }
+
+        // type params are checked against their own bounds
+        if ty.cls_id(ctxt).is_none() {
+            continue;
+        }
+
+        for (&trait_id, trait_params) in &tp.trait_bounds {
+            let trait_params: TypeParams = trait_params
+                .iter()
+                .map(|t| specialize::specialize_bound(ctxt, t, args))
+                .collect::<Vec<_>>()
+                .into();
+
+            if !ty.implements_trait_with(ctxt, trait_id, &trait_params) {
+                let list_id = ctxt.lists.borrow_mut().insert(trait_params);
+                let bound = BuiltinType::Trait(trait_id, list_id);
+
+                let msg = Msg::TraitBoundNotSatisfied(ty.name(ctxt), bound.name(ctxt));
+                let trait_pos = ctxt.traits[trait_id].borrow().pos;
+                ctxt.diag.borrow_mut().report(trait_pos, msg);
+            }
+        }
+    }
+}
+
+// the type as written if it names an alias, diagnostics show the alias
+// instead of the aliased type
+pub fn alias_name<'ast>(ctxt: &SemContext<'ast>, t: &Type) -> Option<String> {
+    let basic = match *t {
+        TypeBasic(ref basic) => basic,
+        TypeNullable(ref nullable) => match *nullable.subtype {
+            TypeBasic(ref basic) => basic,
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    let sym = ctxt.sym.borrow().get(basic.name);
+
+    if sym.map_or(false, |sym| sym.is_alias()) {
+        Some(t.to_string(&ctxt.interner))
+    } else {
+        None
+    }
+}
+
+fn read_alias<'ast>(
+    ctxt: &SemContext<'ast>,
+    basic: &'ast TypeBasicType,
+    alias_id: AliasId,
+) -> Option<BuiltinType> {
+    let alias = ctxt.aliases[alias_id].borrow();
+    let type_params = alias.type_params();
+
+    if type_params.len() != basic.params.len() {
+        let msg = if type_params.is_empty() {
+            Msg::NoTypeParamsExpected
+        } else {
+            Msg::WrongNumberTypeParams(type_params.len(), basic.params.len())
+        };
+
+        ctxt.diag.borrow_mut().report(basic.pos, msg);
+        return None;
+    }
+
+    let mut args = Vec::with_capacity(basic.params.len());
+
+    for param in &basic.params {
+        args.push(read_type(ctxt, param)?);
+    }
+
+    expand_alias(ctxt, &*alias, &args, basic.pos)
+}
+
+// reads the aliased type with the alias' type params bound to `args`
+pub fn expand_alias<'ast>(
+    ctxt: &SemContext<'ast>,
+    alias: &AliasData<'ast>,
+    args: &[BuiltinType],
+    pos: Position,
+) -> Option<BuiltinType> {
+    if let Some(ty) = alias.ty.get() {
+        return if ty.is_error() { None } else { Some(ty) };
+    }
+
+    if alias.expanding.get() {
+        let name = ctxt.interner.str(alias.name).to_string();
+        ctxt.diag
+            .borrow_mut()
+            .report(pos, Msg::RecursiveAlias(name));
+        return None;
+    }
+
+    alias.expanding.set(true);
+
+    // the aliased type is resolved in the module of the alias, not at the use site
+    let saved = ctxt.sym.borrow_mut().push_module(alias.module);
+    ctxt.sym.borrow_mut().push_level();
+
+    if let Some(trait_id) = alias.trait_id {
+        aliasdefck::add_trait_aliases(ctxt, trait_id);
+    }
+
+    for (param, &arg) in alias.type_params().iter().zip(args) {
+        ctxt.sym
+            .borrow_mut()
+            .insert(param.name, SymAliasTypeParam(arg));
+    }
+
+    let ty = read_type(ctxt, &alias.ast.ty);
+
+    ctxt.sym.borrow_mut().pop_level();
+    ctxt.sym.borrow_mut().pop_module(saved);
+    alias.expanding.set(false);
+
+    if args.is_empty() {
+        alias.ty.set(Some(ty.unwrap_or(BuiltinType::Error)));
+    }
+
+    ty
+}
+
 pub fn always_returns(s: &Stmt) -> bool {
     match returnck::returns_value(s) {
         Ok(_) => true,
@@ -350,7 +593,7 @@ pub fn always_returns(s: &Stmt) -> bool {
 #[cfg(test)]
 mod tests {
     use ctxt::SemContext;
-    use dora_parser::error::msg::Msg;
+    use dora_parser::error::msg::{Msg, MsgWithPos};
     use dora_parser::lexer::position::Position;
     use test;
 
@@ -397,8 +640,7 @@ mod tests {
             println!("errors = {:?}", errors);
 
             assert_eq!(1, errors.len());
-            assert_eq!(pos, errors[0].pos);
-            assert_eq!(msg, errors[0].msg);
+            assert_eq!(MsgWithPos::new(pos, msg), without_span(&errors[0]));
         });
     }
 
@@ -411,12 +653,28 @@ mod tests {
             assert_eq!(vec.len(), errors.len());
 
             for (ind, error) in errors.iter().enumerate() {
-                assert_eq!(vec[ind].0, error.pos);
-                assert_eq!(vec[ind].1, error.msg);
+                let expected = MsgWithPos::new(vec[ind].0, vec[ind].1.clone());
+                assert_eq!(expected, without_span(error));
             }
         });
     }
 
+    // positions in tests only carry line and column, so drop file and span
+    // of the reported positions before comparing
+    fn without_span(error: &MsgWithPos) -> MsgWithPos {
+        let strip = |pos: Position| Position::new(pos.line, pos.column);
+
+        let msg = match error.msg {
+            Msg::MethodExists(ref cls, ref name, pos) => {
+                Msg::MethodExists(cls.clone(), name.clone(), strip(pos))
+            }
+
+            ref msg => msg.clone(),
+        };
+
+        MsgWithPos::new(strip(error.pos), msg)
+    }
+
     pub fn pos(line: u32, col: u32) -> Position {
         Position::new(line, col)
     }
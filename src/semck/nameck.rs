@@ -19,7 +19,7 @@ pub fn check<'ast>(ctxt: &SemContext<'ast>) {
     for fct in ctxt.fcts.iter() {
         let fct = fct.borrow();
 
-        if !fct.is_src() {
+        if !fct.is_src() || fct.skip_default_check(ctxt) {
             continue;
         }
 
@@ -32,6 +32,7 @@ pub fn check<'ast>(ctxt: &SemContext<'ast>) {
             fct: &fct,
             src: &mut src,
             ast: ast,
+            lambdas: Vec::new(),
         };
 
         nameck.check();
@@ -43,10 +44,15 @@ struct NameCheck<'a, 'ast: 'a> {
     fct: &'a Fct<'ast>,
     src: &'a mut FctSrc,
     ast: &'ast Function,
+
+    // enclosing lambdas: ast id of lambda and its closure variable,
+    // variables declared before the closure variable need to be captured
+    lambdas: Vec<(NodeId, VarId)>,
 }
 
 impl<'a, 'ast> NameCheck<'a, 'ast> {
     fn check(&mut self) {
+        self.ctxt.sym.borrow_mut().enter_module(self.fct.module);
         self.ctxt.sym.borrow_mut().push_level();
 
         if self.fct.has_self() {
@@ -72,6 +78,8 @@ impl<'a, 'ast> NameCheck<'a, 'ast> {
                 self.ctxt.classes[ximpl.cls_id()].borrow().ty
             }
 
+            FctParent::Trait(_) => BuiltinType::This,
+
             _ => unreachable!(),
         };
 
@@ -145,6 +153,36 @@ impl<'a, 'ast> NameCheck<'a, 'ast> {
         }
     }
 
+    fn check_stmt_var_tuple(&mut self, stmt: &'ast StmtVarTupleType) {
+        self.visit_expr(&stmt.expr);
+
+        for var in &stmt.vars {
+            let name = match var.name {
+                Some(name) => name,
+                None => continue,
+            };
+
+            let var_ctxt = Var {
+                id: VarId(0),
+                name: name,
+                reassignable: stmt.reassignable,
+                ty: BuiltinType::Unit,
+                node_id: var.id,
+            };
+
+            match self.add_var(var_ctxt, |sym| !sym.is_class()) {
+                Ok(var_id) => {
+                    self.src.map_vars.insert(var.id, var_id);
+                }
+
+                Err(_) => {
+                    let name = str(self.ctxt, name);
+                    report(self.ctxt, var.pos, Msg::ShadowClass(name));
+                }
+            }
+        }
+    }
+
     fn check_stmt_for(&mut self, for_loop: &'ast StmtForType) {
         self.visit_expr(&for_loop.expr);
 
@@ -222,6 +260,7 @@ impl<'a, 'ast> NameCheck<'a, 'ast> {
 
         match sym {
             Some(SymVar(id)) => {
+                self.capture_var(id);
                 self.src.map_idents.insert(ident.id, IdentType::Var(id));
                 return;
             }
@@ -253,6 +292,18 @@ impl<'a, 'ast> NameCheck<'a, 'ast> {
     fn check_expr_call(&mut self, call: &'ast ExprCallType) {
         let mut found = false;
 
+        if let Some(ref callee) = call.callee {
+            self.visit_expr(callee);
+
+            for arg in &call.args {
+                self.visit_expr(arg);
+            }
+
+            let call_type = CallType::Lambda(LambdaCallee::Expr);
+            self.src.map_calls.insert(call.id, Rc::new(call_type));
+            return;
+        }
+
         // do not check method calls yet
         if let Some(ref object) = call.object {
             self.visit_expr(object);
@@ -288,6 +339,13 @@ impl<'a, 'ast> NameCheck<'a, 'ast> {
                     found = true;
                 }
 
+                SymVar(var_id) => {
+                    self.capture_var(var_id);
+                    let call_type = CallType::Lambda(LambdaCallee::Var(var_id));
+                    self.src.map_calls.insert(call.id, Rc::new(call_type));
+                    found = true;
+                }
+
                 _ => {}
             }
         }
@@ -303,6 +361,122 @@ impl<'a, 'ast> NameCheck<'a, 'ast> {
         }
     }
 
+    fn check_closure(&mut self, fct: &'ast Function) {
+        let name = self.ctxt.interner.intern("closure");
+        let closure = VarId(self.src.vars.len());
+
+        self.src.vars.push(Var {
+            id: closure,
+            name: name,
+            ty: BuiltinType::Unit,
+            reassignable: false,
+            node_id: fct.id,
+        });
+
+        let info = LambdaInfo {
+            fct_id: None,
+            closure: closure,
+            captures: Vec::new(),
+        };
+
+        self.src.map_lambdas.insert(fct.id, info);
+        self.lambdas.push((fct.id, closure));
+        self.ctxt.sym.borrow_mut().push_level();
+
+        for p in &fct.params {
+            self.visit_param(p);
+        }
+
+        self.visit_stmt(fct.block());
+
+        self.ctxt.sym.borrow_mut().pop_level();
+        self.lambdas.pop();
+    }
+
+    fn check_expr_self(&mut self) {
+        if self.fct.has_self() {
+            let var_id = self.src.var_self().id;
+            self.capture_var(var_id);
+        }
+    }
+
+    fn check_expr_assign(&mut self, assign: &'ast ExprAssignType) {
+        self.visit_expr(&assign.lhs);
+        self.visit_expr(&assign.rhs);
+
+        let ident = match assign.lhs.to_ident() {
+            Some(ident) => ident,
+            None => return,
+        };
+
+        let var_id = match self.src.map_idents.get(ident.id) {
+            Some(&IdentType::Var(var_id)) => var_id,
+            _ => return,
+        };
+
+        if let Some(&(_, closure)) = self.lambdas.last() {
+            if var_id.0 < closure.0 {
+                let name = str(self.ctxt, ident.name);
+                report(self.ctxt, assign.pos, Msg::CapturedVarReassigned(name));
+            }
+        }
+    }
+
+    // variables of enclosing functions are copied into each lambda
+    // that is nested between declaration and usage
+    fn capture_var(&mut self, var_id: VarId) {
+        for &(lambda_id, closure) in &self.lambdas {
+            if var_id.0 >= closure.0 {
+                continue;
+            }
+
+            let info = self.src.map_lambdas.get_mut(lambda_id).unwrap();
+
+            if !info.captures.contains(&var_id) {
+                info.captures.push(var_id);
+            }
+        }
+    }
+
+    fn check_expr_match(&mut self, xmatch: &'ast ExprMatchType) {
+        self.visit_expr(&xmatch.expr);
+
+        for arm in &xmatch.arms {
+            self.ctxt.sym.borrow_mut().push_level();
+
+            if let MatchPattern::Variant(_, ref params) = arm.pattern {
+                for param in params {
+                    let name = match param.name {
+                        Some(name) => name,
+                        None => continue,
+                    };
+
+                    let var_ctxt = Var {
+                        id: VarId(0),
+                        name: name,
+                        reassignable: false,
+                        ty: BuiltinType::Unit,
+                        node_id: param.id,
+                    };
+
+                    match self.add_var(var_ctxt, |sym| !sym.is_class()) {
+                        Ok(var_id) => {
+                            self.src.map_vars.insert(param.id, var_id);
+                        }
+
+                        Err(_) => {
+                            let name = str(self.ctxt, name);
+                            report(self.ctxt, param.pos, Msg::ShadowClass(name));
+                        }
+                    }
+                }
+            }
+
+            self.visit_expr(&arm.value);
+            self.ctxt.sym.borrow_mut().pop_level();
+        }
+    }
+
     fn check_expr_struct(&mut self, struc: &'ast ExprLitStructType) {
         if let Some(sid) = self.ctxt.sym.borrow().get_struct(struc.path.name()) {
             self.src.map_idents.insert(struc.id, IdentType::Struct(sid));
@@ -310,6 +484,10 @@ impl<'a, 'ast> NameCheck<'a, 'ast> {
             let name = self.ctxt.interner.str(struc.path.name()).to_string();
             report(self.ctxt, struc.pos, Msg::UnknownStruct(name));
         }
+
+        for arg in &struc.args {
+            self.visit_expr(&arg.expr);
+        }
     }
 }
 
@@ -346,6 +524,7 @@ impl<'a, 'ast> Visitor<'ast> for NameCheck<'a, 'ast> {
     fn visit_stmt(&mut self, s: &'ast Stmt) {
         match *s {
             StmtVar(ref stmt) => self.check_stmt_var(stmt),
+            StmtVarTuple(ref stmt) => self.check_stmt_var_tuple(stmt),
             StmtBlock(ref stmt) => self.check_stmt_block(stmt),
             StmtDo(ref stmt) => self.check_stmt_do(stmt),
             StmtFor(ref stmt) => self.check_stmt_for(stmt),
@@ -360,6 +539,11 @@ impl<'a, 'ast> Visitor<'ast> for NameCheck<'a, 'ast> {
             &ExprIdent(ref ident) => self.check_expr_ident(ident),
             &ExprCall(ref call) => self.check_expr_call(call),
             &ExprLitStruct(ref lit) => self.check_expr_struct(lit),
+            &ExprLambda(ref lambda) => self.check_closure(&lambda.fct),
+            &ExprSpawn(ref spawn) => self.check_closure(&spawn.fct),
+            &ExprAssign(ref assign) => self.check_expr_assign(assign),
+            &ExprMatch(ref xmatch) => self.check_expr_match(xmatch),
+            &ExprSelf(_) | &ExprSuper(_) => self.check_expr_self(),
 
             // no need to handle rest of expressions
             _ => visit::walk_expr(self, e),
@@ -380,6 +564,22 @@ mod tests {
     use dora_parser::error::msg::Msg;
     use semck::tests::*;
 
+    #[test]
+    fn lambda_captures() {
+        ok("fun f(a: int) { let b = 1; let x = || -> int { return a + b; }; }");
+        ok("fun f() { let x = || { var a = 1; a = 2; }; }");
+        err(
+            "fun f() { var a = 1; let x = || { a = 2; }; }",
+            pos(1, 37),
+            Msg::CapturedVarReassigned("a".into()),
+        );
+        err(
+            "fun f() { let x = || { y(); }; }",
+            pos(1, 24),
+            Msg::UnknownFunction("y".into()),
+        );
+    }
+
     #[test]
     fn multiple_functions() {
         ok("fun f() {}\nfun g() {}");
@@ -396,7 +596,10 @@ mod tests {
 
     #[test]
     fn shadow_type_with_function() {
-        err("fun int() {}", pos(1, 1), Msg::ShadowClass("int".into()));
+        err("class Foo fun Foo() {}", pos(1, 11), Msg::ShadowClass("Foo".into()));
+
+        // items of the stdlib can be shadowed in user code
+        ok("fun int() {}");
     }
 
     #[test]
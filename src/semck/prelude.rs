@@ -2,16 +2,21 @@ use std::cell::Cell;
 
 use class::ClassId;
 use ctxt::{FctKind, Intrinsic, SemContext, TraitId};
+use dora_parser::interner::Name;
 use exception;
 use stdlib;
+use sym::Sym;
 use ty::BuiltinType;
 
 pub fn internal_classes<'ast>(ctxt: &mut SemContext<'ast>) {
     ctxt.vips.bool_class = internal_class(ctxt, "bool", Some(BuiltinType::Bool));
     ctxt.vips.byte_class = internal_class(ctxt, "byte", Some(BuiltinType::Byte));
     ctxt.vips.char_class = internal_class(ctxt, "char", Some(BuiltinType::Char));
+    ctxt.vips.short_class = internal_class(ctxt, "short", Some(BuiltinType::Short));
     ctxt.vips.int_class = internal_class(ctxt, "int", Some(BuiltinType::Int));
+    ctxt.vips.uint_class = internal_class(ctxt, "uint", Some(BuiltinType::UInt));
     ctxt.vips.long_class = internal_class(ctxt, "long", Some(BuiltinType::Long));
+    ctxt.vips.ulong_class = internal_class(ctxt, "ulong", Some(BuiltinType::ULong));
 
     ctxt.vips.float_class = internal_class(ctxt, "float", Some(BuiltinType::Float));
     ctxt.vips.double_class = internal_class(ctxt, "double", Some(BuiltinType::Double));
@@ -26,10 +31,20 @@ pub fn internal_classes<'ast>(ctxt: &mut SemContext<'ast>) {
     ctxt.vips.testing_class = internal_class(ctxt, "Testing", None);
 
     ctxt.vips.exception_class = internal_class(ctxt, "Exception", None);
+    ctxt.vips.arithmetic_exception_class = internal_class(ctxt, "ArithmeticException", None);
+    ctxt.vips.index_out_of_bounds_exception_class =
+        internal_class(ctxt, "IndexOutOfBoundsException", None);
+    ctxt.vips.nil_pointer_exception_class = internal_class(ctxt, "NilPointerException", None);
+    ctxt.vips.cast_exception_class = internal_class(ctxt, "CastException", None);
+    ctxt.vips.out_of_memory_error_class = internal_class(ctxt, "OutOfMemoryError", None);
     ctxt.vips.stack_trace_element_class = internal_class(ctxt, "StackTraceElement", None);
 
+    ctxt.vips.thread_class = internal_class(ctxt, "Thread", None);
+    ctxt.vips.spawn_thread_class = internal_class(ctxt, "SpawnThread", None);
+
     ctxt.vips.comparable_trait = find_trait(ctxt, "Comparable");
     ctxt.vips.equals_trait = find_trait(ctxt, "Equals");
+    ctxt.vips.stringable_trait = find_trait(ctxt, "Stringable");
     ctxt.vips.iterator_trait = Cell::new(Some(find_trait(ctxt, "Iterator")));
 }
 
@@ -39,7 +54,7 @@ fn internal_class<'ast>(
     ty: Option<BuiltinType>,
 ) -> ClassId {
     let iname = ctxt.interner.intern(name);
-    let clsid = ctxt.sym.borrow().get_class(iname);
+    let clsid = std_item(ctxt, iname).and_then(|sym| sym.to_class());
 
     if let Some(clsid) = clsid {
         let mut cls = ctxt.classes[clsid].borrow_mut();
@@ -60,7 +75,7 @@ fn internal_class<'ast>(
 
 fn find_trait<'ast>(ctxt: &mut SemContext<'ast>, name: &str) -> TraitId {
     let iname = ctxt.interner.intern(name);
-    let tid = ctxt.sym.borrow().get_trait(iname);
+    let tid = std_item(ctxt, iname).and_then(|sym| sym.to_trait());
 
     if let Some(tid) = tid {
         tid
@@ -69,6 +84,15 @@ fn find_trait<'ast>(ctxt: &mut SemContext<'ast>, name: &str) -> TraitId {
     }
 }
 
+// internal classes and functions are always looked up in the stdlib,
+// even when user code defines an item with the same name
+fn std_item<'ast>(ctxt: &SemContext<'ast>, name: Name) -> Option<Sym> {
+    let sym = ctxt.sym.borrow();
+    let std = sym.prelude().expect("stdlib module missing");
+
+    sym.get_item(std, name)
+}
+
 pub fn internal_functions<'ast>(ctxt: &mut SemContext<'ast>) {
     native_fct(ctxt, "fatalError", stdlib::fatal_error as *const u8);
     native_fct(ctxt, "abort", stdlib::abort as *const u8);
@@ -108,6 +132,7 @@ pub fn internal_functions<'ast>(ctxt: &mut SemContext<'ast>) {
 
     let clsid = ctxt.vips.byte_class;
     native_method(ctxt, clsid, "toString", stdlib::byte_to_string as *const u8);
+    intrinsic_method(ctxt, clsid, "toShort", Intrinsic::ByteToShort);
     intrinsic_method(ctxt, clsid, "toLong", Intrinsic::ByteToLong);
     intrinsic_method(ctxt, clsid, "toInt", Intrinsic::ByteToInt);
 
@@ -123,8 +148,42 @@ pub fn internal_functions<'ast>(ctxt: &mut SemContext<'ast>) {
     intrinsic_method(ctxt, clsid, "equals", Intrinsic::CharEq);
     intrinsic_method(ctxt, clsid, "compareTo", Intrinsic::CharCmp);
 
+    // shorts are kept sign-extended in registers, so only operations that
+    // can leave the 16-bit range need their own intrinsic
+    let clsid = ctxt.vips.short_class;
+    native_method(ctxt, clsid, "toString", stdlib::short_to_string as *const u8);
+    intrinsic_method(ctxt, clsid, "toByte", Intrinsic::IntToByte);
+    intrinsic_method(ctxt, clsid, "toInt", Intrinsic::ShortToInt);
+    intrinsic_method(ctxt, clsid, "toLong", Intrinsic::IntToLong);
+
+    intrinsic_method(ctxt, clsid, "equals", Intrinsic::IntEq);
+    intrinsic_method(ctxt, clsid, "compareTo", Intrinsic::IntCmp);
+
+    intrinsic_method(ctxt, clsid, "plus", Intrinsic::ShortAdd);
+    intrinsic_method(ctxt, clsid, "minus", Intrinsic::ShortSub);
+    intrinsic_method(ctxt, clsid, "times", Intrinsic::ShortMul);
+    intrinsic_method(ctxt, clsid, "div", Intrinsic::ShortDiv);
+    intrinsic_method(ctxt, clsid, "mod", Intrinsic::IntMod);
+
+    intrinsic_method(ctxt, clsid, "bitwiseOr", Intrinsic::IntOr);
+    intrinsic_method(ctxt, clsid, "bitwiseAnd", Intrinsic::IntAnd);
+    intrinsic_method(ctxt, clsid, "bitwiseXor", Intrinsic::IntXor);
+
+    intrinsic_method(ctxt, clsid, "shiftLeft", Intrinsic::ShortShl);
+    intrinsic_method(ctxt, clsid, "shiftRight", Intrinsic::IntSar);
+
+    intrinsic_method(ctxt, clsid, "unaryPlus", Intrinsic::IntPlus);
+    intrinsic_method(ctxt, clsid, "unaryMinus", Intrinsic::ShortNeg);
+    intrinsic_method(ctxt, clsid, "not", Intrinsic::IntNot);
+
+    let trait_id = ctxt.vips.stringable_trait;
+    native_impl(ctxt, clsid, trait_id, "toString", stdlib::short_to_string as *const u8);
+
     let clsid = ctxt.vips.int_class;
     intrinsic_method(ctxt, clsid, "toByte", Intrinsic::IntToByte);
+    intrinsic_method(ctxt, clsid, "toShort", Intrinsic::IntToShort);
+    intrinsic_method(ctxt, clsid, "toUInt", Intrinsic::IntToUInt);
+    intrinsic_method(ctxt, clsid, "toULong", Intrinsic::IntToLong);
     intrinsic_method(ctxt, clsid, "toCharUnchecked", Intrinsic::IntToChar);
     intrinsic_method(ctxt, clsid, "toLong", Intrinsic::IntToLong);
     native_method(ctxt, clsid, "toString", stdlib::int_to_string as *const u8);
@@ -141,6 +200,16 @@ pub fn internal_functions<'ast>(ctxt: &mut SemContext<'ast>) {
     intrinsic_method(ctxt, clsid, "div", Intrinsic::IntDiv);
     intrinsic_method(ctxt, clsid, "mod", Intrinsic::IntMod);
 
+    intrinsic_method(ctxt, clsid, "checkedAdd", Intrinsic::IntCheckedAdd);
+    intrinsic_method(ctxt, clsid, "checkedSub", Intrinsic::IntCheckedSub);
+    intrinsic_method(ctxt, clsid, "checkedMul", Intrinsic::IntCheckedMul);
+    intrinsic_method(ctxt, clsid, "wrappingAdd", Intrinsic::IntWrappingAdd);
+    intrinsic_method(ctxt, clsid, "wrappingSub", Intrinsic::IntWrappingSub);
+    intrinsic_method(ctxt, clsid, "wrappingMul", Intrinsic::IntWrappingMul);
+    intrinsic_method(ctxt, clsid, "saturatingAdd", Intrinsic::IntSaturatingAdd);
+    intrinsic_method(ctxt, clsid, "saturatingSub", Intrinsic::IntSaturatingSub);
+    intrinsic_method(ctxt, clsid, "saturatingMul", Intrinsic::IntSaturatingMul);
+
     intrinsic_method(ctxt, clsid, "bitwiseOr", Intrinsic::IntOr);
     intrinsic_method(ctxt, clsid, "bitwiseAnd", Intrinsic::IntAnd);
     intrinsic_method(ctxt, clsid, "bitwiseXor", Intrinsic::IntXor);
@@ -159,11 +228,51 @@ pub fn internal_functions<'ast>(ctxt: &mut SemContext<'ast>) {
     let trait_id = ctxt.vips.comparable_trait;
     intrinsic_impl(ctxt, clsid, trait_id, "compareTo", Intrinsic::IntCmp);
 
+    let trait_id = ctxt.vips.stringable_trait;
+    native_impl(ctxt, clsid, trait_id, "toString", stdlib::int_to_string as *const u8);
+
+    // two's complement addition, subtraction, multiplication and bitwise
+    // operations are the same for signed and unsigned integers
+    let clsid = ctxt.vips.uint_class;
+    native_method(ctxt, clsid, "toString", stdlib::uint_to_string as *const u8);
+    intrinsic_method(ctxt, clsid, "toByte", Intrinsic::IntToByte);
+    intrinsic_method(ctxt, clsid, "toShort", Intrinsic::IntToShort);
+    intrinsic_method(ctxt, clsid, "toInt", Intrinsic::UIntToInt);
+    intrinsic_method(ctxt, clsid, "toLong", Intrinsic::UIntToLong);
+    intrinsic_method(ctxt, clsid, "toULong", Intrinsic::UIntToLong);
+    intrinsic_method(ctxt, clsid, "toFloat", Intrinsic::UIntToFloat);
+    intrinsic_method(ctxt, clsid, "toDouble", Intrinsic::UIntToDouble);
+
+    intrinsic_method(ctxt, clsid, "equals", Intrinsic::IntEq);
+    intrinsic_method(ctxt, clsid, "compareTo", Intrinsic::UIntCmp);
+
+    intrinsic_method(ctxt, clsid, "plus", Intrinsic::IntWrappingAdd);
+    intrinsic_method(ctxt, clsid, "minus", Intrinsic::IntWrappingSub);
+    intrinsic_method(ctxt, clsid, "times", Intrinsic::IntWrappingMul);
+    intrinsic_method(ctxt, clsid, "div", Intrinsic::UIntDiv);
+    intrinsic_method(ctxt, clsid, "mod", Intrinsic::UIntMod);
+
+    intrinsic_method(ctxt, clsid, "bitwiseOr", Intrinsic::IntOr);
+    intrinsic_method(ctxt, clsid, "bitwiseAnd", Intrinsic::IntAnd);
+    intrinsic_method(ctxt, clsid, "bitwiseXor", Intrinsic::IntXor);
+
+    intrinsic_method(ctxt, clsid, "shiftLeft", Intrinsic::IntShl);
+    intrinsic_method(ctxt, clsid, "shiftRight", Intrinsic::IntShr);
+
+    intrinsic_method(ctxt, clsid, "unaryPlus", Intrinsic::IntPlus);
+    intrinsic_method(ctxt, clsid, "not", Intrinsic::IntNot);
+
+    let trait_id = ctxt.vips.stringable_trait;
+    native_impl(ctxt, clsid, trait_id, "toString", stdlib::uint_to_string as *const u8);
+
     let clsid = ctxt.vips.long_class;
     native_method(ctxt, clsid, "toString", stdlib::long_to_string as *const u8);
     intrinsic_method(ctxt, clsid, "toCharUnchecked", Intrinsic::LongToChar);
     intrinsic_method(ctxt, clsid, "toInt", Intrinsic::LongToInt);
     intrinsic_method(ctxt, clsid, "toByte", Intrinsic::LongToByte);
+    intrinsic_method(ctxt, clsid, "toShort", Intrinsic::LongToShort);
+    intrinsic_method(ctxt, clsid, "toUInt", Intrinsic::LongToInt);
+    intrinsic_method(ctxt, clsid, "toULong", Intrinsic::LongToULong);
 
     intrinsic_method(ctxt, clsid, "toFloat", Intrinsic::LongToFloat);
     intrinsic_method(ctxt, clsid, "toDouble", Intrinsic::LongToDouble);
@@ -177,6 +286,16 @@ pub fn internal_functions<'ast>(ctxt: &mut SemContext<'ast>) {
     intrinsic_method(ctxt, clsid, "div", Intrinsic::LongDiv);
     intrinsic_method(ctxt, clsid, "mod", Intrinsic::LongMod);
 
+    intrinsic_method(ctxt, clsid, "checkedAdd", Intrinsic::LongCheckedAdd);
+    intrinsic_method(ctxt, clsid, "checkedSub", Intrinsic::LongCheckedSub);
+    intrinsic_method(ctxt, clsid, "checkedMul", Intrinsic::LongCheckedMul);
+    intrinsic_method(ctxt, clsid, "wrappingAdd", Intrinsic::LongWrappingAdd);
+    intrinsic_method(ctxt, clsid, "wrappingSub", Intrinsic::LongWrappingSub);
+    intrinsic_method(ctxt, clsid, "wrappingMul", Intrinsic::LongWrappingMul);
+    intrinsic_method(ctxt, clsid, "saturatingAdd", Intrinsic::LongSaturatingAdd);
+    intrinsic_method(ctxt, clsid, "saturatingSub", Intrinsic::LongSaturatingSub);
+    intrinsic_method(ctxt, clsid, "saturatingMul", Intrinsic::LongSaturatingMul);
+
     intrinsic_method(ctxt, clsid, "bitwiseOr", Intrinsic::LongOr);
     intrinsic_method(ctxt, clsid, "bitwiseAnd", Intrinsic::LongAnd);
     intrinsic_method(ctxt, clsid, "bitwiseXor", Intrinsic::LongXor);
@@ -189,6 +308,41 @@ pub fn internal_functions<'ast>(ctxt: &mut SemContext<'ast>) {
     intrinsic_method(ctxt, clsid, "unaryMinus", Intrinsic::LongNeg);
     intrinsic_method(ctxt, clsid, "not", Intrinsic::LongNot);
 
+    let trait_id = ctxt.vips.stringable_trait;
+    native_impl(ctxt, clsid, trait_id, "toString", stdlib::long_to_string as *const u8);
+
+    let clsid = ctxt.vips.ulong_class;
+    native_method(ctxt, clsid, "toString", stdlib::ulong_to_string as *const u8);
+    intrinsic_method(ctxt, clsid, "toByte", Intrinsic::LongToByte);
+    intrinsic_method(ctxt, clsid, "toShort", Intrinsic::LongToShort);
+    intrinsic_method(ctxt, clsid, "toInt", Intrinsic::LongToInt);
+    intrinsic_method(ctxt, clsid, "toUInt", Intrinsic::LongToInt);
+    intrinsic_method(ctxt, clsid, "toLong", Intrinsic::ULongToLong);
+    intrinsic_method(ctxt, clsid, "toFloat", Intrinsic::ULongToFloat);
+    intrinsic_method(ctxt, clsid, "toDouble", Intrinsic::ULongToDouble);
+
+    intrinsic_method(ctxt, clsid, "equals", Intrinsic::LongEq);
+    intrinsic_method(ctxt, clsid, "compareTo", Intrinsic::ULongCmp);
+
+    intrinsic_method(ctxt, clsid, "plus", Intrinsic::LongWrappingAdd);
+    intrinsic_method(ctxt, clsid, "minus", Intrinsic::LongWrappingSub);
+    intrinsic_method(ctxt, clsid, "times", Intrinsic::LongWrappingMul);
+    intrinsic_method(ctxt, clsid, "div", Intrinsic::ULongDiv);
+    intrinsic_method(ctxt, clsid, "mod", Intrinsic::ULongMod);
+
+    intrinsic_method(ctxt, clsid, "bitwiseOr", Intrinsic::LongOr);
+    intrinsic_method(ctxt, clsid, "bitwiseAnd", Intrinsic::LongAnd);
+    intrinsic_method(ctxt, clsid, "bitwiseXor", Intrinsic::LongXor);
+
+    intrinsic_method(ctxt, clsid, "shiftLeft", Intrinsic::LongShl);
+    intrinsic_method(ctxt, clsid, "shiftRight", Intrinsic::LongShr);
+
+    intrinsic_method(ctxt, clsid, "unaryPlus", Intrinsic::LongPlus);
+    intrinsic_method(ctxt, clsid, "not", Intrinsic::LongNot);
+
+    let trait_id = ctxt.vips.stringable_trait;
+    native_impl(ctxt, clsid, trait_id, "toString", stdlib::ulong_to_string as *const u8);
+
     let clsid = ctxt.vips.bool_class;
     intrinsic_method(ctxt, clsid, "toInt", Intrinsic::BoolToInt);
     intrinsic_method(ctxt, clsid, "toLong", Intrinsic::BoolToLong);
@@ -285,12 +439,9 @@ pub fn internal_functions<'ast>(ctxt: &mut SemContext<'ast>) {
         exception::stack_element as *const u8,
     );
 
-    let iname = ctxt.interner.intern("Thread");
-    let clsid = ctxt.sym.borrow().get_class(iname);
-
-    if let Some(clsid) = clsid {
-        native_method(ctxt, clsid, "start", stdlib::spawn_thread as *const u8);
-    }
+    let clsid = ctxt.vips.thread_class;
+    native_method(ctxt, clsid, "start", stdlib::spawn_thread as *const u8);
+    native_method(ctxt, clsid, "join", stdlib::join_thread as *const u8);
 }
 
 fn native_method<'ast>(ctxt: &mut SemContext<'ast>, clsid: ClassId, name: &str, fctptr: *const u8) {
@@ -331,7 +482,7 @@ fn intrinsic_fct<'ast>(ctxt: &mut SemContext<'ast>, name: &str, intrinsic: Intri
 
 fn internal_fct<'ast>(ctxt: &mut SemContext<'ast>, name: &str, kind: FctKind) {
     let name = ctxt.interner.intern(name);
-    let fctid = ctxt.sym.borrow().get_fct(name);
+    let fctid = std_item(ctxt, name).and_then(|sym| sym.to_fct());
 
     if let Some(fctid) = fctid {
         let mut fct = ctxt.fcts[fctid].borrow_mut();
@@ -343,6 +494,16 @@ fn internal_fct<'ast>(ctxt: &mut SemContext<'ast>, name: &str, kind: FctKind) {
     }
 }
 
+fn native_impl<'ast>(
+    ctxt: &mut SemContext<'ast>,
+    clsid: ClassId,
+    tid: TraitId,
+    name: &str,
+    fctptr: *const u8,
+) {
+    internal_impl(ctxt, clsid, tid, name, FctKind::Native(fctptr));
+}
+
 fn intrinsic_impl<'ast>(
     ctxt: &mut SemContext<'ast>,
     clsid: ClassId,
@@ -11,7 +11,7 @@ pub fn check<'ast>(ctxt: &SemContext<'ast>) {
     for fct in ctxt.fcts.iter() {
         let fct = fct.borrow();
 
-        if !fct.is_src() {
+        if !fct.is_src() || fct.skip_default_check(ctxt) {
             continue;
         }
 
@@ -75,11 +75,13 @@ pub fn returns_value(s: &Stmt) -> Result<(), Position> {
         StmtBreak(ref stmt) => Err(stmt.pos),
         StmtContinue(ref stmt) => Err(stmt.pos),
         StmtVar(ref stmt) => Err(stmt.pos),
+        StmtVarTuple(ref stmt) => Err(stmt.pos),
         StmtExpr(ref stmt) => Err(stmt.pos),
-        StmtSpawn(ref stmt) => Err(stmt.pos),
         StmtThrow(_) => Ok(()),
         StmtDefer(ref stmt) => Err(stmt.pos),
         StmtDo(ref stmt) => do_returns_value(stmt),
+        // do not report a missing return value after a syntax error
+        StmtError(_) => Ok(()),
     }
 }
 
@@ -96,6 +98,11 @@ fn block_returns_value(s: &StmtBlockType) -> Result<(), Position> {
     let mut pos = s.pos;
 
     for stmt in &s.stmts {
+        // defer does not change whether the block returns
+        if stmt.is_defer() {
+            continue;
+        }
+
         match returns_value(stmt) {
             Ok(_) => return Ok(()),
             Err(err_pos) => pos = err_pos,
@@ -183,6 +190,17 @@ mod tests {
         ok("fun f(x: int) -> int { if x == 0 { throw \"abc\"; } else { return -x; } }");
     }
 
+    #[test]
+    fn defer_returns() {
+        ok("fun f() -> int { defer g(); return 1; } fun g() {}");
+        err(
+            "fun f() -> int { if true { return 1; } defer g(); } fun g() {}",
+            pos(1, 18),
+            Msg::NoReturnValue,
+        );
+        test_always_returns("fun f() { defer g(); return; } fun g() {}", true);
+    }
+
     #[test]
     fn do_returns() {
         ok("fun f() -> int { do { return 1; } catch x: Str { return 2; } }");
@@ -3,54 +3,144 @@ use std::ptr;
 
 use baseline::stub::ensure_stub;
 use class::{self, ClassDef, ClassDefId, ClassId, ClassSize, FieldDef, TypeParams};
-use ctxt::{SemContext, StructData, StructDef, StructDefId, StructFieldDef, StructId};
+use ctxt::{EnumId, SemContext, StructData, StructDef, StructDefId, StructFieldDef, StructId};
 use mem;
 use object::Header;
 use vtable::{VTableBox, DISPLAY_SIZE};
-use ty::BuiltinType;
+use ty::{BuiltinType, TupleId, TypeListId};
 
+// replaces the type params of a class or trait with the given type arguments
 pub fn specialize_type<'ast>(
     ctxt: &SemContext<'ast>,
     ty: BuiltinType,
     type_params: &TypeParams,
 ) -> BuiltinType {
-    match ty {
-        BuiltinType::ClassTypeParam(_, id) => type_params[id.idx()],
-
+    replace_type_params(ctxt, ty, &|ty| match ty {
+        BuiltinType::ClassTypeParam(_, id) => Some(type_params[id.idx()]),
+        BuiltinType::TraitTypeParam(_, id) => Some(type_params[id.idx()]),
         BuiltinType::FctTypeParam(_, _) => panic!("no fct type params expected"),
+        _ => None,
+    })
+}
 
-        BuiltinType::Struct(struct_id, list_id) => {
-            let params = ctxt.lists.borrow().get(list_id);
+// replaces only the type params of a trait, e.g. in the signature of a trait method
+pub fn specialize_for_trait<'ast>(
+    ctxt: &SemContext<'ast>,
+    ty: BuiltinType,
+    type_params: &TypeParams,
+) -> BuiltinType {
+    replace_type_params(ctxt, ty, &|ty| match ty {
+        BuiltinType::TraitTypeParam(_, id) => Some(type_params[id.idx()]),
+        _ => None,
+    })
+}
 
-            let params: TypeParams = params
-                .iter()
-                .map(|t| specialize_type(ctxt, t, type_params))
-                .collect::<Vec<_>>()
-                .into();
+// bounds may only refer to the type params of their own class, function
+// or trait, these are replaced by the type arguments at the use site
+pub fn specialize_bound<'ast>(
+    ctxt: &SemContext<'ast>,
+    ty: BuiltinType,
+    type_params: &TypeParams,
+) -> BuiltinType {
+    replace_type_params(ctxt, ty, &|ty| match ty {
+        BuiltinType::ClassTypeParam(_, id)
+        | BuiltinType::TraitTypeParam(_, id)
+        | BuiltinType::FctTypeParam(_, id) => Some(type_params[id.idx()]),
+        _ => None,
+    })
+}
 
-            let list_id = ctxt.lists.borrow_mut().insert(params);
+// replaces `Self` with the class of an impl, used for the copies of
+// a trait's default methods
+pub fn specialize_self<'ast>(
+    ctxt: &SemContext<'ast>,
+    ty: BuiltinType,
+    self_ty: BuiltinType,
+) -> BuiltinType {
+    replace_type_params(ctxt, ty, &|ty| match ty {
+        BuiltinType::This => Some(self_ty),
+        _ => None,
+    })
+}
 
+fn replace_type_params<'ast>(
+    ctxt: &SemContext<'ast>,
+    ty: BuiltinType,
+    replace: &Fn(BuiltinType) -> Option<BuiltinType>,
+) -> BuiltinType {
+    if let Some(ty) = replace(ty) {
+        return ty;
+    }
+
+    match ty {
+        BuiltinType::Struct(struct_id, list_id) => {
+            let list_id = replace_in_list(ctxt, list_id, replace);
             BuiltinType::Struct(struct_id, list_id)
         }
 
         BuiltinType::Class(cls_id, list_id) => {
-            let params = ctxt.lists.borrow().get(list_id);
+            let list_id = replace_in_list(ctxt, list_id, replace);
+            BuiltinType::Class(cls_id, list_id)
+        }
 
-            let params: TypeParams = params
+        BuiltinType::Trait(trait_id, list_id) => {
+            let list_id = replace_in_list(ctxt, list_id, replace);
+            BuiltinType::Trait(trait_id, list_id)
+        }
+
+        BuiltinType::Lambda(lambda_id) => {
+            let lambda = ctxt.lambda_types.borrow().get(lambda_id);
+
+            let params: Vec<_> = lambda
+                .params()
                 .iter()
-                .map(|t| specialize_type(ctxt, t, type_params))
-                .collect::<Vec<_>>()
-                .into();
+                .map(|&t| replace_type_params(ctxt, t, replace))
+                .collect();
+            let ret = replace_type_params(ctxt, lambda.ret(), replace);
 
-            let list_id = ctxt.lists.borrow_mut().insert(params);
+            let lambda_id = ctxt.lambda_types.borrow_mut().insert(params, ret);
 
-            BuiltinType::Class(cls_id, list_id)
+            BuiltinType::Lambda(lambda_id)
+        }
+
+        BuiltinType::Tuple(tuple_id) => {
+            let elements = ctxt.tuples.borrow().get(tuple_id);
+
+            let elements: Vec<_> = elements
+                .iter()
+                .map(|&t| replace_type_params(ctxt, t, replace))
+                .collect();
+
+            let tuple_id = ctxt.tuples.borrow_mut().insert(elements);
+
+            BuiltinType::Tuple(tuple_id)
+        }
+
+        BuiltinType::Nullable(_) => {
+            let ty = replace_type_params(ctxt, ty.non_nullable(ctxt), replace);
+            BuiltinType::nullable(ctxt, ty)
         }
 
         _ => ty,
     }
 }
 
+fn replace_in_list<'ast>(
+    ctxt: &SemContext<'ast>,
+    list_id: TypeListId,
+    replace: &Fn(BuiltinType) -> Option<BuiltinType>,
+) -> TypeListId {
+    let params = ctxt.lists.borrow().get(list_id);
+
+    let params: TypeParams = params
+        .iter()
+        .map(|t| replace_type_params(ctxt, t, replace))
+        .collect::<Vec<_>>()
+        .into();
+
+    ctxt.lists.borrow_mut().insert(params)
+}
+
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub enum SpecializeFor {
     Fct,
@@ -71,6 +161,19 @@ pub fn specialize_struct_id_params(
     specialize_struct(ctxt, &*struc, type_params)
 }
 
+pub fn specialize_struct_ty(ctxt: &SemContext, ty: BuiltinType) -> StructDefId {
+    match ty {
+        BuiltinType::Struct(struct_id, list_id) => {
+            let params = ctxt.lists.borrow().get(list_id);
+            specialize_struct_id_params(ctxt, struct_id, params)
+        }
+
+        BuiltinType::Tuple(tuple_id) => specialize_tuple(ctxt, tuple_id),
+
+        _ => unreachable!(),
+    }
+}
+
 pub fn specialize_struct(
     ctxt: &SemContext,
     struc: &StructData,
@@ -103,13 +206,46 @@ fn create_specialized_struct(
         ref_fields: Vec::new(),
     });
 
+    let types = struc
+        .fields
+        .iter()
+        .map(|f| specialize_type(ctxt, f.ty, &type_params))
+        .collect::<Vec<_>>();
+
+    layout_struct_def(ctxt, id, &types);
+
+    id
+}
+
+pub fn specialize_tuple(ctxt: &SemContext, tuple_id: TupleId) -> StructDefId {
+    if let Some(id) = ctxt.tuples.borrow().struct_def(tuple_id) {
+        return id;
+    }
+
+    let id: StructDefId = ctxt.struct_defs.len().into();
+    ctxt.tuples.borrow_mut().set_struct_def(tuple_id, id);
+
+    ctxt.struct_defs.push(StructDef {
+        size: 0,
+        align: 0,
+        fields: Vec::new(),
+        ref_fields: Vec::new(),
+    });
+
+    let elements = ctxt.tuples.borrow().get(tuple_id);
+    layout_struct_def(ctxt, id, &elements);
+
+    id
+}
+
+// places values of the given types one after another, each at its natural alignment
+fn layout_struct_def(ctxt: &SemContext, id: StructDefId, types: &[BuiltinType]) {
     let mut size = 0;
     let mut align = 0;
-    let mut fields = Vec::with_capacity(struc.fields.len());
+    let mut fields = Vec::with_capacity(types.len());
     let mut ref_fields = Vec::new();
 
-    for f in &struc.fields {
-        let ty = specialize_type(ctxt, f.ty, &type_params);
+    for &ty in types {
         debug_assert!(!ty.contains_type_param(ctxt));
 
         let field_size = ty.size(ctxt);
@@ -124,18 +260,28 @@ fn create_specialized_struct(
         size = offset + field_size;
         align = max(align, field_align);
 
-        if ty.reference_type() {
-            ref_fields.push(offset);
-        }
+        add_ref_fields(ctxt, &mut ref_fields, ty, offset);
     }
 
     let mut struct_def = ctxt.struct_defs[id].borrow_mut();
-    struct_def.size = size;
+    // pad size such that struct values can be stored consecutively in arrays
+    struct_def.size = mem::align_i32(size, align);
     struct_def.align = align;
     struct_def.fields = fields;
     struct_def.ref_fields = ref_fields;
+}
 
-    id
+// records offsets of references stored in a value of type `ty` at `offset`,
+// structs are embedded and contribute all their reference fields
+pub fn add_ref_fields(ctxt: &SemContext, ref_fields: &mut Vec<i32>, ty: BuiltinType, offset: i32) {
+    if ty.is_struct() {
+        let sid = specialize_struct_ty(ctxt, ty);
+        let struc = ctxt.struct_defs[sid].borrow();
+
+        ref_fields.extend(struc.ref_fields.iter().map(|&field| offset + field));
+    } else if ty.reference_type() {
+        ref_fields.push(offset);
+    }
 }
 
 pub fn specialize_class_id(ctxt: &SemContext, cls_id: ClassId) -> ClassDefId {
@@ -208,10 +354,19 @@ fn create_specialized_class(
         ref_fields = Vec::new();
 
         size = if cls.is_array {
-            if type_params[0].reference_type() {
+            let element_type = type_params[0];
+
+            if element_type.reference_type() {
                 ClassSize::ObjArray
             } else {
-                ClassSize::Array(type_params[0].size(ctxt))
+                // references in struct elements are stored relative to each element
+                add_ref_fields(ctxt, &mut ref_fields, element_type, 0);
+
+                if ref_fields.is_empty() {
+                    ClassSize::Array(element_type.size(ctxt))
+                } else {
+                    ClassSize::StructArray(element_type.size(ctxt))
+                }
             }
         } else {
             ClassSize::Str
@@ -257,9 +412,7 @@ fn create_specialized_class(
 
             csize = offset + field_size;
 
-            if ty.reference_type() {
-                ref_fields.push(offset);
-            }
+            add_ref_fields(ctxt, &mut ref_fields, ty, offset);
         }
 
         size = ClassSize::Fixed(mem::align_i32(csize, mem::ptr_width()));
@@ -283,6 +436,124 @@ fn create_specialized_class(
     id
 }
 
+// layout of closure objects: captured values are stored as fields after the header
+pub fn lambda_field_offsets(ctxt: &SemContext, captures: &[BuiltinType]) -> (Vec<i32>, i32) {
+    let mut offsets = Vec::with_capacity(captures.len());
+    let mut csize = Header::size();
+
+    for ty in captures {
+        let offset = mem::align_i32(csize, ty.align(ctxt));
+        offsets.push(offset);
+
+        csize = offset + ty.size(ctxt);
+    }
+
+    (offsets, mem::align_i32(csize, mem::ptr_width()))
+}
+
+pub fn specialize_lambda(
+    ctxt: &SemContext,
+    captures: &[BuiltinType],
+    fct_ptr: *const u8,
+) -> ClassDefId {
+    let id: ClassDefId = ctxt.class_defs.len().into();
+    let (offsets, size) = lambda_field_offsets(ctxt, captures);
+
+    let mut fields = Vec::with_capacity(captures.len());
+    let mut ref_fields = Vec::new();
+
+    for (&ty, &offset) in captures.iter().zip(&offsets) {
+        debug_assert!(!ty.contains_type_param(ctxt));
+
+        fields.push(FieldDef {
+            offset: offset,
+            ty: ty,
+        });
+
+        add_ref_fields(ctxt, &mut ref_fields, ty, offset);
+    }
+
+    ctxt.class_defs.push(ClassDef {
+        id: id,
+        cls_id: ctxt.vips.object_class,
+        type_params: TypeParams::empty(),
+        parent_id: None,
+        size: ClassSize::Fixed(size),
+        fields: fields,
+        ref_fields: ref_fields,
+        vtable: None,
+    });
+
+    let mut cls_def = ctxt.class_defs[id].borrow_mut();
+
+    // the only vtable entry is the code of the lambda
+    let clsptr = (&*cls_def) as *const class::ClassDef as *mut class::ClassDef;
+    let vtable = VTableBox::new(clsptr, &[fct_ptr as usize]);
+    cls_def.vtable = Some(vtable);
+
+    ensure_display(ctxt, &mut cls_def);
+
+    id
+}
+
+// layout of enum objects: the first field stores the tag (the index of the variant),
+// the variant's values are stored in the following fields
+pub fn specialize_enum_variant(ctxt: &SemContext, enum_id: EnumId, variant: usize) -> ClassDefId {
+    let xenum = ctxt.enums[enum_id].borrow();
+    let variant = &xenum.variants[variant];
+
+    if let Some(id) = *variant.cls_def.borrow() {
+        return id;
+    }
+
+    let id: ClassDefId = ctxt.class_defs.len().into();
+
+    let mut fields = Vec::with_capacity(variant.types.len() + 1);
+    let mut ref_fields = Vec::new();
+
+    fields.push(FieldDef {
+        offset: Header::size(),
+        ty: BuiltinType::Int,
+    });
+
+    let mut csize = Header::size() + BuiltinType::Int.size(ctxt);
+
+    for &ty in &variant.types {
+        let offset = mem::align_i32(csize, ty.align(ctxt));
+        fields.push(FieldDef {
+            offset: offset,
+            ty: ty,
+        });
+
+        csize = offset + ty.size(ctxt);
+
+        add_ref_fields(ctxt, &mut ref_fields, ty, offset);
+    }
+
+    ctxt.class_defs.push(ClassDef {
+        id: id,
+        cls_id: ctxt.vips.object_class,
+        type_params: TypeParams::empty(),
+        parent_id: None,
+        size: ClassSize::Fixed(mem::align_i32(csize, mem::ptr_width())),
+        fields: fields,
+        ref_fields: ref_fields,
+        vtable: None,
+    });
+
+    *variant.cls_def.borrow_mut() = Some(id);
+
+    let mut cls_def = ctxt.class_defs[id].borrow_mut();
+
+    let clsptr = (&*cls_def) as *const class::ClassDef as *mut class::ClassDef;
+    let vtable = VTableBox::new(clsptr, &[]);
+    cls_def.vtable = Some(vtable);
+
+    ensure_display(ctxt, &mut cls_def);
+
+    id
+}
+
 fn ensure_display<'ast>(ctxt: &SemContext<'ast>, cls_def: &mut ClassDef) -> usize {
     let vtable = cls_def.vtable.as_mut().unwrap();
 
@@ -32,6 +32,13 @@ impl<'x, 'ast> StructCheck<'x, 'ast> {
 }
 
 impl<'x, 'ast> Visitor<'ast> for StructCheck<'x, 'ast> {
+    fn visit_file(&mut self, f: &'ast ast::File) {
+        let module = self.ctxt.file_module(f);
+        self.ctxt.sym.borrow_mut().enter_module(module);
+
+        visit::walk_file(self, f);
+    }
+
     fn visit_struct(&mut self, s: &'ast ast::Struct) {
         self.struct_id = Some(*self.map_struct_defs.get(s.id).unwrap());
 
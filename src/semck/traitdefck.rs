@@ -1,8 +1,13 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+
 use dora_parser::ast;
 use dora_parser::ast::visit::{self, Visitor};
-use ctxt::{Fct, FctId, FctKind, FctParent, NodeMap, SemContext, TraitId};
+use ctxt::{Fct, FctId, FctKind, FctParent, FctSrc, NodeMap, SemContext, TraitId};
 use dora_parser::error::msg::Msg;
 use dora_parser::lexer::position::Position;
+use semck;
+use sym::Sym;
 use ty::BuiltinType;
 
 pub fn check<'ast>(ctxt: &mut SemContext<'ast>, map_trait_defs: &NodeMap<TraitId>) {
@@ -28,12 +33,84 @@ impl<'x, 'ast> TraitCheck<'x, 'ast> {
     fn check(&mut self) {
         self.visit_ast(self.ast);
     }
+
+    fn check_type_params(&mut self, t: &'ast ast::Trait) {
+        let type_params = match t.type_params {
+            Some(ref type_params) => type_params,
+            None => return,
+        };
+
+        if type_params.is_empty() {
+            report(self.ctxt, t.pos, Msg::TypeParamsExpected);
+            return;
+        }
+
+        let trait_id = self.trait_id.unwrap();
+        let mut names = HashSet::new();
+
+        self.ctxt.sym.borrow_mut().push_level();
+
+        for (id, type_param) in type_params.iter().enumerate() {
+            if !names.insert(type_param.name) {
+                let name = self.ctxt.interner.str(type_param.name).to_string();
+                report(self.ctxt, type_param.pos, Msg::TypeParamNameNotUnique(name));
+            }
+
+            let sym = Sym::SymTraitTypeParam(trait_id, id.into());
+            self.ctxt.sym.borrow_mut().insert(type_param.name, sym);
+        }
+
+        for (id, type_param) in type_params.iter().enumerate() {
+            for bound in &type_param.bounds {
+                let ty = semck::read_type(self.ctxt, bound);
+
+                let mut xtrait = self.ctxt.traits[trait_id].borrow_mut();
+                let tp = &mut xtrait.type_params[id];
+
+                match ty {
+                    Some(BuiltinType::Class(cls_id, _)) => {
+                        if tp.class_bound.is_none() {
+                            tp.class_bound = Some(cls_id);
+                        } else {
+                            report(self.ctxt, type_param.pos, Msg::MultipleClassBounds);
+                        }
+                    }
+
+                    Some(BuiltinType::Trait(bound_id, list_id)) => {
+                        let params = self.ctxt.lists.borrow().get(list_id);
+
+                        if tp.trait_bounds.insert(bound_id, params).is_some() {
+                            report(self.ctxt, type_param.pos, Msg::DuplicateTraitBound);
+                        }
+                    }
+
+                    None => {
+                        // unknown type, error is already thrown
+                    }
+
+                    _ => {
+                        report(self.ctxt, bound.pos(), Msg::BoundExpected);
+                    }
+                }
+            }
+        }
+
+        self.ctxt.sym.borrow_mut().pop_level();
+    }
 }
 
 impl<'x, 'ast> Visitor<'ast> for TraitCheck<'x, 'ast> {
+    fn visit_file(&mut self, f: &'ast ast::File) {
+        let module = self.ctxt.file_module(f);
+        self.ctxt.sym.borrow_mut().enter_module(module);
+
+        visit::walk_file(self, f);
+    }
+
     fn visit_trait(&mut self, t: &'ast ast::Trait) {
         self.trait_id = Some(*self.map_trait_defs.get(t.id).unwrap());
 
+        self.check_type_params(t);
         visit::walk_trait(self, t);
 
         self.trait_id = None;
@@ -44,15 +121,19 @@ impl<'x, 'ast> Visitor<'ast> for TraitCheck<'x, 'ast> {
             return;
         }
 
-        if f.block.is_some() {
-            report(self.ctxt, f.pos, Msg::TraitMethodWithBody);
-        }
+        // methods with a body are defaults for impls that don't define them
+        let kind = if f.block.is_some() {
+            FctKind::Source(RefCell::new(FctSrc::new()))
+        } else {
+            FctKind::Definition
+        };
 
         let fct = Fct {
             id: FctId(0),
             ast: f,
             pos: f.pos,
             name: f.name,
+            module: self.ctxt.traits[self.trait_id.unwrap()].borrow().module,
             param_types: Vec::new(),
             return_type: BuiltinType::Unit,
             parent: FctParent::Trait(self.trait_id.unwrap()),
@@ -70,9 +151,10 @@ impl<'x, 'ast> Visitor<'ast> for TraitCheck<'x, 'ast> {
             vtable_index: None,
             initialized: false,
             impl_for: None,
+            is_default: false,
 
             type_params: Vec::new(),
-            kind: FctKind::Definition,
+            kind: kind,
         };
 
         let fctid = self.ctxt.add_fct(fct);
@@ -93,10 +175,32 @@ mod tests {
 
     #[test]
     fn trait_method_with_body() {
+        ok("trait Foo { fun foo() -> int { return 1; } }");
+        ok("trait Foo {
+                fun foo() -> int;
+                fun bar() -> int { return self.foo() + 1; }
+            }");
+        ok("trait Foo {
+                fun foo(other: Self) -> bool;
+                fun bar(other: Self) -> Self {
+                    if self.foo(other) { return self; }
+                    return other;
+                }
+            }");
+        ok("trait Foo<T> {
+                fun foo() -> T;
+                fun bar() -> T { return self.foo(); }
+            }");
+
         err(
-            "trait Foo { fun foo() -> int { return 1; } }",
-            pos(1, 13),
-            Msg::TraitMethodWithBody,
+            "trait Foo { fun foo() -> int { return true; } }",
+            pos(1, 32),
+            Msg::ReturnType("int".into(), "bool".into()),
+        );
+        err(
+            "trait Foo { fun foo() { self.bar(); } }",
+            pos(1, 29),
+            Msg::UnknownMethod("Self".into(), "bar".into(), Vec::new()),
         );
     }
 
@@ -138,4 +242,18 @@ mod tests {
             Msg::MethodExists("Foo".into(), "foo".into(), pos(2, 13)),
         );
     }
+
+    #[test]
+    fn generic_trait() {
+        ok("trait Foo<T> { fun foo() -> T; }");
+        ok("trait Foo<A, B> { fun foo(a: A) -> B; }");
+        ok("trait Foo<T: Equals> { fun foo(a: T) -> bool; }");
+
+        err(
+            "trait Foo<T, T> {}",
+            pos(1, 14),
+            Msg::TypeParamNameNotUnique("T".into()),
+        );
+        err("trait Foo<> {}", pos(1, 1), Msg::TypeParamsExpected);
+    }
 }
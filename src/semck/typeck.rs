@@ -1,11 +1,14 @@
 use std::{f32, f64};
+use std::cell::RefCell;
+use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
+use std::mem;
 use std::rc::Rc;
 
 use ctxt;
-use ctxt::{CallType, ConstData, ConstValue, ConvInfo, Fct, FctId, FctParent, FctSrc, ForTypeInfo,
-           IdentType, SemContext, TraitId};
-use class::{ClassId, TypeParams};
+use ctxt::{CallType, ConstId, ConstValue, ConvInfo, EnumId, Fct, FctId, FctKind, FctParent,
+           FctSrc, ForTypeInfo, IdentType, Intrinsic, LambdaCallee, SemContext, TraitId, VarId};
+use class::{ClassId, FieldId, TypeParams};
 use dora_parser::error::msg::Msg;
 
 use dora_parser::ast::*;
@@ -15,15 +18,18 @@ use dora_parser::ast::visit::Visitor;
 use dora_parser::interner::Name;
 use dora_parser::lexer::position::Position;
 use dora_parser::lexer::token::{FloatSuffix, IntBase, IntSuffix};
-use semck::specialize::specialize_type;
-use sym::Sym::SymClass;
+use semck;
+use semck::returnck;
+use semck::specialize::{specialize_bound, specialize_for_trait, specialize_type};
+use sym::Sym::{SymClass, SymConst, SymEnum};
 use ty::BuiltinType;
 
 pub fn check<'a, 'ast>(ctxt: &SemContext<'ast>) {
     for fct in ctxt.fcts.iter() {
         let fct = fct.borrow();
 
-        if !fct.is_src() {
+        // lambdas are already checked as part of their enclosing function
+        if !fct.is_src() || fct.is_lambda() || fct.skip_default_check(ctxt) {
             continue;
         }
 
@@ -31,32 +37,36 @@ pub fn check<'a, 'ast>(ctxt: &SemContext<'ast>) {
         let mut src = src.borrow_mut();
         let ast = fct.ast;
 
-        let mut typeck = TypeCheck {
-            ctxt: ctxt,
-            fct: &fct,
-            src: &mut src,
-            ast: ast,
-            expr_type: BuiltinType::Unit,
-            negative_expr_id: NodeId(0),
-        };
-
-        typeck.check();
-    }
-
-    for xconst in ctxt.consts.iter() {
-        let mut xconst = xconst.borrow_mut();
-
-        let (_, value) = {
-            let mut constck = ConstCheck {
+        let lambdas = {
+            let mut typeck = TypeCheck {
                 ctxt: ctxt,
-                xconst: &*xconst,
+                fct: &fct,
+                src: &mut src,
+                ast: ast,
+                expr_type: BuiltinType::Unit,
                 negative_expr_id: NodeId(0),
+                return_type: fct.return_type,
+                return_data_type: ast.return_type.as_ref(),
+                lambdas: Vec::new(),
+                non_nil: HashSet::new(),
+                declared_vars: HashMap::new(),
             };
 
-            constck.check_expr(xconst.expr)
+            typeck.check();
+            typeck.lambdas
         };
 
-        xconst.value = value;
+        create_lambda_fcts(ctxt, &*fct, &mut src, &lambdas);
+    }
+
+    let mut constck = ConstCheck {
+        ctxt: ctxt,
+        states: HashMap::new(),
+        negative_expr_id: NodeId(0),
+    };
+
+    for id in 0..ctxt.consts.len() {
+        constck.check_const(id.into());
     }
 }
 
@@ -67,28 +77,134 @@ struct TypeCheck<'a, 'ast: 'a> {
     ast: &'ast Function,
     expr_type: BuiltinType,
     negative_expr_id: NodeId,
+
+    // return type of innermost function or lambda
+    return_type: BuiltinType,
+    return_data_type: Option<&'ast Type>,
+    lambdas: Vec<&'ast Function>,
+
+    // variables of nullable type known to be non-nil at the current point
+    non_nil: HashSet<VarId>,
+
+    // types variables were declared with, diagnostics name their aliases
+    declared_vars: HashMap<VarId, &'ast Type>,
 }
 
 impl<'a, 'ast> TypeCheck<'a, 'ast> {
     fn check(&mut self) {
+        self.ctxt.sym.borrow_mut().enter_module(self.fct.module);
+        self.declare_params(&self.ast.params);
         self.visit_fct(self.ast);
+
+        if self.fct.ctor.is() {
+            self.check_ctor_fields();
+        }
+    }
+
+    // fields without default value need to be assigned by every ctor,
+    // unless the ctor delegates to another ctor of the same class
+    fn check_ctor_fields(&self) {
+        let cls_id = self.fct.cls_id();
+        let cls = self.ctxt.classes[cls_id].borrow();
+
+        if cls.internal {
+            return;
+        }
+
+        let mut assigned = Vec::new();
+
+        if let Some(ref block) = self.ast.block {
+            if self.assigned_fields(block, cls_id, &mut assigned) {
+                return;
+            }
+        }
+
+        for field in &cls.fields {
+            if field.ty.has_default_value(self.ctxt) || assigned.contains(&field.id) {
+                continue;
+            }
+
+            let name = self.ctxt.interner.str(field.name).to_string();
+            let ty = field
+                .alias
+                .clone()
+                .unwrap_or_else(|| field.ty.name(self.ctxt));
+            let msg = Msg::MissingInitialization(name, ty);
+            self.ctxt.diag.borrow_mut().report(self.ast.pos, msg);
+        }
+    }
+
+    // collects fields assigned unconditionally in the ctor body, returns true
+    // when the ctor delegates to another ctor with `: self(...)`
+    fn assigned_fields(&self, s: &'ast Stmt, cls_id: ClassId, fields: &mut Vec<FieldId>) -> bool {
+        match *s {
+            StmtBlock(ref block) => {
+                for stmt in &block.stmts {
+                    if self.assigned_fields(stmt, cls_id, fields) {
+                        return true;
+                    }
+                }
+            }
+
+            StmtExpr(ref stmt) => match *stmt.expr {
+                ExprDelegation(ref e) if e.ty == DelegationType::This => return true,
+
+                ExprAssign(ref e) => match *e.lhs {
+                    ExprField(ref field) if field.object.is_this() => {
+                        if let Some(&IdentType::Field(ty, field_id)) =
+                            self.src.map_idents.get(field.id)
+                        {
+                            if ty.cls_id(self.ctxt) == Some(cls_id) {
+                                fields.push(field_id);
+                            }
+                        }
+                    }
+
+                    _ => {}
+                },
+
+                _ => {}
+            },
+
+            _ => {}
+        }
+
+        false
+    }
+
+    fn declare_params(&mut self, params: &'ast [Param]) {
+        for param in params.iter().filter(|param| !param.variadic) {
+            if let Some(&var_id) = self.src.map_vars.get(param.id) {
+                self.declared_vars.insert(var_id, &param.data_type);
+            }
+        }
     }
 
     fn check_stmt_var(&mut self, s: &'ast StmtVarType) {
         let var = *self.src.map_vars.get(s.id).unwrap();
 
-        let expr_type = s.expr.as_ref().map(|expr| {
-            self.visit_expr(&expr);
-            self.expr_type
-        });
+        if let Some(ref data_type) = s.data_type {
+            self.declared_vars.insert(var, data_type);
+        }
 
-        let defined_type = if let Some(_) = s.data_type {
+        let declared_type = if let Some(_) = s.data_type {
             let ty = self.src.vars[var].ty;
             if ty == BuiltinType::Unit {
                 None
             } else {
                 Some(ty)
             }
+        } else {
+            None
+        };
+
+        let expr_type = s.expr.as_ref().map(|expr| {
+            self.visit_expr_expecting(&expr, declared_type);
+            self.expr_type
+        });
+
+        let defined_type = if let Some(_) = s.data_type {
+            declared_type
         } else {
             expr_type
         };
@@ -113,24 +229,109 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         if let Some(expr_type) = expr_type {
             if !expr_type.is_error() && !defined_type.allows(self.ctxt, expr_type) {
                 let name = self.ctxt.interner.str(s.name).to_string();
-                let defined_type = defined_type.name(self.ctxt);
-                let expr_type = expr_type.name(self.ctxt);
+                let defined_type = self.declared_type_name(s.data_type.as_ref(), defined_type);
+                let expr_type = self.expr_type_name(s.expr.as_ref().unwrap(), expr_type);
                 let msg = Msg::AssignType(name, defined_type, expr_type);
                 self.ctxt.diag.borrow_mut().report(s.pos, msg);
             }
 
+            self.narrow_assigned(var, expr_type);
+
         // let variable binding needs to be assigned
         } else if !s.reassignable {
             self.ctxt
                 .diag
                 .borrow_mut()
                 .report(s.pos, Msg::LetMissingInitialization);
+
+        // var without initializer would hold zero, which isn't a value of e.g. `Foo`
+        } else if !defined_type.has_default_value(self.ctxt) {
+            let name = self.ctxt.interner.str(s.name).to_string();
+            let defined_type = self.declared_type_name(s.data_type.as_ref(), defined_type);
+            let msg = Msg::MissingInitialization(name, defined_type);
+            self.ctxt.diag.borrow_mut().report(s.pos, msg);
+        }
+    }
+
+    fn check_stmt_var_tuple(&mut self, s: &'ast StmtVarTupleType) {
+        self.visit_expr(&s.expr);
+        let expr_type = self.expr_type;
+
+        let ty = if let Some(ref data_type) = s.data_type {
+            let defined_type = self.src.ty(data_type.id());
+
+            if !expr_type.is_error() && !defined_type.allows(self.ctxt, expr_type) {
+                let name = self.tuple_pattern_name(s);
+                let defined_type = defined_type.name(self.ctxt);
+                let expr_type = expr_type.name(self.ctxt);
+                let msg = Msg::AssignType(name, defined_type, expr_type);
+                self.ctxt.diag.borrow_mut().report(s.pos, msg);
+            }
+
+            defined_type
+        } else {
+            expr_type
+        };
+
+        let elements = match ty {
+            BuiltinType::Tuple(tuple_id) => Some(self.ctxt.tuples.borrow().get(tuple_id)),
+
+            BuiltinType::Error => None,
+
+            _ => {
+                let msg = Msg::TupleExpected(ty.name(self.ctxt));
+                self.ctxt.diag.borrow_mut().report(s.expr.pos(), msg);
+                None
+            }
+        };
+
+        if let Some(ref elements) = elements {
+            if elements.len() != s.vars.len() {
+                let msg = Msg::TuplePatternLength(ty.name(self.ctxt), elements.len(), s.vars.len());
+                self.ctxt.diag.borrow_mut().report(s.pos, msg);
+            }
+        }
+
+        for (idx, var) in s.vars.iter().enumerate() {
+            if var.name.is_none() {
+                continue;
+            }
+
+            let var_ty = elements
+                .as_ref()
+                .and_then(|elements| elements.get(idx).cloned())
+                .unwrap_or(BuiltinType::Error);
+
+            let var_id = *self.src.map_vars.get(var.id).unwrap();
+            self.src.vars[var_id].ty = var_ty;
+        }
+    }
+
+    fn tuple_pattern_name(&self, s: &'ast StmtVarTupleType) -> String {
+        let names = s.vars
+            .iter()
+            .map(|var| match var.name {
+                Some(name) => self.ctxt.interner.str(name).to_string(),
+                None => "_".into(),
+            })
+            .collect::<Vec<_>>();
+
+        format!("({})", names.join(", "))
+    }
+
+    // like `visit_expr` but lets calls infer missing type params from the
+    // type the expression is expected to have
+    fn visit_expr_expecting(&mut self, e: &'ast Expr, expected_type: Option<BuiltinType>) {
+        match *e {
+            ExprCall(ref expr) => self.check_expr_call(expr, false, expected_type),
+            ExprTry(ref expr) => self.check_expr_try(expr, expected_type),
+            _ => self.visit_expr(e),
         }
     }
 
     fn check_stmt_for(&mut self, s: &'ast StmtForType) {
         self.visit_expr(&s.expr);
-        let object_type = self.expr_type;
+        let object_type = self.check_non_nullable(self.expr_type, s.expr.pos());
 
         let name = self.ctxt.interner.intern("makeIterator");
 
@@ -149,10 +350,15 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                 // find fct next() & hasNext() in iterator-trait
                 let has_next_name = self.ctxt.interner.intern("hasNext");
                 let next_name = self.ctxt.interner.intern("next");
+                let trait_params = make_iterator_ret
+                    .trait_type_params(self.ctxt, iterator_trait_id)
+                    .unwrap();
                 let trai = self.ctxt.traits[iterator_trait_id].borrow();
-                let next_id = trai.find_method(self.ctxt, false, next_name, None, &[])
+                let next_id = trai
+                    .find_method(self.ctxt, false, next_name, None, &trait_params, &[])
                     .expect("next() not found");
-                let has_next_id = trai.find_method(self.ctxt, false, has_next_name, None, &[])
+                let has_next_id = trai
+                    .find_method(self.ctxt, false, has_next_name, None, &trait_params, &[])
                     .expect("hasNext() not found");
 
                 // find impl for ret that implements Iterator
@@ -172,9 +378,11 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                     .find_implements(self.ctxt, has_next_id)
                     .expect("hasNext() impl not found");
 
-                // get return type of next() in impl
+                // get return type of next() in impl, specialized for the
+                // type params of the iterator class
                 let fct = self.ctxt.fcts[impl_next_id].borrow();
-                let ret = fct.return_type;
+                let type_params = make_iterator_ret.type_params(self.ctxt);
+                let ret = specialize_type(self.ctxt, fct.return_type, &type_params);
 
                 // set variable type to return type of next
                 let var_id = *self.src.map_vars.get(s.id).unwrap();
@@ -197,10 +405,14 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
             }
         }
 
+        self.forget_assigned_in(&s.block);
         self.visit_stmt(&s.block);
+        self.forget_assigned_in(&s.block);
     }
 
     fn check_stmt_while(&mut self, s: &'ast StmtWhileType) {
+        // the condition is evaluated again after every iteration
+        self.forget_assigned_in(&s.block);
         self.visit_expr(&s.cond);
 
         if self.expr_type != BuiltinType::Bool {
@@ -209,7 +421,17 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
             self.ctxt.diag.borrow_mut().report(s.pos, msg);
         }
 
+        let before = self.non_nil.clone();
+        let (when_true, _) = self.nil_checks(&s.cond);
+        self.non_nil.extend(when_true);
+        self.visit_stmt(&s.block);
+        self.non_nil = before;
+    }
+
+    fn check_stmt_loop(&mut self, s: &'ast StmtLoopType) {
+        self.forget_assigned_in(&s.block);
         self.visit_stmt(&s.block);
+        self.forget_assigned_in(&s.block);
     }
 
     fn check_stmt_if(&mut self, s: &'ast StmtIfType) {
@@ -221,33 +443,115 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
             self.ctxt.diag.borrow_mut().report(s.pos, msg);
         }
 
+        let (when_true, when_false) = self.nil_checks(&s.cond);
+        let before = self.non_nil.clone();
+
+        self.non_nil.extend(when_true);
         self.visit_stmt(&s.then_block);
+        let after_then = mem::replace(&mut self.non_nil, before);
+
+        self.non_nil.extend(when_false);
 
         if let Some(ref else_block) = s.else_block {
             self.visit_stmt(else_block);
         }
+
+        // only branches that fall through to the next statement contribute
+        if always_exits(&s.then_block) {
+            return;
+        }
+
+        let else_exits = s.else_block.as_ref().map_or(false, |b| always_exits(b));
+
+        if else_exits {
+            self.non_nil = after_then;
+        } else {
+            self.non_nil = self.non_nil.intersection(&after_then).cloned().collect();
+        }
+    }
+
+    // names a declared type by the alias it was written with
+    fn declared_type_name(&self, data_type: Option<&'ast Type>, ty: BuiltinType) -> String {
+        data_type
+            .and_then(|data_type| semck::alias_name(self.ctxt, data_type))
+            .unwrap_or_else(|| ty.name(self.ctxt))
+    }
+
+    // names the type of an expression by the alias its variable, field,
+    // global or function was declared with
+    fn expr_type_name(&self, e: &'ast Expr, ty: BuiltinType) -> String {
+        let ident = match *e {
+            ExprIdent(_) | ExprField(_) => self.src.map_idents.get(e.id()).cloned(),
+            _ => None,
+        };
+
+        let alias = match ident {
+            Some(IdentType::Var(var_id)) => self.declared_vars
+                .get(&var_id)
+                .and_then(|data_type| semck::alias_name(self.ctxt, data_type)),
+
+            Some(IdentType::Global(global_id)) => {
+                let global = self.ctxt.globals[global_id].borrow();
+                semck::alias_name(self.ctxt, &global.ast.data_type)
+            }
+
+            Some(IdentType::Field(cls_ty, field_id)) => cls_ty.cls_id(self.ctxt).and_then(|cls_id| {
+                self.ctxt.classes[cls_id].borrow().fields[field_id].alias.clone()
+            }),
+
+            _ => match *e {
+                ExprCall(ref call) => self.return_alias(call),
+                _ => None,
+            },
+        };
+
+        alias.unwrap_or_else(|| ty.name(self.ctxt))
+    }
+
+    fn return_alias(&self, call: &'ast ExprCallType) -> Option<String> {
+        let fct_id = match self.src.map_calls.get(call.id).map(|call| &**call) {
+            Some(&CallType::Fct(fct_id, _, _)) | Some(&CallType::Method(_, fct_id, _)) => fct_id,
+            _ => return None,
+        };
+
+        let fct = self.ctxt.fcts[fct_id].borrow();
+        let alias = fct.ast
+            .return_type
+            .as_ref()
+            .and_then(|data_type| semck::alias_name(self.ctxt, data_type));
+
+        alias
+    }
+
+    fn expr_type_names(&self, args: &'ast [Box<Expr>], tys: &[BuiltinType]) -> Vec<String> {
+        args.iter()
+            .zip(tys)
+            .map(|(arg, &ty)| self.expr_type_name(arg, ty))
+            .collect()
     }
 
     fn check_stmt_return(&mut self, s: &'ast StmtReturnType) {
+        let fct_type = self.return_type;
+
         let expr_type = s.expr
             .as_ref()
             .map(|expr| {
-                self.visit_expr(&expr);
+                self.visit_expr_expecting(&expr, Some(fct_type));
 
                 self.expr_type
             })
             .unwrap_or(BuiltinType::Unit);
 
-        let fct_type = self.fct.return_type;
-
         if !expr_type.is_error() && !fct_type.allows(self.ctxt, expr_type) {
-            let msg = if expr_type.is_nil() {
-                let fct_type = fct_type.name(self.ctxt);
+            let fct_type = self.declared_type_name(self.return_data_type, fct_type);
 
+            let msg = if expr_type.is_nil() {
                 Msg::IncompatibleWithNil(fct_type)
             } else {
-                let fct_type = fct_type.name(self.ctxt);
-                let expr_type = expr_type.name(self.ctxt);
+                let expr_type = match s.expr {
+                    Some(ref expr) => self.expr_type_name(expr, expr_type),
+                    None => expr_type.name(self.ctxt),
+                };
 
                 Msg::ReturnType(fct_type, expr_type)
             };
@@ -262,6 +566,8 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
 
         if ty.is_nil() {
             self.ctxt.diag.borrow_mut().report(s.pos, Msg::ThrowNil);
+        } else if ty.is_nullable() {
+            self.check_non_nullable(ty, s.pos);
         } else if !ty.reference_type() {
             let tyname = ty.name(self.ctxt);
             self.ctxt
@@ -283,15 +589,149 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
     }
 
     fn check_stmt_do(&mut self, s: &'ast StmtDoType) {
+        // catch and finally blocks may start anywhere in the do block
+        self.forget_assigned_in(&s.do_block);
+
+        for catch in &s.catch_blocks {
+            self.forget_assigned_in(&catch.block);
+        }
+
+        if let Some(ref finally_block) = s.finally_block {
+            self.forget_assigned_in(&finally_block.block);
+        }
+
+        let before = self.non_nil.clone();
         self.visit_stmt(&s.do_block);
 
         for catch in &s.catch_blocks {
+            self.non_nil = before.clone();
             self.visit_stmt(&catch.block);
         }
 
         if let Some(ref finally_block) = s.finally_block {
+            self.non_nil = before.clone();
             self.visit_stmt(&finally_block.block);
         }
+
+        self.non_nil = before;
+    }
+
+    fn var_type(&self, var_id: VarId) -> BuiltinType {
+        let ty = self.src.vars[var_id].ty;
+
+        if self.non_nil.contains(&var_id) {
+            ty.non_nullable(self.ctxt)
+        } else {
+            ty
+        }
+    }
+
+    // tracks whether a nullable variable holds a non-nil value after an assignment
+    fn narrow_assigned(&mut self, var_id: VarId, ty: BuiltinType) {
+        if !self.src.vars[var_id].ty.is_nullable() {
+            return;
+        }
+
+        if ty.is_nullable() || ty.is_nil() || ty.is_error() {
+            self.non_nil.remove(&var_id);
+        } else {
+            self.non_nil.insert(var_id);
+        }
+    }
+
+    fn forget_assigned_in(&mut self, s: &'ast Stmt) {
+        if !self.non_nil.is_empty() {
+            let assigned = AssignedVars::find(&*self.src, |finder| finder.visit_stmt(s));
+            self.forget(assigned);
+        }
+    }
+
+    fn forget_assigned_in_expr(&mut self, e: &'ast Expr) {
+        if !self.non_nil.is_empty() {
+            let assigned = AssignedVars::find(&*self.src, |finder| finder.visit_expr(e));
+            self.forget(assigned);
+        }
+    }
+
+    fn forget(&mut self, vars: Vec<VarId>) {
+        for var_id in vars {
+            self.non_nil.remove(&var_id);
+        }
+    }
+
+    // returns the nullable variables that are known to be non-nil when `cond`
+    // evaluates to true and when it evaluates to false
+    fn nil_checks(&self, cond: &'ast Expr) -> (Vec<VarId>, Vec<VarId>) {
+        match *cond {
+            ExprBin(ref e) => match e.op {
+                BinOp::Cmp(CmpOp::Is) | BinOp::Cmp(CmpOp::IsNot) => {
+                    let var_id = if e.rhs.is_nil() {
+                        self.nullable_var(&e.lhs)
+                    } else if e.lhs.is_nil() {
+                        self.nullable_var(&e.rhs)
+                    } else {
+                        None
+                    };
+
+                    let vars = var_id.into_iter().collect::<Vec<_>>();
+
+                    if e.op == BinOp::Cmp(CmpOp::IsNot) {
+                        (vars, Vec::new())
+                    } else {
+                        (Vec::new(), vars)
+                    }
+                }
+
+                BinOp::And => {
+                    let (mut vars, _) = self.nil_checks(&e.lhs);
+                    vars.extend(self.nil_checks(&e.rhs).0);
+                    (vars, Vec::new())
+                }
+
+                BinOp::Or => {
+                    let (_, mut vars) = self.nil_checks(&e.lhs);
+                    vars.extend(self.nil_checks(&e.rhs).1);
+                    (Vec::new(), vars)
+                }
+
+                _ => (Vec::new(), Vec::new()),
+            },
+
+            ExprUn(ref e) if e.op == UnOp::Not => {
+                let (when_true, when_false) = self.nil_checks(&e.opnd);
+                (when_false, when_true)
+            }
+
+            _ => (Vec::new(), Vec::new()),
+        }
+    }
+
+    fn nullable_var(&self, e: &'ast Expr) -> Option<VarId> {
+        if let Some(ident) = e.to_ident() {
+            if let Some(&IdentType::Var(var_id)) = self.src.map_idents.get(ident.id) {
+                if self.src.vars[var_id].ty.is_nullable() {
+                    return Some(var_id);
+                }
+            }
+        }
+
+        None
+    }
+
+    // reports a dereference of a value that may be nil, checking continues
+    // with the non-nullable type
+    fn check_non_nullable(&mut self, ty: BuiltinType, pos: Position) -> BuiltinType {
+        if !ty.is_nullable() {
+            return ty;
+        }
+
+        let name = ty.name(self.ctxt);
+        self.ctxt
+            .diag
+            .borrow_mut()
+            .report(pos, Msg::PossibleNilDereference(name));
+
+        ty.non_nullable(self.ctxt)
     }
 
     fn check_expr_ident(&mut self, e: &'ast ExprIdentType) {
@@ -299,7 +739,7 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
 
         match ident_type {
             IdentType::Var(varid) => {
-                let ty = self.src.vars[varid].ty;
+                let ty = self.var_type(varid);
                 self.src.set_ty(e.id, ty);
                 self.expr_type = ty;
             }
@@ -332,6 +772,8 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                 self.src.set_ty(e.id, xconst.ty);
                 self.expr_type = xconst.ty;
             }
+
+            IdentType::StructField(_, _) => unreachable!(),
         }
     }
 
@@ -340,7 +782,7 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
             let array = e.lhs.to_array().unwrap();
 
             self.visit_expr(&array.object);
-            let object_type = self.expr_type;
+            let object_type = self.check_non_nullable(self.expr_type, e.pos);
 
             self.visit_expr(&array.index);
             let index_type = self.expr_type;
@@ -390,9 +832,19 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
             }
         } else if e.lhs.is_field() || e.lhs.is_ident() {
             self.visit_expr(&e.lhs);
-            let lhs_type = self.expr_type;
+            let mut lhs_type = self.expr_type;
 
-            self.visit_expr(&e.rhs);
+            // assigning to a narrowed variable may store nil again
+            let assigned_var = match self.src.map_idents.get(e.lhs.id()) {
+                Some(&IdentType::Var(var_id)) => Some(var_id),
+                _ => None,
+            };
+
+            if let Some(var_id) = assigned_var {
+                lhs_type = self.src.vars[var_id].ty;
+            }
+
+            self.visit_expr_expecting(&e.rhs, Some(lhs_type));
             let rhs_type = self.expr_type;
 
             if let Some(ident_type) = self.src.map_idents.get(e.lhs.id()) {
@@ -407,7 +859,10 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                     }
 
                     &IdentType::Global(gid) => {
-                        if !self.ctxt.globals[gid].borrow().reassignable {
+                        let glob = self.ctxt.globals[gid].borrow();
+
+                        // the initializer is the only place that may assign a `let` global
+                        if !glob.reassignable && glob.initializer != Some(self.fct.id) {
                             self.ctxt
                                 .diag
                                 .borrow_mut()
@@ -428,6 +883,23 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                         }
                     }
 
+                    &IdentType::StructField(ty, field_id) => {
+                        let struct_id = match ty {
+                            BuiltinType::Struct(struct_id, _) => struct_id,
+                            _ => unreachable!(),
+                        };
+
+                        let struc = self.ctxt.structs[struct_id].borrow();
+                        let struc_name = self.ctxt.interner.str(struc.name).to_string();
+                        let field = &struc.fields[field_id.idx()];
+                        let field_name = self.ctxt.interner.str(field.name).to_string();
+
+                        self.ctxt
+                            .diag
+                            .borrow_mut()
+                            .report(e.pos, Msg::StructFieldImmutable(struc_name, field_name));
+                    }
+
                     &IdentType::Struct(_) => {
                         unimplemented!();
                     }
@@ -444,8 +916,8 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                     let msg = if e.lhs.is_ident() {
                         let ident = e.lhs.to_ident().unwrap();
                         let name = self.ctxt.interner.str(ident.name).to_string();
-                        let lhs_type = lhs_type.name(self.ctxt);
-                        let rhs_type = rhs_type.name(self.ctxt);
+                        let lhs_type = self.expr_type_name(&e.lhs, lhs_type);
+                        let rhs_type = self.expr_type_name(&e.rhs, rhs_type);
 
                         Msg::AssignType(name, lhs_type, rhs_type)
                     } else {
@@ -455,8 +927,8 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                         let field_type = self.src.ty(field.object.id());
                         let field_type = field_type.name(self.ctxt);
 
-                        let lhs_type = lhs_type.name(self.ctxt);
-                        let rhs_type = rhs_type.name(self.ctxt);
+                        let lhs_type = self.expr_type_name(&e.lhs, lhs_type);
+                        let rhs_type = self.expr_type_name(&e.rhs, rhs_type);
 
                         Msg::AssignField(name, field_type, lhs_type, rhs_type)
                     };
@@ -464,6 +936,10 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                     self.ctxt.diag.borrow_mut().report(e.pos, msg);
                 }
             }
+
+            if let Some(var_id) = assigned_var {
+                self.narrow_assigned(var_id, rhs_type);
+            }
         } else {
             self.ctxt
                 .diag
@@ -508,11 +984,23 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
             };
 
             self.ctxt.diag.borrow_mut().report(pos, msg);
+        } else if let Some((_, fct_id, _)) = result {
+            self.check_fct_visible(pos, fct_id);
         }
 
         result
     }
 
+    fn check_fct_visible(&mut self, pos: Position, fct_id: FctId) {
+        if self.ctxt.fct_visible_in(fct_id, self.fct.module) {
+            return;
+        }
+
+        let name = self.ctxt.fcts[fct_id].borrow().name;
+        let name = self.ctxt.interner.str(name).to_string();
+        self.ctxt.diag.borrow_mut().report(pos, Msg::NotPublic(name));
+    }
+
     fn check_expr_un(&mut self, e: &'ast ExprUnType) {
         if e.op == UnOp::Neg {
             if self.negative_expr_id != e.id {
@@ -521,7 +1009,7 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         }
 
         self.visit_expr(&e.opnd);
-        let opnd = self.expr_type;
+        let opnd = self.check_non_nullable(self.expr_type, e.pos);
 
         match e.op {
             UnOp::Plus => self.check_expr_un_method(e, e.op, "unaryPlus", opnd),
@@ -552,7 +1040,7 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                 return;
             }
 
-            let ty = ty.name(self.ctxt);
+            let ty = self.expr_type_name(&e.opnd, ty);
             let msg = Msg::UnOpType(op.as_str().into(), ty);
 
             self.ctxt.diag.borrow_mut().report(e.pos, msg);
@@ -566,7 +1054,22 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         self.visit_expr(&e.lhs);
         let lhs_type = self.expr_type;
 
-        self.visit_expr(&e.rhs);
+        // the rhs of `&&` and `||` is only evaluated depending on the lhs
+        let narrowed = match e.op {
+            BinOp::And => self.nil_checks(&e.lhs).0,
+            BinOp::Or => self.nil_checks(&e.lhs).1,
+            _ => Vec::new(),
+        };
+
+        if narrowed.is_empty() {
+            self.visit_expr(&e.rhs);
+        } else {
+            let before = self.non_nil.clone();
+            self.non_nil.extend(narrowed);
+            self.visit_expr(&e.rhs);
+            self.non_nil = before;
+        }
+
         let rhs_type = self.expr_type;
 
         match e.op {
@@ -608,6 +1111,7 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         lhs_type: BuiltinType,
         rhs_type: BuiltinType,
     ) {
+        let lhs_type = self.check_non_nullable(lhs_type, e.pos);
         let name = self.ctxt.interner.intern(name);
         let call_types = [rhs_type];
 
@@ -628,8 +1132,8 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
             self.src.set_ty(e.id, return_type);
             self.expr_type = return_type;
         } else {
-            let lhs_type = lhs_type.name(self.ctxt);
-            let rhs_type = rhs_type.name(self.ctxt);
+            let lhs_type = self.expr_type_name(&e.lhs, lhs_type);
+            let rhs_type = self.expr_type_name(&e.rhs, rhs_type);
             let msg = Msg::BinOpType(op.as_str().into(), lhs_type, rhs_type);
 
             self.ctxt.diag.borrow_mut().report(e.pos, msg);
@@ -649,7 +1153,7 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         match cmp {
             CmpOp::Is | CmpOp::IsNot => {
                 if !lhs_type.reference_type() {
-                    let lhs_type = lhs_type.name(self.ctxt);
+                    let lhs_type = self.expr_type_name(&e.lhs, lhs_type);
                     self.ctxt
                         .diag
                         .borrow_mut()
@@ -657,18 +1161,21 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                 }
 
                 if !rhs_type.reference_type() {
-                    let rhs_type = rhs_type.name(self.ctxt);
+                    let rhs_type = self.expr_type_name(&e.rhs, rhs_type);
                     self.ctxt
                         .diag
                         .borrow_mut()
                         .report(e.pos, Msg::ReferenceTypeExpected(rhs_type));
                 }
 
-                if !(lhs_type.is_nil() || lhs_type.allows(self.ctxt, rhs_type))
-                    && !(rhs_type.is_nil() || rhs_type.allows(self.ctxt, lhs_type))
+                let lhs = lhs_type.non_nullable(self.ctxt);
+                let rhs = rhs_type.non_nullable(self.ctxt);
+
+                if !(lhs.is_nil() || lhs.allows(self.ctxt, rhs))
+                    && !(rhs.is_nil() || rhs.allows(self.ctxt, lhs))
                 {
-                    let lhs_type = lhs_type.name(self.ctxt);
-                    let rhs_type = rhs_type.name(self.ctxt);
+                    let lhs_type = self.expr_type_name(&e.lhs, lhs_type);
+                    let rhs_type = self.expr_type_name(&e.rhs, rhs_type);
                     self.ctxt
                         .diag
                         .borrow_mut()
@@ -702,23 +1209,44 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         if !expected_type.allows(self.ctxt, lhs_type) || !expected_type.allows(self.ctxt, rhs_type)
         {
             let op = op.as_str().into();
-            let lhs_type = lhs_type.name(self.ctxt);
-            let rhs_type = rhs_type.name(self.ctxt);
+            let lhs_type = self.expr_type_name(&e.lhs, lhs_type);
+            let rhs_type = self.expr_type_name(&e.rhs, rhs_type);
             let msg = Msg::BinOpType(op, lhs_type, rhs_type);
 
             self.ctxt.diag.borrow_mut().report(e.pos, msg);
         }
     }
 
-    fn check_expr_call(&mut self, e: &'ast ExprCallType, in_try: bool) {
-        let object_type = if e.object.is_some() {
-            let object = e.object.as_ref().unwrap();
+    fn check_expr_call(
+        &mut self,
+        e: &'ast ExprCallType,
+        in_try: bool,
+        expected_type: Option<BuiltinType>,
+    ) {
+        if let Some(ref callee) = e.callee {
+            self.visit_expr(callee);
+            let callee_type = self.expr_type;
 
-            let object_type = if object.is_super() {
-                self.super_type(e.pos)
+            let call_types: Vec<BuiltinType> = e.args
+                .iter()
+                .map(|arg| {
+                    self.visit_expr(arg);
+                    self.expr_type
+                })
+                .collect();
+
+            self.check_expr_call_lambda(e, callee_type, &call_types);
+            return;
+        }
+
+        let object_type = if e.object.is_some() {
+            let object = e.object.as_ref().unwrap();
+
+            let object_type = if object.is_super() {
+                self.super_type(e.pos)
             } else {
                 self.visit_expr(object);
-                self.expr_type
+                self.check_non_nullable(self.expr_type, e.pos)
             };
 
             Some(object_type)
@@ -733,6 +1261,7 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                 self.expr_type
             })
             .collect();
+        let arg_names = self.expr_type_names(&e.args, &call_types);
 
         let type_params: Vec<BuiltinType> = if let Some(ref type_params) = e.type_params {
             type_params.iter().map(|p| self.src.ty(p.id())).collect()
@@ -743,7 +1272,7 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         let type_params: TypeParams = TypeParams::with(type_params);
 
         if let Some(object_type) = object_type {
-            if object_type.is_type_param() {
+            if object_type.is_type_param() || object_type == BuiltinType::This {
                 self.check_generic_method_call(e, in_try, object_type, &call_types);
                 return;
             }
@@ -754,15 +1283,22 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                 return;
             }
 
+            if let Some(ty) = self.lambda_field(e, object_type) {
+                self.check_expr_call_lambda(e, ty, &call_types);
+                return;
+            }
+
             let mut lookup = MethodLookup::new(self.ctxt)
                 .method(object_type)
                 .pos(e.pos)
                 .name(e.path.name())
-                .args(&call_types);
+                .args(&call_types)
+                .arg_names(&arg_names);
 
             if lookup.find() {
                 let fct_id = lookup.found_fct_id().unwrap();
                 let return_type = lookup.found_ret().unwrap();
+                self.check_fct_visible(e.pos, fct_id);
 
                 let call_type = CallType::Method(object_type, fct_id, TypeParams::empty());
                 self.src
@@ -792,19 +1328,27 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                 Some(SymClass(cls_id)) => {
                     assert_eq!(2, e.path.len());
 
-                    let mut lookup = MethodLookup::new(self.ctxt)
+                    let lookup = MethodLookup::new(self.ctxt)
                         .pos(e.pos)
                         .static_method(cls_id)
                         .name(e.path[1])
                         .args(&call_types)
-                        .fct_type_params(&type_params);
+                        .arg_names(&arg_names);
+
+                    let mut lookup = if e.type_params.is_some() {
+                        lookup.fct_type_params(&type_params)
+                    } else {
+                        lookup.infer_type_params(expected_type)
+                    };
 
                     if lookup.find() {
                         let fct_id = lookup.found_fct_id().unwrap();
+                        self.check_fct_visible(e.pos, fct_id);
+
                         let call_type = Rc::new(CallType::Fct(
                             fct_id,
                             TypeParams::empty(),
-                            type_params.clone(),
+                            lookup.found_fct_type_params().unwrap(),
                         ));
                         self.src.map_calls.insert(e.id, call_type.clone());
 
@@ -815,6 +1359,11 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                     }
                 }
 
+                Some(SymEnum(enum_id)) => {
+                    self.check_enum_variant(e.id, e.pos, enum_id, e.path[1], &call_types);
+                    return;
+                }
+
                 _ => {
                     let name = self.ctxt.interner.str(e.path[0]).to_string();
                     let msg = Msg::ClassExpected(name);
@@ -828,21 +1377,42 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
             self.src.map_calls.get(e.id).unwrap().clone()
         };
 
+        if let CallType::Lambda(LambdaCallee::Var(var_id)) = *call_type {
+            let ty = self.var_type(var_id);
+            self.check_expr_call_lambda(e, ty, &call_types);
+            return;
+        }
+
         match *call_type {
             CallType::CtorNew(cls_id, _, _) => {
-                let mut lookup = MethodLookup::new(self.ctxt)
+                let lookup = MethodLookup::new(self.ctxt)
                     .pos(e.pos)
                     .ctor(cls_id)
                     .args(&call_types)
-                    .cls_type_params(&type_params);
+                    .arg_names(&arg_names);
+
+                let mut lookup = if e.type_params.is_some() {
+                    lookup.cls_type_params(&type_params)
+                } else {
+                    lookup.infer_type_params(expected_type)
+                };
 
                 let ty = if lookup.find() {
                     let fct_id = lookup.found_fct_id().unwrap();
                     let cls_id = lookup.found_cls_id().unwrap();
                     let cls = self.ctxt.classes[cls_id].borrow();
+                    let type_params = lookup.found_cls_type_params().unwrap();
+
+                    // `Array::<T>(len)` fills the array with zero values
+                    if cls.is_array
+                        && self.ctxt.fcts[fct_id].borrow().params_without_self().len() == 1
+                    {
+                        self.check_default_value(e.pos, type_params[0]);
+                    }
 
-                    let call_type = CallType::CtorNew(cls_id, fct_id, type_params.clone());
+                    let call_type = CallType::CtorNew(cls_id, fct_id, type_params);
                     self.src.map_calls.replace(e.id, Rc::new(call_type));
+                    self.check_fct_visible(e.pos, fct_id);
 
                     if cls.is_abstract {
                         let msg = Msg::NewAbstractClass;
@@ -859,15 +1429,28 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
             }
 
             CallType::Fct(callee_id, _, _) => {
-                let mut lookup = MethodLookup::new(self.ctxt)
+                let lookup = MethodLookup::new(self.ctxt)
                     .pos(e.pos)
                     .callee(callee_id)
                     .args(&call_types)
-                    .fct_type_params(&type_params);
+                    .arg_names(&arg_names);
+
+                let mut lookup = if e.type_params.is_some() {
+                    lookup.fct_type_params(&type_params)
+                } else {
+                    lookup.infer_type_params(expected_type)
+                };
 
                 let ty = if lookup.find() {
-                    let call_type =
-                        CallType::Fct(callee_id, TypeParams::empty(), type_params.clone());
+                    let type_params = lookup.found_fct_type_params().unwrap();
+
+                    if let FctKind::Builtin(Intrinsic::DefaultValue) =
+                        self.ctxt.fcts[callee_id].borrow().kind
+                    {
+                        self.check_default_value(e.pos, type_params[0]);
+                    }
+
+                    let call_type = CallType::Fct(callee_id, TypeParams::empty(), type_params);
                     self.src.map_calls.replace(e.id, Rc::new(call_type));
 
                     lookup.found_ret().unwrap()
@@ -893,6 +1476,244 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         }
     }
 
+    // only the stdlib may create zero values of types without default value,
+    // it never hands them out, e.g. the unused capacity of `Vec`
+    fn check_default_value(&self, pos: Position, ty: BuiltinType) {
+        let in_stdlib = self.ctxt.sym.borrow().prelude() == Some(self.fct.module);
+
+        if !in_stdlib && !ty.has_default_value(self.ctxt) {
+            let msg = Msg::NoDefaultValue(ty.name(self.ctxt));
+            self.ctxt.diag.borrow_mut().report(pos, msg);
+        }
+    }
+
+    fn check_expr_path(&mut self, e: &'ast ExprPathType) {
+        let enum_id = match self.ctxt.sym.borrow().get(e.path[0]) {
+            Some(SymEnum(enum_id)) if e.path.len() == 2 => Some(enum_id),
+            _ => None,
+        };
+
+        if let Some(enum_id) = enum_id {
+            self.check_enum_variant(e.id, e.pos, enum_id, e.path[1], &[]);
+        } else {
+            let name = self.ctxt.interner.str(e.path[0]).to_string();
+            let msg = Msg::EnumExpected(name);
+            self.ctxt.diag.borrow_mut().report(e.pos, msg);
+
+            self.src.set_ty(e.id, BuiltinType::Error);
+            self.expr_type = BuiltinType::Error;
+        }
+    }
+
+    fn check_enum_variant(
+        &mut self,
+        id: NodeId,
+        pos: Position,
+        enum_id: EnumId,
+        name: Name,
+        args: &[BuiltinType],
+    ) {
+        let xenum = self.ctxt.enums[enum_id].borrow();
+        let enum_name = self.ctxt.interner.str(xenum.name).to_string();
+        let variant_name = self.ctxt.interner.str(name).to_string();
+
+        let ty = if let Some(idx) = xenum.find_variant(name) {
+            let variant = &xenum.variants[idx];
+            let empty = TypeParams::empty();
+
+            if !args_compatible(self.ctxt, &variant.types, args, None, None, &empty, &empty) {
+                let def = variant
+                    .types
+                    .iter()
+                    .map(|a| a.name(self.ctxt))
+                    .collect::<Vec<_>>();
+                let args = args.iter().map(|a| a.name(self.ctxt)).collect::<Vec<_>>();
+                let msg = Msg::EnumVariantArgsIncompatible(enum_name, variant_name, def, args);
+                self.ctxt.diag.borrow_mut().report(pos, msg);
+            }
+
+            self.src
+                .map_calls
+                .insert_or_replace(id, Rc::new(CallType::Enum(enum_id, idx)));
+
+            BuiltinType::Enum(enum_id)
+        } else {
+            let msg = Msg::UnknownEnumVariant(enum_name, variant_name);
+            self.ctxt.diag.borrow_mut().report(pos, msg);
+
+            BuiltinType::Error
+        };
+
+        self.src.set_ty(id, ty);
+        self.expr_type = ty;
+    }
+
+    fn check_expr_match(&mut self, e: &'ast ExprMatchType) {
+        self.visit_expr(&e.expr);
+        let expr_type = self.expr_type;
+
+        let enum_id = match expr_type {
+            BuiltinType::Enum(enum_id) => Some(enum_id),
+            BuiltinType::Error => None,
+
+            _ => {
+                let name = expr_type.name(self.ctxt);
+                let msg = Msg::MatchEnumExpected(name);
+                self.ctxt.diag.borrow_mut().report(e.expr.pos(), msg);
+
+                None
+            }
+        };
+
+        let variants = enum_id.map_or(0, |id| self.ctxt.enums[id].borrow().variants.len());
+        let mut covered = vec![false; variants];
+        let mut has_underscore = false;
+        let mut match_type: Option<BuiltinType> = None;
+
+        for arm in &e.arms {
+            self.forget_assigned_in_expr(&arm.value);
+        }
+
+        let before = self.non_nil.clone();
+
+        for arm in &e.arms {
+            self.check_match_pattern(arm, enum_id, &mut covered, &mut has_underscore);
+
+            self.non_nil = before.clone();
+            self.visit_expr(&arm.value);
+            let arm_type = self.expr_type;
+
+            match_type = match match_type {
+                None => Some(arm_type),
+                Some(ty) if ty.is_error() || arm_type.is_error() => Some(ty),
+                Some(ty) if ty.is_nil() && arm_type.reference_type() => {
+                    Some(BuiltinType::nullable(self.ctxt, arm_type))
+                }
+                Some(ty) if !ty.is_nil() && ty.allows(self.ctxt, arm_type) => Some(ty),
+                Some(ty) if ty.reference_type() && arm_type.is_nil() => {
+                    Some(BuiltinType::nullable(self.ctxt, ty))
+                }
+                Some(ty) if arm_type.is_nullable() && arm_type.allows(self.ctxt, ty) => {
+                    Some(arm_type)
+                }
+
+                Some(ty) => {
+                    let ty = ty.name(self.ctxt);
+                    let arm_type = arm_type.name(self.ctxt);
+                    let msg = Msg::MatchArmTypes(ty, arm_type);
+                    self.ctxt.diag.borrow_mut().report(arm.pos, msg);
+
+                    Some(BuiltinType::Error)
+                }
+            };
+        }
+
+        self.non_nil = before;
+
+        if let Some(enum_id) = enum_id {
+            if !has_underscore && covered.iter().any(|&c| !c) {
+                let xenum = self.ctxt.enums[enum_id].borrow();
+                let missing = xenum
+                    .variants
+                    .iter()
+                    .zip(&covered)
+                    .filter(|&(_, &c)| !c)
+                    .map(|(v, _)| self.ctxt.interner.str(v.name).to_string())
+                    .collect::<Vec<_>>();
+
+                let msg = Msg::MatchNotExhaustive(missing);
+                self.ctxt.diag.borrow_mut().report(e.pos, msg);
+            }
+        }
+
+        let ty = match_type.unwrap_or(BuiltinType::Unit);
+        self.src.set_ty(e.id, ty);
+        self.expr_type = ty;
+    }
+
+    // resolves the variant of a match arm, marks it as covered and
+    // determines the types of the variables bound by the pattern
+    fn check_match_pattern(
+        &mut self,
+        arm: &'ast MatchArm,
+        enum_id: Option<EnumId>,
+        covered: &mut [bool],
+        has_underscore: &mut bool,
+    ) {
+        let (path, params) = match arm.pattern {
+            MatchPattern::Underscore => {
+                if *has_underscore || (enum_id.is_some() && covered.iter().all(|&c| c)) {
+                    let msg = Msg::MatchUnreachableArm;
+                    self.ctxt.diag.borrow_mut().report(arm.pos, msg);
+                }
+
+                *has_underscore = true;
+                return;
+            }
+
+            MatchPattern::Variant(ref path, ref params) => (path, params),
+        };
+
+        let mut types = Vec::new();
+
+        if let Some(enum_id) = enum_id {
+            let xenum = self.ctxt.enums[enum_id].borrow();
+            let name = path.path[path.len() - 1];
+
+            let prefix_ok = match path.len() {
+                1 => true,
+                2 => match self.ctxt.sym.borrow().get(path.path[0]) {
+                    Some(SymEnum(id)) => id == enum_id,
+                    _ => false,
+                },
+                _ => false,
+            };
+
+            if !prefix_ok {
+                let expected = self.ctxt.interner.str(xenum.name).to_string();
+                let found = path.path[..path.len() - 1]
+                    .iter()
+                    .map(|&n| self.ctxt.interner.str(n).to_string())
+                    .collect::<Vec<_>>()
+                    .join("::");
+                let msg = Msg::TypesIncompatible(expected, found);
+                self.ctxt.diag.borrow_mut().report(arm.pos, msg);
+            } else if let Some(idx) = xenum.find_variant(name) {
+                if *has_underscore || covered[idx] {
+                    let msg = Msg::MatchUnreachableArm;
+                    self.ctxt.diag.borrow_mut().report(arm.pos, msg);
+                }
+
+                covered[idx] = true;
+
+                let variant = &xenum.variants[idx];
+
+                if variant.types.len() != params.len() {
+                    let name = self.ctxt.interner.str(name).to_string();
+                    let msg = Msg::MatchPatternParams(name, variant.types.len(), params.len());
+                    self.ctxt.diag.borrow_mut().report(arm.pos, msg);
+                }
+
+                types = variant.types.clone();
+            } else {
+                let enum_name = self.ctxt.interner.str(xenum.name).to_string();
+                let name = self.ctxt.interner.str(name).to_string();
+                let msg = Msg::UnknownEnumVariant(enum_name, name);
+                self.ctxt.diag.borrow_mut().report(arm.pos, msg);
+            }
+        }
+
+        for (idx, param) in params.iter().enumerate() {
+            if param.name.is_none() {
+                continue;
+            }
+
+            let ty = types.get(idx).cloned().unwrap_or(BuiltinType::Error);
+            let var_id = *self.src.map_vars.get(param.id).unwrap();
+            self.src.vars[var_id].ty = ty;
+        }
+    }
+
     fn check_expr_delegation(&mut self, e: &'ast ExprDelegationType) {
         let arg_types: Vec<BuiltinType> = e.args
             .iter()
@@ -935,7 +1756,7 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
 
             if args_compatible(
                 self.ctxt,
-                &ctor.params_without_self(),
+                &ctor.params_for_args(self.ctxt, &arg_types),
                 &arg_types,
                 Some(cls_id),
                 None,
@@ -990,6 +1811,28 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                 self.check_generic_method_call_for_type_param(e, in_try, obj, args, tp);
             }
 
+            BuiltinType::TraitTypeParam(trait_id, tpid) => {
+                let xtrait = self.ctxt.traits[trait_id].borrow();
+                let tp = &xtrait.type_params[tpid.idx()];
+                self.check_generic_method_call_for_type_param(e, in_try, obj, args, tp);
+            }
+
+            BuiltinType::This => {
+                // in a default method `Self` is bound by the trait itself
+                let mut tp = ctxt::TypeParam::new(e.path.name());
+
+                if let FctParent::Trait(trait_id) = self.fct.parent {
+                    let xtrait = self.ctxt.traits[trait_id].borrow();
+                    let params: Vec<_> = (0..xtrait.type_params.len())
+                        .map(|id| BuiltinType::TraitTypeParam(trait_id, id.into()))
+                        .collect();
+
+                    tp.trait_bounds.insert(trait_id, params.into());
+                }
+
+                self.check_generic_method_call_for_type_param(e, in_try, obj, args, &tp);
+            }
+
             _ => unreachable!(),
         }
     }
@@ -1002,15 +1845,20 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         args: &[BuiltinType],
         tp: &ctxt::TypeParam,
     ) {
-        for &trait_id in &tp.trait_bounds {
+        for (&trait_id, trait_params) in &tp.trait_bounds {
             let trai = self.ctxt.traits[trait_id].borrow();
 
-            if let Some(fid) = trai.find_method(self.ctxt, false, e.path.name(), None, args) {
+            if let Some(fid) =
+                trai.find_method(self.ctxt, false, e.path.name(), None, trait_params, args)
+            {
                 let call_type = CallType::Method(obj, fid, TypeParams::empty());
                 self.src.map_calls.insert(e.id, Rc::new(call_type));
 
                 let fct = self.ctxt.fcts[fid].borrow();
-                let return_type = fct.return_type;
+                let return_type = match fct.return_type {
+                    BuiltinType::This => obj,
+                    ty => specialize_for_trait(self.ctxt, ty, trait_params),
+                };
 
                 if fct.throws && !in_try {
                     let msg = Msg::ThrowingCallWithoutTry;
@@ -1039,7 +1887,20 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
     fn check_expr_field(&mut self, e: &'ast ExprFieldType) {
         self.visit_expr(&e.object);
 
-        let ty = self.expr_type;
+        let ty = self.check_non_nullable(self.expr_type, e.pos);
+
+        if let BuiltinType::Struct(struct_id, _) = ty {
+            let struc = self.ctxt.structs[struct_id].borrow();
+
+            if let Some(field) = struc.fields.iter().find(|field| field.name == e.name) {
+                let ident_type = IdentType::StructField(ty, field.id);
+                self.src.map_idents.insert_or_replace(e.id, ident_type);
+
+                self.src.set_ty(e.id, field.ty);
+                self.expr_type = field.ty;
+                return;
+            }
+        }
 
         let cls_id = ty.cls_id(self.ctxt);
 
@@ -1058,6 +1919,13 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                 self.src.map_idents.insert_or_replace(e.id, ident_type);
 
                 let field = &cls.fields[field_id];
+
+                if cls.module != self.fct.module && !field.is_pub {
+                    let name = self.ctxt.interner.str(e.name).to_string();
+                    let msg = Msg::NotPublic(name);
+                    self.ctxt.diag.borrow_mut().report(e.pos, msg);
+                }
+
                 let class_type_params = ty.type_params(self.ctxt);
                 let fty = replace_type_param(
                     self.ctxt,
@@ -1082,6 +1950,68 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         self.expr_type = BuiltinType::Error;
     }
 
+    fn check_expr_tuple(&mut self, e: &'ast ExprTupleType) {
+        let mut elements = Vec::with_capacity(e.values.len());
+        let mut has_error = false;
+
+        for value in &e.values {
+            self.visit_expr(value);
+            let ty = self.expr_type;
+
+            if ty.is_nil() {
+                self.ctxt
+                    .diag
+                    .borrow_mut()
+                    .report(value.pos(), Msg::TupleNilElement);
+                has_error = true;
+            } else if ty.is_error() {
+                has_error = true;
+            }
+
+            elements.push(ty);
+        }
+
+        let ty = if has_error {
+            BuiltinType::Error
+        } else {
+            let tuple_id = self.ctxt.tuples.borrow_mut().insert(elements);
+            BuiltinType::Tuple(tuple_id)
+        };
+
+        self.src.set_ty(e.id, ty);
+        self.expr_type = ty;
+    }
+
+    fn check_expr_tuple_element(&mut self, e: &'ast ExprTupleElementType) {
+        self.visit_expr(&e.object);
+        let object_type = self.expr_type;
+
+        let ty = match object_type {
+            BuiltinType::Tuple(tuple_id) => {
+                let elements = self.ctxt.tuples.borrow().get(tuple_id);
+
+                if let Some(&ty) = elements.get(e.idx as usize) {
+                    ty
+                } else {
+                    let msg = Msg::TupleIndexOutOfBounds(object_type.name(self.ctxt), e.idx);
+                    self.ctxt.diag.borrow_mut().report(e.pos, msg);
+                    BuiltinType::Error
+                }
+            }
+
+            BuiltinType::Error => BuiltinType::Error,
+
+            _ => {
+                let msg = Msg::TupleExpected(object_type.name(self.ctxt));
+                self.ctxt.diag.borrow_mut().report(e.pos, msg);
+                BuiltinType::Error
+            }
+        };
+
+        self.src.set_ty(e.id, ty);
+        self.expr_type = ty;
+    }
+
     fn check_expr_this(&mut self, e: &'ast ExprSelfType) {
         match self.fct.parent {
             FctParent::Class(clsid) => {
@@ -1097,6 +2027,11 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
                 self.expr_type = ty;
             }
 
+            FctParent::Trait(_) => {
+                self.src.set_ty(e.id, BuiltinType::This);
+                self.expr_type = BuiltinType::This;
+            }
+
             _ => {
                 let msg = Msg::ThisUnavailable;
                 self.ctxt.diag.borrow_mut().report(e.pos, msg);
@@ -1120,7 +2055,7 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
 
     fn check_expr_array(&mut self, e: &'ast ExprArrayType) {
         self.visit_expr(&e.object);
-        let object_type = self.expr_type;
+        let object_type = self.check_non_nullable(self.expr_type, e.pos);
 
         self.visit_expr(&e.index);
         let index_type = self.expr_type;
@@ -1157,15 +2092,18 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         }
     }
 
-    fn check_expr_try(&mut self, e: &'ast ExprTryType) {
+    fn check_expr_try(&mut self, e: &'ast ExprTryType, expected_type: Option<BuiltinType>) {
         if let Some(call) = e.expr.to_call() {
-            self.check_expr_call(call, true);
+            self.check_expr_call(call, true, expected_type);
             let e_type = self.expr_type;
             self.src.set_ty(e.id, e_type);
 
             if let Some(call_type) = self.src.map_calls.get(call.id) {
-                let fct_id = call_type.fct_id();
-                let throws = self.ctxt.fcts[fct_id].borrow().throws;
+                // lambdas are not allowed to throw, creating enum variants never throws
+                let throws = match **call_type {
+                    CallType::Lambda(_) | CallType::Enum(_, _) => false,
+                    ref call_type => self.ctxt.fcts[call_type.fct_id()].borrow().throws,
+                };
 
                 if !throws {
                     self.ctxt
@@ -1203,13 +2141,31 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
     }
 
     fn check_expr_lambda(&mut self, e: &'ast ExprLambdaType) {
-        let ret = if let Some(ref ty) = e.ret {
+        let ty = self.check_closure(&e.fct);
+
+        self.expr_type = ty;
+        self.src.set_ty(e.id, ty);
+    }
+
+    fn check_expr_spawn(&mut self, e: &'ast ExprSpawnType) {
+        self.check_closure(&e.fct);
+
+        let ty = self.ctxt.cls(self.ctxt.vips.thread_class);
+
+        self.expr_type = ty;
+        self.src.set_ty(e.id, ty);
+    }
+
+    // checks body of lambda or spawn, returns lambda type of closure
+    fn check_closure(&mut self, fct: &'ast Function) -> BuiltinType {
+        let ret = if let Some(ref ty) = fct.return_type {
             self.src.ty(ty.id())
         } else {
             BuiltinType::Unit
         };
 
-        let params = e.params
+        let params = fct
+            .params
             .iter()
             .map(|p| self.src.ty(p.data_type.id()))
             .collect::<Vec<_>>();
@@ -1217,57 +2173,298 @@ impl<'a, 'ast> TypeCheck<'a, 'ast> {
         let ty = self.ctxt.lambda_types.borrow_mut().insert(params, ret);
         let ty = BuiltinType::Lambda(ty);
 
-        self.expr_type = ty;
-        self.src.set_ty(e.id, ty);
+        self.declare_params(&fct.params);
+
+        let closure = self.src.map_lambdas.get(fct.id).unwrap().closure;
+        self.src.vars[closure].ty = ty;
+
+        // the closure may run after captured variables were reassigned
+        let old_non_nil = self.non_nil.clone();
+        {
+            let vars = &self.src.vars;
+            self.non_nil.retain(|&var_id| !vars[var_id].reassignable);
+        }
+
+        let old_return_type = self.return_type;
+        let old_return_data_type = self.return_data_type;
+        self.return_type = ret;
+        self.return_data_type = fct.return_type.as_ref();
+        self.visit_stmt(fct.block());
+        self.return_type = old_return_type;
+        self.return_data_type = old_return_data_type;
+        self.non_nil = old_non_nil;
+
+        self.lambdas.push(fct);
+
+        ty
     }
 
-    fn check_expr_conv(&mut self, e: &'ast ExprConvType) {
-        self.visit_expr(&e.object);
-        let object_type = self.expr_type;
-        self.src.set_ty(e.object.id(), self.expr_type);
+    // `obj.f(x)` calls the closure in field `f` if the class has no method `f`
+    fn lambda_field(
+        &mut self,
+        e: &'ast ExprCallType,
+        object_type: BuiltinType,
+    ) -> Option<BuiltinType> {
+        let (cls_id, list_id) = match object_type {
+            BuiltinType::Class(cls_id, list_id) => (cls_id, list_id),
+            _ => return None,
+        };
 
-        let check_type = self.src.ty(e.data_type.id());
+        let name = e.path.name();
+        let cls = self.ctxt.classes[cls_id].borrow();
 
-        if !check_type.reference_type() {
-            let name = check_type.name(self.ctxt);
-            self.ctxt
-                .diag
-                .borrow_mut()
-                .report(e.pos, Msg::ReferenceTypeExpected(name));
-            return;
+        if !cls.find_methods(self.ctxt, name, false).is_empty() {
+            return None;
         }
 
-        let mut valid = false;
+        let (cls_id, field_id) = cls.find_field(self.ctxt, name)?;
+        let cls = self.ctxt.classes[cls_id].borrow();
+        let field = &cls.fields[field_id];
 
-        if object_type.subclass_from(self.ctxt, check_type) {
-            // open class A { } class B: A { }
-            // (b is A) is valid
+        let class_type_params = object_type.type_params(self.ctxt);
+        let ty = replace_type_param(self.ctxt, field.ty, &class_type_params, &TypeParams::empty());
 
-            valid = true;
-        } else if check_type.subclass_from(self.ctxt, object_type) {
-            // normal check
+        if !ty.non_nullable(self.ctxt).is_lambda() {
+            return None;
+        }
 
-        } else {
-            let object_type = object_type.name(self.ctxt);
-            let check_type = check_type.name(self.ctxt);
-            let msg = Msg::TypesIncompatible(object_type, check_type);
+        if cls.module != self.fct.module && !field.is_pub {
+            let name = self.ctxt.interner.str(name).to_string();
+            let msg = Msg::NotPublic(name);
             self.ctxt.diag.borrow_mut().report(e.pos, msg);
         }
 
-        self.src.map_convs.insert(
-            e.id,
-            ConvInfo {
-                cls_id: check_type.cls_id(self.ctxt).unwrap(),
-                valid: valid,
-            },
-        );
+        let object_type = BuiltinType::Class(cls_id, list_id);
+        let call_type = CallType::Lambda(LambdaCallee::Field(object_type, field_id));
+        self.src
+            .map_calls
+            .insert_or_replace(e.id, Rc::new(call_type));
 
-        self.expr_type = if e.is { BuiltinType::Bool } else { check_type };
+        Some(ty)
     }
 
-    fn check_expr_lit_struct(&mut self, e: &'ast ExprLitStructType) {
-        let sid = self.src.map_idents.get(e.id).unwrap().struct_id();
-        let struc = self.ctxt.structs[sid].borrow();
+    fn check_expr_call_lambda(
+        &mut self,
+        e: &'ast ExprCallType,
+        ty: BuiltinType,
+        args: &[BuiltinType],
+    ) {
+        if ty.is_error() {
+            self.src.set_ty(e.id, BuiltinType::Error);
+            self.expr_type = BuiltinType::Error;
+            return;
+        }
+
+        let ty = self.check_non_nullable(ty, e.pos);
+
+        // calls through an expression have no name, use the lambda type instead
+        let name = if e.callee.is_some() {
+            ty.name(self.ctxt)
+        } else {
+            self.ctxt.interner.str(e.path.name()).to_string()
+        };
+
+        let lambda = match ty {
+            BuiltinType::Lambda(lambda_id) => self.ctxt.lambda_types.borrow().get(lambda_id),
+
+            _ => {
+                let msg = if e.callee.is_some() {
+                    Msg::LambdaExpected(name)
+                } else {
+                    Msg::UnknownFunction(name)
+                };
+                self.ctxt.diag.borrow_mut().report(e.pos, msg);
+
+                self.src.set_ty(e.id, BuiltinType::Error);
+                self.expr_type = BuiltinType::Error;
+                return;
+            }
+        };
+
+        let empty = TypeParams::empty();
+
+        if !args_compatible(self.ctxt, lambda.params(), args, None, None, &empty, &empty) {
+            let lambda_params = lambda
+                .params()
+                .iter()
+                .map(|a| a.name(self.ctxt))
+                .collect::<Vec<_>>();
+            let call_types = self.expr_type_names(&e.args, args);
+            let msg = Msg::ParamTypesIncompatible(name, lambda_params, call_types);
+            self.ctxt.diag.borrow_mut().report(e.pos, msg);
+        }
+
+        self.src.set_ty(e.id, lambda.ret());
+        self.expr_type = lambda.ret();
+    }
+
+    fn check_expr_conv(&mut self, e: &'ast ExprConvType) {
+        self.visit_expr(&e.object);
+        let object_type = self.expr_type;
+        self.src.set_ty(e.object.id(), self.expr_type);
+
+        let check_type = self.src.ty(e.data_type.id());
+
+        if !check_type.reference_type() {
+            let name = check_type.name(self.ctxt);
+            self.ctxt
+                .diag
+                .borrow_mut()
+                .report(e.pos, Msg::ReferenceTypeExpected(name));
+            return;
+        }
+
+        // nil passes `as` unchanged and fails `is`, so only the non-nullable
+        // types need to be related
+        let result_type = if object_type.is_nullable() {
+            BuiltinType::nullable(self.ctxt, check_type)
+        } else {
+            check_type
+        };
+
+        let object_type = object_type.non_nullable(self.ctxt);
+        let check_type = check_type.non_nullable(self.ctxt);
+
+        let mut valid = false;
+
+        if object_type.subclass_from(self.ctxt, check_type) {
+            // open class A { } class B: A { }
+            // (b is A) is valid
+
+            valid = true;
+        } else if check_type.subclass_from(self.ctxt, object_type) {
+            // normal check
+
+        } else {
+            let object_type = object_type.name(self.ctxt);
+            let check_type = check_type.name(self.ctxt);
+            let msg = Msg::TypesIncompatible(object_type, check_type);
+            self.ctxt.diag.borrow_mut().report(e.pos, msg);
+        }
+
+        self.src.map_convs.insert(
+            e.id,
+            ConvInfo {
+                cls_id: check_type.cls_id(self.ctxt).unwrap(),
+                valid: valid,
+            },
+        );
+
+        self.expr_type = if e.is {
+            BuiltinType::Bool
+        } else {
+            result_type
+        };
+    }
+
+    fn check_expr_template(&mut self, e: &'ast ExprTemplateType) {
+        let str_ty = self.ctxt.cls(self.ctxt.vips.str_class);
+
+        for part in &e.parts {
+            self.visit_expr(part);
+
+            if part.is_lit_str() {
+                continue;
+            }
+
+            let ty = self.check_non_nullable(self.expr_type, part.pos());
+
+            // strings are copied into the result as they are
+            if ty == str_ty || ty.is_error() {
+                continue;
+            }
+
+            if let Some(fct_id) = self.find_to_string(ty) {
+                if self.ctxt.fcts[fct_id].borrow().throws {
+                    let msg = Msg::ThrowingCallWithoutTry;
+                    self.ctxt.diag.borrow_mut().report(part.pos(), msg);
+                }
+
+                self.src.map_templates.insert(part.id(), fct_id);
+            } else {
+                let ty = ty.name(self.ctxt);
+                let msg = Msg::TemplateNotStringable(ty);
+                self.ctxt.diag.borrow_mut().report(part.pos(), msg);
+            }
+        }
+
+        self.src.set_ty(e.id, str_ty);
+        self.expr_type = str_ty;
+    }
+
+    fn find_to_string(&self, ty: BuiltinType) -> Option<FctId> {
+        let name = self.ctxt.interner.intern("toString");
+        let str_ty = self.ctxt.cls(self.ctxt.vips.str_class);
+
+        let trait_bounds: Vec<(TraitId, TypeParams)> = match ty {
+            BuiltinType::FctTypeParam(_, tpid) => self.fct.type_params[tpid.idx()]
+                .trait_bounds
+                .iter()
+                .map(|(&trait_id, params)| (trait_id, params.clone()))
+                .collect(),
+
+            BuiltinType::ClassTypeParam(cls_id, tpid) => {
+                let cls = self.ctxt.classes[cls_id].borrow();
+                cls.type_params[tpid.idx()]
+                    .trait_bounds
+                    .iter()
+                    .map(|(&trait_id, params)| (trait_id, params.clone()))
+                    .collect()
+            }
+
+            BuiltinType::TraitTypeParam(trait_id, tpid) => {
+                let xtrait = self.ctxt.traits[trait_id].borrow();
+                xtrait.type_params[tpid.idx()]
+                    .trait_bounds
+                    .iter()
+                    .map(|(&trait_id, params)| (trait_id, params.clone()))
+                    .collect()
+            }
+
+            BuiltinType::This => match self.fct.parent {
+                FctParent::Trait(trait_id) => {
+                    let xtrait = self.ctxt.traits[trait_id].borrow();
+                    let params: Vec<_> = (0..xtrait.type_params.len())
+                        .map(|id| BuiltinType::TraitTypeParam(trait_id, id.into()))
+                        .collect();
+
+                    vec![(trait_id, params.into())]
+                }
+
+                _ => Vec::new(),
+            },
+
+            _ => {
+                return lookup_method(
+                    self.ctxt,
+                    ty,
+                    false,
+                    name,
+                    &[],
+                    &TypeParams::empty(),
+                    Some(str_ty),
+                ).map(|(_, fct_id, _)| fct_id);
+            }
+        };
+
+        for (trait_id, trait_params) in trait_bounds {
+            let trai = self.ctxt.traits[trait_id].borrow();
+
+            if let Some(fct_id) = trai.find_method(self.ctxt, false, name, None, &trait_params, &[]) {
+                let fct = self.ctxt.fcts[fct_id].borrow();
+
+                if specialize_for_trait(self.ctxt, fct.return_type, &trait_params) == str_ty {
+                    return Some(fct_id);
+                }
+            }
+        }
+
+        None
+    }
+
+    fn check_expr_lit_struct(&mut self, e: &'ast ExprLitStructType) {
+        let sid = self.src.map_idents.get(e.id).unwrap().struct_id();
+        let struc = self.ctxt.structs[sid].borrow();
 
         let mut initialized: HashMap<Name, BuiltinType> = Default::default();
 
@@ -1341,6 +2538,7 @@ impl<'a, 'ast> Visitor<'ast> for TypeCheck<'a, 'ast> {
                 self.src.set_ty(id, str_ty);
                 self.expr_type = str_ty;
             }
+            ExprTemplate(ref expr) => self.check_expr_template(expr),
             ExprLitBool(ExprLitBoolType { id, .. }) => {
                 self.src.set_ty(id, BuiltinType::Bool);
                 self.expr_type = BuiltinType::Bool;
@@ -1350,7 +2548,7 @@ impl<'a, 'ast> Visitor<'ast> for TypeCheck<'a, 'ast> {
             ExprAssign(ref expr) => self.check_expr_assign(expr),
             ExprUn(ref expr) => self.check_expr_un(expr),
             ExprBin(ref expr) => self.check_expr_bin(expr),
-            ExprCall(ref expr) => self.check_expr_call(expr, false),
+            ExprCall(ref expr) => self.check_expr_call(expr, false, None),
             ExprDelegation(ref expr) => self.check_expr_delegation(expr),
             ExprField(ref expr) => self.check_expr_field(expr),
             ExprSelf(ref expr) => self.check_expr_this(expr),
@@ -1358,30 +2556,82 @@ impl<'a, 'ast> Visitor<'ast> for TypeCheck<'a, 'ast> {
             ExprNil(ref expr) => self.check_expr_nil(expr),
             ExprArray(ref expr) => self.check_expr_array(expr),
             ExprConv(ref expr) => self.check_expr_conv(expr),
-            ExprTry(ref expr) => self.check_expr_try(expr),
+            ExprTry(ref expr) => self.check_expr_try(expr, None),
             ExprLambda(ref expr) => self.check_expr_lambda(expr),
+            ExprSpawn(ref expr) => self.check_expr_spawn(expr),
+            ExprPath(ref expr) => self.check_expr_path(expr),
+            ExprMatch(ref expr) => self.check_expr_match(expr),
+            ExprTuple(ref expr) => self.check_expr_tuple(expr),
+            ExprTupleElement(ref expr) => self.check_expr_tuple_element(expr),
         }
     }
 
     fn visit_stmt(&mut self, s: &'ast Stmt) {
         match *s {
             StmtVar(ref stmt) => self.check_stmt_var(stmt),
+            StmtVarTuple(ref stmt) => self.check_stmt_var_tuple(stmt),
             StmtWhile(ref stmt) => self.check_stmt_while(stmt),
             StmtFor(ref stmt) => self.check_stmt_for(stmt),
             StmtIf(ref stmt) => self.check_stmt_if(stmt),
             StmtReturn(ref stmt) => self.check_stmt_return(stmt),
             StmtThrow(ref stmt) => self.check_stmt_throw(stmt),
-            StmtSpawn(_) => unimplemented!(),
             StmtDefer(ref stmt) => self.check_stmt_defer(stmt),
             StmtDo(ref stmt) => self.check_stmt_do(stmt),
+            StmtLoop(ref stmt) => self.check_stmt_loop(stmt),
 
             // for the rest of the statements, no special handling is necessary
             StmtBreak(_) => visit::walk_stmt(self, s),
             StmtContinue(_) => visit::walk_stmt(self, s),
-            StmtLoop(_) => visit::walk_stmt(self, s),
             StmtExpr(_) => visit::walk_stmt(self, s),
             StmtBlock(_) => visit::walk_stmt(self, s),
+            StmtError(_) => {}
+        }
+    }
+}
+
+// whether control never reaches the statement following `s`
+fn always_exits(s: &Stmt) -> bool {
+    match *s {
+        StmtBreak(_) | StmtContinue(_) => true,
+        StmtBlock(ref block) => block.stmts.iter().any(|s| always_exits(s)),
+        StmtIf(ref stmt) => {
+            always_exits(&stmt.then_block)
+                && stmt.else_block.as_ref().map_or(false, |s| always_exits(s))
+        }
+        _ => returnck::returns_value(s).is_ok(),
+    }
+}
+
+// collects the local variables assigned somewhere in a statement
+struct AssignedVars<'a> {
+    src: &'a FctSrc,
+    vars: Vec<VarId>,
+}
+
+impl<'a> AssignedVars<'a> {
+    fn find<F>(src: &'a FctSrc, f: F) -> Vec<VarId>
+    where
+        F: FnOnce(&mut AssignedVars<'a>),
+    {
+        let mut finder = AssignedVars {
+            src: src,
+            vars: Vec::new(),
+        };
+
+        f(&mut finder);
+        finder.vars
+    }
+}
+
+impl<'a, 'ast> Visitor<'ast> for AssignedVars<'a> {
+    fn visit_expr(&mut self, e: &'ast Expr) {
+        if let ExprAssign(ref assign) = *e {
+            if let Some(&IdentType::Var(var_id)) = self.src.map_idents.get(assign.lhs.id()) {
+                self.vars.push(var_id);
+            }
         }
+
+        visit::walk_expr(self, e);
     }
 }
 
@@ -1423,14 +2673,19 @@ fn arg_allows(
         | BuiltinType::Byte
         | BuiltinType::Char
         | BuiltinType::Struct(_, _)
+        | BuiltinType::Enum(_)
+        | BuiltinType::Short
         | BuiltinType::Int
+        | BuiltinType::UInt
         | BuiltinType::Long
+        | BuiltinType::ULong
         | BuiltinType::Float
         | BuiltinType::Double => def == arg,
         BuiltinType::Nil => panic!("nil should not occur in fct definition."),
         BuiltinType::Ptr => panic!("ptr should not occur in fct definition."),
-        BuiltinType::This => panic!("this should not occur in fct definition."),
-        BuiltinType::Trait(_) => panic!("trait should not occur in fct definition."),
+        BuiltinType::This => def == arg,
+        BuiltinType::Trait(_, _) => panic!("trait should not occur in fct definition."),
+        BuiltinType::TraitTypeParam(_, _) => def == arg,
 
         BuiltinType::ClassTypeParam(cls_id, tpid) => {
             if def == arg {
@@ -1472,7 +2727,7 @@ fn arg_allows(
         }
 
         BuiltinType::Class(cls_id, list_id) => {
-            if def == arg || arg.is_nil() {
+            if def == arg {
                 return true;
             }
 
@@ -1516,6 +2771,33 @@ fn arg_allows(
             //                             sub class for return type
             def == arg
         }
+
+        BuiltinType::Nullable(_) => {
+            arg.is_nil() || arg_allows(
+                ctxt,
+                def.non_nullable(ctxt),
+                arg.non_nullable(ctxt),
+                global_cls_id,
+                global_fct_id,
+                cls_tps,
+                fct_tps,
+            )
+        }
+
+        BuiltinType::Tuple(tuple_id) => {
+            let other_id = match arg {
+                BuiltinType::Tuple(other_id) => other_id,
+                _ => return false,
+            };
+
+            let elements = ctxt.tuples.borrow().get(tuple_id);
+            let other_elements = ctxt.tuples.borrow().get(other_id);
+
+            elements.len() == other_elements.len()
+                && elements.iter().zip(other_elements.iter()).all(|(&a, &b)| {
+                    arg_allows(ctxt, a, b, global_cls_id, global_fct_id, cls_tps, fct_tps)
+                })
+        }
     }
 }
 
@@ -1526,27 +2808,42 @@ fn check_lit_int<'ast>(
 ) -> (BuiltinType, i64) {
     let ty = match e.suffix {
         IntSuffix::Byte => BuiltinType::Byte,
+        IntSuffix::Short => BuiltinType::Short,
         IntSuffix::Int => BuiltinType::Int,
+        IntSuffix::UInt => BuiltinType::UInt,
         IntSuffix::Long => BuiltinType::Long,
+        IntSuffix::ULong => BuiltinType::ULong,
     };
 
     let ty_name = match e.suffix {
         IntSuffix::Byte => "byte",
+        IntSuffix::Short => "short",
         IntSuffix::Int => "int",
+        IntSuffix::UInt => "uint",
         IntSuffix::Long => "long",
+        IntSuffix::ULong => "ulong",
     };
 
     let val = e.value;
-    let negative = e.suffix != IntSuffix::Byte && negative_expr_id == e.id;
+    let negative = match e.suffix {
+        IntSuffix::Byte | IntSuffix::UInt | IntSuffix::ULong => false,
+        IntSuffix::Short | IntSuffix::Int | IntSuffix::Long => negative_expr_id == e.id,
+    };
 
     if e.base == IntBase::Dec {
         let max = match e.suffix {
-            IntSuffix::Byte => 256,
-            IntSuffix::Int => (1u64 << 31),
-            IntSuffix::Long => (1u64 << 63),
+            IntSuffix::Byte => 255,
+            IntSuffix::Short => (1u64 << 15) - 1,
+            IntSuffix::Int => (1u64 << 31) - 1,
+            IntSuffix::UInt => u32::max_value() as u64,
+            IntSuffix::Long => (1u64 << 63) - 1,
+            IntSuffix::ULong => u64::max_value(),
         };
 
-        if (negative && val > max) || (!negative && val >= max) {
+        // two's complement allows one more negative than positive value
+        let max = if negative { max + 1 } else { max };
+
+        if val > max {
             ctxt.diag
                 .borrow_mut()
                 .report(e.pos, Msg::NumberOverflow(ty_name.into()));
@@ -1554,8 +2851,9 @@ fn check_lit_int<'ast>(
     } else {
         let max = match e.suffix {
             IntSuffix::Byte => 256 as u64,
-            IntSuffix::Int => u32::max_value() as u64,
-            IntSuffix::Long => u64::max_value() as u64,
+            IntSuffix::Short => u16::max_value() as u64,
+            IntSuffix::Int | IntSuffix::UInt => u32::max_value() as u64,
+            IntSuffix::Long | IntSuffix::ULong => u64::max_value() as u64,
         };
 
         if val > max {
@@ -1609,94 +2907,425 @@ fn check_lit_float<'ast>(
     (ty, value)
 }
 
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum ConstState {
+    Checking,
+    Checked,
+}
+
+// evaluates const initializers, consts referenced by an initializer are
+// evaluated first (possibly in another module)
 struct ConstCheck<'a, 'ast: 'a> {
     ctxt: &'a SemContext<'ast>,
-    xconst: &'a ConstData<'ast>,
+    states: HashMap<ConstId, ConstState>,
     negative_expr_id: NodeId,
 }
 
 impl<'a, 'ast> ConstCheck<'a, 'ast> {
+    fn check_const(&mut self, id: ConstId) -> (BuiltinType, ConstValue) {
+        let (pos, name, module, ty, expr) = {
+            let xconst = self.ctxt.consts[id].borrow();
+            (xconst.pos, xconst.name, xconst.module, xconst.ty, xconst.expr)
+        };
+
+        match self.states.get(&id).cloned() {
+            Some(ConstState::Checked) => {
+                let value = self.ctxt.consts[id].borrow().value.clone();
+                return const_result(ty, value);
+            }
+
+            Some(ConstState::Checking) => {
+                let name = self.ctxt.interner.str(name).to_string();
+                self.ctxt.diag.borrow_mut().report(pos, Msg::ConstCycle(name));
+                return (BuiltinType::Error, ConstValue::None);
+            }
+
+            None => {}
+        }
+
+        self.states.insert(id, ConstState::Checking);
+
+        let saved = self.ctxt.sym.borrow_mut().push_module(module);
+        let negative_expr_id = mem::replace(&mut self.negative_expr_id, NodeId(0));
+        let (expr_ty, value) = self.check_expr(expr);
+        self.negative_expr_id = negative_expr_id;
+        self.ctxt.sym.borrow_mut().pop_module(saved);
+
+        let value = if expr_ty.is_error() || ty.is_error() {
+            ConstValue::None
+        } else if !ty.allows(self.ctxt, expr_ty) {
+            let name = self.ctxt.interner.str(name).to_string();
+            let const_ty = ty.name(self.ctxt);
+            let expr_ty = expr_ty.name(self.ctxt);
+            let msg = Msg::AssignType(name, const_ty, expr_ty);
+            self.ctxt.diag.borrow_mut().report(expr.pos(), msg);
+
+            ConstValue::None
+        } else {
+            value
+        };
+
+        self.ctxt.consts[id].borrow_mut().value = value.clone();
+        self.states.insert(id, ConstState::Checked);
+
+        const_result(ty, value)
+    }
+
     fn check_expr(&mut self, expr: &'ast Expr) -> (BuiltinType, ConstValue) {
-        let (ty, lit) = match expr {
+        match expr {
             &ExprLitChar(ref expr) => (BuiltinType::Char, ConstValue::Char(expr.value)),
             &ExprLitInt(ref expr) => {
                 let (ty, val) = check_lit_int(self.ctxt, expr, self.negative_expr_id);
-                (ty, ConstValue::Int(val))
+                (ty, ConstValue::Int(truncate_int(ty, val)))
             }
             &ExprLitFloat(ref expr) => {
                 let (ty, val) = check_lit_float(self.ctxt, expr, self.negative_expr_id);
                 (ty, ConstValue::Float(val))
             }
             &ExprLitBool(ref expr) => (BuiltinType::Bool, ConstValue::Bool(expr.value)),
+            &ExprIdent(ref expr) => self.check_expr_ident(expr),
+            &ExprUn(ref expr) => self.check_expr_un(expr),
+            &ExprBin(ref expr) => self.check_expr_bin(expr),
 
-            &ExprUn(ref expr) if expr.op == UnOp::Neg => {
-                if self.negative_expr_id != expr.id {
-                    self.negative_expr_id = expr.opnd.id();
-                }
-
-                let (ty, val) = self.check_expr(&expr.opnd);
-                let name = self.ctxt.interner.intern("unaryMinus");
+            _ => {
+                let msg = Msg::ConstValueExpected;
+                self.ctxt.diag.borrow_mut().report(expr.pos(), msg);
+                (BuiltinType::Error, ConstValue::None)
+            }
+        }
+    }
 
-                if lookup_method(
-                    self.ctxt,
-                    ty,
-                    false,
-                    name,
-                    &[],
-                    &TypeParams::empty(),
-                    Some(ty),
-                ).is_none()
-                {
-                    let ty = ty.name(self.ctxt);
-                    let msg = Msg::UnOpType(expr.op.as_str().into(), ty);
+    fn check_expr_ident(&mut self, e: &'ast ExprIdentType) -> (BuiltinType, ConstValue) {
+        let sym = self.ctxt.sym.borrow().get(e.name);
 
-                    self.ctxt.diag.borrow_mut().report(expr.pos, msg);
-                }
+        match sym {
+            Some(SymConst(id)) => self.check_const(id),
 
-                return (ty, val);
+            Some(_) => {
+                let msg = Msg::ConstValueExpected;
+                self.ctxt.diag.borrow_mut().report(e.pos, msg);
+                (BuiltinType::Error, ConstValue::None)
             }
 
-            _ => {
-                let msg = Msg::ConstValueExpected;
-                self.ctxt.diag.borrow_mut().report(expr.pos(), msg);
-                return (BuiltinType::Error, ConstValue::None);
+            None => {
+                let name = self.ctxt.interner.str(e.name).to_string();
+                let msg = Msg::UnknownIdentifier(name);
+                self.ctxt.diag.borrow_mut().report(e.pos, msg);
+                (BuiltinType::Error, ConstValue::None)
             }
+        }
+    }
+
+    fn check_expr_un(&mut self, e: &'ast ExprUnType) -> (BuiltinType, ConstValue) {
+        // a negated literal already evaluates to its negative value
+        let mut negated = false;
+
+        if e.op == UnOp::Neg && self.negative_expr_id != e.id {
+            self.negative_expr_id = e.opnd.id();
+
+            negated = match *e.opnd {
+                ExprLitInt(_) | ExprLitFloat(_) => true,
+                _ => false,
+            };
+        }
+
+        let (ty, value) = self.check_expr(&e.opnd);
+
+        if ty.is_error() {
+            return (BuiltinType::Error, ConstValue::None);
+        }
+
+        let name = match e.op {
+            UnOp::Plus => "unaryPlus",
+            UnOp::Neg => "unaryMinus",
+            UnOp::Not => "not",
         };
+        let name = self.ctxt.interner.intern(name);
 
-        if !self.xconst.ty.allows(self.ctxt, ty) {
-            let name = self.ctxt.interner.str(self.xconst.name).to_string();
-            let const_ty = self.xconst.ty.name(self.ctxt);
+        if lookup_method(
+            self.ctxt,
+            ty,
+            false,
+            name,
+            &[],
+            &TypeParams::empty(),
+            None,
+        ).is_none()
+        {
             let ty = ty.name(self.ctxt);
-            let msg = Msg::AssignType(name, const_ty, ty);
-            self.ctxt.diag.borrow_mut().report(expr.pos(), msg);
+            let msg = Msg::UnOpType(e.op.as_str().into(), ty);
+            self.ctxt.diag.borrow_mut().report(e.pos, msg);
+
+            return (BuiltinType::Error, ConstValue::None);
         }
 
-        (ty, lit)
+        let value = match (e.op, value) {
+            (UnOp::Neg, value) if negated => Some(value),
+            (UnOp::Neg, ConstValue::Int(val)) => neg_int(ty, val).map(ConstValue::Int),
+            (UnOp::Neg, ConstValue::Float(val)) => Some(ConstValue::Float(-val)),
+            (UnOp::Not, ConstValue::Bool(val)) => Some(ConstValue::Bool(!val)),
+            (UnOp::Not, ConstValue::Int(val)) => Some(ConstValue::Int(truncate_int(ty, !val))),
+            (_, value) => Some(value),
+        };
+
+        match value {
+            Some(value) => (ty, value),
+            None => {
+                let msg = Msg::NumberOverflow(ty.name(self.ctxt));
+                self.ctxt.diag.borrow_mut().report(e.pos, msg);
+                (BuiltinType::Error, ConstValue::None)
+            }
+        }
     }
-}
 
-#[derive(Copy, Clone)]
-enum LookupKind {
-    Fct,
-    Method(BuiltinType),
-    Static(ClassId),
-    Callee(FctId),
-    Ctor(ClassId),
-}
+    fn check_expr_bin(&mut self, e: &'ast ExprBinType) -> (BuiltinType, ConstValue) {
+        let (lhs_type, lhs) = self.check_expr(&e.lhs);
+        let (rhs_type, rhs) = self.check_expr(&e.rhs);
 
-struct MethodLookup<'a, 'ast: 'a> {
-    ctxt: &'a SemContext<'ast>,
+        if lhs_type.is_error() || rhs_type.is_error() {
+            return (BuiltinType::Error, ConstValue::None);
+        }
+
+        let name = match e.op {
+            BinOp::Or | BinOp::And => None,
+            BinOp::Cmp(CmpOp::Eq) | BinOp::Cmp(CmpOp::Ne) => Some("equals"),
+            BinOp::Cmp(CmpOp::Is) | BinOp::Cmp(CmpOp::IsNot) => {
+                let msg = Msg::ConstValueExpected;
+                self.ctxt.diag.borrow_mut().report(e.pos, msg);
+                return (BuiltinType::Error, ConstValue::None);
+            }
+            BinOp::Cmp(_) => Some("compareTo"),
+            BinOp::Add => Some("plus"),
+            BinOp::Sub => Some("minus"),
+            BinOp::Mul => Some("times"),
+            BinOp::Div => Some("div"),
+            BinOp::Mod => Some("mod"),
+            BinOp::BitOr => Some("bitwiseOr"),
+            BinOp::BitAnd => Some("bitwiseAnd"),
+            BinOp::BitXor => Some("bitwiseXor"),
+            BinOp::ShiftL => Some("shiftLeft"),
+            BinOp::ShiftR => Some("shiftRight"),
+            BinOp::UnShiftR => Some("unsignedShiftRight"),
+        };
+
+        let ty = if let Some(name) = name {
+            let name = self.ctxt.interner.intern(name);
+
+            lookup_method(
+                self.ctxt,
+                lhs_type,
+                false,
+                name,
+                &[rhs_type],
+                &TypeParams::empty(),
+                None,
+            ).map(|(_, _, return_type)| match e.op {
+                BinOp::Cmp(_) => BuiltinType::Bool,
+                _ => return_type,
+            })
+        } else if lhs_type == BuiltinType::Bool && rhs_type == BuiltinType::Bool {
+            Some(BuiltinType::Bool)
+        } else {
+            None
+        };
+
+        let ty = match ty {
+            Some(ty) => ty,
+            None => {
+                let lhs_type = lhs_type.name(self.ctxt);
+                let rhs_type = rhs_type.name(self.ctxt);
+                let msg = Msg::BinOpType(e.op.as_str().into(), lhs_type, rhs_type);
+                self.ctxt.diag.borrow_mut().report(e.pos, msg);
+
+                return (BuiltinType::Error, ConstValue::None);
+            }
+        };
+
+        let value = match (lhs, rhs) {
+            (ConstValue::Bool(lhs), ConstValue::Bool(rhs)) => Ok(ConstValue::Bool(match e.op {
+                BinOp::Or => lhs || rhs,
+                BinOp::And => lhs && rhs,
+                BinOp::Cmp(CmpOp::Eq) => lhs == rhs,
+                _ => lhs != rhs,
+            })),
+            (ConstValue::Char(lhs), ConstValue::Char(rhs)) => {
+                Ok(ConstValue::Bool(cmp_matches(e.op, lhs.cmp(&rhs))))
+            }
+            (ConstValue::Int(lhs), ConstValue::Int(rhs)) => eval_int(lhs_type, e.op, lhs, rhs),
+            (ConstValue::Float(lhs), ConstValue::Float(rhs)) => {
+                eval_float(lhs_type, e.op, lhs, rhs)
+            }
+            _ => unreachable!(),
+        };
+
+        match value {
+            Ok(value) => (ty, value),
+            Err(msg) => {
+                self.ctxt.diag.borrow_mut().report(e.pos, msg);
+                (BuiltinType::Error, ConstValue::None)
+            }
+        }
+    }
+}
+
+fn const_result(ty: BuiltinType, value: ConstValue) -> (BuiltinType, ConstValue) {
+    match value {
+        ConstValue::None => (BuiltinType::Error, ConstValue::None),
+        value => (ty, value),
+    }
+}
+
+// int constants are stored sign- or zero-extended depending on the type
+fn truncate_int(ty: BuiltinType, val: i64) -> i64 {
+    match ty {
+        BuiltinType::Byte => val as u8 as i64,
+        BuiltinType::Short => val as i16 as i64,
+        BuiltinType::Int => val as i32 as i64,
+        BuiltinType::UInt => val as u32 as i64,
+        _ => val,
+    }
+}
+
+fn neg_int(ty: BuiltinType, val: i64) -> Option<i64> {
+    match ty {
+        BuiltinType::Short => (val as i16).checked_neg().map(|v| v as i64),
+        BuiltinType::Int => (val as i32).checked_neg().map(|v| v as i64),
+        BuiltinType::Long => val.checked_neg(),
+        _ => None,
+    }
+}
+
+fn cmp_matches(op: BinOp, ord: Ordering) -> bool {
+    match op {
+        BinOp::Cmp(CmpOp::Eq) => ord == Ordering::Equal,
+        BinOp::Cmp(CmpOp::Ne) => ord != Ordering::Equal,
+        BinOp::Cmp(CmpOp::Lt) => ord == Ordering::Less,
+        BinOp::Cmp(CmpOp::Le) => ord != Ordering::Greater,
+        BinOp::Cmp(CmpOp::Gt) => ord == Ordering::Greater,
+        BinOp::Cmp(CmpOp::Ge) => ord != Ordering::Less,
+        _ => unreachable!(),
+    }
+}
+
+// computes the operation in the width of the type, `None` signals an overflow
+macro_rules! eval_int_as {
+    ($t:ty, $ut:ty, $op:expr, $lhs:expr, $rhs:expr) => {{
+        let lhs = $lhs as $t;
+        let rhs = $rhs as $t;
+        let bits = (mem::size_of::<$t>() * 8) as i64;
+
+        let value = match $op {
+            BinOp::Add => lhs.checked_add(rhs),
+            BinOp::Sub => lhs.checked_sub(rhs),
+            BinOp::Mul => lhs.checked_mul(rhs),
+            BinOp::Div => lhs.checked_div(rhs),
+            BinOp::Mod => lhs.checked_rem(rhs),
+            BinOp::BitOr => Some(lhs | rhs),
+            BinOp::BitAnd => Some(lhs & rhs),
+            BinOp::BitXor => Some(lhs ^ rhs),
+            BinOp::ShiftL | BinOp::ShiftR | BinOp::UnShiftR if $rhs < 0 || $rhs >= bits => None,
+            BinOp::ShiftL => Some(lhs << rhs),
+            BinOp::ShiftR => Some(lhs >> rhs),
+            BinOp::UnShiftR => Some(((lhs as $ut) >> rhs) as $t),
+            BinOp::Cmp(_) => {
+                return Ok(ConstValue::Bool(cmp_matches($op, lhs.cmp(&rhs))));
+            }
+            BinOp::Or | BinOp::And => unreachable!(),
+        };
+
+        value.map(|v| ConstValue::Int(v as i64))
+    }};
+}
+
+fn eval_int(ty: BuiltinType, op: BinOp, lhs: i64, rhs: i64) -> Result<ConstValue, Msg> {
+    match op {
+        BinOp::Div | BinOp::Mod if rhs == 0 => return Err(Msg::ConstDivisionByZero),
+        _ => {}
+    }
+
+    let value = match ty {
+        BuiltinType::Byte => eval_int_as!(u8, u8, op, lhs, rhs),
+        BuiltinType::Short => eval_int_as!(i16, u16, op, lhs, rhs),
+        BuiltinType::Int => eval_int_as!(i32, u32, op, lhs, rhs),
+        BuiltinType::UInt => eval_int_as!(u32, u32, op, lhs, rhs),
+        BuiltinType::Long => eval_int_as!(i64, u64, op, lhs, rhs),
+        BuiltinType::ULong => eval_int_as!(u64, u64, op, lhs, rhs),
+        _ => unreachable!(),
+    };
+
+    value.ok_or_else(|| Msg::NumberOverflow(prim_type_name(ty).into()))
+}
+
+fn prim_type_name(ty: BuiltinType) -> &'static str {
+    match ty {
+        BuiltinType::Byte => "byte",
+        BuiltinType::Short => "short",
+        BuiltinType::Int => "int",
+        BuiltinType::UInt => "uint",
+        BuiltinType::Long => "long",
+        BuiltinType::ULong => "ulong",
+        BuiltinType::Float => "float",
+        _ => "double",
+    }
+}
+
+fn eval_float(ty: BuiltinType, op: BinOp, lhs: f64, rhs: f64) -> Result<ConstValue, Msg> {
+    let value = match op {
+        BinOp::Cmp(CmpOp::Eq) => return Ok(ConstValue::Bool(lhs == rhs)),
+        BinOp::Cmp(CmpOp::Ne) => return Ok(ConstValue::Bool(lhs != rhs)),
+        BinOp::Cmp(_) => {
+            let ord = lhs.partial_cmp(&rhs).unwrap();
+            return Ok(ConstValue::Bool(cmp_matches(op, ord)));
+        }
+        BinOp::Div if rhs == 0.0 => return Err(Msg::ConstDivisionByZero),
+        BinOp::Add => lhs + rhs,
+        BinOp::Sub => lhs - rhs,
+        BinOp::Mul => lhs * rhs,
+        BinOp::Div => lhs / rhs,
+        _ => unreachable!(),
+    };
+
+    let value = if ty == BuiltinType::Float {
+        value as f32 as f64
+    } else {
+        value
+    };
+
+    if value.is_infinite() {
+        Err(Msg::NumberOverflow(prim_type_name(ty).into()))
+    } else {
+        Ok(ConstValue::Float(value))
+    }
+}
+
+#[derive(Copy, Clone)]
+enum LookupKind {
+    Fct,
+    Method(BuiltinType),
+    Static(ClassId),
+    Callee(FctId),
+    Ctor(ClassId),
+}
+
+struct MethodLookup<'a, 'ast: 'a> {
+    ctxt: &'a SemContext<'ast>,
     kind: Option<LookupKind>,
     name: Option<Name>,
     args: Option<&'a [BuiltinType]>,
+    arg_names: Option<&'a [String]>,
     cls_tps: Option<&'a TypeParams>,
     fct_tps: Option<&'a TypeParams>,
+    infer_tps: bool,
+    expected: Option<BuiltinType>,
     ret: Option<BuiltinType>,
     pos: Option<Position>,
 
     found_fct_id: Option<FctId>,
     found_cls_id: Option<ClassId>,
+    found_cls_tps: Option<TypeParams>,
+    found_fct_tps: Option<TypeParams>,
     found_ret: Option<BuiltinType>,
+    infer_error: Option<Msg>,
 }
 
 impl<'a, 'ast> MethodLookup<'a, 'ast> {
@@ -1706,14 +3335,20 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
             kind: None,
             name: None,
             args: None,
+            arg_names: None,
             cls_tps: None,
             fct_tps: None,
+            infer_tps: false,
+            expected: None,
             ret: None,
             pos: None,
 
             found_fct_id: None,
             found_cls_id: None,
+            found_cls_tps: None,
+            found_fct_tps: None,
             found_ret: None,
+            infer_error: None,
         }
     }
 
@@ -1747,6 +3382,12 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
         self
     }
 
+    // names of the argument types in diagnostics, defaults to the types' names
+    fn arg_names(mut self, arg_names: &'a [String]) -> MethodLookup<'a, 'ast> {
+        self.arg_names = Some(arg_names);
+        self
+    }
+
     fn pos(mut self, pos: Position) -> MethodLookup<'a, 'ast> {
         self.pos = Some(pos);
         self
@@ -1762,6 +3403,14 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
         self
     }
 
+    // type params not given explicitly are inferred from the arguments
+    // and, if still unknown, from the type the call is expected to have
+    fn infer_type_params(mut self, expected: Option<BuiltinType>) -> MethodLookup<'a, 'ast> {
+        self.infer_tps = true;
+        self.expected = expected;
+        self
+    }
+
     fn name(mut self, name: Name) -> MethodLookup<'a, 'ast> {
         self.name = Some(name);
         self
@@ -1802,13 +3451,23 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
             }
 
             LookupKind::Ctor(cls_id) => {
-                assert!(self.cls_tps.is_some());
+                assert!(self.cls_tps.is_some() || self.infer_tps);
                 self.find_ctor(cls_id)
             }
         };
 
         self.found_fct_id = fct_id;
 
+        if fct_id.is_none() {
+            if let Some(msg) = self.infer_error.take() {
+                self.ctxt
+                    .diag
+                    .borrow_mut()
+                    .report(self.pos.expect("pos not set"), msg);
+                return false;
+            }
+        }
+
         let fct_id = if let Some(fct_id) = fct_id {
             fct_id
         } else {
@@ -1867,6 +3526,8 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
 
         let cls_tps: TypeParams = if let Some(cls_tps) = self.cls_tps {
             cls_tps.clone()
+        } else if let Some(ref cls_tps) = self.found_cls_tps {
+            cls_tps.clone()
         } else if let LookupKind::Method(obj) = kind {
             obj.type_params(self.ctxt)
         } else {
@@ -1883,13 +3544,30 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
             }
 
             fct_tps.clone()
+        } else if self.infer_tps {
+            let fct_tps = match self.infer_fct_tps(&*fct, args) {
+                Ok(fct_tps) => fct_tps,
+                Err(msg) => {
+                    self.ctxt
+                        .diag
+                        .borrow_mut()
+                        .report(self.pos.expect("pos not set"), msg);
+                    return false;
+                }
+            };
+
+            if !self.check_fct_tps(&fct_tps) {
+                return false;
+            }
+
+            fct_tps
         } else {
             TypeParams::empty()
         };
 
         if !args_compatible(
             self.ctxt,
-            &fct.params_without_self(),
+            &fct.params_for_args(self.ctxt, args),
             args,
             cls_id,
             Some(fct_id),
@@ -1899,9 +3577,21 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
             let fct_name = self.ctxt.interner.str(fct.name).to_string();
             let fct_params = fct.params_without_self()
                 .iter()
-                .map(|a| a.name(self.ctxt))
+                .zip(&fct.ast.params)
+                .map(|(ty, param)| {
+                    let alias = if param.variadic {
+                        None
+                    } else {
+                        semck::alias_name(self.ctxt, &param.data_type)
+                    };
+
+                    alias.unwrap_or_else(|| ty.name(self.ctxt))
+                })
                 .collect::<Vec<_>>();
-            let call_types = args.iter().map(|a| a.name(self.ctxt)).collect::<Vec<_>>();
+            let call_types = match self.arg_names {
+                Some(arg_names) => arg_names.to_vec(),
+                None => args.iter().map(|a| a.name(self.ctxt)).collect::<Vec<_>>(),
+            };
             let msg = Msg::ParamTypesIncompatible(fct_name, fct_params, call_types);
             self.ctxt
                 .diag
@@ -1912,7 +3602,7 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
 
         let cmp_type = match kind {
             LookupKind::Ctor(cls_id) => {
-                let list_id = self.ctxt.lists.borrow_mut().insert(cls_tps);
+                let list_id = self.ctxt.lists.borrow_mut().insert(cls_tps.clone());
                 BuiltinType::Class(cls_id, list_id)
             }
 
@@ -1920,6 +3610,8 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
         };
 
         if self.ret.is_none() || self.ret.unwrap() == cmp_type {
+            self.found_cls_tps = Some(cls_tps);
+            self.found_fct_tps = Some(fct_tps);
             self.found_ret = Some(cmp_type);
             true
         } else {
@@ -1931,24 +3623,56 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
         unimplemented!()
     }
 
-    fn find_ctor(&self, cls_id: ClassId) -> Option<FctId> {
+    fn find_ctor(&mut self, cls_id: ClassId) -> Option<FctId> {
         let cls = self.ctxt.classes[cls_id].borrow();
-
-        let type_params = self.cls_tps.as_ref().unwrap();
         let args = self.args.unwrap();
 
         for &ctor_id in &cls.ctors {
             let ctor = self.ctxt.fcts[ctor_id].borrow();
+            let params = ctor.params_for_args(self.ctxt, args);
+
+            let type_params = if let Some(cls_tps) = self.cls_tps {
+                cls_tps.clone()
+            } else if params.len() != args.len() {
+                continue;
+            } else {
+                let name = self.ctxt.interner.str(cls.name).to_string();
+                let list = (0..cls.type_params.len())
+                    .map(|idx| BuiltinType::ClassTypeParam(cls_id, idx.into()))
+                    .collect::<Vec<_>>();
+                let list_id = self.ctxt.lists.borrow_mut().insert(list.into());
+                let cls_ty = BuiltinType::Class(cls_id, list_id);
+
+                let mut inference = TypeParamInference::new(
+                    self.ctxt,
+                    InferTarget::Class(cls_id),
+                    cls.type_params.len(),
+                );
+                inference.unify_all(&params, args);
+
+                match inference.finish(&cls.type_params, cls_ty, self.expected, name) {
+                    Ok(type_params) => type_params,
+                    Err(msg) => {
+                        if self.infer_error.is_none() {
+                            self.infer_error = Some(msg);
+                        }
+
+                        continue;
+                    }
+                }
+            };
 
             if args_compatible(
                 self.ctxt,
-                &ctor.params_without_self(),
+                &params,
                 &args,
                 Some(cls_id),
                 None,
-                type_params,
+                &type_params,
                 &TypeParams::empty(),
             ) {
+                self.found_cls_tps = Some(type_params);
+                self.infer_error = None;
                 return Some(ctor_id);
             }
         }
@@ -1956,6 +3680,15 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
         None
     }
 
+    fn infer_fct_tps(&self, fct: &Fct<'ast>, args: &[BuiltinType]) -> Result<TypeParams, Msg> {
+        let name = self.ctxt.interner.str(fct.name).to_string();
+        let mut inference =
+            TypeParamInference::new(self.ctxt, InferTarget::Fct(fct.id), fct.type_params.len());
+        inference.unify_all(&fct.params_for_args(self.ctxt, args), args);
+
+        inference.finish(&fct.type_params, fct.return_type, self.expected, name)
+    }
+
     fn find_method(&self, cls_id: ClassId, name: Name, is_static: bool) -> Option<FctId> {
         let cls = self.ctxt.classes[cls_id].borrow();
 
@@ -2003,12 +3736,17 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
                 let ok = match ty {
                     BuiltinType::ClassTypeParam(cls_id, tpid) => {
                         let cls = self.ctxt.classes[cls_id].borrow();
-                        self.check_tp_against_tp(tp, &cls.type_params[tpid.idx()], ty)
+                        self.check_tp_against_tp(tp, &cls.type_params[tpid.idx()], ty, tps)
                     }
 
                     BuiltinType::FctTypeParam(fct_id, tpid) => {
                         let fct = self.ctxt.fcts[fct_id].borrow();
-                        self.check_tp_against_tp(tp, &fct.type_params[tpid.idx()], ty)
+                        self.check_tp_against_tp(tp, &fct.type_params[tpid.idx()], ty, tps)
+                    }
+
+                    BuiltinType::TraitTypeParam(trait_id, tpid) => {
+                        let xtrait = self.ctxt.traits[trait_id].borrow();
+                        self.check_tp_against_tp(tp, &xtrait.type_params[tpid.idx()], ty, tps)
                     }
 
                     _ => unreachable!(),
@@ -2017,7 +3755,7 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
                 if !ok {
                     succeeded = false;
                 }
-            } else if !self.check_tp(tp, ty) {
+            } else if !self.check_tp(tp, ty, tps) {
                 succeeded = false;
             }
         }
@@ -2025,7 +3763,17 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
         succeeded
     }
 
-    fn check_tp(&self, tp: &ctxt::TypeParam, ty: BuiltinType) -> bool {
+    // bounds of a type param are specialized with the type arguments
+    // `tps` before they are checked
+    fn specialize_trait_bound(&self, params: &TypeParams, tps: &TypeParams) -> TypeParams {
+        params
+            .iter()
+            .map(|t| specialize_bound(self.ctxt, t, tps))
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    fn check_tp(&self, tp: &ctxt::TypeParam, ty: BuiltinType, tps: &TypeParams) -> bool {
         let mut succeeded = true;
 
         if let Some(cls_id) = tp.class_bound {
@@ -2036,12 +3784,12 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
             }
         }
 
-        let cls_id = ty.cls_id(self.ctxt).unwrap();
-        let cls = self.ctxt.classes[cls_id].borrow();
+        for (&trait_bound, trait_params) in &tp.trait_bounds {
+            let trait_params = self.specialize_trait_bound(trait_params, tps);
 
-        for &trait_bound in &tp.trait_bounds {
-            if !cls.traits.contains(&trait_bound) {
-                self.fail_trait_bound(trait_bound, ty);
+            // lambdas have no class and therefore implement no traits
+            if !ty.implements_trait_with(self.ctxt, trait_bound, &trait_params) {
+                self.fail_trait_bound(trait_bound, &trait_params, ty);
                 succeeded = false;
             }
         }
@@ -2054,6 +3802,7 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
         tp: &ctxt::TypeParam,
         arg: &ctxt::TypeParam,
         arg_ty: BuiltinType,
+        tps: &TypeParams,
     ) -> bool {
         let mut succeeded = true;
 
@@ -2068,11 +3817,11 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
             return succeeded;
         }
 
-        let traits_set = arg.trait_bounds.iter().collect::<HashSet<_>>();
+        for (&trait_bound, trait_params) in &tp.trait_bounds {
+            let trait_params = self.specialize_trait_bound(trait_params, tps);
 
-        for &trait_bound in &tp.trait_bounds {
-            if !traits_set.contains(&trait_bound) {
-                self.fail_trait_bound(trait_bound, arg_ty);
+            if arg.trait_bounds.get(&trait_bound) != Some(&trait_params) {
+                self.fail_trait_bound(trait_bound, &trait_params, arg_ty);
                 succeeded = false;
             }
         }
@@ -2092,11 +3841,10 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
             .report(self.pos.expect("pos not set"), msg);
     }
 
-    fn fail_trait_bound(&self, trait_id: TraitId, ty: BuiltinType) {
-        let bound = self.ctxt.traits[trait_id].borrow();
-        let name = ty.name(self.ctxt);
-        let trait_name = self.ctxt.interner.str(bound.name).to_string();
-        let msg = Msg::TraitBoundNotSatisfied(name, trait_name);
+    fn fail_trait_bound(&self, trait_id: TraitId, trait_params: &TypeParams, ty: BuiltinType) {
+        let list_id = self.ctxt.lists.borrow_mut().insert(trait_params.clone());
+        let bound = BuiltinType::Trait(trait_id, list_id);
+        let msg = Msg::TraitBoundNotSatisfied(ty.name(self.ctxt), bound.name(self.ctxt));
         self.ctxt
             .diag
             .borrow_mut()
@@ -2111,11 +3859,181 @@ impl<'a, 'ast> MethodLookup<'a, 'ast> {
         self.found_cls_id
     }
 
+    fn found_cls_type_params(&self) -> Option<TypeParams> {
+        self.found_cls_tps.clone()
+    }
+
+    fn found_fct_type_params(&self) -> Option<TypeParams> {
+        self.found_fct_tps.clone()
+    }
+
     fn found_ret(&self) -> Option<BuiltinType> {
         self.found_ret
     }
 }
 
+#[derive(Copy, Clone)]
+enum InferTarget {
+    Class(ClassId),
+    Fct(FctId),
+}
+
+struct TypeParamInference<'a, 'ast: 'a> {
+    ctxt: &'a SemContext<'ast>,
+    target: InferTarget,
+    inferred: Vec<Option<BuiltinType>>,
+    conflict: Option<(usize, BuiltinType, BuiltinType)>,
+}
+
+impl<'a, 'ast> TypeParamInference<'a, 'ast> {
+    fn new(
+        ctxt: &'a SemContext<'ast>,
+        target: InferTarget,
+        len: usize,
+    ) -> TypeParamInference<'a, 'ast> {
+        TypeParamInference {
+            ctxt: ctxt,
+            target: target,
+            inferred: vec![None; len],
+            conflict: None,
+        }
+    }
+
+    fn unify_all(&mut self, def: &[BuiltinType], args: &[BuiltinType]) {
+        for (&def, &arg) in def.iter().zip(args.iter()) {
+            self.unify(def, arg);
+        }
+    }
+
+    fn unify(&mut self, def: BuiltinType, arg: BuiltinType) {
+        if let Some(idx) = self.type_param_idx(def) {
+            self.bind(idx, arg);
+            return;
+        }
+
+        match (def, arg) {
+            (BuiltinType::Class(cls_id, list_id), BuiltinType::Class(other_id, other_list_id))
+                if cls_id == other_id =>
+            {
+                let params = self.ctxt.lists.borrow().get(list_id);
+                let other_params = self.ctxt.lists.borrow().get(other_list_id);
+
+                for (def, arg) in params.iter().zip(other_params.iter()) {
+                    self.unify(def, arg);
+                }
+            }
+
+            (BuiltinType::Tuple(tuple_id), BuiltinType::Tuple(other_id)) => {
+                let elements = self.ctxt.tuples.borrow().get(tuple_id);
+                let other_elements = self.ctxt.tuples.borrow().get(other_id);
+
+                if elements.len() == other_elements.len() {
+                    self.unify_all(&elements, &other_elements);
+                }
+            }
+
+            (BuiltinType::Lambda(lambda_id), BuiltinType::Lambda(other_id)) => {
+                let lambda = self.ctxt.lambda_types.borrow().get(lambda_id);
+                let other = self.ctxt.lambda_types.borrow().get(other_id);
+
+                if lambda.params().len() == other.params().len() {
+                    self.unify_all(lambda.params(), other.params());
+                    self.unify(lambda.ret(), other.ret());
+                }
+            }
+
+            (BuiltinType::Nullable(_), _) | (_, BuiltinType::Nullable(_)) => {
+                let def = def.non_nullable(self.ctxt);
+                let arg = arg.non_nullable(self.ctxt);
+                self.unify(def, arg);
+            }
+
+            _ => {}
+        }
+    }
+
+    fn type_param_idx(&self, ty: BuiltinType) -> Option<usize> {
+        match (self.target, ty) {
+            (InferTarget::Class(cls_id), BuiltinType::ClassTypeParam(id, tpid)) if cls_id == id => {
+                Some(tpid.idx())
+            }
+
+            (InferTarget::Fct(fct_id), BuiltinType::FctTypeParam(id, tpid)) if fct_id == id => {
+                Some(tpid.idx())
+            }
+
+            _ => None,
+        }
+    }
+
+    // when a type param is bound more than once, the more general type wins
+    fn bind(&mut self, idx: usize, ty: BuiltinType) {
+        if ty.is_error() || ty.is_nil() {
+            return;
+        }
+
+        match self.inferred[idx] {
+            None => self.inferred[idx] = Some(ty),
+
+            Some(prev) => {
+                if prev.allows(self.ctxt, ty) {
+                    // keep more general type
+                } else if ty.allows(self.ctxt, prev) {
+                    self.inferred[idx] = Some(ty);
+                } else if self.conflict.is_none() {
+                    self.conflict = Some((idx, prev, ty));
+                }
+            }
+        }
+    }
+
+    // type params that could not be inferred from the arguments are taken
+    // from unifying the return type `ret` with the expected type
+    fn finish(
+        mut self,
+        type_params: &[ctxt::TypeParam],
+        ret: BuiltinType,
+        expected: Option<BuiltinType>,
+        name: String,
+    ) -> Result<TypeParams, Msg> {
+        if let Some((idx, first, second)) = self.conflict {
+            let tp = self.ctxt.interner.str(type_params[idx].name).to_string();
+            let first = first.name(self.ctxt);
+            let second = second.name(self.ctxt);
+
+            return Err(Msg::TypeParamInferredConflict(name, tp, first, second));
+        }
+
+        if let Some(expected) = expected {
+            if self.inferred.iter().any(|ty| ty.is_none()) {
+                let mut from_expected =
+                    TypeParamInference::new(self.ctxt, self.target, self.inferred.len());
+                from_expected.unify(ret, expected);
+
+                for (ty, expected_ty) in self.inferred.iter_mut().zip(from_expected.inferred) {
+                    if ty.is_none() {
+                        *ty = expected_ty;
+                    }
+                }
+            }
+        }
+
+        let mut result = Vec::with_capacity(self.inferred.len());
+
+        for (idx, ty) in self.inferred.iter().enumerate() {
+            match *ty {
+                Some(ty) => result.push(ty),
+                None => {
+                    let tp = self.ctxt.interner.str(type_params[idx].name).to_string();
+                    return Err(Msg::TypeParamNotInferred(name, tp));
+                }
+            }
+        }
+
+        Ok(result.into())
+    }
+}
+
 fn lookup_method<'ast>(
     ctxt: &SemContext<'ast>,
     object_type: BuiltinType,
@@ -2203,12 +4121,100 @@ fn replace_type_param(
             BuiltinType::Class(cls_id, list_id)
         }
 
-        BuiltinType::Lambda(_) => unimplemented!(),
+        BuiltinType::Lambda(lambda_id) => {
+            let lambda = ctxt.lambda_types.borrow().get(lambda_id);
+
+            let params = lambda
+                .params()
+                .iter()
+                .map(|&p| replace_type_param(ctxt, p, cls_tp, fct_tp))
+                .collect::<Vec<_>>();
+            let ret = replace_type_param(ctxt, lambda.ret(), cls_tp, fct_tp);
+
+            let lambda_id = ctxt.lambda_types.borrow_mut().insert(params, ret);
+            BuiltinType::Lambda(lambda_id)
+        }
+
+        BuiltinType::Tuple(tuple_id) => {
+            let elements = ctxt.tuples.borrow().get(tuple_id);
+
+            let elements = elements
+                .iter()
+                .map(|&t| replace_type_param(ctxt, t, cls_tp, fct_tp))
+                .collect::<Vec<_>>();
+
+            let tuple_id = ctxt.tuples.borrow_mut().insert(elements);
+            BuiltinType::Tuple(tuple_id)
+        }
+
+        BuiltinType::Nullable(_) => {
+            let ty = replace_type_param(ctxt, ty.non_nullable(ctxt), cls_tp, fct_tp);
+            BuiltinType::nullable(ctxt, ty)
+        }
 
         _ => ty,
     }
 }
 
+// every lambda or spawn block gets its own function, which is compiled separately.
+// the lambda shares variables and types with its enclosing function.
+fn create_lambda_fcts<'ast>(
+    ctxt: &SemContext<'ast>,
+    fct: &Fct<'ast>,
+    src: &mut FctSrc,
+    lambdas: &[&'ast Function],
+) {
+    let mut lambda_ids = Vec::with_capacity(lambdas.len());
+
+    for &lambda in lambdas {
+        let closure = src.map_lambdas.get(lambda.id).unwrap().closure;
+
+        let lambda_type = match src.vars[closure].ty {
+            BuiltinType::Lambda(lambda_id) => ctxt.lambda_types.borrow().get(lambda_id),
+            _ => unreachable!(),
+        };
+
+        let lambda_id = FctId(ctxt.fcts.len());
+
+        ctxt.fcts.push(Fct {
+            id: lambda_id,
+            pos: lambda.pos,
+            ast: lambda,
+            name: lambda.name,
+            module: fct.module,
+            param_types: lambda_type.params().to_vec(),
+            return_type: lambda_type.ret(),
+            parent: FctParent::Fct(fct.id),
+            has_override: false,
+            has_open: false,
+            has_final: false,
+            is_pub: false,
+            is_static: false,
+            is_abstract: false,
+            internal: false,
+            internal_resolved: false,
+            overrides: None,
+            throws: false,
+            ctor: CtorType::None,
+            vtable_index: None,
+            initialized: true,
+            impl_for: None,
+            is_default: false,
+
+            type_params: Vec::new(),
+            kind: FctKind::Source(RefCell::new(FctSrc::new())),
+        });
+
+        src.map_lambdas.get_mut(lambda.id).unwrap().fct_id = Some(lambda_id);
+        lambda_ids.push(lambda_id);
+    }
+
+    for lambda_id in lambda_ids {
+        let lambda = ctxt.fcts[lambda_id].borrow();
+        *lambda.src().borrow_mut() = src.clone();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use ctxt::ConstValue;
@@ -2436,6 +4442,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn type_after_syntax_error() {
+        errors(
+            "fun f() { let a = ; let b : int = true; }",
+            &[
+                (pos(1, 19), Msg::ExpectedFactor(";".into())),
+                (pos(1, 21), Msg::AssignType("b".into(), "int".into(), "bool".into())),
+            ],
+        );
+        err(
+            "fun f() -> int { g(; }",
+            pos(1, 20),
+            Msg::ExpectedFactor(";".into()),
+        );
+    }
+
     #[test]
     fn type_while() {
         ok("fun x() { while true { } }");
@@ -2618,29 +4640,90 @@ mod tests {
     }
 
     #[test]
-    fn type_return_nil() {
-        ok("fun foo() -> Str { return nil; }");
-        ok("class Foo fun foo() -> Foo { return nil; }");
-        err(
-            "fun foo() -> int { return nil; }",
-            pos(1, 20),
-            Msg::IncompatibleWithNil("int".into()),
-        );
-    }
+    fn type_variadic_params() {
+        ok("fun foo(a: int...) {}\nfun f() { foo(); foo(1); foo(1, 2, 3); }");
+        ok("fun foo(a: Str, b: int...) -> int { return b.len(); }\nfun f() { foo(\"x\", 1, 2); }");
+        ok("fun foo(a: int...) {}\nfun f(x: Array<int>) { foo(x); }");
+        ok("fun foo<T>(a: T...) -> T { return a[0]; }\nfun f() -> int { return foo(1, 2); }");
+        ok("class Foo(a: int...)\nfun f() { Foo(); Foo(1, 2); }");
+        ok("fun foo(a: Array<int>...) -> Array<int> { return a[0]; }
+            fun f(x: Array<int>) -> Array<int> { return foo(x); }");
+        ok("fun foo(a: Object...) -> Array<Object> { return a; }
+            fun f(x: Array<Object>) -> Array<Object> { return foo(x); }");
 
-    #[test]
-    fn type_nil_as_argument() {
-        ok("fun foo(a: Str) {} fun test() { foo(nil); }");
         err(
-            "fun foo(a: int) {} fun test() { foo(nil); }",
-            pos(1, 33),
-            Msg::ParamTypesIncompatible("foo".into(), vec!["int".into()], vec!["nil".into()]),
+            "class Foo\nfun foo(a: Object...) {}\nfun f(x: Array<Foo>) { foo(x); }",
+            pos(3, 24),
+            Msg::ParamTypesIncompatible(
+                "foo".into(),
+                vec!["Array<Object>".into()],
+                vec!["Array<Foo>".into()],
+            ),
+        );
+        err(
+            "fun foo(a: Str, b: int...) {}\nfun f() { foo(); }",
+            pos(2, 11),
+            Msg::ParamTypesIncompatible(
+                "foo".into(),
+                vec!["Str".into(), "Array<int>".into()],
+                vec![],
+            ),
+        );
+        err(
+            "fun foo(a: int...) {}\nfun f() { foo(1, true); }",
+            pos(2, 11),
+            Msg::ParamTypesIncompatible(
+                "foo".into(),
+                vec!["Array<int>".into()],
+                vec!["int".into(), "bool".into()],
+            ),
+        );
+        err(
+            "fun foo(a: int..., b: int) {}",
+            pos(1, 9),
+            Msg::VariadicParameterNeedsToBeLast,
+        );
+    }
+
+    #[test]
+    fn type_return_nil() {
+        ok("fun foo() -> Str? { return nil; }");
+        ok("class Foo fun foo() -> Foo? { return nil; }");
+        err(
+            "fun foo() -> Str { return nil; }",
+            pos(1, 20),
+            Msg::IncompatibleWithNil("Str".into()),
+        );
+        err(
+            "fun foo() -> int { return nil; }",
+            pos(1, 20),
+            Msg::IncompatibleWithNil("int".into()),
+        );
+    }
+
+    #[test]
+    fn type_nil_as_argument() {
+        ok("fun foo(a: Str?) {} fun test() { foo(nil); }");
+        err(
+            "fun foo(a: Str) {} fun test() { foo(nil); }",
+            pos(1, 33),
+            Msg::ParamTypesIncompatible("foo".into(), vec!["Str".into()], vec!["nil".into()]),
+        );
+        err(
+            "fun foo(a: int) {} fun test() { foo(nil); }",
+            pos(1, 33),
+            Msg::ParamTypesIncompatible("foo".into(), vec!["int".into()], vec!["nil".into()]),
         );
     }
 
     #[test]
     fn type_nil_for_ctor() {
-        ok("class Foo(let a: Str) fun test() { Foo(nil); }");
+        ok("class Foo(let a: Str?) fun test() { Foo(nil); }");
+        err(
+            "class Foo(let a: Str) fun test() { Foo(nil); }",
+            pos(1, 36),
+            Msg::UnknownCtor("Foo".into(), vec!["nil".into()]),
+        );
         err(
             "class Foo(let a: int) fun test() { Foo(nil); }",
             pos(1, 36),
@@ -2650,7 +4733,12 @@ mod tests {
 
     #[test]
     fn type_nil_for_local_variable() {
-        ok("fun f() { let x: Str = nil; }");
+        ok("fun f() { let x: Str? = nil; }");
+        err(
+            "fun f() { let x: Str = nil; }",
+            pos(1, 11),
+            Msg::AssignType("x".into(), "Str".into(), "nil".into()),
+        );
         err(
             "fun f() { let x: int = nil; }",
             pos(1, 11),
@@ -2660,7 +4748,12 @@ mod tests {
 
     #[test]
     fn type_nil_for_field() {
-        ok("class Foo(var a: Str) fun f() { Foo(nil).a = nil; }");
+        ok("class Foo(var a: Str?) fun f() { Foo(nil).a = nil; }");
+        err(
+            "class Foo(var a: Str) fun f() { Foo(\"\").a = nil; }",
+            pos(1, 43),
+            Msg::AssignField("a".into(), "Foo".into(), "Str".into(), "nil".into()),
+        );
         err(
             "class Foo(var a: int) fun f() { Foo(1).a = nil; }",
             pos(1, 42),
@@ -2680,7 +4773,7 @@ mod tests {
     #[test]
     fn type_nil_as_method_argument() {
         ok("class Foo {
-            fun f(a: Str) {}
+            fun f(a: Str?) {}
         } fun f() { Foo().f(nil); }");
     }
 
@@ -2758,6 +4851,28 @@ mod tests {
         err("fun f() { do {} }", pos(1, 11), Msg::CatchOrFinallyExpected);
     }
 
+    #[test]
+    fn try_unreachable_catch() {
+        ok("open class A class B: A
+            fun f() throws {}
+            fun g() { do { try f(); } catch x: B {} catch y: A {} }");
+        err(
+            "fun f() throws {}
+            fun g() { do { try f(); } catch x: Exception {} catch y: ArithmeticException {} }",
+            pos(2, 70),
+            Msg::UnreachableCatch("ArithmeticException".into(), "Exception".into()),
+        );
+        err(
+            "fun f() throws {}
+            fun g() { do { try f(); } catch x: Str {} catch y: Str {} }",
+            pos(2, 64),
+            Msg::UnreachableCatch("Str".into(), "Str".into()),
+        );
+        ok("class Foo<T>
+            fun f() throws {}
+            fun g() { do { try f(); } catch x: Foo<int> {} catch y: Foo<Str> {} }");
+    }
+
     #[test]
     fn try_check_blocks() {
         err(
@@ -2794,6 +4909,105 @@ mod tests {
     #[test]
     fn var_without_initialization() {
         ok("fun f() { var x: int; }");
+        ok("fun f() { var x: Str?; }");
+        ok("fun f() { var x: (int, bool); }");
+        ok("struct Foo { a: int } fun f() { var x: Foo; }");
+
+        err(
+            "fun f() { var x: Str; }",
+            pos(1, 11),
+            Msg::MissingInitialization("x".into(), "Str".into()),
+        );
+        err(
+            "class Foo fun f() { var x: Foo; }",
+            pos(1, 21),
+            Msg::MissingInitialization("x".into(), "Foo".into()),
+        );
+        err(
+            "type Name = Str; fun f() { var x: Name; }",
+            pos(1, 28),
+            Msg::MissingInitialization("x".into(), "Name".into()),
+        );
+        err(
+            "fun f() { var x: () -> int; }",
+            pos(1, 11),
+            Msg::MissingInitialization("x".into(), "() -> int".into()),
+        );
+        err(
+            "fun f() { var x: (int, Str); }",
+            pos(1, 11),
+            Msg::MissingInitialization("x".into(), "(int, Str)".into()),
+        );
+        err(
+            "struct Foo { a: Str } fun f() { var x: Foo; }",
+            pos(1, 33),
+            Msg::MissingInitialization("x".into(), "Foo".into()),
+        );
+    }
+
+    #[test]
+    fn var_field_without_initialization() {
+        ok("class Foo { var a: int; }");
+        ok("class Foo { var a: Str?; }");
+        ok("class Foo { var a: Str = \"a\"; }");
+        ok("class Foo(var a: Str)");
+        ok("class Foo { var a: Str; init() { self.a = \"a\"; } }");
+        ok("class Foo { var a: Str; init(x: bool) { { self.a = \"a\"; } } }");
+        ok("class Foo { var a: Str;
+            init() { self.a = \"a\"; }
+            init(x: int): self() {} }");
+        ok("open class A class Foo: A { var a: Str; init(): super() { self.a = \"a\"; } }");
+
+        err(
+            "class Foo { var a: Str; }",
+            pos(1, 1),
+            Msg::MissingInitialization("a".into(), "Str".into()),
+        );
+        err(
+            "class Foo { var a: Str; init(x: bool) { if x { self.a = \"a\"; } } }",
+            pos(1, 25),
+            Msg::MissingInitialization("a".into(), "Str".into()),
+        );
+        err(
+            "class Foo { var a: Str; init() { self.a = \"a\"; } init(x: int) {} }",
+            pos(1, 50),
+            Msg::MissingInitialization("a".into(), "Str".into()),
+        );
+        err(
+            "class Foo(x: int) { var a: Str; init() : self(1) { self.a = \"a\"; } }",
+            pos(1, 1),
+            Msg::MissingInitialization("a".into(), "Str".into()),
+        );
+    }
+
+    #[test]
+    fn array_without_default_value() {
+        ok("fun f() { Array::<int>(10); }");
+        ok("fun f() { Array::<Str?>(10); }");
+        ok("fun f() { Array::<Str>(); }");
+        ok("fun f() { Array::<Str>(10, \"a\"); }");
+        ok("fun f() { defaultValue::<Str?>(); }");
+
+        err(
+            "fun f() { Array::<Str>(10); }",
+            pos(1, 11),
+            Msg::NoDefaultValue("Str".into()),
+        );
+        err(
+            "fun f() { let a: Array<Str> = Array(10); }",
+            pos(1, 31),
+            Msg::NoDefaultValue("Str".into()),
+        );
+        err(
+            "fun f<T>() { Array::<T>(10); }",
+            pos(1, 14),
+            Msg::NoDefaultValue("T".into()),
+        );
+        err(
+            "fun f() { defaultValue::<Str>(); }",
+            pos(1, 11),
+            Msg::NoDefaultValue("Str".into()),
+        );
     }
 
     #[test]
@@ -3222,6 +5436,54 @@ mod tests {
         ok("fun f() { let x = -9223372036854775808L; }");
     }
 
+    #[test]
+    fn test_literal_short_overflow() {
+        err(
+            "fun f() { let x = 32768S; }",
+            pos(1, 19),
+            Msg::NumberOverflow("short".into()),
+        );
+        ok("fun f() { let x = 32767S; }");
+        err(
+            "fun f() { let x = -32769S; }",
+            pos(1, 20),
+            Msg::NumberOverflow("short".into()),
+        );
+        ok("fun f() { let x = -32768S; }");
+        ok("fun f() { let x: short = 0xFFFFS; }");
+    }
+
+    #[test]
+    fn test_literal_unsigned_overflow() {
+        err(
+            "fun f() { let x = 4294967296U; }",
+            pos(1, 19),
+            Msg::NumberOverflow("uint".into()),
+        );
+        ok("fun f() { let x: uint = 4294967295U; }");
+        ok("fun f() { let x: ulong = 18446744073709551615UL; }");
+        ok("fun f() { let x: ulong = 0xFFFF_FFFF_FFFF_FFFFUL; }");
+    }
+
+    #[test]
+    fn test_unsigned_ops() {
+        ok("fun f(a: uint, b: uint) -> uint { return a / b % b >> b; }");
+        ok("fun f(a: uint, b: uint) -> bool { return a < b; }");
+        ok("fun f(a: ulong, b: ulong) -> ulong { return a * b - a; }");
+        ok("fun f(a: ulong) -> long { return a.toLong(); }");
+        ok("fun f(a: short) -> short { return -a + 1S; }");
+        err(
+            "fun f(a: uint) -> uint { return -a; }",
+            pos(1, 33),
+            Msg::UnOpType("-".into(), "uint".into()),
+        );
+        err(
+            "fun f(a: uint, b: int) -> uint { return a + b; }",
+            pos(1, 43),
+            Msg::BinOpType("+".into(), "uint".into(), "int".into()),
+        );
+    }
+
     #[test]
     fn test_literal_float_overflow() {
         err(
@@ -3271,7 +5533,7 @@ mod tests {
                 let a = A();
             }",
             pos(3, 25),
-            Msg::WrongNumberTypeParams(1, 0),
+            Msg::TypeParamNotInferred("A".into(), "T".into()),
         );
 
         err(
@@ -3318,7 +5580,7 @@ mod tests {
         err(
             "fun f<T>() {} fun g() { f(); }",
             pos(1, 25),
-            Msg::WrongNumberTypeParams(1, 0),
+            Msg::TypeParamNotInferred("f".into(), "T".into()),
         );
         ok("fun f<T>() {} fun g() { f::<int>(); }");
         ok("fun f<T1, T2>() {} fun g() { f::<int, Str>(); }");
@@ -3363,6 +5625,102 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_const_expr() {
+        ok_with_test(
+            "  const a: int = 1 + 2 * 3;
+                        const b: int = c - a;
+                        const c: int = (1 << 4) | 3;
+                        const d: long = -(100L / 7L) % 5L;
+                        const e: bool = a < b && !(c == 20);
+                        const f: uint = 0xFFFFFFFFU >> 28U;
+                        const g: int = -1 >>> 28;
+                        const h: double = 1.5 * 4.0;
+                        const i: bool = 'a' < 'b';
+                        const j: int = !0xFF;",
+            |ctxt| {
+                assert_eq!(ConstValue::Int(7), ctxt.consts[0].borrow().value);
+                assert_eq!(ConstValue::Int(12), ctxt.consts[1].borrow().value);
+                assert_eq!(ConstValue::Int(19), ctxt.consts[2].borrow().value);
+                assert_eq!(ConstValue::Int(-4), ctxt.consts[3].borrow().value);
+                assert_eq!(ConstValue::Bool(true), ctxt.consts[4].borrow().value);
+                assert_eq!(ConstValue::Int(15), ctxt.consts[5].borrow().value);
+                assert_eq!(ConstValue::Int(15), ctxt.consts[6].borrow().value);
+                assert_eq!(ConstValue::Float(6.0), ctxt.consts[7].borrow().value);
+                assert_eq!(ConstValue::Bool(true), ctxt.consts[8].borrow().value);
+                assert_eq!(ConstValue::Int(-256), ctxt.consts[9].borrow().value);
+            },
+        );
+    }
+
+    #[test]
+    fn test_const_overflow() {
+        err(
+            "const a: int = 2147483647 + 1;",
+            pos(1, 27),
+            Msg::NumberOverflow("int".into()),
+        );
+        err(
+            "const a: uint = 1U - 2U;",
+            pos(1, 20),
+            Msg::NumberOverflow("uint".into()),
+        );
+        err(
+            "const a: short = 200S * 200S;",
+            pos(1, 23),
+            Msg::NumberOverflow("short".into()),
+        );
+        err(
+            "const a: int = -2147483648; const b: int = -a;",
+            pos(1, 44),
+            Msg::NumberOverflow("int".into()),
+        );
+        err(
+            "const a: long = 1L << 64L;",
+            pos(1, 20),
+            Msg::NumberOverflow("long".into()),
+        );
+        err(
+            "const a: int = 1 / 0;",
+            pos(1, 18),
+            Msg::ConstDivisionByZero,
+        );
+        err(
+            "const a: int = 0; const b: int = 1 % a;",
+            pos(1, 36),
+            Msg::ConstDivisionByZero,
+        );
+    }
+
+    #[test]
+    fn test_const_invalid_expr() {
+        err(
+            "const a: int = b; const b: int = a + 1;",
+            pos(1, 1),
+            Msg::ConstCycle("a".into()),
+        );
+        err(
+            "const a: int = 1 + 1L;",
+            pos(1, 18),
+            Msg::BinOpType("+".into(), "int".into(), "long".into()),
+        );
+        err(
+            "const a: long = 1 + 2;",
+            pos(1, 19),
+            Msg::AssignType("a".into(), "long".into(), "int".into()),
+        );
+        err(
+            "fun f() -> int { return 1; } const a: int = f();",
+            pos(1, 45),
+            Msg::ConstValueExpected,
+        );
+        err(
+            "const a: int = b;",
+            pos(1, 16),
+            Msg::UnknownIdentifier("b".into()),
+        );
+    }
+
     #[test]
     fn test_assignment_to_const() {
         err(
@@ -3388,18 +5746,18 @@ mod tests {
     fn test_generic_class_bounds() {
         ok("class Foo
             class A<T: Foo>
-            fun f() -> A<Foo> { return nil; }");
+            fun f() -> A<Foo>? { return nil; }");
 
         ok("open class Foo
             class Bar: Foo
             class A<T: Foo>
-            fun f() -> A<Bar> { return nil; }");
+            fun f() -> A<Bar>? { return nil; }");
 
         err(
             "class Foo
             class Bar
             class A<T: Foo>
-            fun f() -> A<Bar> { return nil; }",
+            fun f() -> A<Bar>? { return nil; }",
             pos(4, 24),
             Msg::ClassBoundNotSatisfied("Bar".into(), "Foo".into()),
         );
@@ -3419,13 +5777,13 @@ mod tests {
             class X
             impl Foo for X {}
             class A<T: Foo>
-            fun f() -> A<X> { return nil; }");
+            fun f() -> A<X>? { return nil; }");
 
         err(
             "trait Foo {}
             class X
             class A<T: Foo>
-            fun f() -> A<X> { return nil; }",
+            fun f() -> A<X>? { return nil; }",
             pos(1, 1),
             Msg::TraitBoundNotSatisfied("X".into(), "Foo".into()),
         );
@@ -3439,6 +5797,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_generic_trait_bounds_with_params() {
+        ok("trait Foo<T> {}
+            class X
+            impl Foo<int> for X {}
+            class A<T: Foo<int> >
+            fun f() -> A<X>? { return nil; }");
+
+        err(
+            "trait Foo<T> {}
+            class X
+            impl Foo<int> for X {}
+            class A<T: Foo<bool> >
+            fun f() -> A<X>? { return nil; }",
+            pos(1, 1),
+            Msg::TraitBoundNotSatisfied("X".into(), "Foo<bool>".into()),
+        );
+
+        err(
+            "trait Foo<T> {}
+            class X
+            impl Foo<int> for X {}
+            fun f<T: Foo<Str> >() {}
+            fun t() { f::<X>(); }",
+            pos(5, 23),
+            Msg::TraitBoundNotSatisfied("X".into(), "Foo<Str>".into()),
+        );
+    }
+
+    #[test]
+    fn test_call_through_generic_trait_bound() {
+        ok("trait Foo<T> { fun get() -> T; }
+            fun f<T: Foo<int> >(a: T) -> int { return a.get(); }");
+
+        ok("trait Foo<T> { fun get() -> T; }
+            fun f<A, T: Foo<A> >(a: T) -> A { return a.get(); }");
+
+        err(
+            "trait Foo<T> { fun get() -> T; }
+            fun f<T: Foo<int> >(a: T) -> bool { return a.get(); }",
+            pos(2, 49),
+            Msg::ReturnType("bool".into(), "int".into()),
+        );
+    }
+
     #[test]
     fn test_operator_on_generic_type() {
         err(
@@ -3511,6 +5914,87 @@ mod tests {
         );
     }
 
+    #[test]
+    fn lambda_call() {
+        ok("fun f() { let x = |a: int| -> int { return a; }; x(1); }");
+        ok("fun f(x: (int, bool) -> ()) { x(1, true); }");
+        ok("fun f() -> int { let a = 1; let x = || -> int { return a; }; return x(); }");
+        err(
+            "fun f() { let x = |a: int| {}; x(true); }",
+            pos(1, 32),
+            Msg::ParamTypesIncompatible("x".into(), vec!["int".into()], vec!["bool".into()]),
+        );
+        err(
+            "fun f() { let x = 1; x(); }",
+            pos(1, 22),
+            Msg::UnknownFunction("x".into()),
+        );
+    }
+
+    #[test]
+    fn lambda_call_field() {
+        ok("class A(let f: (int) -> bool) fun f(a: A) -> bool { return a.f(1); }");
+        ok("class A(let f: (int) -> bool) { fun g() -> bool { return self.f(1); } }");
+        ok("class A(let f: () -> int) { fun f() -> bool { return true; } }
+            fun g(a: A) -> bool { return a.f(); }");
+        err(
+            "class A(let f: (int) -> bool) fun f(a: A) { a.f(true); }",
+            pos(1, 46),
+            Msg::ParamTypesIncompatible("f".into(), vec!["int".into()], vec!["bool".into()]),
+        );
+        err(
+            "class A(let f: int) fun f(a: A) { a.f(1); }",
+            pos(1, 36),
+            Msg::UnknownMethod("A".into(), "f".into(), vec!["int".into()]),
+        );
+    }
+
+    #[test]
+    fn lambda_call_expr() {
+        ok("fun f(a: Array<(int) -> int>) -> int { return a[0](1); }");
+        ok("fun f(x: () -> () -> int) -> int { return x()(); }");
+        ok("fun f(x: (int) -> int) -> int { return (x)(1); }");
+        err(
+            "fun f(a: Array<(int) -> int>) { a[0](true); }",
+            pos(1, 37),
+            Msg::ParamTypesIncompatible(
+                "(int) -> int".into(),
+                vec!["int".into()],
+                vec!["bool".into()],
+            ),
+        );
+        err(
+            "fun f(a: Array<int>) { a[0](1); }",
+            pos(1, 28),
+            Msg::LambdaExpected("int".into()),
+        );
+    }
+
+    #[test]
+    fn lambda_return() {
+        err(
+            "fun f() { let x = || -> int { return true; }; }",
+            pos(1, 31),
+            Msg::ReturnType("int".into(), "bool".into()),
+        );
+    }
+
+    #[test]
+    fn spawn_block() {
+        ok("fun f() { let t: Thread = spawn { }; t.join(); }");
+        ok("fun f(a: int) { spawn { let b = a + 1; }; }");
+        err(
+            "fun f() { spawn { return 1; }; }",
+            pos(1, 19),
+            Msg::ReturnType("()".into(), "int".into()),
+        );
+        err(
+            "fun f() { var a = 1; spawn { a = 2; }; }",
+            pos(1, 32),
+            Msg::CapturedVarReassigned("a".into()),
+        );
+    }
+
     #[test]
     fn generic_trait_method_call() {
         ok("trait Foo { fun bar(); }
@@ -3557,7 +6041,7 @@ mod tests {
             "class Foo<A, B>()
             fun test() { Foo(); }",
             pos(2, 26),
-            Msg::WrongNumberTypeParams(2, 0),
+            Msg::TypeParamNotInferred("Foo".into(), "A".into()),
         );
     }
 
@@ -3571,6 +6055,164 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_infer_fct_type_params() {
+        ok("fun f<T>(x: T) -> T { return x; } fun g() -> int { return f(1); }");
+        ok("fun f<T>(a: Array<T>) -> T { return a.get(0); }
+            fun g(a: Array<Str>) -> Str { return f(a); }");
+        ok("fun f<A, B>(a: A, b: B) -> (B, A) { return (b, a); }
+            fun g() -> (Str, int) { return f(1, \"a\"); }");
+        ok("fun f<T>(x: T) {}
+            fun g<X>(x: X) { f(x); }");
+        ok("fun f<T>() -> Array<T> { return Array::<T>(); }
+            fun g() { let a: Array<int> = f(); }");
+        ok("open class A class B: A
+            fun f<T>(a: T, b: T) -> T { return a; }
+            fun g() -> A { return f(B(), A()); }");
+        ok("fun f<T: Comparable>(a: T, b: T) -> bool { return a.compareTo(b) < 0; }
+            fun g() -> bool { return f(1, 2); }");
+
+        err(
+            "fun f<T>(a: T, b: T) {} fun g() { f(1, \"a\"); }",
+            pos(1, 35),
+            Msg::TypeParamInferredConflict("f".into(), "T".into(), "int".into(), "Str".into()),
+        );
+
+        err(
+            "fun f<T>(a: Array<T>) {} fun g() { f(nil); }",
+            pos(1, 36),
+            Msg::TypeParamNotInferred("f".into(), "T".into()),
+        );
+
+        err(
+            "fun f<T: Comparable>(x: T) {} fun g() { f(true); }",
+            pos(1, 41),
+            Msg::TraitBoundNotSatisfied("bool".into(), "Comparable".into()),
+        );
+
+        err(
+            "fun f<T>(x: T) -> T { return x; } fun g() { let x: Str = f(1); }",
+            pos(1, 45),
+            Msg::AssignType("x".into(), "Str".into(), "int".into()),
+        );
+    }
+
+    #[test]
+    fn test_infer_ctor_type_params() {
+        ok("class Foo<T>(let x: T) fun f() -> int { return Foo(1).x; }");
+        ok("class Foo<A, B>(let a: A, let b: B)
+            fun f() -> Foo<int, Str> { return Foo(1, \"a\"); }");
+        ok("fun f() -> Array<int> { return Array(10); }");
+        ok("fun f() { let a: Array<Str> = Array(2, \"\"); }");
+        ok("fun f() { var a: Array<int>? = nil; a = Array(5); }");
+        ok("class Foo<T>(let x: T) fun f() -> Foo<Str?> { return Foo(nil); }");
+
+        err(
+            "fun f() { let a = Array(10); }",
+            pos(1, 19),
+            Msg::TypeParamNotInferred("Array".into(), "T".into()),
+        );
+
+        err(
+            "class Foo<T: Comparable>(let x: T) fun f() { Foo(true); }",
+            pos(1, 46),
+            Msg::TraitBoundNotSatisfied("bool".into(), "Comparable".into()),
+        );
+    }
+
+    #[test]
+    fn test_nullable_types() {
+        ok("class Foo fun f() -> Foo? { return nil; }");
+        ok("class Foo fun f(x: Foo) -> Foo? { return x; }");
+        ok("class Foo fun f(x: Foo?) -> bool { return x === nil; }");
+        ok("fun f(x: (() -> int)?) {}");
+        ok("open class A class B: A fun f(x: A?) -> B? { return x as B; }");
+
+        err(
+            "fun f(x: int?) {}",
+            pos(1, 10),
+            Msg::ReferenceTypeExpected("int".into()),
+        );
+        err(
+            "fun f<T>(x: T?) {}",
+            pos(1, 13),
+            Msg::ReferenceTypeExpected("T".into()),
+        );
+        err(
+            "class Foo fun f(x: Foo?) -> Foo { return x; }",
+            pos(1, 35),
+            Msg::ReturnType("Foo".into(), "Foo?".into()),
+        );
+    }
+
+    #[test]
+    fn test_nil_dereference() {
+        err(
+            "class Foo(let a: int) fun f(x: Foo?) -> int { return x.a; }",
+            pos(1, 55),
+            Msg::PossibleNilDereference("Foo?".into()),
+        );
+        err(
+            "fun f(x: Str?) -> int { return x.len(); }",
+            pos(1, 33),
+            Msg::PossibleNilDereference("Str?".into()),
+        );
+        err(
+            "fun f(x: Array<int>?) -> int { return x[0]; }",
+            pos(1, 40),
+            Msg::PossibleNilDereference("Array<int>?".into()),
+        );
+        err(
+            "fun f(x: (() -> int)?) -> int { return x(); }",
+            pos(1, 40),
+            Msg::PossibleNilDereference("(() -> int)?".into()),
+        );
+        err(
+            "fun f(x: Str?) -> Str { return x + \"a\"; }",
+            pos(1, 34),
+            Msg::PossibleNilDereference("Str?".into()),
+        );
+        err(
+            "fun f(x: Str?) { throw x; }",
+            pos(1, 18),
+            Msg::PossibleNilDereference("Str?".into()),
+        );
+    }
+
+    #[test]
+    fn test_nil_check_narrowing() {
+        ok("fun f(x: Str?) -> int { if x !== nil { return x.len(); } return 0; }");
+        ok("fun f(x: Str?) -> int { if x === nil { return 0; } return x.len(); }");
+        ok("fun f(x: Str?) -> int { if !(x === nil) { return x.len(); } return 0; }");
+        ok("fun f(x: Str?) -> int { if x === nil { return 0; } else { return x.len(); } }");
+        ok("fun f(x: Str?, y: Str?) -> bool { return x !== nil && y !== nil && x == y; }");
+        ok("fun f(x: Str?) -> bool { return x === nil || x.len() == 0; }");
+        ok("fun f() -> int { var x: Str? = nil; x = \"abc\"; return x.len(); }");
+        ok("fun f(x: Str?) -> int { var y = x; if y === nil { y = \"a\"; } return y.len(); }");
+        ok("fun f(x: Str?) { var y = x; while y !== nil { y.len(); y = nil; } }");
+
+        err(
+            "fun f(x: Str?) -> int { if x !== nil { } return x.len(); }",
+            pos(1, 50),
+            Msg::PossibleNilDereference("Str?".into()),
+        );
+        err(
+            "fun f(x: Str?) { var y = x; if y !== nil { y = nil; y.len(); } }",
+            pos(1, 54),
+            Msg::PossibleNilDereference("Str?".into()),
+        );
+        err(
+            "fun f(x: Str?) { var y = x; if y !== nil { while true { y.len(); y = nil; } } }",
+            pos(1, 58),
+            Msg::PossibleNilDereference("Str?".into()),
+        );
+        err(
+            "fun f() { var x: Str? = \"a\"; let l = || -> int { return x.len(); }; }",
+            pos(1, 58),
+            Msg::PossibleNilDereference("Str?".into()),
+        );
+    }
+
     #[test]
     fn test_for_supports_make_iterator() {
         err(
@@ -3590,7 +6232,7 @@ mod tests {
         ok(
             "class Foo { fun makeIterator() -> FooIter { return FooIter(); } }
             class FooIter
-            impl Iterator for FooIter {
+            impl Iterator<int> for FooIter {
                 fun hasNext() -> bool { return false; }
                 fun next() -> int { return 0; }
             }
@@ -3644,6 +6286,242 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_struct_field_access() {
+        ok("struct Foo { a: int } fun f(foo: Foo) -> int { return foo.a; }");
+        ok("struct Foo { a: int } fun f() -> int { return Foo { a: 1 }.a; }");
+        ok("struct Bar { a: Str } struct Foo { bar: Bar }
+            fun f(foo: Foo) -> Str { return foo.bar.a; }");
+        err(
+            "struct Foo { a: int } fun f(foo: Foo) -> bool { return foo.a; }",
+            pos(1, 49),
+            Msg::ReturnType("bool".into(), "int".into()),
+        );
+        err(
+            "struct Foo { a: int } fun f(foo: Foo) -> int { return foo.b; }",
+            pos(1, 58),
+            Msg::UnknownField("b".into(), "Foo".into()),
+        );
+    }
+
+    #[test]
+    fn test_struct_field_assign() {
+        err(
+            "struct Foo { a: int } fun f() { var foo = Foo { a: 1 }; foo.a = 2; }",
+            pos(1, 63),
+            Msg::StructFieldImmutable("Foo".into(), "a".into()),
+        );
+    }
+
+    #[test]
+    fn test_struct_by_value() {
+        ok("struct Foo { a: int } fun f(foo: Foo) -> Foo { return foo; }");
+        ok("struct Foo { a: int } class Bar(var foo: Foo)
+            fun f(bar: Bar) { bar.foo = Foo { a: 1 }; }");
+        ok("struct Foo { a: int } fun f() -> Foo { let x = Array::<Foo>(1); return x[0]; }");
+        err(
+            "struct Foo { a: int } fun f() { let foo: Foo = nil; }",
+            pos(1, 33),
+            Msg::AssignType("foo".into(), "Foo".into(), "nil".into()),
+        );
+    }
+
+    #[test]
+    fn test_enum_variant() {
+        ok("enum Foo { A, B(int) } fun f() -> Foo { return Foo::B(1); }");
+        ok("enum Foo { A, B(int) } fun f() -> Foo { return Foo::A; }");
+        err(
+            "enum Foo { A, B(int) } fun f() { Foo::C; }",
+            pos(1, 34),
+            Msg::UnknownEnumVariant("Foo".into(), "C".into()),
+        );
+        err(
+            "enum Foo { A, B(int) } fun f() { Foo::B(true); }",
+            pos(1, 34),
+            Msg::EnumVariantArgsIncompatible(
+                "Foo".into(),
+                "B".into(),
+                vec!["int".into()],
+                vec!["bool".into()],
+            ),
+        );
+        err(
+            "enum Foo { A } fun f() { let foo: Foo = nil; }",
+            pos(1, 26),
+            Msg::AssignType("foo".into(), "Foo".into(), "nil".into()),
+        );
+    }
+
+    #[test]
+    fn test_match() {
+        ok("enum Foo { A, B(int) } fun f(foo: Foo) -> int {
+                return match foo { A => 0, B(x) => x };
+            }");
+        ok("enum Foo { A, B(int) } fun f(foo: Foo) -> int {
+                return match foo { Foo::B(_) => 1, _ => 0 };
+            }");
+        err(
+            "fun f() -> int { return match 1 { _ => 0 }; }",
+            pos(1, 31),
+            Msg::MatchEnumExpected("int".into()),
+        );
+        err(
+            "enum Foo { A, B(int) } fun f(foo: Foo) -> int { return match foo { A => 0 }; }",
+            pos(1, 56),
+            Msg::MatchNotExhaustive(vec!["B".into()]),
+        );
+        err(
+            "enum Foo { A, B(int) } fun f(foo: Foo) -> int {
+                return match foo { A => 0, _ => 1, B(x) => x };
+            }",
+            pos(2, 52),
+            Msg::MatchUnreachableArm,
+        );
+        err(
+            "enum Foo { A, B(int) } fun f(foo: Foo) -> int { return match foo { A => 0, B => 1 }; }",
+            pos(1, 76),
+            Msg::MatchPatternParams("B".into(), 1, 0),
+        );
+        err(
+            "enum Foo { A, B(int) } fun f(foo: Foo) -> int { return match foo { A => 0, C => 1, B(x) => x }; }",
+            pos(1, 76),
+            Msg::UnknownEnumVariant("Foo".into(), "C".into()),
+        );
+        err(
+            "enum Foo { A, B(int) } fun f(foo: Foo) -> int { return match foo { A => 0, B(x) => true }; }",
+            pos(1, 76),
+            Msg::MatchArmTypes("int".into(), "bool".into()),
+        );
+    }
+
+    #[test]
+    fn test_alias() {
+        ok("type Meters = int; fun f(x: Meters) -> int { return x + 1; }");
+        ok("type Ints = Array<int>; fun f() -> Ints { return Array::<int>(2); }");
+        ok("type Pair<T> = Array<T>; fun f(x: Pair<Str>) -> Str { return x[0]; }");
+        ok("trait Foo { type Item = int; fun get() -> Item; }");
+        err(
+            "type Meters = int; fun f() -> Meters { return true; }",
+            pos(1, 40),
+            Msg::ReturnType("Meters".into(), "bool".into()),
+        );
+        err(
+            "type Pair<T> = Array<T>; fun f() { let x: Pair<int> = 1; }",
+            pos(1, 36),
+            Msg::AssignType("x".into(), "Pair<int>".into(), "int".into()),
+        );
+        err(
+            "trait Foo { type Item = int; } fun f() -> Item { return 1; }",
+            pos(1, 43),
+            Msg::UnknownType("Item".into()),
+        );
+    }
+
+    #[test]
+    fn test_alias_in_mismatch() {
+        err(
+            "type Meters = int; fun f(x: Meters) {} fun g() { f(true); }",
+            pos(1, 50),
+            Msg::ParamTypesIncompatible("f".into(), vec!["Meters".into()], vec!["bool".into()]),
+        );
+        err(
+            "type Name = Str; fun f(x: int) {} fun g(n: Name) { f(n); }",
+            pos(1, 52),
+            Msg::ParamTypesIncompatible("f".into(), vec!["int".into()], vec!["Name".into()]),
+        );
+        err(
+            "type Meters = int; class Foo { var a: Meters; }
+            fun f(foo: Foo) { foo.a = \"x\"; }",
+            pos(2, 37),
+            Msg::AssignField("a".into(), "Foo".into(), "Meters".into(), "Str".into()),
+        );
+        err(
+            "type Name = Str; fun f(a: Name, b: bool) { a + b; }",
+            pos(1, 46),
+            Msg::BinOpType("+".into(), "Name".into(), "bool".into()),
+        );
+        err(
+            "type Name = Str; fun name() -> Name { return \"x\"; } fun f() { let x: int = name(); }",
+            pos(1, 63),
+            Msg::AssignType("x".into(), "int".into(), "Name".into()),
+        );
+    }
+
+    #[test]
+    fn test_tuple() {
+        ok("fun f() -> (int, Str) { return (1, \"a\"); }");
+        ok("fun f(x: (int, (bool, Str))) -> Str { return x.1.1; }");
+        ok("fun f(x: (int, Str)) -> int { let (a, b) = x; return a; }");
+        ok("fun f() -> int { var (a, _): (int, bool) = (1, true); a = 2; return a; }");
+        ok("open class A class B: A fun f() -> (A, int) { return (B(), 1); }");
+        err(
+            "fun f() -> (int, Str) { return (\"a\", 1); }",
+            pos(1, 25),
+            Msg::ReturnType("(int, Str)".into(), "(Str, int)".into()),
+        );
+        err(
+            "fun f(x: (int, Str)) -> int { return x.2; }",
+            pos(1, 39),
+            Msg::TupleIndexOutOfBounds("(int, Str)".into(), 2),
+        );
+        err(
+            "fun f(x: int) -> int { return x.0; }",
+            pos(1, 32),
+            Msg::TupleExpected("int".into()),
+        );
+        err(
+            "fun f(x: (int, Str)) { let (a, b, c) = x; }",
+            pos(1, 24),
+            Msg::TuplePatternLength("(int, Str)".into(), 2, 3),
+        );
+        err(
+            "fun f(x: int) { let (a, b) = x; }",
+            pos(1, 30),
+            Msg::TupleExpected("int".into()),
+        );
+        err(
+            "fun f() { let x = (1, nil); }",
+            pos(1, 23),
+            Msg::TupleNilElement,
+        );
+        err(
+            "fun f(x: (int, Str)) { let (a, b) = x; a = 1; }",
+            pos(1, 42),
+            Msg::LetReassigned,
+        );
+    }
+
+    #[test]
+    fn test_string_template() {
+        ok("fun f(x: int, y: Str) -> Str { return \"x=${x} y=${y}\"; }");
+        ok("fun f(x: bool, y: long, z: char) -> Str { return \"${x}${y}${z}\"; }");
+        ok("class Foo { fun toString() -> Str { return \"Foo\"; } }
+            fun f(x: Foo) -> Str { return \"${x}\"; }");
+        ok("fun f<T: Stringable>(x: T) -> Str { return \"value: ${x}\"; }");
+        ok("fun f(x: Str?) -> Str { if x !== nil { return \"${x}\"; } return \"\"; }");
+        err(
+            "class Foo fun f(x: Foo) -> Str { return \"a${x}\"; }",
+            pos(1, 45),
+            Msg::TemplateNotStringable("Foo".into()),
+        );
+        err(
+            "fun f<T>(x: T) -> Str { return \"${x}\"; }",
+            pos(1, 35),
+            Msg::TemplateNotStringable("T".into()),
+        );
+        err(
+            "fun f(x: Str?) -> Str { return \"${x}\"; }",
+            pos(1, 35),
+            Msg::PossibleNilDereference("Str?".into()),
+        );
+        err(
+            "class Foo { fun toString() throws -> Str { throw Exception(); } }
+            fun f(x: Foo) -> Str { return \"${x}\"; }",
+            pos(2, 46),
+            Msg::ThrowingCallWithoutTry,
+        );
+    }
+
     #[test]
     fn test_ctor_with_type_param() {
         err(
@@ -11,7 +11,8 @@ use baseline;
 use class::TypeParams;
 use ctxt::{exception_set, get_ctxt};
 use exception::alloc_exception;
-use object::{ByteArray, Handle, Obj, Str};
+use object::{alloc, ByteArray, Handle, Obj, SpawnThread, Str, Thread};
+use semck::specialize::specialize_class_id;
 
 use sym::Sym::SymFct;
 
@@ -29,6 +30,13 @@ pub extern "C" fn char_to_string(val: char) -> Handle<Str> {
     Str::from_buffer(ctxt, buffer.as_bytes())
 }
 
+pub extern "C" fn short_to_string(val: i16) -> Handle<Str> {
+    let buffer = val.to_string();
+    let ctxt = get_ctxt();
+
+    Str::from_buffer(ctxt, buffer.as_bytes())
+}
+
 pub extern "C" fn int_to_string(val: i32) -> Handle<Str> {
     let buffer = val.to_string();
     let ctxt = get_ctxt();
@@ -43,6 +51,20 @@ pub extern "C" fn long_to_string(val: i64) -> Handle<Str> {
     Str::from_buffer(ctxt, buffer.as_bytes())
 }
 
+pub extern "C" fn uint_to_string(val: u32) -> Handle<Str> {
+    let buffer = val.to_string();
+    let ctxt = get_ctxt();
+
+    Str::from_buffer(ctxt, buffer.as_bytes())
+}
+
+pub extern "C" fn ulong_to_string(val: u64) -> Handle<Str> {
+    let buffer = val.to_string();
+    let ctxt = get_ctxt();
+
+    Str::from_buffer(ctxt, buffer.as_bytes())
+}
+
 pub extern "C" fn float_to_string(val: f32) -> Handle<Str> {
     let buffer = val.to_string();
     let ctxt = get_ctxt();
@@ -148,6 +170,16 @@ pub extern "C" fn strcat(lhs: Handle<Str>, rhs: Handle<Str>) -> Handle<Str> {
     Str::concat(ctxt, lhs, rhs).direct()
 }
 
+// parts of a string template are stored in consecutive stack slots
+pub extern "C" fn str_concat_parts(parts: *const Handle<Str>, count: i32) -> Handle<Str> {
+    let ctxt = get_ctxt();
+    let parts = (0..count)
+        .map(|idx| ctxt.handles.root(unsafe { *parts.offset(idx as isize) }))
+        .collect::<Vec<_>>();
+
+    Str::concat_all(ctxt, &parts).direct()
+}
+
 pub extern "C" fn str_clone(val: Handle<Str>) -> Handle<Str> {
     let ctxt = get_ctxt();
 
@@ -257,14 +289,59 @@ pub extern "C" fn native_free(addr: *const u8) {
     unsafe { libc::free(addr as *mut libc::c_void) }
 }
 
-pub extern "C" fn spawn_thread(obj: Handle<Obj>) {
+pub extern "C" fn spawn_lambda(fct: Handle<Obj>) -> Handle<SpawnThread> {
+    let ctxt = get_ctxt();
+    let fct = ctxt.handles.root(fct);
+
+    let cls_def_id = specialize_class_id(ctxt, ctxt.vips.spawn_thread_class);
+    let obj: Handle<SpawnThread> = alloc(ctxt, cls_def_id).cast();
+    let mut obj = ctxt.handles.root(obj);
+
+    obj.thread.handle = 0;
+    obj.fct = fct.direct();
+
+    spawn_thread(obj.direct().cast());
+
+    obj.direct()
+}
+
+pub extern "C" fn join_thread(obj: Handle<Thread>) {
+    let handle = obj.take_handle();
+
+    // thread was never started or is already joined
+    if handle == 0 {
+        return;
+    }
+
+    let handle = unsafe { Box::from_raw(handle as *mut thread::JoinHandle<()>) };
+
+    // never unwind a panic through this native frame, the joining
+    // dora thread sees an exception instead
+    if handle.join().is_err() {
+        let ctxt = get_ctxt();
+        let msg = Str::from_buffer(ctxt, b"joined thread panicked");
+        let msg = ctxt.handles.root(msg);
+        let exception = alloc_exception(ctxt, msg.direct());
+
+        exception_set(exception.raw() as *const u8);
+    }
+}
+
+pub extern "C" fn spawn_thread(obj: Handle<Thread>) {
     use baseline;
     use exception::DoraToNativeInfo;
 
-    thread::spawn(move || {
+    // the new OS thread reads its object from this root, so that the GC
+    // can update it until `run` is compiled and called
+    let ctxt = get_ctxt();
+    let root = Box::into_raw(Box::new(obj.cast::<Obj>())) as usize;
+    ctxt.thread_roots.lock().unwrap().push(root);
+
+    let handle = thread::spawn(move || {
         let ctxt = get_ctxt();
 
         let main = {
+            let obj: Handle<Obj> = unsafe { *(root as *const Handle<Obj>) };
             let cls_id = obj.header().vtbl().class().cls_id;
             let cls = ctxt.classes[cls_id].borrow();
             let name = ctxt.interner.intern("run");
@@ -280,7 +357,17 @@ pub extern "C" fn spawn_thread(obj: Handle<Obj>) {
             })
         };
 
-        let fct: extern "C" fn(Handle<Obj>) = unsafe { mem::transmute(fct_ptr) };
+        let obj: Handle<Thread> = {
+            let mut roots = ctxt.thread_roots.lock().unwrap();
+            roots.retain(|&r| r != root);
+
+            unsafe { *Box::from_raw(root as *mut Handle<Obj>) }.cast()
+        };
+
+        let fct: extern "C" fn(Handle<Thread>) = unsafe { mem::transmute(fct_ptr) };
         fct(obj);
     });
+
+    // keep the handle in the object for joining the thread later
+    obj.set_handle(Box::into_raw(Box::new(handle)) as usize);
 }
@@ -1,13 +1,27 @@
 use std::collections::HashMap;
+use std::mem;
 
 use self::Sym::*;
 
 use class::{ClassId, TypeParamId};
 use ctxt::*;
 use dora_parser::interner::Name;
+use ty::BuiltinType;
 
 #[derive(Debug)]
 pub struct SymTable {
+    // the first level holds the items of the current module
+    levels: Vec<SymLevel>,
+    modules: Vec<ModuleSyms>,
+    module: ModuleId,
+
+    // public items of this module are visible in all other modules
+    prelude: Option<ModuleId>,
+}
+
+// local levels stashed away while looking up names in another module
+pub struct SavedScope {
+    module: ModuleId,
     levels: Vec<SymLevel>,
 }
 
@@ -15,9 +29,60 @@ impl SymTable {
     pub fn new() -> SymTable {
         SymTable {
             levels: vec![SymLevel::new()],
+            modules: vec![ModuleSyms::new()],
+            module: ModuleId::root(),
+            prelude: None,
         }
     }
 
+    pub fn add_module(&mut self) -> ModuleId {
+        self.modules.push(ModuleSyms::new());
+
+        ((self.modules.len() - 1) as u32).into()
+    }
+
+    pub fn module(&self) -> ModuleId {
+        self.module
+    }
+
+    pub fn enter_module(&mut self, module: ModuleId) {
+        assert!(self.levels.len() == 1);
+
+        if self.module == module {
+            return;
+        }
+
+        let items = mem::replace(&mut self.modules[module.idx()].items, SymLevel::new());
+        let items = mem::replace(&mut self.levels[0], items);
+        self.modules[self.module.idx()].items = items;
+        self.module = module;
+    }
+
+    pub fn push_module(&mut self, module: ModuleId) -> SavedScope {
+        let levels = self.levels.split_off(1);
+        let saved = SavedScope {
+            module: self.module,
+            levels: levels,
+        };
+
+        self.enter_module(module);
+
+        saved
+    }
+
+    pub fn pop_module(&mut self, saved: SavedScope) {
+        self.enter_module(saved.module);
+        self.levels.extend(saved.levels);
+    }
+
+    pub fn prelude(&self) -> Option<ModuleId> {
+        self.prelude
+    }
+
+    pub fn set_prelude(&mut self, module: ModuleId) {
+        self.prelude = Some(module);
+    }
+
     pub fn push_level(&mut self) {
         self.levels.push(SymLevel::new());
     }
@@ -39,7 +104,45 @@ impl SymTable {
             }
         }
 
-        None
+        if let Some(val) = self.modules[self.module.idx()].imports.get(name) {
+            return Some(val.clone());
+        }
+
+        match self.prelude {
+            Some(prelude) if prelude != self.module => {
+                self.modules[prelude.idx()].exports.get(name).cloned()
+            }
+
+            _ => None,
+        }
+    }
+
+    // only looks into the innermost level
+    pub fn get_local(&self, name: Name) -> Option<Sym> {
+        self.levels.last().unwrap().get(name).cloned()
+    }
+
+    pub fn get_item(&self, module: ModuleId, name: Name) -> Option<Sym> {
+        let items = if module == self.module {
+            &self.levels[0]
+        } else {
+            &self.modules[module.idx()].items
+        };
+
+        items.get(name).cloned()
+    }
+
+    pub fn get_export(&self, module: ModuleId, name: Name) -> Option<Sym> {
+        self.modules[module.idx()].exports.get(name).cloned()
+    }
+
+    pub fn exports(&self, module: ModuleId) -> Vec<(Name, Sym)> {
+        self.modules[module.idx()]
+            .exports
+            .map
+            .iter()
+            .map(|(&name, sym)| (name, sym.clone()))
+            .collect()
     }
 
     pub fn get_var(&self, name: Name) -> Option<VarId> {
@@ -58,6 +161,14 @@ impl SymTable {
         self.get(name).and_then(|n| n.to_struct())
     }
 
+    pub fn get_enum(&self, name: Name) -> Option<EnumId> {
+        self.get(name).and_then(|n| n.to_enum())
+    }
+
+    pub fn get_alias(&self, name: Name) -> Option<AliasId> {
+        self.get(name).and_then(|n| n.to_alias())
+    }
+
     pub fn get_trait(&self, name: Name) -> Option<TraitId> {
         self.get(name).and_then(|n| n.to_trait())
     }
@@ -65,6 +176,33 @@ impl SymTable {
     pub fn insert(&mut self, name: Name, sym: Sym) -> Option<Sym> {
         self.levels.last_mut().unwrap().insert(name, sym)
     }
+
+    // makes an item of the current module visible to other modules
+    pub fn export(&mut self, name: Name, sym: Sym) {
+        self.modules[self.module.idx()].exports.insert(name, sym);
+    }
+
+    pub fn import(&mut self, name: Name, sym: Sym) -> Option<Sym> {
+        self.modules[self.module.idx()].imports.insert(name, sym)
+    }
+}
+
+#[derive(Debug)]
+struct ModuleSyms {
+    // moved into the first level of the table while the module is entered
+    items: SymLevel,
+    exports: SymLevel,
+    imports: SymLevel,
+}
+
+impl ModuleSyms {
+    fn new() -> ModuleSyms {
+        ModuleSyms {
+            items: SymLevel::new(),
+            exports: SymLevel::new(),
+            imports: SymLevel::new(),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -96,10 +234,14 @@ pub enum Sym {
     SymVar(VarId),
     SymClass(ClassId),
     SymStruct(StructId),
+    SymEnum(EnumId),
+    SymAlias(AliasId),
+    SymAliasTypeParam(BuiltinType),
     SymTrait(TraitId),
     SymGlobal(GlobalId),
     SymClassTypeParam(ClassId, TypeParamId),
     SymFctTypeParam(FctId, TypeParamId),
+    SymTraitTypeParam(TraitId, TypeParamId),
     SymConst(ConstId),
 }
 
@@ -160,6 +302,34 @@ impl Sym {
         }
     }
 
+    pub fn is_enum(&self) -> bool {
+        match *self {
+            SymEnum(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn to_enum(&self) -> Option<EnumId> {
+        match *self {
+            SymEnum(id) => Some(id),
+            _ => None,
+        }
+    }
+
+    pub fn is_alias(&self) -> bool {
+        match *self {
+            SymAlias(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn to_alias(&self) -> Option<AliasId> {
+        match *self {
+            SymAlias(id) => Some(id),
+            _ => None,
+        }
+    }
+
     pub fn is_trait(&self) -> bool {
         match *self {
             SymTrait(_) => true,
@@ -192,6 +362,7 @@ impl Sym {
         match *self {
             SymClassTypeParam(_, _) => true,
             SymFctTypeParam(_, _) => true,
+            SymTraitTypeParam(_, _) => true,
             _ => false,
         }
     }
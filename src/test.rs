@@ -1,6 +1,7 @@
 use ctxt::SemContext;
 use driver::cmd::Args;
 use dora_parser::ast::Ast;
+use dora_parser::error::diag::Diagnostic;
 use dora_parser::interner::Interner;
 use dora_parser::lexer::reader::Reader;
 use os;
@@ -13,7 +14,7 @@ where
 {
     parse_with_errors(code, |ctxt| {
         if ctxt.diag.borrow().has_errors() {
-            ctxt.diag.borrow().dump();
+            ctxt.diag.borrow().dump(ctxt.ast);
             println!("{}", code);
             panic!("unexpected error in test::parse()");
         }
@@ -31,21 +32,30 @@ where
     let id_generator = NodeIdGenerator::new();
     let mut interner = Interner::new();
     let mut ast = Ast::new();
+    let mut diag = Diagnostic::new();
     let args: Args = Default::default();
 
+    let std_module = vec![interner.intern("std")];
+
     for file in &["stdlib/prelude.dora", "stdlib/str.dora", "stdlib/test.dora"] {
-        let reader = Reader::from_file(file).unwrap();
-        let mut parser = Parser::new(reader, &id_generator, &mut ast, &mut interner);
-        parser.parse().unwrap()
+        {
+            let reader = Reader::from_file(file).unwrap();
+            let mut parser = Parser::new(reader, &id_generator, &mut ast, &mut interner);
+            parser.parse(&mut diag);
+            assert!(!diag.has_errors());
+        }
+
+        ast.files.last_mut().unwrap().module = std_module.clone();
     }
 
     {
         let reader = Reader::from_string(code);
         let mut parser = Parser::new(reader, &id_generator, &mut ast, &mut interner);
-        parser.parse().unwrap()
+        parser.parse(&mut diag);
     }
 
     let mut ctxt = SemContext::new(args, &ast, interner);
+    *ctxt.diag.borrow_mut() = diag;
 
     semck::check(&mut ctxt);
 
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::rc::Rc;
 
 use class::{ClassId, TypeParamId, TypeParams};
-use ctxt::{FctId, SemContext, StructId, TraitId};
+use ctxt::{EnumId, FctId, SemContext, StructDefId, StructId, TraitId};
 use mem;
 use semck;
 
@@ -17,8 +17,11 @@ pub enum BuiltinType {
     // value types
     Char,
     Byte,
+    Short,
     Int,
+    UInt,
     Long,
+    ULong,
 
     Float,
     Double,
@@ -40,15 +43,25 @@ pub enum BuiltinType {
     // some struct
     Struct(StructId, TypeListId),
 
-    // some trait
-    Trait(TraitId),
+    // some enum
+    Enum(EnumId),
+
+    // some trait with its type arguments
+    Trait(TraitId, TypeListId),
 
     // some type variable
     FctTypeParam(FctId, TypeParamId),
     ClassTypeParam(ClassId, TypeParamId),
+    TraitTypeParam(TraitId, TypeParamId),
 
     // some lambda
     Lambda(LambdaId),
+
+    // tuple with at least two elements
+    Tuple(TupleId),
+
+    // reference type that may also be nil, e.g. `Foo?`
+    Nullable(NullableId),
 }
 
 impl BuiltinType {
@@ -80,6 +93,13 @@ impl BuiltinType {
         }
     }
 
+    pub fn is_lambda(&self) -> bool {
+        match *self {
+            BuiltinType::Lambda(_) => true,
+            _ => false,
+        }
+    }
+
     pub fn is_float(&self) -> bool {
         match self {
             &BuiltinType::Float | &BuiltinType::Double => true,
@@ -87,6 +107,28 @@ impl BuiltinType {
         }
     }
 
+    // tuples are laid out like structs and share their code paths
+    pub fn is_struct(&self) -> bool {
+        match *self {
+            BuiltinType::Struct(_, _) | BuiltinType::Tuple(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_tuple(&self) -> bool {
+        match *self {
+            BuiltinType::Tuple(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_enum(&self) -> bool {
+        match *self {
+            BuiltinType::Enum(_) => true,
+            _ => false,
+        }
+    }
+
     pub fn is_bool(&self) -> bool {
         match self {
             &BuiltinType::Bool => true,
@@ -94,10 +136,34 @@ impl BuiltinType {
         }
     }
 
+    pub fn is_nullable(&self) -> bool {
+        match *self {
+            BuiltinType::Nullable(_) => true,
+            _ => false,
+        }
+    }
+
+    // `T?` for the reference type `T`, nullable types and nil stay as they are
+    pub fn nullable(ctxt: &SemContext, ty: BuiltinType) -> BuiltinType {
+        match ty {
+            BuiltinType::Nullable(_) | BuiltinType::Nil | BuiltinType::Error => ty,
+            _ => BuiltinType::Nullable(ctxt.nullables.borrow_mut().insert(ty)),
+        }
+    }
+
+    // `T` for `T?`, all other types are returned unchanged
+    pub fn non_nullable(&self, ctxt: &SemContext) -> BuiltinType {
+        match *self {
+            BuiltinType::Nullable(id) => ctxt.nullables.borrow().get(id),
+            _ => *self,
+        }
+    }
+
     pub fn is_type_param(&self) -> bool {
         match self {
             &BuiltinType::ClassTypeParam(_, _) => true,
             &BuiltinType::FctTypeParam(_, _) => true,
+            &BuiltinType::TraitTypeParam(_, _) => true,
             _ => false,
         }
     }
@@ -108,8 +174,11 @@ impl BuiltinType {
             BuiltinType::Bool => Some(ctxt.vips.bool_class),
             BuiltinType::Byte => Some(ctxt.vips.byte_class),
             BuiltinType::Char => Some(ctxt.vips.char_class),
+            BuiltinType::Short => Some(ctxt.vips.short_class),
             BuiltinType::Int => Some(ctxt.vips.int_class),
+            BuiltinType::UInt => Some(ctxt.vips.uint_class),
             BuiltinType::Long => Some(ctxt.vips.long_class),
+            BuiltinType::ULong => Some(ctxt.vips.ulong_class),
             BuiltinType::Float => Some(ctxt.vips.float_class),
             BuiltinType::Double => Some(ctxt.vips.double_class),
             _ => None,
@@ -125,6 +194,33 @@ impl BuiltinType {
         false
     }
 
+    // type arguments of the trait as implemented by this type,
+    // e.g. `int` for a class with `impl Iterator<int>`
+    pub fn trait_type_params(&self, ctxt: &SemContext, trait_id: TraitId) -> Option<TypeParams> {
+        let cls_id = self.cls_id(ctxt)?;
+        let cls = ctxt.classes[cls_id].borrow();
+        let impl_id = cls.find_impl_for_trait(ctxt, trait_id)?;
+        let ximpl = ctxt.impls[impl_id].borrow();
+
+        let type_params = self.type_params(ctxt);
+        let params: Vec<_> = ximpl
+            .trait_type_params
+            .iter()
+            .map(|ty| semck::specialize::specialize_type(ctxt, ty, &type_params))
+            .collect();
+
+        Some(params.into())
+    }
+
+    pub fn implements_trait_with(
+        &self,
+        ctxt: &SemContext,
+        trait_id: TraitId,
+        trait_type_params: &TypeParams,
+    ) -> bool {
+        self.trait_type_params(ctxt, trait_id).as_ref() == Some(trait_type_params)
+    }
+
     pub fn type_params(&self, ctxt: &SemContext) -> TypeParams {
         match self {
             &BuiltinType::Class(_, list_id) => ctxt.lists.borrow().get(list_id),
@@ -137,13 +233,25 @@ impl BuiltinType {
         match self {
             &BuiltinType::ClassTypeParam(_, _) => true,
             &BuiltinType::FctTypeParam(_, _) => true,
+            &BuiltinType::TraitTypeParam(_, _) => true,
 
-            &BuiltinType::Class(_, list_id) => {
+            &BuiltinType::Class(_, list_id) | &BuiltinType::Trait(_, list_id) => {
                 let params = ctxt.lists.borrow().get(list_id);
                 params.iter().any(|t| t.contains_type_param(ctxt))
             }
 
-            &BuiltinType::Lambda(_) => unimplemented!(),
+            &BuiltinType::Lambda(id) => {
+                let lambda = ctxt.lambda_types.borrow().get(id);
+                lambda.params.iter().any(|t| t.contains_type_param(ctxt))
+                    || lambda.ret.contains_type_param(ctxt)
+            }
+
+            &BuiltinType::Tuple(id) => {
+                let elements = ctxt.tuples.borrow().get(id);
+                elements.iter().any(|t| t.contains_type_param(ctxt))
+            }
+
+            &BuiltinType::Nullable(_) => self.non_nullable(ctxt).contains_type_param(ctxt),
 
             _ => false,
         }
@@ -158,14 +266,57 @@ impl BuiltinType {
             BuiltinType::Unit
             | BuiltinType::Bool
             | BuiltinType::Byte
+            | BuiltinType::Short
             | BuiltinType::Int
+            | BuiltinType::UInt
             | BuiltinType::Long
+            | BuiltinType::ULong
             | BuiltinType::Float
-            | BuiltinType::Double => true,
+            | BuiltinType::Double
+            | BuiltinType::Struct(_, _)
+            | BuiltinType::Tuple(_) => true,
             _ => false,
         }
     }
 
+    // values of these types are zero-initialized when not assigned explicitly,
+    // which is only valid when zero is a value of the type
+    pub fn has_default_value(&self, ctxt: &SemContext) -> bool {
+        match *self {
+            BuiltinType::Error
+            | BuiltinType::Unit
+            | BuiltinType::Bool
+            | BuiltinType::Byte
+            | BuiltinType::Char
+            | BuiltinType::Short
+            | BuiltinType::Int
+            | BuiltinType::UInt
+            | BuiltinType::Long
+            | BuiltinType::ULong
+            | BuiltinType::Float
+            | BuiltinType::Double
+            | BuiltinType::Nil
+            | BuiltinType::Ptr
+            | BuiltinType::Enum(_)
+            | BuiltinType::Nullable(_) => true,
+            BuiltinType::Struct(sid, _) => {
+                let struc = ctxt.structs[sid].borrow();
+                struc.fields.iter().all(|f| f.ty.has_default_value(ctxt))
+            }
+            BuiltinType::Tuple(id) => {
+                let elements = ctxt.tuples.borrow().get(id);
+                elements.iter().all(|ty| ty.has_default_value(ctxt))
+            }
+            BuiltinType::This
+            | BuiltinType::Class(_, _)
+            | BuiltinType::Trait(_, _)
+            | BuiltinType::Lambda(_)
+            | BuiltinType::FctTypeParam(_, _)
+            | BuiltinType::ClassTypeParam(_, _)
+            | BuiltinType::TraitTypeParam(_, _) => false,
+        }
+    }
+
     pub fn subclass_from(&self, ctxt: &SemContext, ty: BuiltinType) -> bool {
         if !self.is_cls() {
             return false;
@@ -185,8 +336,11 @@ impl BuiltinType {
             BuiltinType::Unit => "()".into(),
             BuiltinType::Byte => "byte".into(),
             BuiltinType::Char => "char".into(),
+            BuiltinType::Short => "short".into(),
             BuiltinType::Int => "int".into(),
+            BuiltinType::UInt => "uint".into(),
             BuiltinType::Long => "long".into(),
+            BuiltinType::ULong => "ulong".into(),
             BuiltinType::Float => "float".into(),
             BuiltinType::Double => "float".into(),
             BuiltinType::Bool => "bool".into(),
@@ -228,10 +382,28 @@ impl BuiltinType {
                     format!("{}<{}>", name, params)
                 }
             }
-            BuiltinType::Trait(tid) => {
-                let name = ctxt.traits[tid].borrow().name;
+            BuiltinType::Enum(eid) => {
+                let name = ctxt.enums[eid].borrow().name;
                 ctxt.interner.str(name).to_string()
             }
+            BuiltinType::Trait(tid, list_id) => {
+                let name = ctxt.traits[tid].borrow().name;
+                let name = ctxt.interner.str(name).to_string();
+
+                let params = ctxt.lists.borrow().get(list_id);
+
+                if params.len() == 0 {
+                    name
+                } else {
+                    let params = params
+                        .iter()
+                        .map(|ty| ty.name(ctxt))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+
+                    format!("{}<{}>", name, params)
+                }
+            }
             BuiltinType::ClassTypeParam(cid, id) => {
                 let cls = ctxt.classes[cid].borrow();
                 ctxt.interner
@@ -246,6 +418,13 @@ impl BuiltinType {
                     .to_string()
             }
 
+            BuiltinType::TraitTypeParam(tid, id) => {
+                let xtrait = ctxt.traits[tid].borrow();
+                ctxt.interner
+                    .str(xtrait.type_params[id.idx()].name)
+                    .to_string()
+            }
+
             BuiltinType::Lambda(id) => {
                 let lambda = ctxt.lambda_types.borrow().get(id);
                 let params = lambda
@@ -258,6 +437,27 @@ impl BuiltinType {
 
                 format!("({}) -> {}", params, ret)
             }
+
+            BuiltinType::Tuple(id) => {
+                let elements = ctxt.tuples.borrow().get(id);
+                let elements = elements
+                    .iter()
+                    .map(|ty| ty.name(ctxt))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                format!("({})", elements)
+            }
+
+            BuiltinType::Nullable(_) => {
+                let ty = self.non_nullable(ctxt);
+                let name = ty.name(ctxt);
+
+                match ty {
+                    BuiltinType::Lambda(_) => format!("({})?", name),
+                    _ => format!("{}?", name),
+                }
+            }
         }
     }
 
@@ -271,20 +471,20 @@ impl BuiltinType {
             | BuiltinType::Bool
             | BuiltinType::Byte
             | BuiltinType::Char
-            | BuiltinType::Struct(_, _) => *self == other,
-            BuiltinType::Int => *self == other,
-            BuiltinType::Long => *self == other,
+            | BuiltinType::Struct(_, _)
+            | BuiltinType::Enum(_) => *self == other,
+            BuiltinType::Short | BuiltinType::Int | BuiltinType::UInt => *self == other,
+            BuiltinType::Long | BuiltinType::ULong => *self == other,
             BuiltinType::Float | BuiltinType::Double => *self == other,
             BuiltinType::Nil => panic!("nil does not allow any other types"),
             BuiltinType::Ptr => panic!("ptr does not allow any other types"),
-            BuiltinType::This => unreachable!(),
-            BuiltinType::Class(_, _) => {
-                *self == other || other.is_nil() || other.subclass_from(ctxt, *self)
-            }
-            BuiltinType::Trait(_) => unimplemented!(),
+            BuiltinType::This => *self == other,
+            BuiltinType::Class(_, _) => *self == other || other.subclass_from(ctxt, *self),
+            BuiltinType::Trait(_, _) => unimplemented!(),
 
             BuiltinType::ClassTypeParam(_, _) => *self == other,
             BuiltinType::FctTypeParam(_, _) => *self == other,
+            BuiltinType::TraitTypeParam(_, _) => *self == other,
 
             BuiltinType::Lambda(_) => {
                 // for now expect the exact same params and return types
@@ -292,6 +492,25 @@ impl BuiltinType {
                 //                             sub class for return type
                 *self == other
             }
+
+            BuiltinType::Nullable(_) => {
+                other.is_nil() || self.non_nullable(ctxt).allows(ctxt, other.non_nullable(ctxt))
+            }
+
+            BuiltinType::Tuple(id) => match other {
+                BuiltinType::Tuple(other_id) => {
+                    let elements = ctxt.tuples.borrow().get(id);
+                    let other_elements = ctxt.tuples.borrow().get(other_id);
+
+                    elements.len() == other_elements.len()
+                        && elements
+                            .iter()
+                            .zip(other_elements.iter())
+                            .all(|(&a, &b)| a.allows(ctxt, b))
+                }
+
+                _ => false,
+            },
         }
     }
 
@@ -309,16 +528,19 @@ impl BuiltinType {
             BuiltinType::Unit => 0,
             BuiltinType::Bool => 1,
             BuiltinType::Byte => 1,
+            BuiltinType::Short => 2,
             BuiltinType::Char => 4,
-            BuiltinType::Int => 4,
-            BuiltinType::Long => 8,
+            BuiltinType::Int | BuiltinType::UInt => 4,
+            BuiltinType::Long | BuiltinType::ULong => 8,
             BuiltinType::Float => 4,
             BuiltinType::Double => 8,
             BuiltinType::Nil => panic!("no size for nil."),
             BuiltinType::This => panic!("no size for Self."),
-            BuiltinType::Class(_, _) | BuiltinType::Lambda(_) | BuiltinType::Ptr => {
-                mem::ptr_width()
-            }
+            BuiltinType::Class(_, _)
+            | BuiltinType::Lambda(_)
+            | BuiltinType::Nullable(_)
+            | BuiltinType::Enum(_)
+            | BuiltinType::Ptr => mem::ptr_width(),
             BuiltinType::Struct(sid, list_id) => {
                 let params = ctxt.lists.borrow().get(list_id);
                 let sid = semck::specialize::specialize_struct_id_params(ctxt, sid, params);
@@ -326,8 +548,16 @@ impl BuiltinType {
 
                 struc.size
             }
-            BuiltinType::Trait(_) => 2 * mem::ptr_width(),
-            BuiltinType::ClassTypeParam(_, _) | BuiltinType::FctTypeParam(_, _) => {
+            BuiltinType::Tuple(id) => {
+                let sid = semck::specialize::specialize_tuple(ctxt, id);
+                let struc = ctxt.struct_defs[sid].borrow();
+
+                struc.size
+            }
+            BuiltinType::Trait(_, _) => 2 * mem::ptr_width(),
+            BuiltinType::ClassTypeParam(_, _)
+            | BuiltinType::FctTypeParam(_, _)
+            | BuiltinType::TraitTypeParam(_, _) => {
                 panic!("no size for type variable.")
             }
         }
@@ -339,16 +569,19 @@ impl BuiltinType {
             BuiltinType::Unit => 0,
             BuiltinType::Bool => 1,
             BuiltinType::Byte => 1,
+            BuiltinType::Short => 2,
             BuiltinType::Char => 4,
-            BuiltinType::Int => 4,
-            BuiltinType::Long => 8,
+            BuiltinType::Int | BuiltinType::UInt => 4,
+            BuiltinType::Long | BuiltinType::ULong => 8,
             BuiltinType::Float => 4,
             BuiltinType::Double => 8,
             BuiltinType::Nil => panic!("no alignment for nil."),
             BuiltinType::This => panic!("no alignment for Self."),
-            BuiltinType::Class(_, _) | BuiltinType::Lambda(_) | BuiltinType::Ptr => {
-                mem::ptr_width()
-            }
+            BuiltinType::Class(_, _)
+            | BuiltinType::Lambda(_)
+            | BuiltinType::Nullable(_)
+            | BuiltinType::Enum(_)
+            | BuiltinType::Ptr => mem::ptr_width(),
             BuiltinType::Struct(sid, list_id) => {
                 let params = ctxt.lists.borrow().get(list_id);
                 let sid = semck::specialize::specialize_struct_id_params(ctxt, sid, params);
@@ -356,8 +589,16 @@ impl BuiltinType {
 
                 struc.align
             }
-            BuiltinType::Trait(_) => mem::ptr_width(),
-            BuiltinType::ClassTypeParam(_, _) | BuiltinType::FctTypeParam(_, _) => {
+            BuiltinType::Tuple(id) => {
+                let sid = semck::specialize::specialize_tuple(ctxt, id);
+                let struc = ctxt.struct_defs[sid].borrow();
+
+                struc.align
+            }
+            BuiltinType::Trait(_, _) => mem::ptr_width(),
+            BuiltinType::ClassTypeParam(_, _)
+            | BuiltinType::FctTypeParam(_, _)
+            | BuiltinType::TraitTypeParam(_, _) => {
                 panic!("no alignment for type variable.")
             }
         }
@@ -369,19 +610,25 @@ impl BuiltinType {
             BuiltinType::Unit => panic!("no machine mode for ()."),
             BuiltinType::Bool => MachineMode::Int8,
             BuiltinType::Byte => MachineMode::Int8,
+            BuiltinType::Short => MachineMode::Int16,
             BuiltinType::Char => MachineMode::Int32,
-            BuiltinType::Int => MachineMode::Int32,
-            BuiltinType::Long => MachineMode::Int64,
+            BuiltinType::Int | BuiltinType::UInt => MachineMode::Int32,
+            BuiltinType::Long | BuiltinType::ULong => MachineMode::Int64,
             BuiltinType::Float => MachineMode::Float32,
             BuiltinType::Double => MachineMode::Float64,
             BuiltinType::Nil => panic!("no machine mode for nil."),
             BuiltinType::This => panic!("no machine mode for Self."),
-            BuiltinType::Class(_, _) | BuiltinType::Lambda(_) | BuiltinType::Ptr => {
-                MachineMode::Ptr
-            }
+            BuiltinType::Class(_, _)
+            | BuiltinType::Lambda(_)
+            | BuiltinType::Nullable(_)
+            | BuiltinType::Enum(_)
+            | BuiltinType::Ptr => MachineMode::Ptr,
             BuiltinType::Struct(_, _) => panic!("no machine mode for struct."),
-            BuiltinType::Trait(_) => unimplemented!(),
-            BuiltinType::ClassTypeParam(_, _) | BuiltinType::FctTypeParam(_, _) => {
+            BuiltinType::Tuple(_) => panic!("no machine mode for tuple."),
+            BuiltinType::Trait(_, _) => unimplemented!(),
+            BuiltinType::ClassTypeParam(_, _)
+            | BuiltinType::FctTypeParam(_, _)
+            | BuiltinType::TraitTypeParam(_, _) => {
                 panic!("no machine mode for type variable.")
             }
         }
@@ -391,6 +638,7 @@ impl BuiltinType {
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum MachineMode {
     Int8,
+    Int16,
     Int32,
     Int64,
     Float32,
@@ -402,6 +650,7 @@ impl MachineMode {
     pub fn size(self) -> i32 {
         match self {
             MachineMode::Int8 => 1,
+            MachineMode::Int16 => 2,
             MachineMode::Int32 => 4,
             MachineMode::Int64 => 8,
             MachineMode::Ptr => mem::ptr_width(),
@@ -516,6 +765,103 @@ pub struct LambdaType {
     ret: BuiltinType,
 }
 
+impl LambdaType {
+    pub fn params(&self) -> &[BuiltinType] {
+        &self.params
+    }
+
+    pub fn ret(&self) -> BuiltinType {
+        self.ret
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TupleId(usize);
+
+impl From<usize> for TupleId {
+    fn from(val: usize) -> TupleId {
+        TupleId(val)
+    }
+}
+
+pub struct TupleTypes {
+    types: HashMap<Rc<Vec<BuiltinType>>, TupleId>,
+    values: Vec<Rc<Vec<BuiltinType>>>,
+    struct_defs: HashMap<TupleId, StructDefId>,
+}
+
+impl TupleTypes {
+    pub fn new() -> TupleTypes {
+        TupleTypes {
+            types: HashMap::new(),
+            values: Vec::new(),
+            struct_defs: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, elements: Vec<BuiltinType>) -> TupleId {
+        assert!(elements.len() >= 2);
+
+        if let Some(&val) = self.types.get(&elements) {
+            return val;
+        }
+
+        let id = TupleId(self.values.len());
+        let elements = Rc::new(elements);
+        self.types.insert(elements.clone(), id);
+
+        self.values.push(elements);
+
+        id
+    }
+
+    pub fn get(&self, id: TupleId) -> Rc<Vec<BuiltinType>> {
+        self.values[id.0].clone()
+    }
+
+    pub fn struct_def(&self, id: TupleId) -> Option<StructDefId> {
+        self.struct_defs.get(&id).cloned()
+    }
+
+    pub fn set_struct_def(&mut self, id: TupleId, struct_def_id: StructDefId) {
+        let old = self.struct_defs.insert(id, struct_def_id);
+        assert!(old.is_none());
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct NullableId(usize);
+
+pub struct NullableTypes {
+    types: HashMap<BuiltinType, NullableId>,
+    values: Vec<BuiltinType>,
+}
+
+impl NullableTypes {
+    pub fn new() -> NullableTypes {
+        NullableTypes {
+            types: HashMap::new(),
+            values: Vec::new(),
+        }
+    }
+
+    pub fn insert(&mut self, ty: BuiltinType) -> NullableId {
+        if let Some(&val) = self.types.get(&ty) {
+            return val;
+        }
+
+        let id = NullableId(self.values.len());
+        self.types.insert(ty, id);
+        self.values.push(ty);
+
+        id
+    }
+
+    pub fn get(&self, id: NullableId) -> BuiltinType {
+        self.values[id.0]
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -524,6 +870,7 @@ mod tests {
     #[test]
     fn mode_size() {
         assert_eq!(1, MachineMode::Int8.size());
+        assert_eq!(2, MachineMode::Int16.size());
         assert_eq!(4, MachineMode::Int32.size());
         assert_eq!(mem::ptr_width(), MachineMode::Ptr.size());
     }
@@ -531,7 +878,10 @@ mod tests {
     #[test]
     fn mode_for_types() {
         assert_eq!(MachineMode::Int8, BuiltinType::Bool.mode());
+        assert_eq!(MachineMode::Int16, BuiltinType::Short.mode());
         assert_eq!(MachineMode::Int32, BuiltinType::Int.mode());
+        assert_eq!(MachineMode::Int32, BuiltinType::UInt.mode());
+        assert_eq!(MachineMode::Int64, BuiltinType::ULong.mode());
         assert_eq!(MachineMode::Ptr, BuiltinType::Ptr.mode());
     }
 
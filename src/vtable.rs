@@ -126,6 +126,22 @@ impl VTable {
         offset_of!(VTable, subtype_overflow) as i32
     }
 
+    pub fn subclass_from(&self, super_vtable: &VTable) -> bool {
+        let depth = super_vtable.subtype_depth as usize;
+
+        if depth > self.subtype_depth as usize {
+            return false;
+        }
+
+        let entry = if depth < DISPLAY_SIZE {
+            self.subtype_display[depth]
+        } else {
+            self.get_subtype_overflow(depth - DISPLAY_SIZE)
+        };
+
+        entry == super_vtable as *const VTable
+    }
+
     pub fn get_subtype_overflow(&self, ind: usize) -> *const VTable {
         assert!(
             self.subtype_depth as usize >= DISPLAY_SIZE